@@ -0,0 +1,238 @@
+//! Derive macros for the `FromSsvRow` and `ToSsvRow` traits of the `ssv` crate.
+//!
+//! They are meant to be used through the `derive` feature of the `ssv` crate,
+//! which re-exports them. The `#[ssv(...)]` attributes of the fields are
+//! documented in its `engine::mapping` module.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Literal, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{
+    parse_macro_input, Data, DeriveInput, GenericArgument, LitStr, Member, Path, PathArguments,
+    Type,
+};
+
+/// Derives the `FromSsvRow` trait for a struct.
+#[proc_macro_derive(FromSsvRow, attributes(ssv))]
+pub fn derive_from_ssv_row(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    from_ssv_row(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives the `ToSsvRow` trait for a struct.
+#[proc_macro_derive(ToSsvRow, attributes(ssv))]
+pub fn derive_to_ssv_row(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    to_ssv_row(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn from_ssv_row(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = fields(input, "FromSsvRow")?;
+
+    let columns = fields.iter().map(|field| {
+        let name = &field.name;
+        let column = match &field.column {
+            Some(column) => quote!(::core::option::Option::Some(#column)),
+            None => quote!(::core::option::Option::None),
+        };
+        let required = !field.optional && field.default.is_none();
+        quote! {
+            ::ssv::engine::mapping::FieldColumn {
+                field: #name,
+                name: #column,
+                required: #required,
+            }
+        }
+    });
+
+    let values = fields.iter().enumerate().map(|(index, field)| {
+        let member = &field.member;
+        let name = &field.name;
+        let index = Literal::usize_unsuffixed(index);
+        let value = quote!(values[#index]);
+        let expression = match &field.default {
+            _ if field.optional => {
+                quote!(::ssv::engine::mapping::parse_optional_value(#name, #value)?)
+            }
+            Some(FieldDefault::Trait) => quote! {
+                ::ssv::engine::mapping::parse_optional_value(#name, #value)?
+                    .unwrap_or_else(::core::default::Default::default)
+            },
+            Some(FieldDefault::Path(path)) => quote! {
+                ::ssv::engine::mapping::parse_optional_value(#name, #value)?
+                    .unwrap_or_else(#path)
+            },
+            None => quote!(::ssv::engine::mapping::parse_value(#name, #value)?),
+        };
+        quote!(#member: #expression)
+    });
+
+    let ident = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::ssv::engine::mapping::FromSsvRow for #ident #type_generics
+        #where_clause
+        {
+            const COLUMNS: &'static [::ssv::engine::mapping::FieldColumn] = &[#(#columns),*];
+
+            #[allow(unused_variables)]
+            fn from_ssv_values(
+                values: &[::core::option::Option<&str>],
+            ) -> ::core::result::Result<Self, ::ssv::engine::mapping::FieldError> {
+                ::core::result::Result::Ok(Self { #(#values),* })
+            }
+        }
+    })
+}
+
+fn to_ssv_row(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = fields(input, "ToSsvRow")?;
+
+    let header = fields
+        .iter()
+        .map(|field| field.column.as_ref().unwrap_or(&field.name));
+
+    let quoting_policies = fields.iter().map(|field| match &field.quote {
+        Some(policy) => {
+            quote!(::core::option::Option::Some(::ssv::engine::options::QuotingPolicy::#policy))
+        }
+        None => quote!(::core::option::Option::None),
+    });
+
+    let values = fields.iter().map(|field| {
+        let member = &field.member;
+        if field.optional {
+            quote!(::ssv::engine::mapping::format_optional_value(&self.#member))
+        } else {
+            quote!(::std::string::ToString::to_string(&self.#member))
+        }
+    });
+
+    let ident = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::ssv::engine::mapping::ToSsvRow for #ident #type_generics
+        #where_clause
+        {
+            const HEADER: &'static [&'static str] = &[#(#header),*];
+
+            const QUOTING_POLICIES: &'static [::core::option::Option<
+                ::ssv::engine::options::QuotingPolicy,
+            >] = &[#(#quoting_policies),*];
+
+            fn to_ssv_values(&self) -> ::std::vec::Vec<::std::string::String> {
+                ::std::vec![#(#values),*]
+            }
+        }
+    })
+}
+
+/// A field and its `#[ssv(...)]` attributes.
+struct Field {
+    member: Member,
+    name: String,
+    column: Option<String>,
+    optional: bool,
+    default: Option<FieldDefault>,
+    quote: Option<syn::Ident>,
+}
+
+enum FieldDefault {
+    Trait,
+    Path(Path),
+}
+
+fn fields(input: &DeriveInput, trait_name: &str) -> syn::Result<Vec<Field>> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            input,
+            format!("{trait_name} can only be derived for structs"),
+        ));
+    };
+
+    data.fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let (member, name) = match &field.ident {
+                Some(ident) => (Member::Named(ident.clone()), ident.to_string()),
+                None => (Member::Unnamed(index.into()), index.to_string()),
+            };
+            let mut result = Field {
+                member,
+                name,
+                column: None,
+                optional: is_option(&field.ty),
+                default: None,
+                quote: None,
+            };
+
+            for attr in field
+                .attrs
+                .iter()
+                .filter(|attr| attr.path().is_ident("ssv"))
+            {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("column") {
+                        let column: LitStr = meta.value()?.parse()?;
+                        result.column = Some(column.value());
+                    } else if meta.path.is_ident("optional") {
+                        result.optional = true;
+                    } else if meta.path.is_ident("default") {
+                        result.default = Some(if meta.input.peek(syn::Token![=]) {
+                            let path: LitStr = meta.value()?.parse()?;
+                            FieldDefault::Path(path.parse()?)
+                        } else {
+                            FieldDefault::Trait
+                        });
+                    } else if meta.path.is_ident("quote") {
+                        let policy: LitStr = meta.value()?.parse()?;
+                        let variant = match policy.value().as_str() {
+                            "minimal" => "Minimal",
+                            "always" => "Always",
+                            "non_numeric" => "NonNumeric",
+                            "never" => "Never",
+                            _ => return Err(syn::Error::new_spanned(
+                                policy,
+                                "expected \"minimal\", \"always\", \"non_numeric\" or \"never\"",
+                            )),
+                        };
+                        result.quote = Some(syn::Ident::new(variant, policy.span()));
+                    } else {
+                        return Err(meta.error("unsupported ssv attribute"));
+                    }
+                    Ok(())
+                })?;
+            }
+
+            if result.optional && result.default.is_some() {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    "an optional field can't have a default value",
+                ));
+            }
+            Ok(result)
+        })
+        .collect()
+}
+
+/// Returns whether the type is written as `Option<T>`.
+fn is_option(ty: &Type) -> bool {
+    let Type::Path(type_path) = ty else {
+        return false;
+    };
+    let Some(segment) = type_path.path.segments.last() else {
+        return false;
+    };
+    segment.ident == "Option"
+        && matches!(
+            &segment.arguments,
+            PathArguments::AngleBracketed(arguments)
+                if arguments.args.len() == 1
+                    && matches!(arguments.args[0], GenericArgument::Type(_))
+        )
+}
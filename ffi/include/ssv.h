@@ -0,0 +1,154 @@
+/*
+ * C bindings for the ssv crate.
+ *
+ * SSV means Space-Separated Values, and is an alternative to CSV. These
+ * functions read and write SSV through opaque handles. Values are sequences of
+ * bytes, which are not NUL-terminated and may contain NUL bytes.
+ *
+ * This header declares the functions of the `ffi` module of the ssv crate,
+ * which are built as a shared library by the ssv-ffi crate. Keep it in sync
+ * with `src/ffi.rs`.
+ */
+
+#ifndef SSV_H
+#define SSV_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* The result of a function. Errors have negative codes. */
+typedef enum ssv_status {
+    /* The function succeeded. */
+    SSV_OK = 0,
+    /* There are no more rows to read. */
+    SSV_END = 1,
+    /* A quote in a value was not duplicated. */
+    SSV_ERROR_UNPAIRED_QUOTE = -1,
+    /* The input ended before reaching the closing quote of a quoted value. */
+    SSV_ERROR_UNCLOSED_QUOTED_VALUE = -2,
+    /* An IO error happened when reading or writing. */
+    SSV_ERROR_IO = -3,
+    /* A value containing non-spacing elements was tried to be used as spacing. */
+    SSV_ERROR_INVALID_SPACING = -4,
+    /* A value requiring quotes was tried to be written without them. */
+    SSV_ERROR_QUOTES_REQUIRED = -5,
+    /* A comment containing a line-break was tried to be written. */
+    SSV_ERROR_COMMENT_WITH_LINE_BREAK = -6,
+    /* A required pointer was null, or a path was not valid UTF-8. */
+    SSV_ERROR_INVALID_ARGUMENT = -7,
+    /* A row was tried to be written after the writer was finished. */
+    SSV_ERROR_FINISHED = -8
+} ssv_status;
+
+/* The details of an error. */
+typedef struct ssv_error {
+    /* The error code. */
+    ssv_status status;
+    /* The line number of a syntax error, starting from 1, or 0 for other errors. */
+    size_t line_number;
+    /* The column number of a syntax error, in bytes and starting from 1, or 0
+     * for other errors. */
+    size_t column_number;
+    /* The OS error code of an IO error, such as an errno value, or 0 if there
+     * is none. */
+    int os_error;
+} ssv_error;
+
+/* A value, as a pointer to its bytes and their count. */
+typedef struct ssv_value {
+    /* The bytes of the value. It may be NULL only if length is 0. */
+    const uint8_t *data;
+    /* The number of bytes. */
+    size_t length;
+} ssv_value;
+
+/* An opaque handle that reads rows. */
+typedef struct ssv_reader ssv_reader;
+
+/* An opaque handle that writes rows. */
+typedef struct ssv_writer ssv_writer;
+
+/*
+ * In all functions, the `error` argument may be NULL. If it is not NULL, it is
+ * filled when the function fails.
+ */
+
+/*
+ * Returns a static NUL-terminated description of a status code, or a generic
+ * description if it is not the code of a status.
+ */
+const char *ssv_status_message(int status);
+
+/*
+ * Opens a file to read its rows. A compressed file is decompressed. Returns
+ * NULL on failure.
+ */
+ssv_reader *ssv_reader_open_file(const char *path, ssv_error *error);
+
+/*
+ * Creates a reader of the rows in a memory buffer. The buffer is copied, so it
+ * can be released right after this call. Returns NULL if data is NULL and
+ * length is not 0.
+ */
+ssv_reader *ssv_reader_from_buffer(const uint8_t *data, size_t length, ssv_error *error);
+
+/*
+ * Reads the next row. On SSV_OK, `*values` points to an array of `*count`
+ * values, which are valid until the next call or until the reader is freed.
+ * Returns SSV_END when there are no more rows, and an error code on failure.
+ * Reading after an error returns SSV_END.
+ */
+ssv_status ssv_reader_next_row(
+    ssv_reader *reader,
+    const ssv_value **values,
+    size_t *count,
+    ssv_error *error);
+
+/* Frees a reader. Does nothing if reader is NULL. */
+void ssv_reader_free(ssv_reader *reader);
+
+/*
+ * Creates a file, or truncates it if it exists, to write rows to it. The file
+ * is compressed if its extension matches a compression format. Returns NULL
+ * on failure.
+ */
+ssv_writer *ssv_writer_create_file(const char *path, ssv_error *error);
+
+/* Creates a writer of rows to a memory buffer, returned by ssv_writer_buffer. */
+ssv_writer *ssv_writer_new_buffer(void);
+
+/* Writes a row of `count` values, quoted only when required. */
+ssv_status ssv_writer_write_row(
+    ssv_writer *writer,
+    const ssv_value *values,
+    size_t count,
+    ssv_error *error);
+
+/*
+ * Finishes the writing, ending the compressed stream and flushing the file of
+ * a file writer. No rows can be written after this call.
+ */
+ssv_status ssv_writer_finish(ssv_writer *writer, ssv_error *error);
+
+/*
+ * Returns the content written by a writer created with ssv_writer_new_buffer.
+ * The content is valid until the next row is written or until the writer is
+ * freed. Returns SSV_ERROR_INVALID_ARGUMENT for a file writer.
+ */
+ssv_status ssv_writer_buffer(const ssv_writer *writer, const uint8_t **data, size_t *length);
+
+/*
+ * Frees a writer. Does nothing if writer is NULL. A file writer that was not
+ * finished is finished, ignoring eventual errors.
+ */
+void ssv_writer_free(ssv_writer *writer);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* SSV_H */
@@ -0,0 +1,6 @@
+//! Builds the C ABI bindings of the `ssv` crate as a C shared library, whose
+//! functions are declared by the `include/ssv.h` header.
+//!
+//! The bindings are implemented by the `ffi` module of the `ssv` crate.
+
+pub use ssv::ffi::*;
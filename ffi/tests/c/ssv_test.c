@@ -0,0 +1,204 @@
+/*
+ * Tests the C bindings. Receives the path of a directory for temporary files.
+ *
+ * The status codes and the layouts of the types in Rust are given as RUST_*
+ * preprocessor definitions, to be compared with the header.
+ */
+
+#include <errno.h>
+#include <stddef.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "ssv.h"
+
+static int failures = 0;
+
+#define CHECK(condition)                                                     \
+    do {                                                                     \
+        if (!(condition)) {                                                  \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, \
+                    #condition);                                             \
+            failures++;                                                      \
+        }                                                                    \
+    } while (0)
+
+static int value_equals(ssv_value value, const char *expected) {
+    size_t length = strlen(expected);
+    return value.length == length && memcmp(value.data, expected, length) == 0;
+}
+
+static ssv_value text_value(const char *text) {
+    ssv_value value = {(const uint8_t *)text, strlen(text)};
+    return value;
+}
+
+static void test_layout(void) {
+    CHECK(SSV_OK == RUST_SSV_OK);
+    CHECK(SSV_END == RUST_SSV_END);
+    CHECK(SSV_ERROR_UNPAIRED_QUOTE == RUST_SSV_ERROR_UNPAIRED_QUOTE);
+    CHECK(SSV_ERROR_UNCLOSED_QUOTED_VALUE == RUST_SSV_ERROR_UNCLOSED_QUOTED_VALUE);
+    CHECK(SSV_ERROR_IO == RUST_SSV_ERROR_IO);
+    CHECK(SSV_ERROR_INVALID_SPACING == RUST_SSV_ERROR_INVALID_SPACING);
+    CHECK(SSV_ERROR_QUOTES_REQUIRED == RUST_SSV_ERROR_QUOTES_REQUIRED);
+    CHECK(SSV_ERROR_COMMENT_WITH_LINE_BREAK == RUST_SSV_ERROR_COMMENT_WITH_LINE_BREAK);
+    CHECK(SSV_ERROR_INVALID_ARGUMENT == RUST_SSV_ERROR_INVALID_ARGUMENT);
+    CHECK(SSV_ERROR_FINISHED == RUST_SSV_ERROR_FINISHED);
+
+    CHECK(sizeof(ssv_status) == RUST_SIZEOF_SSV_STATUS);
+    CHECK(sizeof(ssv_error) == RUST_SIZEOF_SSV_ERROR);
+    CHECK(offsetof(ssv_error, status) == RUST_OFFSETOF_SSV_ERROR_STATUS);
+    CHECK(offsetof(ssv_error, line_number) == RUST_OFFSETOF_SSV_ERROR_LINE_NUMBER);
+    CHECK(offsetof(ssv_error, column_number) == RUST_OFFSETOF_SSV_ERROR_COLUMN_NUMBER);
+    CHECK(offsetof(ssv_error, os_error) == RUST_OFFSETOF_SSV_ERROR_OS_ERROR);
+    CHECK(sizeof(ssv_value) == RUST_SIZEOF_SSV_VALUE);
+    CHECK(offsetof(ssv_value, data) == RUST_OFFSETOF_SSV_VALUE_DATA);
+    CHECK(offsetof(ssv_value, length) == RUST_OFFSETOF_SSV_VALUE_LENGTH);
+}
+
+static void test_status_message(void) {
+    CHECK(strcmp(ssv_status_message(SSV_ERROR_IO), "IO error") == 0);
+    CHECK(strcmp(ssv_status_message(2), "unknown status") == 0);
+    CHECK(strcmp(ssv_status_message(-9), "unknown status") == 0);
+}
+
+static void test_read_buffer(void) {
+    const char *input = "a \"b c\" \"\"\n# comment\nd \"e\"\"f\"\n";
+    ssv_error error;
+    ssv_reader *reader = ssv_reader_from_buffer((const uint8_t *)input, strlen(input), &error);
+    const ssv_value *values;
+    size_t count;
+
+    CHECK(reader != NULL);
+
+    CHECK(ssv_reader_next_row(reader, &values, &count, &error) == SSV_OK);
+    CHECK(count == 3);
+    CHECK(value_equals(values[0], "a"));
+    CHECK(value_equals(values[1], "b c"));
+    CHECK(value_equals(values[2], ""));
+
+    CHECK(ssv_reader_next_row(reader, &values, &count, &error) == SSV_OK);
+    CHECK(count == 2);
+    CHECK(value_equals(values[0], "d"));
+    CHECK(value_equals(values[1], "e\"f"));
+
+    CHECK(ssv_reader_next_row(reader, &values, &count, &error) == SSV_END);
+    CHECK(count == 0);
+
+    ssv_reader_free(reader);
+}
+
+static void test_read_syntax_error(void) {
+    const char *input = "a b\nc d\"e\n";
+    ssv_error error;
+    ssv_reader *reader = ssv_reader_from_buffer((const uint8_t *)input, strlen(input), NULL);
+    const ssv_value *values;
+    size_t count;
+
+    CHECK(ssv_reader_next_row(reader, &values, &count, &error) == SSV_OK);
+    CHECK(ssv_reader_next_row(reader, &values, &count, &error) == SSV_ERROR_UNPAIRED_QUOTE);
+    CHECK(error.status == SSV_ERROR_UNPAIRED_QUOTE);
+    CHECK(error.line_number == 2);
+    CHECK(error.column_number == 4);
+    CHECK(strcmp(ssv_status_message(error.status), "unpaired quote") == 0);
+    CHECK(ssv_reader_next_row(reader, &values, &count, NULL) == SSV_END);
+
+    ssv_reader_free(reader);
+}
+
+static void test_write_buffer(void) {
+    ssv_writer *writer = ssv_writer_new_buffer();
+    ssv_value row[3];
+    const uint8_t *data;
+    size_t length;
+    const char *expected = "a \"b c\" \"\"\n\"#d\"\n";
+
+    row[0] = text_value("a");
+    row[1] = text_value("b c");
+    row[2].data = NULL;
+    row[2].length = 0;
+    CHECK(ssv_writer_write_row(writer, row, 3, NULL) == SSV_OK);
+    row[0] = text_value("#d");
+    CHECK(ssv_writer_write_row(writer, row, 1, NULL) == SSV_OK);
+    CHECK(ssv_writer_finish(writer, NULL) == SSV_OK);
+
+    CHECK(ssv_writer_buffer(writer, &data, &length) == SSV_OK);
+    CHECK(length == strlen(expected) && memcmp(data, expected, length) == 0);
+    CHECK(ssv_writer_write_row(writer, row, 1, NULL) == SSV_ERROR_FINISHED);
+
+    ssv_writer_free(writer);
+}
+
+static void test_file_round_trip(const char *directory) {
+    char path[4096];
+    ssv_error error;
+    ssv_writer *writer;
+    ssv_reader *reader;
+    ssv_value row[2];
+    const ssv_value *values;
+    size_t count;
+    const uint8_t *data;
+    size_t length;
+
+    snprintf(path, sizeof path, "%s/ssv_test.ssv", directory);
+
+    writer = ssv_writer_create_file(path, &error);
+    CHECK(writer != NULL);
+    row[0] = text_value("x y");
+    row[1] = text_value("z");
+    CHECK(ssv_writer_write_row(writer, row, 2, &error) == SSV_OK);
+    CHECK(ssv_writer_buffer(writer, &data, &length) == SSV_ERROR_INVALID_ARGUMENT);
+    CHECK(ssv_writer_finish(writer, &error) == SSV_OK);
+    CHECK(ssv_writer_finish(writer, &error) == SSV_ERROR_FINISHED);
+    ssv_writer_free(writer);
+
+    reader = ssv_reader_open_file(path, &error);
+    CHECK(reader != NULL);
+    CHECK(ssv_reader_next_row(reader, &values, &count, &error) == SSV_OK);
+    CHECK(count == 2);
+    CHECK(value_equals(values[0], "x y"));
+    CHECK(value_equals(values[1], "z"));
+    CHECK(ssv_reader_next_row(reader, &values, &count, &error) == SSV_END);
+    ssv_reader_free(reader);
+
+    remove(path);
+}
+
+static void test_open_errors(const char *directory) {
+    char path[4096];
+    ssv_error error;
+
+    snprintf(path, sizeof path, "%s/missing/ssv_test.ssv", directory);
+
+    CHECK(ssv_reader_open_file(path, &error) == NULL);
+    CHECK(error.status == SSV_ERROR_IO);
+    CHECK(error.os_error == ENOENT);
+
+    CHECK(ssv_writer_create_file(path, &error) == NULL);
+    CHECK(error.status == SSV_ERROR_IO);
+
+    CHECK(ssv_reader_open_file(NULL, &error) == NULL);
+    CHECK(error.status == SSV_ERROR_INVALID_ARGUMENT);
+}
+
+int main(int argc, char **argv) {
+    if (argc != 2) {
+        fprintf(stderr, "usage: %s TEMP_DIRECTORY\n", argv[0]);
+        return 2;
+    }
+
+    test_layout();
+    test_status_message();
+    test_read_buffer();
+    test_read_syntax_error();
+    test_write_buffer();
+    test_file_round_trip(argv[1]);
+    test_open_errors(argv[1]);
+
+    if (failures > 0) {
+        fprintf(stderr, "%d check(s) failed\n", failures);
+        return EXIT_FAILURE;
+    }
+    return EXIT_SUCCESS;
+}
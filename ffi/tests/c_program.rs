@@ -0,0 +1,121 @@
+//! Compiles and runs the C test program against the shared library.
+
+use std::ffi::c_int;
+use std::mem::{offset_of, size_of};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use ssv::ffi::{SsvError, SsvStatus, SsvValue};
+
+/// Returns the directory with the shared library, which is the `deps`
+/// directory with the test executable.
+fn library_directory() -> PathBuf {
+    let executable = std::env::current_exe().unwrap();
+    executable.parent().unwrap().to_owned()
+}
+
+/// Returns the preprocessor definitions with the status codes and the layouts
+/// of the types in Rust, which the C test program compares with the header.
+fn layout_definitions() -> Vec<String> {
+    let statuses = [
+        ("SSV_OK", SsvStatus::Ok),
+        ("SSV_END", SsvStatus::End),
+        ("SSV_ERROR_UNPAIRED_QUOTE", SsvStatus::UnpairedQuote),
+        (
+            "SSV_ERROR_UNCLOSED_QUOTED_VALUE",
+            SsvStatus::UnclosedQuotedValue,
+        ),
+        ("SSV_ERROR_IO", SsvStatus::IoError),
+        ("SSV_ERROR_INVALID_SPACING", SsvStatus::InvalidSpacing),
+        ("SSV_ERROR_QUOTES_REQUIRED", SsvStatus::QuotesRequired),
+        (
+            "SSV_ERROR_COMMENT_WITH_LINE_BREAK",
+            SsvStatus::CommentWithLineBreak,
+        ),
+        ("SSV_ERROR_INVALID_ARGUMENT", SsvStatus::InvalidArgument),
+        ("SSV_ERROR_FINISHED", SsvStatus::Finished),
+    ];
+    let sizes = [
+        ("SIZEOF_SSV_STATUS", size_of::<SsvStatus>()),
+        ("SIZEOF_SSV_ERROR", size_of::<SsvError>()),
+        ("OFFSETOF_SSV_ERROR_STATUS", offset_of!(SsvError, status)),
+        (
+            "OFFSETOF_SSV_ERROR_LINE_NUMBER",
+            offset_of!(SsvError, line_number),
+        ),
+        (
+            "OFFSETOF_SSV_ERROR_COLUMN_NUMBER",
+            offset_of!(SsvError, column_number),
+        ),
+        (
+            "OFFSETOF_SSV_ERROR_OS_ERROR",
+            offset_of!(SsvError, os_error),
+        ),
+        ("SIZEOF_SSV_VALUE", size_of::<SsvValue>()),
+        ("OFFSETOF_SSV_VALUE_DATA", offset_of!(SsvValue, data)),
+        ("OFFSETOF_SSV_VALUE_LENGTH", offset_of!(SsvValue, length)),
+    ];
+
+    let statuses = statuses
+        .into_iter()
+        .map(|(name, status)| format!("-DRUST_{name}={}", status as c_int));
+    let sizes = sizes
+        .into_iter()
+        .map(|(name, size)| format!("-DRUST_{name}={size}"));
+    statuses.chain(sizes).collect()
+}
+
+#[test]
+fn c_program() {
+    let manifest_directory = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let temp_directory = Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let library_directory = library_directory();
+    let executable = temp_directory.join("ssv_test");
+
+    let compiler = std::env::var("CC").unwrap_or_else(|_| "cc".to_owned());
+    let status = Command::new(compiler)
+        .arg("-std=c99")
+        .arg("-Wall")
+        .arg("-Wextra")
+        .arg("-Werror")
+        .arg("-I")
+        .arg(manifest_directory.join("include"))
+        .args(layout_definitions())
+        .arg(manifest_directory.join("tests/c/ssv_test.c"))
+        .arg("-o")
+        .arg(&executable)
+        .arg("-L")
+        .arg(&library_directory)
+        .arg(format!("-Wl,-rpath,{}", library_directory.display()))
+        .arg("-lssv_ffi")
+        .status()
+        .expect("a C compiler is required to run this test");
+    assert!(status.success(), "compiling the C test program failed");
+
+    let status = Command::new(&executable)
+        .arg(temp_directory)
+        .status()
+        .unwrap();
+    assert!(status.success(), "the C test program failed");
+}
+
+#[test]
+fn header_declares_all_functions() {
+    let manifest_directory = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let source = std::fs::read_to_string(manifest_directory.join("../src/ffi.rs")).unwrap();
+    let header = std::fs::read_to_string(manifest_directory.join("include/ssv.h")).unwrap();
+
+    let functions: Vec<_> = source
+        .split("extern \"C\" fn ")
+        .skip(1)
+        .map(|rest| &rest[..rest.find('(').unwrap()])
+        .collect();
+
+    assert!(!functions.is_empty());
+    for function in functions {
+        assert!(
+            header.contains(&format!(" *{function}(")) || header.contains(&format!(" {function}(")),
+            "{function} is not declared in the header"
+        );
+    }
+}
@@ -0,0 +1,149 @@
+//! Git merge and diff driver for SSV files.
+//!
+//! # Usage
+//!
+//! ```text
+//! ssv-git merge [--header] [--key COLUMN]... BASE OURS THEIRS
+//! ssv-git diff [--header] [--key COLUMN]... OLD NEW
+//! ssv-git textconv FILE
+//! ```
+//!
+//! * `merge` does a three-way merge of the rows of `OURS` and `THEIRS`, which
+//!   are matched by the key columns, and writes the result to `OURS`. It exits
+//!   with status 1 if there are conflicts.
+//! * `diff` reports the differences between the rows of `OLD` and `NEW`. It
+//!   also accepts the 7 arguments that git passes to external diff drivers.
+//! * `textconv` writes `FILE` with the values of each column aligned.
+//!
+//! A `COLUMN` is either a column index, starting from 0, or a column name if
+//! `--header` is given.
+//!
+//! # Git configuration
+//!
+//! ```text
+//! # .gitattributes
+//! *.ssv merge=ssv diff=ssv
+//!
+//! # .git/config
+//! [merge "ssv"]
+//!     name = SSV merge driver
+//!     driver = ssv-git merge --header --key id %O %A %B
+//! [diff "ssv"]
+//!     textconv = ssv-git textconv
+//! ```
+//!
+//! Alternatively, `diff.ssv.command = ssv-git diff --header --key id` reports
+//! the differences cell by cell instead of line by line.
+
+use std::error::Error;
+use std::fs::File;
+use std::io::{stdout, BufReader, Write};
+use std::path::Path;
+use std::process::ExitCode;
+
+use ssv::bytes::{ColumnRef, Differ, Merger};
+use ssv::engine::replace_file;
+
+const USAGE: &str = "\
+usage: ssv-git merge [--header] [--key COLUMN]... BASE OURS THEIRS
+       ssv-git diff [--header] [--key COLUMN]... OLD NEW
+       ssv-git textconv FILE";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(Status::Clean) => ExitCode::SUCCESS,
+        Ok(Status::Conflicts(count)) => {
+            eprintln!("ssv-git: {count} conflict(s)");
+            ExitCode::from(1)
+        }
+        Err(error) => {
+            eprintln!("ssv-git: {error}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+enum Status {
+    Clean,
+    Conflicts(usize),
+}
+
+fn run(args: &[String]) -> Result<Status, Box<dyn Error>> {
+    let (command, args) = args.split_first().ok_or(USAGE)?;
+    let options = Options::parse(args)?;
+
+    match (command.as_str(), options.files.as_slice()) {
+        ("merge", [base, ours, theirs]) => {
+            let mut merger = Merger::new().with_header(options.header);
+            for key in options.keys {
+                merger = merger.key(key);
+            }
+
+            let mut output = Vec::new();
+            let conflicts = merger.merge(
+                BufReader::new(File::open(base)?),
+                BufReader::new(File::open(ours)?),
+                BufReader::new(File::open(theirs)?),
+                &mut output,
+            )?;
+            replace_file(Path::new(ours), |file| file.write_all(&output))?;
+
+            Ok(match conflicts {
+                0 => Status::Clean,
+                count => Status::Conflicts(count),
+            })
+        }
+        ("diff", [old, new] | [_, old, _, _, new, _, _]) => {
+            let mut differ = Differ::new().with_header(options.header);
+            for key in options.keys {
+                differ = differ.key(key);
+            }
+
+            let diff = differ.diff(
+                BufReader::new(File::open(old)?),
+                BufReader::new(File::open(new)?),
+            )?;
+            write!(stdout(), "{diff}")?;
+            Ok(Status::Clean)
+        }
+        ("textconv", [file]) if options.keys.is_empty() && !options.header => {
+            ssv::bytes::align(BufReader::new(File::open(file)?), stdout().lock())?;
+            Ok(Status::Clean)
+        }
+        _ => Err(USAGE.into()),
+    }
+}
+
+struct Options {
+    header: bool,
+    keys: Vec<ColumnRef>,
+    files: Vec<String>,
+}
+
+impl Options {
+    fn parse(args: &[String]) -> Result<Self, Box<dyn Error>> {
+        let mut options = Options {
+            header: false,
+            keys: Vec::new(),
+            files: Vec::new(),
+        };
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--header" => options.header = true,
+                "--key" => {
+                    let column = args.next().ok_or(USAGE)?;
+                    options.keys.push(match column.parse::<usize>() {
+                        Ok(index) => ColumnRef::from(index),
+                        Err(_) => ColumnRef::from(column.as_str()),
+                    });
+                }
+                _ => options.files.push(arg.clone()),
+            }
+        }
+
+        Ok(options)
+    }
+}
@@ -1,35 +1,105 @@
 //! Domain-independent generic implementation.
 
+use std::collections::hash_map::RandomState;
 use std::error::Error;
 use std::fmt::Display;
-use std::fs::File;
-use std::io::{Read, Write};
-use std::path::Path;
+use std::fs::{File, OpenOptions};
+use std::hash::{BuildHasher, Hasher};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
 
+use self::alignment::Line;
+use self::columns::ColumnNotFoundError;
+use self::compression::{CompressingWriter, Compression, DecompressingReader};
 use self::domain::Domain;
+use self::encoding::{DecodingReader, Encoding};
 use self::position::Position;
-use self::reader::Reader;
+use self::reader::{Reader, Record, RecordReader};
 use self::writer::Writer;
+use crate::syntax::writer::SinkWriteError;
+use crate::syntax::SyntaxError;
 
-#[doc(hidden)]
-pub mod domain;
+/// Implements the conversions into an error type that has a
+/// `ReadWriteError(ReadWriteError)` variant from [`ReadWriteError`] and from
+/// the errors it wraps.
+macro_rules! impl_from_read_write_error {
+    ($error:ident) => {
+        impl From<$crate::engine::ReadWriteError> for $error {
+            fn from(error: $crate::engine::ReadWriteError) -> Self {
+                $error::ReadWriteError(error)
+            }
+        }
+
+        impl From<$crate::engine::columns::ColumnNotFoundError> for $error {
+            fn from(error: $crate::engine::columns::ColumnNotFoundError) -> Self {
+                $error::ReadWriteError(error.into())
+            }
+        }
+
+        impl From<$crate::engine::ReadError> for $error {
+            fn from(error: $crate::engine::ReadError) -> Self {
+                $error::ReadWriteError(error.into())
+            }
+        }
 
+        impl From<$crate::engine::WriteError> for $error {
+            fn from(error: $crate::engine::WriteError) -> Self {
+                $error::ReadWriteError(error.into())
+            }
+        }
+    };
+}
+
+pub mod aggregate;
+pub(crate) mod alignment;
+pub mod columns;
+pub mod compression;
+pub mod diff;
+pub mod domain;
+pub mod encoding;
+pub mod filter;
 pub mod fluent_writer;
+pub mod follow;
+pub mod import;
+pub mod join;
+pub mod lint;
+pub mod mapping;
+pub mod merge;
 pub mod options;
 pub mod position;
+pub mod profile;
+pub mod projection;
 pub mod reader;
+pub mod render;
+pub mod reverse_reader;
+pub mod slice_reader;
+pub mod sort;
+pub mod token_writer;
 pub mod tokenizer;
+pub mod update;
 pub mod writer;
 
-/// Line-break types.
-#[doc = generic_item_warning_doc!("LineBreak")]
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
-pub enum LineBreak {
-    /// Line-break consisting only of LF (byte value/codepoint 10).
-    Lf,
+pub use crate::syntax::LineBreak;
 
-    /// Line-break consisting of CR (byte value/codepoint 13) and LF (byte value/codepoint 10).
-    CrLf,
+/// How [`write_file_with_mode`] writes to a file.
+#[doc = generic_item_warning_doc!("WriteMode")]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum WriteMode {
+    /// The file is created, or truncated if it exists, and the rows are written
+    /// to it. If writing fails midway, the file is left incomplete.
+    #[default]
+    Truncate,
+
+    /// The rows are written to a temporary file in the same directory, which is
+    /// synced to the disk and then renamed over the file. If writing fails, the
+    /// file is left untouched. The permissions of an existing file are kept.
+    Atomic,
+
+    /// The rows are appended to the file, which is created if it doesn't exist.
+    /// The rows end with the same kind of line-break as the last line of the
+    /// file, and a line-break is written first if the file doesn't end with
+    /// one.
+    Append,
 }
 
 /// A specialized [`Result`] type for read operations.
@@ -40,8 +110,15 @@ pub type ReadResult<T> = Result<T, ReadError>;
 #[doc = generic_item_warning_doc!("WriteResult")]
 pub type WriteResult<T> = Result<T, WriteError>;
 
+/// A specialized [`Result`] type for operations that both read and write.
+#[doc = generic_item_warning_doc!("ReadWriteResult")]
+pub type ReadWriteResult<T> = Result<T, ReadWriteError>;
+
 /// Reads SSV from a file.
 #[doc = generic_item_warning_doc!("read_file")]
+/// A compressed file is decompressed, with the compression format detected by
+/// the magic bytes at its start (see [`DecompressingReader`]).
+///
 /// # Example
 ///
 /// ```no_run
@@ -52,12 +129,95 @@ pub type WriteResult<T> = Result<T, WriteError>;
 /// }
 /// # Ok::<_, ssv::chars::ReadError>(())
 /// ```
-pub fn read_file<D: Domain, P: AsRef<Path>>(path: P) -> ReadResult<Reader<D, File>> {
-    let file = File::open(path)?;
-    let reader = read(file);
+pub fn read_file<D: Domain, P: AsRef<Path>>(path: P) -> ReadResult<Reader<D, FileReader>> {
+    let reader = read(FileReader::open(path)?);
+    Ok(reader)
+}
+
+/// Reads SSV from a file in the given text encoding.
+#[doc = generic_item_warning_doc!("read_file_with_encoding")]
+/// Like [`read_file`], a compressed file is decompressed. The text is then
+/// decoded to UTF-8 by a [`DecodingReader`], which strips a byte order mark and
+/// uses its encoding instead of the given one.
+///
+/// # Example
+///
+/// ```no_run
+/// use ssv::chars::Encoding;
+///
+/// # let file_path = "";
+/// # #[allow(unused_variables)]
+/// for row in ssv::chars::read_file_with_encoding(file_path, Encoding::Windows1252)? {
+///     // Use `row`
+/// }
+/// # Ok::<_, ssv::chars::ReadError>(())
+/// ```
+pub fn read_file_with_encoding<D: Domain, P: AsRef<Path>>(
+    path: P,
+    encoding: Encoding,
+) -> ReadResult<Reader<D, DecodingReader<FileReader>>> {
+    let reader = read(DecodingReader::new(FileReader::open(path)?, encoding));
     Ok(reader)
 }
 
+/// The byte reader of a file opened by [`read_file`] or
+/// [`read_file_with_encoding`].
+///
+/// A compressed file is decompressed (see [`DecompressingReader`]).
+pub struct FileReader {
+    inner: DecompressingReader<File>,
+}
+
+impl FileReader {
+    fn open<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let file = File::open(path)?;
+        Ok(FileReader {
+            inner: DecompressingReader::new(file)?,
+        })
+    }
+
+    /// Returns the compression format of the file.
+    pub fn compression(&self) -> Compression {
+        self.inner.compression()
+    }
+}
+
+impl Read for FileReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+/// Maps a file into memory to read SSV from it.
+#[doc = generic_item_warning_doc!("map_file")]
+/// It is exactly the same as calling the
+/// [`MappedFile::open`](slice_reader::MappedFile::open) method.
+///
+/// # Safety
+///
+/// The file must not be modified or truncated while it is mapped (see
+/// [`MappedFile::open`](slice_reader::MappedFile::open)).
+///
+/// # Example
+///
+/// ```no_run
+/// # let file_path = "";
+/// let file = unsafe { ssv::chars::map_file(file_path)? };
+///
+/// # #[allow(unused_variables)]
+/// for row in file.rows() {
+///     // Use `row`
+/// }
+/// # Ok::<_, ssv::chars::ReadError>(())
+/// ```
+#[cfg(feature = "mmap")]
+pub unsafe fn map_file<D: Domain, P: AsRef<Path>>(
+    path: P,
+) -> ReadResult<slice_reader::MappedFile<D>> {
+    // SAFETY: the caller upholds the contract of `MappedFile::open`.
+    unsafe { slice_reader::MappedFile::open(path) }
+}
+
 /// Reads SSV from a reader.
 #[doc = generic_item_warning_doc!("read")]
 /// It is exactly the same as calling the [`Reader::new`] method.
@@ -67,6 +227,9 @@ pub fn read<D: Domain, R: Read>(reader: R) -> Reader<D, R> {
 
 /// Writes SSV to a file.
 #[doc = generic_item_warning_doc!("write_file")]
+/// The file is compressed if its extension matches a compression format (see
+/// [`Compression::from_extension`]).
+///
 /// # Example
 ///
 /// ```no_run
@@ -87,8 +250,210 @@ pub fn write_file<'a, D: Domain, P: AsRef<Path>>(
 where
     D::StringSlice: 'a,
 {
-    let file = File::create(path)?;
-    write::<'a, D, _>(file, rows)
+    write_file_with_mode::<'a, D, P>(path, WriteMode::Truncate, rows)
+}
+
+/// Writes SSV to a file in the given [mode](WriteMode).
+#[doc = generic_item_warning_doc!("write_file_with_mode")]
+/// Like [`write_file`], the file is compressed if its extension matches a
+/// compression format. When appending to a non-empty file, its compression is
+/// detected by its magic bytes instead, and the rows are appended as a new
+/// compressed stream.
+///
+/// # Example
+///
+/// ```no_run
+/// use ssv::chars::WriteMode;
+///
+/// # let file_path = "";
+/// ssv::chars::write_file_with_mode(file_path, WriteMode::Append, [["new", "row"]])?;
+/// # Ok::<(), ssv::chars::WriteError>(())
+/// ```
+pub fn write_file_with_mode<'a, D: Domain, P: AsRef<Path>>(
+    path: P,
+    mode: WriteMode,
+    rows: impl IntoIterator<Item = impl IntoIterator<Item = &'a D::StringSlice>>,
+) -> WriteResult<()>
+where
+    D::StringSlice: 'a,
+{
+    let path = path.as_ref();
+    match mode {
+        WriteMode::Truncate => write_compressed::<'a, D, _>(
+            File::create(path)?,
+            Compression::from_extension(path),
+            rows,
+        ),
+        WriteMode::Atomic => replace_file(path, |file| {
+            write_compressed::<'a, D, _>(file, Compression::from_extension(path), rows)
+        }),
+        WriteMode::Append => {
+            let mut file = OpenOptions::new()
+                .read(true)
+                .append(true)
+                .create(true)
+                .open(path)?;
+            let compression = if file.metadata()?.len() == 0 {
+                Compression::from_extension(path)
+            } else {
+                DecompressingReader::new(&mut file)?.compression()
+            };
+            let (ends_with_line_break, line_break) = match compression {
+                Compression::None => last_line_break(&mut file)?,
+                _ => {
+                    file.seek(SeekFrom::Start(0))?;
+                    last_decompressed_line_break(DecompressingReader::new(&mut file)?)?
+                }
+            };
+
+            let mut output = CompressingWriter::new(file, compression)?;
+            let mut writer: Writer<D, _> = Writer::new(&mut output);
+            writer.options_mut().set_default_line_break(line_break);
+            if !ends_with_line_break {
+                // An empty row ends the last line
+                writer.new_row().finish()?;
+            }
+            writer.write_rows(rows)?;
+            writer.finish()?;
+            output.finish()?;
+            Ok(())
+        }
+    }
+}
+
+fn write_compressed<'a, D: Domain, W: Write>(
+    writer: W,
+    compression: Compression,
+    rows: impl IntoIterator<Item = impl IntoIterator<Item = &'a D::StringSlice>>,
+) -> WriteResult<()>
+where
+    D::StringSlice: 'a,
+{
+    let mut output = CompressingWriter::new(writer, compression)?;
+    write::<'a, D, _>(&mut output, rows)?;
+    output.finish()?;
+    Ok(())
+}
+
+/// Like [`last_line_break`], but reading the whole content, which is
+/// compressed.
+fn last_decompressed_line_break<R: Read>(mut reader: R) -> std::io::Result<(bool, LineBreak)> {
+    let mut buffer = [0; 8 * 1024];
+    let mut last_byte = None;
+    let mut line_break = LineBreak::Lf;
+    loop {
+        let length = reader.read(&mut buffer)?;
+        if length == 0 {
+            break;
+        }
+        for byte in &buffer[..length] {
+            if *byte == b'\n' {
+                line_break = if last_byte == Some(b'\r') {
+                    LineBreak::CrLf
+                } else {
+                    LineBreak::Lf
+                };
+            }
+            last_byte = Some(*byte);
+        }
+    }
+    Ok((matches!(last_byte, None | Some(b'\n')), line_break))
+}
+
+/// Returns whether the file is empty or ends with a line-break, and the kind
+/// of its last line-break, LF if it has none.
+fn last_line_break(file: &mut File) -> std::io::Result<(bool, LineBreak)> {
+    const CHUNK_SIZE: u64 = 8 * 1024;
+
+    let length = file.seek(SeekFrom::End(0))?;
+    let mut end = length;
+    let mut chunk = Vec::new();
+    while end > 0 {
+        let start = end.saturating_sub(CHUNK_SIZE);
+        // One more byte is read to see whether a LF is preceded by a CR
+        let read_start = start.saturating_sub(1);
+        chunk.resize((end - read_start) as usize, 0);
+        file.seek(SeekFrom::Start(read_start))?;
+        file.read_exact(&mut chunk)?;
+
+        let searched = &chunk[(start - read_start) as usize..];
+        if let Some(index) = searched.iter().rposition(|byte| *byte == b'\n') {
+            let lf_offset = start + index as u64;
+            let chunk_index = (lf_offset - read_start) as usize;
+            let line_break = if chunk_index > 0 && chunk[chunk_index - 1] == b'\r' {
+                LineBreak::CrLf
+            } else {
+                LineBreak::Lf
+            };
+            return Ok((lf_offset + 1 == length, line_break));
+        }
+        end = start;
+    }
+    Ok((length == 0, LineBreak::Lf))
+}
+
+/// Replaces the file at the given path with the content written by the given
+/// function to a temporary file in the same directory.
+///
+/// The temporary file is synced to the disk before being renamed over the file,
+/// so the file is either left untouched or completely replaced, and the
+/// directory is synced after the rename. The permissions of an existing file
+/// are kept.
+///
+/// The name of the temporary file has the process ID and a random suffix, so
+/// that a temporary file left behind by a killed process does not prevent
+/// later replacements.
+pub fn replace_file<E: From<std::io::Error>>(
+    path: &Path,
+    write: impl FnOnce(&mut File) -> Result<(), E>,
+) -> Result<(), E> {
+    let temp_path = temp_sibling_path(path)?;
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&temp_path)?;
+
+    let result = (|| {
+        if let Ok(metadata) = std::fs::metadata(path) {
+            file.set_permissions(metadata.permissions())?;
+        }
+        write(&mut file)?;
+        file.sync_all()?;
+        drop(file);
+        std::fs::rename(&temp_path, path)?;
+        sync_parent_directory(path)?;
+        Ok(())
+    })();
+
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    result
+}
+
+fn temp_sibling_path(path: &Path) -> std::io::Result<PathBuf> {
+    let file_name = path.file_name().ok_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, "path has no file name")
+    })?;
+    let mut temp_name = std::ffi::OsString::from(".");
+    temp_name.push(file_name);
+    let suffix = RandomState::new().build_hasher().finish();
+    temp_name.push(format!(".{}.{suffix:016x}.tmp", std::process::id()));
+    Ok(path.with_file_name(temp_name))
+}
+
+#[cfg(unix)]
+fn sync_parent_directory(path: &Path) -> std::io::Result<()> {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    File::open(parent)?.sync_all()
+}
+
+#[cfg(not(unix))]
+fn sync_parent_directory(_path: &Path) -> std::io::Result<()> {
+    Ok(())
 }
 
 /// Writes SSV to a writer.
@@ -106,6 +471,37 @@ where
     writer.write_rows(rows)
 }
 
+/// Reads SSV from a reader and writes it to a writer with the values of each
+/// column aligned.
+#[doc = generic_item_warning_doc!("align")]
+/// Each value except the last of each row is padded with spaces up to the
+/// width of the widest value of its column. Comments are kept, and line-breaks
+/// are written as LF, except after comments ending with a CR, which are
+/// followed by a CRLF to keep the CR.
+///
+/// # Example
+///
+/// ```
+/// let mut output = Vec::new();
+///
+/// ssv::chars::align("a bbb c\ndd e\n".as_bytes(), &mut output)?;
+///
+/// assert_eq!(output, b"a  bbb c\ndd e\n");
+/// # Ok::<(), ssv::chars::ReadWriteError>(())
+/// ```
+pub fn align<D: Domain, R: Read, W: Write>(reader: R, writer: W) -> ReadWriteResult<()> {
+    let lines = RecordReader::<D, R>::new(reader)
+        .map(|record| {
+            Ok(match record?.value {
+                Record::Row(row) => Line::<D>::Row(row),
+                Record::Comment(comment) => Line::Comment(comment),
+            })
+        })
+        .collect::<ReadResult<Vec<_>>>()?;
+    alignment::write_aligned(writer, &lines)?;
+    Ok(())
+}
+
 /// The error type for read operations.
 #[doc = generic_item_warning_doc!("ReadError")]
 #[derive(Debug)]
@@ -128,6 +524,15 @@ impl From<std::io::Error> for ReadError {
     }
 }
 
+impl From<SyntaxError> for ReadError {
+    fn from(syntax_error: SyntaxError) -> Self {
+        match syntax_error {
+            SyntaxError::UnpairedQuote(position) => ReadError::UnpairedQuote(position),
+            SyntaxError::UnclosedQuotedValue(position) => ReadError::UnclosedQuotedValue(position),
+        }
+    }
+}
+
 impl Display for ReadError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -153,6 +558,19 @@ pub enum WriteError {
     /// A value containing non-spacing elements was tried to be used as spacing.
     InvalidSpacing,
 
+    /// A value requiring quotes was tried to be written with the
+    /// [`QuotingPolicy::Never`](crate::engine::options::QuotingPolicy::Never) policy.
+    QuotesRequired,
+
+    /// A comment containing a line-break was tried to be written with the
+    /// [`CommentLineBreakPolicy::Reject`](crate::engine::options::CommentLineBreakPolicy::Reject)
+    /// policy.
+    CommentWithLineBreak,
+
+    /// A token was tried to be written where it is not allowed (see
+    /// [`TokenWriter`](crate::engine::token_writer::TokenWriter)).
+    UnexpectedToken,
+
     /// An [IO error](std::io::Error) happened when using the underlying writer.
     IoError(std::io::Error),
 }
@@ -165,11 +583,70 @@ impl From<std::io::Error> for WriteError {
     }
 }
 
+impl From<SinkWriteError<std::io::Error>> for WriteError {
+    fn from(sink_write_error: SinkWriteError<std::io::Error>) -> Self {
+        match sink_write_error {
+            SinkWriteError::InvalidSpacing => WriteError::InvalidSpacing,
+            SinkWriteError::QuotesRequired => WriteError::QuotesRequired,
+            SinkWriteError::CommentWithLineBreak => WriteError::CommentWithLineBreak,
+            SinkWriteError::SinkError(io_error) => WriteError::IoError(io_error),
+        }
+    }
+}
+
 impl Display for WriteError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             WriteError::InvalidSpacing => write!(f, "invalid spacing"),
+            WriteError::QuotesRequired => write!(f, "value requires quotes"),
+            WriteError::CommentWithLineBreak => write!(f, "comment contains a line-break"),
+            WriteError::UnexpectedToken => write!(f, "unexpected token"),
             WriteError::IoError(io_error) => write!(f, "IO error: {io_error}"),
         }
     }
 }
+
+/// The error type for operations that both read and write.
+#[doc = generic_item_warning_doc!("ReadWriteError")]
+/// It is also used by the operations that reference columns of the rows.
+#[derive(Debug)]
+pub enum ReadWriteError {
+    /// A referenced column was not found.
+    ColumnNotFound(ColumnNotFoundError),
+
+    /// An error happened when reading.
+    ReadError(ReadError),
+
+    /// An error happened when writing.
+    WriteError(WriteError),
+}
+
+impl Error for ReadWriteError {}
+
+impl From<ColumnNotFoundError> for ReadWriteError {
+    fn from(error: ColumnNotFoundError) -> Self {
+        ReadWriteError::ColumnNotFound(error)
+    }
+}
+
+impl From<ReadError> for ReadWriteError {
+    fn from(read_error: ReadError) -> Self {
+        ReadWriteError::ReadError(read_error)
+    }
+}
+
+impl From<WriteError> for ReadWriteError {
+    fn from(write_error: WriteError) -> Self {
+        ReadWriteError::WriteError(write_error)
+    }
+}
+
+impl Display for ReadWriteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReadWriteError::ColumnNotFound(error) => write!(f, "{error}"),
+            ReadWriteError::ReadError(read_error) => write!(f, "read error: {read_error}"),
+            ReadWriteError::WriteError(write_error) => write!(f, "write error: {write_error}"),
+        }
+    }
+}
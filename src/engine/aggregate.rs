@@ -0,0 +1,777 @@
+//! Aggregates values of SSV rows per group.
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt::Display;
+use std::io::{Read, Write};
+use std::ops::Deref;
+
+use crate::engine::domain::{Domain, DomainString, DomainStringSlice};
+
+use super::columns::ColumnRef;
+use super::reader::Reader;
+use super::writer::Writer;
+use super::{ReadError, ReadResult, ReadWriteError};
+
+/// Computes aggregations (count, sum, min, max, mean, distinct count) of SSV
+/// rows per group.
+#[doc = generic_item_warning_doc!("Aggregator")]
+/// The rows are grouped by the values in the [group columns](Aggregator::group_by).
+/// Each output row has the values of the group columns followed by the results
+/// of the [aggregations](Aggregation), in the order they were added. Without
+/// group columns, all the rows form a single group.
+///
+/// If the aggregator [uses a header](Aggregator::with_header), the first row is
+/// the header row, and a header row is written with the names of the group
+/// columns and the names of the aggregations.
+///
+/// Numeric values are parsed and formatted according to the
+/// [`NumberFormat`]. Values that are not numbers are ignored by the
+/// [`sum`](Aggregation::sum) and [`mean`](Aggregation::mean) aggregations.
+///
+/// # Strategies
+///
+/// * [`GroupingStrategy::Hash`] (the default) keeps all the groups in memory,
+///   and the output follows the order in which the groups first appear. The
+///   number of groups can be bounded with [`Aggregator::max_groups`].
+/// * [`GroupingStrategy::Sorted`] expects the rows to be sorted lexically by
+///   the group columns, in ascending order, and keeps only the current group
+///   in memory. Each group is output as soon as it ends. If the rows are not
+///   sorted, [`AggregateError::UnsortedInput`] is returned.
+///
+/// # Example
+///
+/// ```
+/// use ssv::chars::{Aggregation, Aggregator, NumberFormat};
+///
+/// let input = "city amount\nParis 1,5\nRome 2\nParis 3\n";
+/// let mut output = Vec::new();
+///
+/// Aggregator::new()
+///     .with_header(true)
+///     .with_number_format(NumberFormat::DECIMAL_COMMA)
+///     .group_by("city")
+///     .with(Aggregation::count())
+///     .with(Aggregation::sum("amount").named("total"))
+///     .transform(input.as_bytes(), &mut output)?;
+///
+/// assert_eq!(output, b"city count total\nParis 2 4,5\nRome 1 2\n");
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct Aggregator<D: Domain> {
+    group_columns: Vec<ColumnRef<D>>,
+    aggregations: Vec<Aggregation<D>>,
+    header: bool,
+    strategy: GroupingStrategy,
+    max_groups: Option<usize>,
+    number_format: NumberFormat,
+}
+
+/// How an [`Aggregator`] groups the rows.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum GroupingStrategy {
+    /// All the groups are kept in a hash table.
+    #[default]
+    Hash,
+
+    /// The rows are sorted by the group columns, and only the current group is
+    /// kept in memory.
+    Sorted,
+}
+
+/// How numbers are written in values.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct NumberFormat {
+    /// The decimal separator.
+    pub decimal_separator: char,
+
+    /// The digit group separator, which is accepted when parsing and not used
+    /// when formatting.
+    pub group_separator: Option<char>,
+}
+
+impl NumberFormat {
+    /// Point (`.`) as decimal separator and comma (`,`) as group separator,
+    /// e.g. `1,234.5`.
+    pub const DECIMAL_POINT: Self = NumberFormat {
+        decimal_separator: '.',
+        group_separator: Some(','),
+    };
+
+    /// Comma (`,`) as decimal separator and point (`.`) as group separator,
+    /// e.g. `1.234,5`.
+    pub const DECIMAL_COMMA: Self = NumberFormat {
+        decimal_separator: ',',
+        group_separator: Some('.'),
+    };
+
+    /// Parses a number.
+    ///
+    /// Group separators are accepted only between groups of three digits in
+    /// the integer part, so `1,5` is not a number with
+    /// [`DECIMAL_POINT`](NumberFormat::DECIMAL_POINT). Leading and trailing
+    /// whitespace is not accepted.
+    pub fn parse(&self, text: &str) -> Option<f64> {
+        self.normalize(text)?.parse().ok()
+    }
+
+    /// Formats a number.
+    pub fn format(&self, number: f64) -> String {
+        number
+            .to_string()
+            .replace('.', self.decimal_separator.encode_utf8(&mut [0; 4]))
+    }
+
+    /// Formats a number rounded to the given number of fractional digits.
+    fn format_rounded(&self, number: f64, fraction_digits: usize) -> String {
+        // Avoids writing a rounded negative zero as `-0`
+        let number = if number == 0.0 { 0.0 } else { number };
+        format!("{number:.fraction_digits$}")
+            .replace('.', self.decimal_separator.encode_utf8(&mut [0; 4]))
+    }
+
+    /// Rewrites a number with point as decimal separator and without group
+    /// separators, or returns `None` if the text is not a number.
+    fn normalize(&self, text: &str) -> Option<String> {
+        let unsigned = text.strip_prefix(['+', '-']).unwrap_or(text);
+        let integer_end = unsigned
+            .find([self.decimal_separator, 'e', 'E'])
+            .unwrap_or(unsigned.len());
+        let (integer, rest) = unsigned.split_at(integer_end);
+
+        let mut normalized = String::with_capacity(text.len());
+        normalized.push_str(&text[..text.len() - unsigned.len()]);
+        match self.group_separator {
+            Some(separator) if integer.contains(separator) => {
+                let mut groups = integer.split(separator);
+                let first = groups.next().unwrap_or_default();
+                if first.is_empty() || first.len() > 3 {
+                    return None;
+                }
+                normalized.push_str(first);
+                for group in groups {
+                    if group.len() != 3 {
+                        return None;
+                    }
+                    normalized.push_str(group);
+                }
+            }
+            _ => normalized.push_str(integer),
+        }
+        normalized.extend(rest.chars().map(|char| {
+            if char == self.decimal_separator {
+                '.'
+            } else {
+                char
+            }
+        }));
+
+        let is_number = normalized
+            .chars()
+            .all(|char| char.is_ascii_digit() || matches!(char, '.' | '+' | '-' | 'e' | 'E'));
+        is_number.then_some(normalized)
+    }
+}
+
+/// Returns the number of fractional digits of a normalized number, taking its
+/// exponent into account.
+fn fraction_digits(normalized: &str) -> usize {
+    let (mantissa, exponent) = normalized
+        .split_once(['e', 'E'])
+        .unwrap_or((normalized, "0"));
+    let digits = mantissa
+        .split_once('.')
+        .map_or(0, |(_, fraction)| fraction.len());
+    let exponent: i64 = exponent.parse().unwrap_or(0);
+    (digits as i64 - exponent).max(0) as usize
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self::DECIMAL_POINT
+    }
+}
+
+/// An aggregation computed by an [`Aggregator`].
+#[doc = generic_item_warning_doc!("Aggregation")]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Aggregation<D: Domain> {
+    function: Function,
+    column: Option<ColumnRef<D>>,
+    name: Option<D::String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Function {
+    Count,
+    Sum,
+    Min,
+    Max,
+    Mean,
+    DistinctCount,
+}
+
+impl<D: Domain> Aggregation<D> {
+    /// The number of rows.
+    ///
+    /// Its default name is `count`.
+    pub fn count() -> Self {
+        Self::new(Function::Count, None)
+    }
+
+    /// The sum of the numeric values in the column, or `0` if there are none.
+    ///
+    /// Integers are summed exactly. If there are other numbers, the sum is
+    /// rounded to the greatest number of fractional digits among them, so that
+    /// the sum of `0.1` and `0.2` is `0.3`.
+    ///
+    /// Its default name is `sum(column)`.
+    pub fn sum(column: impl Into<ColumnRef<D>>) -> Self {
+        Self::new(Function::Sum, Some(column.into()))
+    }
+
+    /// The minimum value in the column, or an empty value if there are none.
+    ///
+    /// Numbers are compared numerically, and come before other values, which
+    /// are compared lexically. Empty values are ignored.
+    ///
+    /// Its default name is `min(column)`.
+    pub fn min(column: impl Into<ColumnRef<D>>) -> Self {
+        Self::new(Function::Min, Some(column.into()))
+    }
+
+    /// The maximum value in the column, or an empty value if there are none.
+    ///
+    /// Values are compared like in [`min`](Aggregation::min).
+    ///
+    /// Its default name is `max(column)`.
+    pub fn max(column: impl Into<ColumnRef<D>>) -> Self {
+        Self::new(Function::Max, Some(column.into()))
+    }
+
+    /// The mean of the numeric values in the column, or an empty value if there
+    /// are none.
+    ///
+    /// Its default name is `mean(column)`.
+    pub fn mean(column: impl Into<ColumnRef<D>>) -> Self {
+        Self::new(Function::Mean, Some(column.into()))
+    }
+
+    /// The number of distinct values in the column, including empty values.
+    ///
+    /// Its default name is `distinct(column)`.
+    pub fn distinct_count(column: impl Into<ColumnRef<D>>) -> Self {
+        Self::new(Function::DistinctCount, Some(column.into()))
+    }
+
+    /// Sets the name in the header row.
+    pub fn named(mut self, name: &D::StringSlice) -> Self {
+        self.name = Some(D::String::from(name));
+        self
+    }
+
+    fn new(function: Function, column: Option<ColumnRef<D>>) -> Self {
+        Aggregation {
+            function,
+            column,
+            name: None,
+        }
+    }
+
+    fn name(&self, header: &[D::String], index: Option<usize>) -> D::String {
+        if let Some(name) = &self.name {
+            return name.clone();
+        }
+
+        let function = match self.function {
+            Function::Count => return D::String::from_text("count"),
+            Function::Sum => "sum",
+            Function::Min => "min",
+            Function::Max => "max",
+            Function::Mean => "mean",
+            Function::DistinctCount => "distinct",
+        };
+        let column = match index.and_then(|index| header.get(index)) {
+            Some(name) => name.clone(),
+            None => D::String::from_text(&index.unwrap_or_default().to_string()),
+        };
+        D::concat(&[
+            &D::String::from_text(function),
+            &D::String::from_text("("),
+            &column,
+            &D::String::from_text(")"),
+        ])
+    }
+}
+
+impl<D: Domain> Aggregator<D> {
+    /// Creates an instance without group columns, without aggregations and
+    /// without header.
+    pub fn new() -> Self {
+        Aggregator {
+            group_columns: Vec::new(),
+            aggregations: Vec::new(),
+            header: false,
+            strategy: GroupingStrategy::default(),
+            max_groups: None,
+            number_format: NumberFormat::default(),
+        }
+    }
+
+    /// Sets whether the first row is a header row.
+    ///
+    /// Columns can be referenced by name only if there is a header row.
+    pub fn with_header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Sets the strategy.
+    pub fn with_strategy(mut self, strategy: GroupingStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Sets the maximum number of groups kept in memory with the
+    /// [`GroupingStrategy::Hash`] strategy.
+    ///
+    /// If there are more groups, [`AggregateError::TooManyGroups`] is returned.
+    pub fn max_groups(mut self, max_groups: usize) -> Self {
+        self.max_groups = Some(max_groups);
+        self
+    }
+
+    /// Sets the format used to parse and format numbers.
+    pub fn with_number_format(mut self, number_format: NumberFormat) -> Self {
+        self.number_format = number_format;
+        self
+    }
+
+    /// Adds a group column.
+    pub fn group_by(mut self, column: impl Into<ColumnRef<D>>) -> Self {
+        self.group_columns.push(column.into());
+        self
+    }
+
+    /// Adds an aggregation.
+    pub fn with(mut self, aggregation: Aggregation<D>) -> Self {
+        self.aggregations.push(aggregation);
+        self
+    }
+
+    /// Returns an iterator that returns the aggregated rows, from the rows
+    /// returned by the given iterator, such as a [`Reader`].
+    ///
+    /// If the aggregator [uses a header](Aggregator::with_header), the first
+    /// returned row is the header row.
+    pub fn apply<I>(&self, rows: I) -> Aggregated<'_, D, I>
+    where
+        I: Iterator<Item = ReadResult<Vec<D::String>>>,
+    {
+        Aggregated {
+            aggregator: self,
+            rows,
+            state: AggregatedState::Begin,
+        }
+    }
+
+    /// Reads SSV from the given byte reader, aggregates the rows, and writes the
+    /// result to the given byte writer.
+    pub fn transform<R: Read, W: Write>(&self, reader: R, writer: W) -> AggregateResult<()> {
+        let mut writer = Writer::<D, W>::new(writer);
+        for row in self.apply(Reader::<D, R>::new(reader)) {
+            writer.write_row(row?.iter().map(Deref::deref))?;
+        }
+        writer.finish()?;
+        Ok(())
+    }
+
+    fn bind(&self, header: Option<&[D::String]>) -> AggregateResult<Bound> {
+        let resolve =
+            |column: &ColumnRef<D>| column.try_resolve(header).map_err(AggregateError::from);
+
+        Ok(Bound {
+            group_columns: self
+                .group_columns
+                .iter()
+                .map(resolve)
+                .collect::<AggregateResult<_>>()?,
+            aggregation_columns: self
+                .aggregations
+                .iter()
+                .map(|aggregation| aggregation.column.as_ref().map(resolve).transpose())
+                .collect::<AggregateResult<_>>()?,
+        })
+    }
+
+    fn header(&self, header: &[D::String], bound: &Bound) -> Vec<D::String> {
+        let mut names: Vec<_> = bound
+            .group_columns
+            .iter()
+            .map(|index| header.get(*index).cloned().unwrap_or_else(D::String::new))
+            .collect();
+        names.extend(
+            self.aggregations
+                .iter()
+                .zip(&bound.aggregation_columns)
+                .map(|(aggregation, index)| aggregation.name(header, *index)),
+        );
+        names
+    }
+
+    fn new_group(&self) -> Vec<Accumulator> {
+        self.aggregations
+            .iter()
+            .map(|aggregation| match aggregation.function {
+                Function::Count => Accumulator::Count(0),
+                Function::Sum => Accumulator::Sum {
+                    exact: Some(0),
+                    approximate: 0.0,
+                    fraction_digits: 0,
+                },
+                Function::Min => Accumulator::Extreme(Ordering::Less, None),
+                Function::Max => Accumulator::Extreme(Ordering::Greater, None),
+                Function::Mean => Accumulator::Mean(0.0, 0),
+                Function::DistinctCount => Accumulator::DistinctCount(HashSet::new()),
+            })
+            .collect()
+    }
+
+    fn accumulate(&self, bound: &Bound, group: &mut [Accumulator], row: &[D::String]) {
+        for (accumulator, column) in group.iter_mut().zip(&bound.aggregation_columns) {
+            let value = column
+                .and_then(|index| row.get(index))
+                .map_or(&[][..], |value| value.deref().as_bytes());
+            accumulator.accumulate(value, &self.number_format);
+        }
+    }
+
+    fn output(
+        &self,
+        key: Vec<Vec<u8>>,
+        group: Vec<Accumulator>,
+    ) -> AggregateResult<Vec<D::String>> {
+        key.into_iter()
+            .chain(
+                group
+                    .into_iter()
+                    .map(|accumulator| accumulator.result(&self.number_format)),
+            )
+            .map(|bytes| Ok(D::string_from_bytes(bytes).map_err(ReadError::from)?))
+            .collect()
+    }
+}
+
+impl<D: Domain> Default for Aggregator<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct Bound {
+    group_columns: Vec<usize>,
+    aggregation_columns: Vec<Option<usize>>,
+}
+
+impl Bound {
+    fn key<D: Domain>(&self, row: &[D::String]) -> Vec<Vec<u8>> {
+        self.group_columns
+            .iter()
+            .map(|index| {
+                row.get(*index)
+                    .map_or(Vec::new(), |value| value.deref().as_bytes().to_vec())
+            })
+            .collect()
+    }
+}
+
+enum Accumulator {
+    Count(u64),
+    Sum {
+        /// The sum, while all the numbers are integers and it does not
+        /// overflow.
+        exact: Option<i128>,
+        approximate: f64,
+        fraction_digits: usize,
+    },
+    Extreme(Ordering, Option<(Option<f64>, Vec<u8>)>),
+    Mean(f64, u64),
+    DistinctCount(HashSet<Vec<u8>>),
+}
+
+impl Accumulator {
+    fn accumulate(&mut self, value: &[u8], number_format: &NumberFormat) {
+        let normalized = || {
+            std::str::from_utf8(value)
+                .ok()
+                .and_then(|text| number_format.normalize(text))
+        };
+        let number = || normalized().and_then(|normalized| normalized.parse::<f64>().ok());
+        match self {
+            Accumulator::Count(count) => *count += 1,
+            Accumulator::Sum {
+                exact,
+                approximate,
+                fraction_digits: sum_fraction_digits,
+            } => {
+                let Some(normalized) = normalized() else {
+                    return;
+                };
+                let Ok(number) = normalized.parse::<f64>() else {
+                    return;
+                };
+                *approximate += number;
+                *exact = match normalized.parse::<i128>() {
+                    Ok(integer) => exact.and_then(|exact| exact.checked_add(integer)),
+                    Err(_) => None,
+                };
+                *sum_fraction_digits = (*sum_fraction_digits).max(fraction_digits(&normalized));
+            }
+            Accumulator::Extreme(wanted, extreme) => {
+                if value.is_empty() {
+                    return;
+                }
+                let number = number();
+                let replace = match extreme {
+                    None => true,
+                    Some((current_number, current_value)) => {
+                        let ordering = match (number, *current_number) {
+                            (Some(number), Some(current)) => {
+                                number.partial_cmp(&current).unwrap_or(Ordering::Equal)
+                            }
+                            (Some(_), None) => Ordering::Less,
+                            (None, Some(_)) => Ordering::Greater,
+                            (None, None) => value.cmp(current_value),
+                        };
+                        ordering == *wanted
+                    }
+                };
+                if replace {
+                    *extreme = Some((number, value.to_vec()));
+                }
+            }
+            Accumulator::Mean(sum, count) => {
+                if let Some(number) = number() {
+                    *sum += number;
+                    *count += 1;
+                }
+            }
+            Accumulator::DistinctCount(values) => {
+                if !values.contains(value) {
+                    values.insert(value.to_vec());
+                }
+            }
+        }
+    }
+
+    fn result(self, number_format: &NumberFormat) -> Vec<u8> {
+        match self {
+            Accumulator::Count(count) => count.to_string().into_bytes(),
+            Accumulator::Sum {
+                exact: Some(sum), ..
+            } => sum.to_string().into_bytes(),
+            Accumulator::Sum {
+                exact: None,
+                approximate,
+                fraction_digits,
+            } => number_format
+                .format_rounded(approximate, fraction_digits)
+                .into_bytes(),
+            Accumulator::Extreme(_, extreme) => extreme.map(|(_, value)| value).unwrap_or_default(),
+            Accumulator::Mean(_, 0) => Vec::new(),
+            Accumulator::Mean(sum, count) => number_format.format(sum / count as f64).into_bytes(),
+            Accumulator::DistinctCount(values) => values.len().to_string().into_bytes(),
+        }
+    }
+}
+
+/// An iterator that returns the rows aggregated by an [`Aggregator`].
+#[doc = generic_item_warning_doc!("Aggregated")]
+/// It is returned by the [`Aggregator::apply`] method.
+pub struct Aggregated<'a, D: Domain, I> {
+    aggregator: &'a Aggregator<D>,
+    rows: I,
+    state: AggregatedState<D>,
+}
+
+enum AggregatedState<D: Domain> {
+    Begin,
+    Hashed(std::vec::IntoIter<Vec<D::String>>),
+    Sorted {
+        bound: Bound,
+        current: Option<(Vec<Vec<u8>>, Vec<Accumulator>)>,
+        row_number: usize,
+    },
+    Done,
+}
+
+impl<D: Domain, I> Aggregated<'_, D, I>
+where
+    I: Iterator<Item = ReadResult<Vec<D::String>>>,
+{
+    fn begin(&mut self) -> AggregateResult<Option<Vec<D::String>>> {
+        let aggregator = self.aggregator;
+        let (bound, header_row) = if aggregator.header {
+            match self.rows.next().transpose()? {
+                Some(header) => {
+                    let bound = aggregator.bind(Some(&header))?;
+                    let header_row = aggregator.header(&header, &bound);
+                    (bound, Some(header_row))
+                }
+                None => {
+                    self.state = AggregatedState::Done;
+                    return Ok(None);
+                }
+            }
+        } else {
+            (aggregator.bind(None)?, None)
+        };
+
+        self.state = match aggregator.strategy {
+            GroupingStrategy::Hash => AggregatedState::Hashed(self.hash(&bound)?.into_iter()),
+            GroupingStrategy::Sorted => AggregatedState::Sorted {
+                bound,
+                current: None,
+                row_number: usize::from(header_row.is_some()),
+            },
+        };
+        Ok(header_row)
+    }
+
+    fn hash(&mut self, bound: &Bound) -> AggregateResult<Vec<Vec<D::String>>> {
+        let aggregator = self.aggregator;
+        let mut indices = HashMap::new();
+        let mut groups = Vec::new();
+        for row in self.rows.by_ref() {
+            let row = row?;
+            let key = bound.key::<D>(&row);
+            let index = match indices.get(&key) {
+                Some(index) => *index,
+                None => {
+                    if aggregator.max_groups == Some(groups.len()) {
+                        return Err(AggregateError::TooManyGroups(groups.len()));
+                    }
+                    indices.insert(key.clone(), groups.len());
+                    groups.push((key, aggregator.new_group()));
+                    groups.len() - 1
+                }
+            };
+            aggregator.accumulate(bound, &mut groups[index].1, &row);
+        }
+
+        if groups.is_empty() && bound.group_columns.is_empty() {
+            groups.push((Vec::new(), aggregator.new_group()));
+        }
+
+        groups
+            .into_iter()
+            .map(|(key, group)| aggregator.output(key, group))
+            .collect()
+    }
+
+    fn next_sorted(&mut self) -> AggregateResult<Option<Vec<D::String>>> {
+        let aggregator = self.aggregator;
+        let AggregatedState::Sorted {
+            bound,
+            current,
+            row_number,
+        } = &mut self.state
+        else {
+            unreachable!()
+        };
+
+        for row in self.rows.by_ref() {
+            let row = row?;
+            *row_number += 1;
+            let key = bound.key::<D>(&row);
+            match current {
+                Some((current_key, group)) if *current_key == key => {
+                    aggregator.accumulate(bound, group, &row);
+                }
+                Some((current_key, _)) if key < *current_key => {
+                    return Err(AggregateError::UnsortedInput(*row_number));
+                }
+                _ => {
+                    let mut group = aggregator.new_group();
+                    aggregator.accumulate(bound, &mut group, &row);
+                    if let Some((key, group)) = current.replace((key, group)) {
+                        return aggregator.output(key, group).map(Some);
+                    }
+                }
+            }
+        }
+
+        let last = match current.take() {
+            Some((key, group)) => Some(aggregator.output(key, group)?),
+            None if bound.group_columns.is_empty() => {
+                Some(aggregator.output(Vec::new(), aggregator.new_group())?)
+            }
+            None => None,
+        };
+        self.state = AggregatedState::Done;
+        Ok(last)
+    }
+}
+
+impl<D: Domain, I> Iterator for Aggregated<'_, D, I>
+where
+    I: Iterator<Item = ReadResult<Vec<D::String>>>,
+{
+    type Item = AggregateResult<Vec<D::String>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = match &mut self.state {
+            AggregatedState::Begin => match self.begin() {
+                Ok(Some(header)) => Ok(Some(header)),
+                Ok(None) => return self.next(),
+                Err(error) => Err(error),
+            },
+            AggregatedState::Hashed(rows) => Ok(rows.next()),
+            AggregatedState::Sorted { .. } => self.next_sorted(),
+            AggregatedState::Done => Ok(None),
+        };
+
+        if result.is_err() {
+            self.state = AggregatedState::Done;
+        }
+        result.transpose()
+    }
+}
+
+/// A specialized [`Result`] type for [`Aggregator`] operations.
+pub type AggregateResult<T> = Result<T, AggregateError>;
+
+/// The error type for [`Aggregator`] operations.
+#[derive(Debug)]
+pub enum AggregateError {
+    /// The number of groups exceeded the [maximum](Aggregator::max_groups).
+    TooManyGroups(usize),
+
+    /// With the [`GroupingStrategy::Sorted`] strategy, a row whose group key is
+    /// lower than the key of the previous row was found. It has the row number,
+    /// starting from 1 and including the header row.
+    UnsortedInput(usize),
+
+    /// A referenced column was not found, or an error happened when reading or
+    /// writing.
+    ReadWriteError(ReadWriteError),
+}
+
+impl Error for AggregateError {}
+
+impl_from_read_write_error!(AggregateError);
+
+impl Display for AggregateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AggregateError::TooManyGroups(max_groups) => {
+                write!(f, "more than {max_groups} groups")
+            }
+            AggregateError::UnsortedInput(row_number) => {
+                write!(f, "input is not sorted at row {row_number}")
+            }
+            AggregateError::ReadWriteError(error) => write!(f, "{error}"),
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! Writes SSV with the values of each column aligned.
+
+use std::io::Write;
+
+use crate::engine::domain::{BytesDomain, Domain, DomainString, DomainStringSlice};
+use crate::engine::LineBreak;
+use crate::syntax::writer::PreparedValue;
+
+use super::fluent_writer::FluentWriter;
+use super::WriteResult;
+
+/// A line written by [`write_aligned`].
+pub(crate) enum Line<D: Domain> {
+    /// A row whose values are padded to the widths of the columns.
+    Row(Vec<D::String>),
+
+    /// A row that is written with single spaces, and that does not affect the
+    /// widths of the columns.
+    Unaligned(Vec<D::String>),
+
+    /// A comment read by a tokenizer, which is written exactly as it is.
+    Comment(D::String),
+}
+
+/// Writes the lines, padding each value of the rows with spaces up to the
+/// width of its column. The last value of a row is not padded.
+///
+/// The width of a column is the greatest number of elements of its values, as
+/// written with quotes and escaping, except for the last values of the rows.
+///
+/// The lines end with LF, except comments ending with a CR, which end with CRLF
+/// so that the CR is kept.
+pub(crate) fn write_aligned<D: Domain, W: Write>(writer: W, lines: &[Line<D>]) -> WriteResult<()> {
+    let mut widths: Vec<usize> = Vec::new();
+    for line in lines {
+        if let Line::Row(row) = line {
+            let padded = row.len().saturating_sub(1);
+            if widths.len() < padded {
+                widths.resize(padded, 0);
+            }
+            for (index, (width, value)) in widths.iter_mut().zip(&row[..padded]).enumerate() {
+                *width = (*width).max(written_width::<D>(value, index == 0));
+            }
+        }
+    }
+
+    let mut fluent = FluentWriter::<D, W>::new(writer);
+    for line in lines {
+        match line {
+            Line::Row(row) => {
+                for (index, value) in row.iter().enumerate() {
+                    if index > 0 {
+                        let padding =
+                            widths[index - 1] - written_width::<D>(&row[index - 1], index == 1);
+                        let spacing = D::String::from_text(&" ".repeat(padding + 1));
+                        fluent = fluent.write_spacing(&spacing)?;
+                    }
+                    fluent = fluent.write_value(value)?;
+                }
+                fluent = fluent.write_line_break()?;
+            }
+            Line::Unaligned(row) => {
+                for value in row {
+                    fluent = fluent.write_value(value)?;
+                }
+                fluent = fluent.write_line_break()?;
+            }
+            Line::Comment(comment) => {
+                let line_break = if comment.as_bytes().last() == Some(&BytesDomain::CR) {
+                    LineBreak::CrLf
+                } else {
+                    LineBreak::Lf
+                };
+                fluent = fluent
+                    .write_verbatim_comment(comment)?
+                    .write_this_line_break(line_break)?;
+            }
+        }
+    }
+    fluent.finish()
+}
+
+/// Returns the number of elements of the value as written with the minimal
+/// quoting, at the beginning of a line or after spacing.
+fn written_width<D: Domain>(value: &D::StringSlice, at_line_begin: bool) -> usize {
+    let prepared_value = PreparedValue::from(value.as_bytes());
+    let quotes = if prepared_value.requires_quotes(at_line_begin) {
+        2
+    } else {
+        0
+    };
+    D::element_iterator(prepared_value.bytes.as_slice()).count() + quotes
+}
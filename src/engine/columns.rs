@@ -0,0 +1,78 @@
+//! References to columns by index or by header name.
+
+use std::error::Error;
+use std::fmt::Display;
+
+use crate::engine::domain::{Domain, DomainString, DomainStringSlice};
+
+/// A reference to a column, either by its index or by its name in the header
+/// row.
+#[doc = generic_item_warning_doc!("ColumnRef")]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ColumnRef<D: Domain> {
+    /// The index of the column, starting from 0.
+    Index(usize),
+
+    /// The name of the column in the header row.
+    Name(D::String),
+}
+
+impl<D: Domain> ColumnRef<D> {
+    /// Creates a reference to the column with the given name.
+    pub fn name(name: &D::StringSlice) -> Self {
+        ColumnRef::Name(D::String::from(name))
+    }
+
+    /// Returns the index of the referenced column.
+    ///
+    /// A reference by name is resolved using the header row, and `None` is
+    /// returned if there is no header row or the name is not found in it.
+    pub fn resolve(&self, header: Option<&[D::String]>) -> Option<usize> {
+        match self {
+            ColumnRef::Index(index) => Some(*index),
+            ColumnRef::Name(name) => header?.iter().position(|column| column == name),
+        }
+    }
+
+    /// Returns the index of the referenced column like [`resolve`](ColumnRef::resolve),
+    /// or a [`ColumnNotFoundError`] if it can't be resolved.
+    pub fn try_resolve(&self, header: Option<&[D::String]>) -> Result<usize, ColumnNotFoundError> {
+        self.resolve(header)
+            .ok_or_else(|| ColumnNotFoundError(self.to_string()))
+    }
+}
+
+impl<D: Domain> From<usize> for ColumnRef<D> {
+    fn from(index: usize) -> Self {
+        ColumnRef::Index(index)
+    }
+}
+
+impl<D: Domain> From<&str> for ColumnRef<D> {
+    fn from(name: &str) -> Self {
+        ColumnRef::Name(D::String::from_text(name))
+    }
+}
+
+impl<D: Domain> Display for ColumnRef<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ColumnRef::Index(index) => write!(f, "{index}"),
+            ColumnRef::Name(name) => write!(f, "{:?}", String::from_utf8_lossy(name.as_bytes())),
+        }
+    }
+}
+
+/// The error type for a [`ColumnRef`] that can't be resolved: the column was
+/// not found in the header row, or was referenced by name without a header
+/// row. It has the reference as displayed by [`ColumnRef`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ColumnNotFoundError(pub String);
+
+impl Error for ColumnNotFoundError {}
+
+impl Display for ColumnNotFoundError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "column not found: {}", self.0)
+    }
+}
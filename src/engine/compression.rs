@@ -0,0 +1,268 @@
+//! Reads and writes compressed SSV.
+//!
+//! The gzip and zstd formats are supported with the `gzip` and `zstd` features.
+//! Without them, compressed content is still detected, but reading or writing
+//! it fails with an [IO error](std::io::Error) of kind
+//! [`Unsupported`](std::io::ErrorKind::Unsupported).
+
+use std::io::{Chain, Cursor, Read, Write};
+use std::path::Path;
+
+const GZIP_MAGIC: &[u8] = &[0x1F, 0x8B];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xB5, 0x2F, 0xFD];
+
+/// Compression formats.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Compression {
+    /// No compression.
+    #[default]
+    None,
+
+    /// The gzip format, which requires the `gzip` feature.
+    Gzip,
+
+    /// The zstd format, which requires the `zstd` feature.
+    Zstd,
+}
+
+impl Compression {
+    /// Returns the compression format matching the extension of the given path:
+    /// `.gz` for gzip, and `.zst` or `.zstd` for zstd.
+    pub fn from_extension<P: AsRef<Path>>(path: P) -> Compression {
+        let extension = path
+            .as_ref()
+            .extension()
+            .and_then(|extension| extension.to_str());
+        match extension {
+            Some(extension) if extension.eq_ignore_ascii_case("gz") => Compression::Gzip,
+            Some(extension)
+                if extension.eq_ignore_ascii_case("zst")
+                    || extension.eq_ignore_ascii_case("zstd") =>
+            {
+                Compression::Zstd
+            }
+            _ => Compression::None,
+        }
+    }
+
+    /// Returns the compression format whose magic bytes start the given bytes.
+    pub fn from_magic_bytes(bytes: &[u8]) -> Compression {
+        if bytes.starts_with(GZIP_MAGIC) {
+            Compression::Gzip
+        } else if bytes.starts_with(ZSTD_MAGIC) {
+            Compression::Zstd
+        } else {
+            Compression::None
+        }
+    }
+}
+
+type Peeked<R> = Chain<Cursor<Vec<u8>>, R>;
+
+/// A byte reader that decompresses the content of another byte reader.
+///
+/// It can be given to a [`Tokenizer`](super::tokenizer::Tokenizer) or a
+/// [`Reader`](super::reader::Reader) to read compressed SSV. Concatenated
+/// compressed streams are read one after the other.
+///
+/// # Example
+///
+/// ```no_run
+/// use ssv::chars::{DecompressingReader, Reader};
+///
+/// # let file_path = "";
+/// let file = std::fs::File::open(file_path)?;
+/// let reader = Reader::new(DecompressingReader::new(file)?);
+/// # #[allow(unused_variables)]
+/// for row in reader {
+///     // Use `row`
+/// }
+/// # Ok::<_, ssv::chars::ReadError>(())
+/// ```
+pub struct DecompressingReader<R: Read> {
+    inner: DecompressingInner<R>,
+    compression: Compression,
+}
+
+enum DecompressingInner<R: Read> {
+    None(Peeked<R>),
+    #[cfg(feature = "gzip")]
+    Gzip(flate2::read::MultiGzDecoder<Peeked<R>>),
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::Decoder<'static, std::io::BufReader<Peeked<R>>>),
+}
+
+impl<R: Read> DecompressingReader<R> {
+    /// Creates an instance that detects the compression format by the magic
+    /// bytes at the start of the content. Content without known magic bytes is
+    /// read as it is.
+    pub fn new(mut inner: R) -> std::io::Result<Self> {
+        let mut start = Vec::with_capacity(ZSTD_MAGIC.len());
+        while start.len() < ZSTD_MAGIC.len() {
+            let mut byte = [0];
+            if inner.read(&mut byte)? == 0 {
+                break;
+            }
+            start.push(byte[0]);
+        }
+
+        let compression = Compression::from_magic_bytes(&start);
+        Self::with_compression(Cursor::new(start).chain(inner), compression)
+    }
+
+    fn with_compression(inner: Peeked<R>, compression: Compression) -> std::io::Result<Self> {
+        let inner = match compression {
+            Compression::None => DecompressingInner::None(inner),
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => DecompressingInner::Gzip(flate2::read::MultiGzDecoder::new(inner)),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => DecompressingInner::Zstd(zstd::Decoder::new(inner)?),
+            #[allow(unreachable_patterns)]
+            _ => return Err(unsupported(compression)),
+        };
+        Ok(DecompressingReader { inner, compression })
+    }
+
+    /// Returns the detected compression format.
+    pub fn compression(&self) -> Compression {
+        self.compression
+    }
+}
+
+impl<R: Read> Read for DecompressingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match &mut self.inner {
+            DecompressingInner::None(inner) => inner.read(buf),
+            #[cfg(feature = "gzip")]
+            DecompressingInner::Gzip(inner) => inner.read(buf),
+            #[cfg(feature = "zstd")]
+            DecompressingInner::Zstd(inner) => inner.read(buf),
+        }
+    }
+}
+
+/// A byte writer that compresses the content written to it into another byte
+/// writer.
+///
+/// It can be given to a [`FluentWriter`](super::fluent_writer::FluentWriter) or
+/// a [`Writer`](super::writer::Writer) to write compressed SSV. The compressed
+/// stream is ended when the object is [finished](CompressingWriter::finish) or
+/// dropped. Eventual errors when it is dropped are ignored, so prefer to
+/// explicitly call the [`finish`](CompressingWriter::finish) method.
+///
+/// # Example
+///
+/// ```no_run
+/// use ssv::chars::{CompressingWriter, Compression, Writer};
+///
+/// # let file_path = "";
+/// let file = std::fs::File::create(file_path)?;
+/// let mut compressing_writer = CompressingWriter::new(file, Compression::Gzip)?;
+///
+/// let mut writer = Writer::new(&mut compressing_writer);
+/// writer.write_row(["value", "another value"])?;
+/// writer.finish()?;
+///
+/// compressing_writer.finish()?;
+/// # Ok::<_, ssv::chars::WriteError>(())
+/// ```
+pub struct CompressingWriter<W: Write> {
+    inner: CompressingInner<W>,
+    finished: bool,
+}
+
+enum CompressingInner<W: Write> {
+    None(W),
+    #[cfg(feature = "gzip")]
+    Gzip(flate2::write::GzEncoder<W>),
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::Encoder<'static, W>),
+}
+
+impl<W: Write> CompressingWriter<W> {
+    /// Creates an instance that compresses in the given format with the default
+    /// compression level.
+    pub fn new(inner: W, compression: Compression) -> std::io::Result<Self> {
+        let inner = match compression {
+            Compression::None => CompressingInner::None(inner),
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => CompressingInner::Gzip(flate2::write::GzEncoder::new(
+                inner,
+                flate2::Compression::default(),
+            )),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => CompressingInner::Zstd(zstd::Encoder::new(inner, 0)?),
+            #[allow(unreachable_patterns)]
+            _ => return Err(unsupported(compression)),
+        };
+        Ok(CompressingWriter {
+            inner,
+            finished: false,
+        })
+    }
+
+    /// Ends the compressed stream and flushes the underlying byte writer.
+    pub fn finish(mut self) -> std::io::Result<()> {
+        self.finished = true;
+        self.end()
+    }
+
+    fn end(&mut self) -> std::io::Result<()> {
+        match &mut self.inner {
+            CompressingInner::None(inner) => inner.flush(),
+            #[cfg(feature = "gzip")]
+            CompressingInner::Gzip(inner) => {
+                inner.try_finish()?;
+                inner.get_mut().flush()
+            }
+            #[cfg(feature = "zstd")]
+            CompressingInner::Zstd(inner) => {
+                inner.do_finish()?;
+                inner.get_mut().flush()
+            }
+        }
+    }
+}
+
+impl<W: Write> Write for CompressingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match &mut self.inner {
+            CompressingInner::None(inner) => inner.write(buf),
+            #[cfg(feature = "gzip")]
+            CompressingInner::Gzip(inner) => inner.write(buf),
+            #[cfg(feature = "zstd")]
+            CompressingInner::Zstd(inner) => inner.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match &mut self.inner {
+            CompressingInner::None(inner) => inner.flush(),
+            #[cfg(feature = "gzip")]
+            CompressingInner::Gzip(inner) => inner.flush(),
+            #[cfg(feature = "zstd")]
+            CompressingInner::Zstd(inner) => inner.flush(),
+        }
+    }
+}
+
+impl<W: Write> Drop for CompressingWriter<W> {
+    fn drop(&mut self) {
+        if !self.finished {
+            let _ = self.end();
+        }
+    }
+}
+
+#[allow(dead_code)]
+fn unsupported(compression: Compression) -> std::io::Error {
+    let feature = match compression {
+        Compression::None => "",
+        Compression::Gzip => "gzip",
+        Compression::Zstd => "zstd",
+    };
+    std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        format!("{compression:?} compression requires the `{feature}` feature"),
+    )
+}
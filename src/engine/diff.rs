@@ -0,0 +1,549 @@
+//! Compares the rows of two SSV inputs cell by cell.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Display;
+use std::io::{Read, Write};
+use std::ops::Deref;
+
+use crate::engine::domain::{Domain, DomainString, DomainStringSlice};
+
+use super::columns::ColumnRef;
+use super::position::{Position, WithPosition};
+use super::tokenizer::{Token, Tokenizer};
+use super::writer::Writer;
+use super::{ReadResult, ReadWriteResult, WriteResult};
+
+/// Compares the rows of two SSV inputs cell by cell.
+#[doc = generic_item_warning_doc!("Differ")]
+/// The inputs are parsed before being compared, so differences in spacing,
+/// line-breaks, quoting and comments are ignored, as well as lines without
+/// values.
+///
+/// The rows are matched either:
+/// * by the values in the [key columns](Differ::key), if any. Rows with the
+///   same key are matched in order of appearance; or
+/// * by sequence alignment, like a line-based diff. Unmatched rows between two
+///   matched rows are paired in order of appearance and reported as changed
+///   rows. It takes time and memory proportional to the product of the numbers
+///   of rows after the common leading and trailing rows. If that product
+///   exceeds [`MAX_SEQUENCE_CELLS`], the rows are not aligned, and all of them
+///   are paired in order of appearance as if they were a single group of
+///   unmatched rows.
+///
+/// The cells of matched rows are compared by column index. If the differ
+/// [uses a header](Differ::with_header), the first rows of the inputs are
+/// compared to each other, and the header rows are used to resolve the key
+/// columns and to name the columns in the output.
+///
+/// # Example
+///
+/// ```
+/// use ssv::chars::{Difference, Differ};
+///
+/// let left = "id name\n1 John\n2 Jane\n";
+/// let right = "id   name\n2 \"Jane\"\n1 Jon\n3 Ann\n";
+///
+/// let diff = Differ::new().with_header(true).key("id").diff(left.as_bytes(), right.as_bytes())?;
+///
+/// assert_eq!(diff.differences().len(), 2);
+/// assert!(matches!(diff.differences()[0], Difference::Changed { .. }));
+/// assert!(matches!(diff.differences()[1], Difference::Added(_)));
+/// assert_eq!(
+///     diff.to_string(),
+///     "changed row at 2:1 -> 3:1\n  name: John (2:3) -> Jon (3:3)\nadded row at 4:1: 3 Ann\n"
+/// );
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct Differ<D: Domain> {
+    keys: Vec<ColumnRef<D>>,
+    header: bool,
+}
+
+impl<D: Domain> Differ<D> {
+    /// Creates an instance without key columns and without header.
+    pub fn new() -> Self {
+        Differ {
+            keys: Vec::new(),
+            header: false,
+        }
+    }
+
+    /// Sets whether the first row of each input is a header row.
+    ///
+    /// Columns can be referenced by name only if there are header rows.
+    pub fn with_header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Adds a key column.
+    pub fn key(mut self, column: impl Into<ColumnRef<D>>) -> Self {
+        self.keys.push(column.into());
+        self
+    }
+
+    /// Reads SSV from the given byte readers and compares their rows.
+    pub fn diff<L: Read, R: Read>(&self, left: L, right: R) -> ReadWriteResult<Diff<D>> {
+        let mut left = read_rows::<D, L>(left)?;
+        let mut right = read_rows::<D, R>(right)?;
+
+        let mut differences = Vec::new();
+        let (left_header, right_header) = if self.header {
+            let left_header = left.pop_front();
+            let right_header = right.pop_front();
+            differences.extend(pair(left_header.clone(), right_header.clone()));
+            (left_header.map(values), right_header.map(values))
+        } else {
+            (None, None)
+        };
+
+        if self.keys.is_empty() {
+            align_by_sequence(left, right, &mut differences);
+        } else {
+            let left_keys = self.resolve(left_header.as_deref())?;
+            let right_keys = self.resolve(right_header.as_deref())?;
+            align_by_keys(left, right, &left_keys, &right_keys, &mut differences);
+        }
+
+        Ok(Diff {
+            column_names: left_header.or(right_header).unwrap_or_default(),
+            differences,
+        })
+    }
+
+    fn resolve(&self, header: Option<&[D::String]>) -> ReadWriteResult<Vec<usize>> {
+        self.keys
+            .iter()
+            .map(|column| Ok(column.try_resolve(header)?))
+            .collect()
+    }
+}
+
+impl<D: Domain> Default for Differ<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The result of comparing two SSV inputs with a [`Differ`].
+#[doc = generic_item_warning_doc!("Diff")]
+/// Its [`Display`] implementation returns a human-readable report.
+pub struct Diff<D: Domain> {
+    column_names: Vec<D::String>,
+    differences: Vec<Difference<D>>,
+}
+
+impl<D: Domain> Diff<D> {
+    /// The differences found.
+    pub fn differences(&self) -> &[Difference<D>] {
+        &self.differences
+    }
+
+    /// Returns whether no differences were found.
+    pub fn is_empty(&self) -> bool {
+        self.differences.is_empty()
+    }
+
+    /// Writes the differences as SSV to the given byte writer.
+    ///
+    /// The output has a header row with the columns `change`, `left`, `right`,
+    /// `column`, `old` and `new`, and a row for each changed cell and for each
+    /// cell of the added and removed rows. The `left` and `right` columns have
+    /// the positions of the cells in the inputs, in the `line:column` format.
+    /// Missing positions and values are written as empty values.
+    pub fn write_ssv<W: Write>(&self, writer: W) -> WriteResult<()> {
+        let mut writer = Writer::<D, W>::new(writer);
+        let text = |text: &str| D::String::from_text(text);
+        let position = |cell: Option<&WithPosition<D::String>>| {
+            cell.map_or_else(D::String::new, |cell| text(&format_position(cell.position)))
+        };
+        let value = |cell: Option<&WithPosition<D::String>>| {
+            cell.map_or_else(D::String::new, |cell| cell.value.clone())
+        };
+
+        writer.write_row(
+            ["change", "left", "right", "column", "old", "new"]
+                .map(text)
+                .iter()
+                .map(Deref::deref),
+        )?;
+        for difference in &self.differences {
+            let (change, cells) = match difference {
+                Difference::Added(row) => (
+                    "added",
+                    row.values
+                        .iter()
+                        .enumerate()
+                        .map(|(index, cell)| (index, None, Some(cell)))
+                        .collect::<Vec<_>>(),
+                ),
+                Difference::Removed(row) => (
+                    "removed",
+                    row.values
+                        .iter()
+                        .enumerate()
+                        .map(|(index, cell)| (index, Some(cell), None))
+                        .collect(),
+                ),
+                Difference::Changed { cells, .. } => (
+                    "changed",
+                    cells
+                        .iter()
+                        .map(|cell| (cell.column, cell.left.as_ref(), cell.right.as_ref()))
+                        .collect(),
+                ),
+            };
+            for (column, left, right) in cells {
+                writer.write_row(
+                    [
+                        text(change),
+                        position(left),
+                        position(right),
+                        self.column_name(column),
+                        value(left),
+                        value(right),
+                    ]
+                    .iter()
+                    .map(Deref::deref),
+                )?;
+            }
+        }
+        writer.finish()
+    }
+
+    fn column_name(&self, column: usize) -> D::String {
+        match self.column_names.get(column) {
+            Some(name) => name.clone(),
+            None => D::String::from_text(&column.to_string()),
+        }
+    }
+}
+
+impl<D: Domain> Display for Diff<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for difference in &self.differences {
+            match difference {
+                Difference::Added(row) => writeln!(
+                    f,
+                    "added row at {}: {}",
+                    format_position(row.position),
+                    format_values::<D>(row.values.iter().map(|cell| cell.value.deref()))
+                )?,
+                Difference::Removed(row) => writeln!(
+                    f,
+                    "removed row at {}: {}",
+                    format_position(row.position),
+                    format_values::<D>(row.values.iter().map(|cell| cell.value.deref()))
+                )?,
+                Difference::Changed { left, right, cells } => {
+                    writeln!(
+                        f,
+                        "changed row at {} -> {}",
+                        format_position(left.position),
+                        format_position(right.position)
+                    )?;
+                    for cell in cells {
+                        let format_cell = |cell: &Option<WithPosition<D::String>>| match cell {
+                            Some(cell) => format!(
+                                "{} ({})",
+                                format_values::<D>([cell.value.deref()]),
+                                format_position(cell.position)
+                            ),
+                            None => "(missing)".to_string(),
+                        };
+                        writeln!(
+                            f,
+                            "  {}: {} -> {}",
+                            String::from_utf8_lossy(self.column_name(cell.column).as_bytes()),
+                            format_cell(&cell.left),
+                            format_cell(&cell.right)
+                        )?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A difference found by a [`Differ`].
+#[doc = generic_item_warning_doc!("Difference")]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Difference<D: Domain> {
+    /// A row of the right input that was not matched.
+    Added(DiffRow<D>),
+
+    /// A row of the left input that was not matched.
+    Removed(DiffRow<D>),
+
+    /// Matched rows with different values.
+    Changed {
+        /// The row of the left input.
+        left: DiffRow<D>,
+
+        /// The row of the right input.
+        right: DiffRow<D>,
+
+        /// The cells that differ.
+        cells: Vec<CellDifference<D>>,
+    },
+}
+
+/// A row compared by a [`Differ`].
+#[doc = generic_item_warning_doc!("DiffRow")]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DiffRow<D: Domain> {
+    /// The values, with their positions.
+    pub values: Vec<WithPosition<D::String>>,
+
+    /// The position of the first value.
+    pub position: Position,
+}
+
+/// A cell that differs between matched rows.
+#[doc = generic_item_warning_doc!("CellDifference")]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CellDifference<D: Domain> {
+    /// The column index.
+    pub column: usize,
+
+    /// The value in the left row, or `None` if the row is shorter.
+    pub left: Option<WithPosition<D::String>>,
+
+    /// The value in the right row, or `None` if the row is shorter.
+    pub right: Option<WithPosition<D::String>>,
+}
+
+fn read_rows<D: Domain, R: Read>(reader: R) -> ReadResult<VecDeque<DiffRow<D>>> {
+    let mut rows = VecDeque::new();
+    let mut current: Option<DiffRow<D>> = None;
+
+    for token in Tokenizer::<D, R>::new(reader) {
+        let token = token?;
+        match token.value {
+            Token::UnquotedValue(value) | Token::QuotedValue(value) => {
+                current
+                    .get_or_insert_with(|| DiffRow {
+                        values: Vec::new(),
+                        position: token.position,
+                    })
+                    .values
+                    .push(WithPosition {
+                        value,
+                        position: token.position,
+                    });
+            }
+            Token::LineBreak(_) => rows.extend(current.take()),
+            Token::Spacing(_) | Token::Comment(_) => {}
+        }
+    }
+    rows.extend(current);
+
+    Ok(rows)
+}
+
+fn values<D: Domain>(row: DiffRow<D>) -> Vec<D::String> {
+    row.values.into_iter().map(|cell| cell.value).collect()
+}
+
+fn same_values<D: Domain>(left: &DiffRow<D>, right: &DiffRow<D>) -> bool {
+    left.values.len() == right.values.len()
+        && left
+            .values
+            .iter()
+            .zip(&right.values)
+            .all(|(left, right)| left.value == right.value)
+}
+
+fn pair<D: Domain>(left: Option<DiffRow<D>>, right: Option<DiffRow<D>>) -> Option<Difference<D>> {
+    match (left, right) {
+        (Some(left), Some(right)) => compare(left, right),
+        (Some(left), None) => Some(Difference::Removed(left)),
+        (None, Some(right)) => Some(Difference::Added(right)),
+        (None, None) => None,
+    }
+}
+
+fn compare<D: Domain>(left: DiffRow<D>, right: DiffRow<D>) -> Option<Difference<D>> {
+    let width = left.values.len().max(right.values.len());
+    let cells: Vec<_> = (0..width)
+        .filter_map(|column| {
+            let left = left.values.get(column);
+            let right = right.values.get(column);
+            if left.map(|cell| &cell.value) == right.map(|cell| &cell.value) {
+                None
+            } else {
+                Some(CellDifference {
+                    column,
+                    left: left.cloned(),
+                    right: right.cloned(),
+                })
+            }
+        })
+        .collect();
+
+    if cells.is_empty() {
+        None
+    } else {
+        Some(Difference::Changed { left, right, cells })
+    }
+}
+
+fn align_by_keys<D: Domain>(
+    left: VecDeque<DiffRow<D>>,
+    right: VecDeque<DiffRow<D>>,
+    left_keys: &[usize],
+    right_keys: &[usize],
+    differences: &mut Vec<Difference<D>>,
+) {
+    let key = |row: &DiffRow<D>, indices: &[usize]| -> Vec<Vec<u8>> {
+        indices
+            .iter()
+            .map(|index| {
+                row.values
+                    .get(*index)
+                    .map_or(Vec::new(), |cell| cell.value.as_bytes().to_vec())
+            })
+            .collect()
+    };
+
+    let mut right_rows: Vec<_> = right.into_iter().map(Some).collect();
+    let mut right_indices: HashMap<_, VecDeque<usize>> = HashMap::new();
+    for (index, row) in right_rows.iter().enumerate() {
+        let row = row.as_ref().expect("all the rows are present");
+        right_indices
+            .entry(key(row, right_keys))
+            .or_default()
+            .push_back(index);
+    }
+
+    for left_row in left {
+        let right_row = right_indices
+            .get_mut(&key(&left_row, left_keys))
+            .and_then(VecDeque::pop_front)
+            .and_then(|index| right_rows[index].take());
+        differences.extend(pair(Some(left_row), right_row));
+    }
+    differences.extend(right_rows.into_iter().flatten().map(Difference::Added));
+}
+
+/// The maximum product of the numbers of rows to be aligned by sequence, after
+/// the common leading and trailing rows, which limits the memory used by the
+/// [`Differ`] to 64 MiB.
+pub const MAX_SEQUENCE_CELLS: usize = 1 << 24;
+
+fn align_by_sequence<D: Domain>(
+    mut left: VecDeque<DiffRow<D>>,
+    mut right: VecDeque<DiffRow<D>>,
+    differences: &mut Vec<Difference<D>>,
+) {
+    // Common prefix and suffix
+    while let (Some(left_row), Some(right_row)) = (left.front(), right.front()) {
+        if !same_values(left_row, right_row) {
+            break;
+        }
+        left.pop_front();
+        right.pop_front();
+    }
+    while let (Some(left_row), Some(right_row)) = (left.back(), right.back()) {
+        if !same_values(left_row, right_row) {
+            break;
+        }
+        left.pop_back();
+        right.pop_back();
+    }
+
+    let width = right.len() + 1;
+    if (left.len() + 1).saturating_mul(width) > MAX_SEQUENCE_CELLS {
+        let mut removed: Vec<_> = left.into_iter().enumerate().collect();
+        let mut added: Vec<_> = right.into_iter().enumerate().collect();
+        flush_hunk(&mut removed, &mut added, differences);
+        return;
+    }
+
+    // lengths[i][j] is the length of the longest common subsequence of
+    // left[i..] and right[j..]
+    let mut lengths = vec![0u32; (left.len() + 1) * width];
+    for i in (0..left.len()).rev() {
+        for j in (0..right.len()).rev() {
+            lengths[i * width + j] = if same_values(&left[i], &right[j]) {
+                lengths[(i + 1) * width + j + 1] + 1
+            } else {
+                lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut left = left.into_iter().enumerate().peekable();
+    let mut right = right.into_iter().enumerate().peekable();
+    let mut removed = Vec::new();
+    let mut added = Vec::new();
+    loop {
+        let matched = match (left.peek(), right.peek()) {
+            (Some((i, left_row)), Some((j, right_row))) => {
+                if same_values(left_row, right_row) {
+                    true
+                } else {
+                    if lengths[(i + 1) * width + j] >= lengths[i * width + j + 1] {
+                        removed.extend(left.next());
+                    } else {
+                        added.extend(right.next());
+                    }
+                    continue;
+                }
+            }
+            (Some(_), None) => {
+                removed.extend(left.by_ref());
+                false
+            }
+            (None, Some(_)) => {
+                added.extend(right.by_ref());
+                false
+            }
+            (None, None) => false,
+        };
+
+        flush_hunk(&mut removed, &mut added, differences);
+        if matched {
+            left.next();
+            right.next();
+        } else {
+            break;
+        }
+    }
+}
+
+fn flush_hunk<D: Domain>(
+    removed: &mut Vec<(usize, DiffRow<D>)>,
+    added: &mut Vec<(usize, DiffRow<D>)>,
+    differences: &mut Vec<Difference<D>>,
+) {
+    let mut removed = removed.drain(..).map(|(_, row)| row);
+    let mut added = added.drain(..).map(|(_, row)| row);
+    loop {
+        match (removed.next(), added.next()) {
+            (None, None) => break,
+            (left, right) => differences.extend(pair(left, right)),
+        }
+    }
+}
+
+fn format_position(position: Position) -> String {
+    format!("{}:{}", position.line_number, position.column_number)
+}
+
+fn format_values<'a, D: Domain>(values: impl IntoIterator<Item = &'a D::StringSlice>) -> String {
+    let mut output = Vec::new();
+    let mut writer = Writer::<D, _>::new(&mut output);
+    writer
+        .write_row(values)
+        .and_then(|_| writer.finish())
+        .expect("writing to a Vec does not fail");
+    while output
+        .last()
+        .is_some_and(|byte| matches!(byte, b'\n' | b'\r'))
+    {
+        output.pop();
+    }
+    String::from_utf8_lossy(&output).into_owned()
+}
@@ -1,143 +1,284 @@
+//! The [`Domain`] trait, which defines the types of the values read and
+//! written by the generic implementation.
+//!
+//! This crate has two domains, [`BytesDomain`] and [`CharsDomain`], which are
+//! specialized in the [`bytes`](crate::bytes) and [`chars`](crate::chars)
+//! modules. Other crates can implement the [`Domain`] trait to read and write
+//! their own value types, such as small-string-optimized strings or shared
+//! strings, with the types in the [`engine`](crate::engine) module.
+//!
+//! # Implementing a domain
+//!
+//! A domain is usually a unit struct. Its [`String`](Domain::String) is the
+//! owned value type, which is built while tokenizing by pushing one
+//! [element](Domain::Element) after the other, and its
+//! [`StringSlice`](Domain::StringSlice) is the borrowed value type that is
+//! given to the writers.
+//!
+//! Every domain is also a [`BaseDomain`], which has the element and value types
+//! without the conversions from byte readers, for the `no_std`
+//! [`syntax`](crate::syntax) module.
+//!
+//! The values are read and written as bytes: the
+//! [`element_iterator`](Domain::element_iterator) decodes the elements from a
+//! byte reader, the [`slice_from_bytes`](Domain::slice_from_bytes) and
+//! [`string_from_bytes`](Domain::string_from_bytes) functions decode values
+//! from bytes, and the [`as_bytes`](DomainStringSlice::as_bytes) method encodes
+//! values to bytes. The encoding must be the same in all of them, and the SSV
+//! delimiters (LF, CR, QUOTE, SPACE, TAB and HASH) must be encoded as their
+//! single ASCII bytes and must not appear inside the encoding of other
+//! elements. UTF-8 fulfills these requirements. Content in other encodings can
+//! be converted with a [`DecodingReader`](super::encoding::DecodingReader) and
+//! an [`EncodingWriter`](super::encoding::EncodingWriter).
+//!
+//! # Example
+//!
+//! A domain whose values are shared strings, reusing [`str`] as the
+//! [`StringSlice`](Domain::StringSlice) and [`Chars`] as the
+//! [`ElementIterator`](Domain::ElementIterator):
+//!
+//! ```
+//! use std::io::Read;
+//! use std::ops::Deref;
+//! use std::sync::Arc;
+//!
+//! use ssv::engine::domain::{Chars, Domain, DomainString};
+//! use ssv::engine::reader::Reader;
+//! use ssv::engine::writer::Writer;
+//!
+//! #[derive(Clone, PartialEq, Eq, Debug)]
+//! struct SharedString(Arc<str>);
+//!
+//! impl Deref for SharedString {
+//!     type Target = str;
+//!
+//!     fn deref(&self) -> &str {
+//!         &self.0
+//!     }
+//! }
+//!
+//! impl From<&str> for SharedString {
+//!     fn from(text: &str) -> Self {
+//!         SharedString(text.into())
+//!     }
+//! }
+//!
+//! impl DomainString<char> for SharedString {
+//!     fn new() -> Self {
+//!         SharedString("".into())
+//!     }
+//!
+//!     fn push(&mut self, element: char) {
+//!         let mut string = self.0.to_string();
+//!         string.push(element);
+//!         self.0 = string.into();
+//!     }
+//!
+//!     fn quotes(length: usize) -> Self {
+//!         SharedString("\"".repeat(length).into())
+//!     }
+//!
+//!     fn from_text(text: &str) -> Self {
+//!         SharedString(text.into())
+//!     }
+//! }
+//!
+//! #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+//! struct SharedDomain;
+//!
+//! impl Domain for SharedDomain {
+//!     type Element = char;
+//!     type ElementIterator<R: Read> = Chars<R>;
+//!     type String = SharedString;
+//!     type StringSlice = str;
+//!
+//!     const LF: char = '\n';
+//!     const CR: char = '\r';
+//!     const QUOTE: char = '"';
+//!     const SPACE: char = ' ';
+//!     const HASH: char = '#';
+//!
+//!     fn is_spacing_element(element: char) -> bool {
+//!         matches!(element, ' ' | '\t')
+//!     }
+//!
+//!     fn is_valid_spacing(spacing: &str) -> bool {
+//!         !spacing.is_empty() && spacing.chars().all(Self::is_spacing_element)
+//!     }
+//!
+//!     fn element_iterator<R: Read>(inner: R) -> Chars<R> {
+//!         Chars::new(inner)
+//!     }
+//!
+//!     fn slice_from_bytes(bytes: &[u8]) -> std::io::Result<&str> {
+//!         std::str::from_utf8(bytes)
+//!             .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error))
+//!     }
+//!
+//!     fn string_from_bytes(bytes: Vec<u8>) -> std::io::Result<SharedString> {
+//!         Ok(SharedString::from(Self::slice_from_bytes(&bytes)?))
+//!     }
+//!
+//!     fn concat(parts: &[&str]) -> SharedString {
+//!         SharedString(parts.concat().into())
+//!     }
+//! }
+//!
+//! let input = "name  age\n\"John Doe\"  53\n";
+//! let rows = Reader::<SharedDomain, _>::new(input.as_bytes())
+//!     .collect::<Result<Vec<_>, _>>()?;
+//! assert_eq!(rows[1][0], SharedString::from("John Doe"));
+//!
+//! let mut output = Vec::new();
+//! let mut writer = Writer::<SharedDomain, _>::new(&mut output);
+//! for row in &rows {
+//!     writer.write_row(row.iter().map(Deref::deref))?;
+//! }
+//! writer.finish()?;
+//! assert_eq!(output, b"name age\n\"John Doe\" 53\n");
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+
 use std::fmt::Debug;
 use std::io::{BufRead, BufReader, Bytes, Read};
 use std::ops::Deref;
 
-pub trait Domain: Eq + Clone + Debug {
+use crate::syntax::domain::{bytes_domain_items, chars_domain_items};
+
+pub use crate::syntax::domain::{
+    BaseDomain, BytesDomain, CharsDomain, DomainString, DomainStringSlice,
+};
+
+/// The types of the values read and written by the generic implementation.
+///
+/// See the [module documentation](self) for how to implement it.
+pub trait Domain: Eq + Clone + Debug + 'static {
+    /// The type of the elements of the values, such as a byte or a character.
     type Element: Copy + Eq + Debug;
+
+    /// An iterator that decodes the elements from a byte reader.
     type ElementIterator<R: Read>: Iterator<Item = std::io::Result<Self::Element>>;
-    type String: DomainString<Self::Element> + Deref<Target = Self::StringSlice>;
-    type StringSlice: DomainStringSlice<Self::Element> + ToOwned<Owned = Self::String> + ?Sized;
 
+    /// The owned value type.
+    type String: DomainString<Self::Element>
+        + Deref<Target = Self::StringSlice>
+        + for<'a> From<&'a Self::StringSlice>;
+
+    /// The borrowed value type.
+    type StringSlice: DomainStringSlice<Self::Element> + ToOwned<Owned: Debug> + Eq + Debug + ?Sized;
+
+    /// The LF element (byte value/codepoint 10).
     const LF: Self::Element;
+
+    /// The CR element (byte value/codepoint 13).
     const CR: Self::Element;
+
+    /// The QUOTE element (`"`).
     const QUOTE: Self::Element;
+
+    /// The SPACE element (byte value/codepoint 32).
     const SPACE: Self::Element;
+
+    /// The HASH element (`#`).
     const HASH: Self::Element;
 
+    /// Returns whether the element is a SPACE or a TAB.
     fn is_spacing_element(element: Self::Element) -> bool;
+
+    /// Returns whether the value is not empty and contains only spacing
+    /// elements.
     fn is_valid_spacing(spacing: &Self::StringSlice) -> bool;
+
+    /// Creates an iterator that decodes the elements from the byte reader.
     fn element_iterator<R: Read>(inner: R) -> Self::ElementIterator<R>;
-}
 
-pub trait DomainString<E>: Sized + Clone + Eq + Debug {
-    fn new() -> Self;
-    fn push(&mut self, element: E);
-    fn quotes(length: usize) -> Self;
+    /// Decodes a borrowed value from bytes, returning an [IO
+    /// error](std::io::Error) of kind
+    /// [`InvalidData`](std::io::ErrorKind::InvalidData) if they are not valid.
+    fn slice_from_bytes(bytes: &[u8]) -> std::io::Result<&Self::StringSlice>;
 
-    fn from_element(element: E) -> Self {
-        let mut string = Self::new();
-        string.push(element);
-        string
-    }
-}
+    /// Decodes an owned value from bytes, returning an [IO
+    /// error](std::io::Error) of kind
+    /// [`InvalidData`](std::io::ErrorKind::InvalidData) if they are not valid.
+    fn string_from_bytes(bytes: Vec<u8>) -> std::io::Result<Self::String>;
 
-pub trait DomainStringSlice<E> {
-    fn as_bytes(&self) -> &[u8];
+    /// Returns the concatenation of the values.
+    fn concat(parts: &[&Self::StringSlice]) -> Self::String;
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
-pub struct BytesDomain;
-impl Domain for BytesDomain {
-    type Element = u8;
-    type ElementIterator<R: Read> = Bytes<R>;
-    type String = Vec<u8>;
-    type StringSlice = [u8];
+impl<D: Domain> BaseDomain for D {
+    type Element = D::Element;
+    type String = D::String;
+    type StringSlice = D::StringSlice;
 
-    const LF: Self::Element = b'\n';
-    const CR: Self::Element = b'\r';
-    const QUOTE: Self::Element = b'"';
-    const SPACE: Self::Element = b' ';
-    const HASH: Self::Element = b'#';
+    const LF: Self::Element = D::LF;
+    const CR: Self::Element = D::CR;
+    const QUOTE: Self::Element = D::QUOTE;
+    const SPACE: Self::Element = D::SPACE;
+    const HASH: Self::Element = D::HASH;
 
     fn is_spacing_element(element: Self::Element) -> bool {
-        matches!(element, b' ' | b'\t')
+        D::is_spacing_element(element)
     }
 
     fn is_valid_spacing(spacing: &Self::StringSlice) -> bool {
-        !spacing.is_empty() && spacing.iter().all(|byte| Self::is_spacing_element(*byte))
+        D::is_valid_spacing(spacing)
     }
 
-    fn element_iterator<R: Read>(inner: R) -> Self::ElementIterator<R> {
-        inner.bytes()
+    fn concat(parts: &[&Self::StringSlice]) -> Self::String {
+        D::concat(parts)
     }
 }
 
-impl DomainString<u8> for Vec<u8> {
-    fn new() -> Self {
-        Vec::new()
-    }
+impl Domain for BytesDomain {
+    bytes_domain_items!(Domain);
+
+    type ElementIterator<R: Read> = Bytes<BufReader<R>>;
 
-    fn push(&mut self, element: u8) {
-        Vec::push(self, element);
+    fn element_iterator<R: Read>(inner: R) -> Self::ElementIterator<R> {
+        BufReader::new(inner).bytes()
     }
 
-    fn quotes(length: usize) -> Self {
-        [BytesDomain::QUOTE].repeat(length)
+    fn slice_from_bytes(bytes: &[u8]) -> std::io::Result<&Self::StringSlice> {
+        Ok(bytes)
     }
-}
 
-impl DomainStringSlice<u8> for [u8] {
-    fn as_bytes(&self) -> &[u8] {
-        self
+    fn string_from_bytes(bytes: Vec<u8>) -> std::io::Result<Self::String> {
+        Ok(bytes)
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
-pub struct CharsDomain;
 impl Domain for CharsDomain {
-    type Element = char;
-    type ElementIterator<R: Read> = Chars<R>;
-    type String = String;
-    type StringSlice = str;
-
-    const LF: Self::Element = '\n';
-    const CR: Self::Element = '\r';
-    const QUOTE: Self::Element = '"';
-    const SPACE: Self::Element = ' ';
-    const HASH: Self::Element = '#';
-
-    fn is_spacing_element(element: Self::Element) -> bool {
-        matches!(element, ' ' | '\t')
-    }
+    chars_domain_items!(Domain);
 
-    fn is_valid_spacing(spacing: &Self::StringSlice) -> bool {
-        !spacing.is_empty() && spacing.chars().all(Self::is_spacing_element)
-    }
+    type ElementIterator<R: Read> = Chars<R>;
 
     fn element_iterator<R: Read>(inner: R) -> Self::ElementIterator<R> {
-        Chars {
-            inner: BufReader::new(inner),
-            chars: None,
-        }
+        Chars::new(inner)
     }
-}
 
-impl DomainString<char> for String {
-    fn new() -> Self {
-        String::new()
+    fn slice_from_bytes(bytes: &[u8]) -> std::io::Result<&Self::StringSlice> {
+        std::str::from_utf8(bytes)
+            .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error))
     }
 
-    fn push(&mut self, element: char) {
-        String::push(self, element);
-    }
-
-    fn quotes(length: usize) -> Self {
-        Self::from_element(CharsDomain::QUOTE).repeat(length)
-    }
-}
-
-impl DomainStringSlice<char> for str {
-    fn as_bytes(&self) -> &[u8] {
-        self.as_bytes()
+    fn string_from_bytes(bytes: Vec<u8>) -> std::io::Result<Self::String> {
+        String::from_utf8(bytes)
+            .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error))
     }
 }
 
+/// An iterator that decodes UTF-8 characters from a byte reader.
+///
+/// Invalid UTF-8 is returned as an [IO error](std::io::Error) of kind
+/// [`InvalidData`](std::io::ErrorKind::InvalidData).
 pub struct Chars<R: Read> {
     inner: BufReader<R>,
     chars: Option<std::vec::IntoIter<char>>,
 }
 
 impl<R: Read> Chars<R> {
+    /// Creates an iterator that reads from the given byte reader.
     pub fn new(reader: R) -> Self {
         Chars {
             inner: BufReader::new(reader),
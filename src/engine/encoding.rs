@@ -0,0 +1,370 @@
+//! Reads and writes SSV in text encodings other than UTF-8.
+//!
+//! The [chars domain](crate::chars) reads and writes UTF-8. A
+//! [`DecodingReader`] converts other encodings to UTF-8 on input, and an
+//! [`EncodingWriter`] converts UTF-8 to other encodings on output, so the
+//! positions in read errors are still counted in chars.
+
+use std::io::{Read, Write};
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// The characters of the bytes 0x80 to 0x9F in Windows-1252. The bytes that
+/// are not assigned are mapped to the C1 control characters with the same
+/// values.
+const WINDOWS_1252_HIGH: [char; 32] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}',
+    '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+];
+
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// Text encodings.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Encoding {
+    /// UTF-8.
+    #[default]
+    Utf8,
+
+    /// UTF-16, little-endian.
+    Utf16Le,
+
+    /// UTF-16, big-endian.
+    Utf16Be,
+
+    /// ISO-8859-1, where each byte is the codepoint of a character.
+    Latin1,
+
+    /// Windows-1252, which is like [`Latin1`](Encoding::Latin1), except that
+    /// the bytes 0x80 to 0x9F are mostly printable characters.
+    Windows1252,
+}
+
+impl Encoding {
+    /// Returns the byte order mark of the encoding, if it has one.
+    pub fn bom(&self) -> Option<&'static [u8]> {
+        match self {
+            Encoding::Utf8 => Some(UTF8_BOM),
+            Encoding::Utf16Le => Some(UTF16LE_BOM),
+            Encoding::Utf16Be => Some(UTF16BE_BOM),
+            Encoding::Latin1 | Encoding::Windows1252 => None,
+        }
+    }
+
+    /// Returns the encoding whose byte order mark starts the given bytes.
+    pub fn from_bom(bytes: &[u8]) -> Option<Encoding> {
+        [Encoding::Utf8, Encoding::Utf16Le, Encoding::Utf16Be]
+            .into_iter()
+            .find(|encoding| encoding.bom().is_some_and(|bom| bytes.starts_with(bom)))
+    }
+}
+
+/// A byte reader that decodes the text of another byte reader to UTF-8.
+///
+/// A byte order mark at the start of the text is detected and stripped, and
+/// its encoding overrides the given one. Invalid UTF-16 is reported as an
+/// [IO error](std::io::Error) of kind [`InvalidData`](std::io::ErrorKind::InvalidData).
+///
+/// # Example
+///
+/// ```
+/// use ssv::chars::{DecodingReader, Encoding, Reader};
+///
+/// let input = b"\xFF\xFEa\x00 \x00\xE9\x00\n\x00";
+///
+/// let reader = Reader::new(DecodingReader::new(&input[..], Encoding::Latin1));
+///
+/// let rows: Vec<_> = reader.collect::<Result<_, _>>()?;
+/// assert_eq!(rows, [vec!["a", "é"]]);
+/// # Ok::<_, ssv::chars::ReadError>(())
+/// ```
+pub struct DecodingReader<R: Read> {
+    inner: R,
+    encoding: Encoding,
+    /// Whether the start of the text was checked for a byte order mark.
+    started: bool,
+    /// The bytes read from the inner reader and not decoded yet.
+    input: Vec<u8>,
+    /// The decoded bytes, from `output_index` on, that were not returned yet.
+    output: Vec<u8>,
+    output_index: usize,
+}
+
+impl<R: Read> DecodingReader<R> {
+    /// Creates an instance that decodes the text in the given encoding, unless
+    /// it starts with a byte order mark.
+    pub fn new(inner: R, encoding: Encoding) -> Self {
+        DecodingReader {
+            inner,
+            encoding,
+            started: false,
+            input: Vec::new(),
+            output: Vec::new(),
+            output_index: 0,
+        }
+    }
+
+    /// Returns the encoding of the text.
+    ///
+    /// It changes to the encoding of the byte order mark, if any, after the
+    /// first read.
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    /// Reads and decodes more text into the output buffer. Returns `false` at
+    /// the end of the text.
+    fn fill_output(&mut self) -> std::io::Result<bool> {
+        self.output.clear();
+        self.output_index = 0;
+
+        let mut chunk = [0; CHUNK_SIZE];
+        while self.output.is_empty() {
+            let length = self.inner.read(&mut chunk)?;
+            self.input.extend_from_slice(&chunk[..length]);
+
+            if !self.started {
+                let longest_bom = UTF8_BOM.len();
+                if length > 0 && self.input.len() < longest_bom {
+                    continue;
+                }
+                if let Some(encoding) = Encoding::from_bom(&self.input) {
+                    self.encoding = encoding;
+                    self.input.drain(..encoding.bom().unwrap().len());
+                }
+                self.started = true;
+            }
+
+            self.decode()?;
+            if length == 0 {
+                if !self.input.is_empty() {
+                    return Err(invalid_data("incomplete UTF-16 code unit"));
+                }
+                return Ok(!self.output.is_empty());
+            }
+        }
+        Ok(true)
+    }
+
+    /// Decodes the input into the output, leaving an incomplete UTF-16
+    /// character in the input.
+    fn decode(&mut self) -> std::io::Result<()> {
+        let decoded_length = match self.encoding {
+            Encoding::Utf8 => {
+                self.output.extend_from_slice(&self.input);
+                self.input.len()
+            }
+            Encoding::Latin1 => {
+                for byte in &self.input {
+                    push_char(&mut self.output, char::from(*byte));
+                }
+                self.input.len()
+            }
+            Encoding::Windows1252 => {
+                for byte in &self.input {
+                    let c = match byte {
+                        0x80..=0x9F => WINDOWS_1252_HIGH[usize::from(byte - 0x80)],
+                        _ => char::from(*byte),
+                    };
+                    push_char(&mut self.output, c);
+                }
+                self.input.len()
+            }
+            Encoding::Utf16Le => self.decode_utf16(u16::from_le_bytes)?,
+            Encoding::Utf16Be => self.decode_utf16(u16::from_be_bytes)?,
+        };
+        self.input.drain(..decoded_length);
+        Ok(())
+    }
+
+    fn decode_utf16(&mut self, from_bytes: fn([u8; 2]) -> u16) -> std::io::Result<usize> {
+        let units: Vec<u16> = self
+            .input
+            .chunks_exact(2)
+            .map(|pair| from_bytes([pair[0], pair[1]]))
+            .collect();
+        // A high surrogate at the end may be completed by the next chunk
+        let complete_units = match units.last() {
+            Some(unit) if (0xD800..0xDC00).contains(unit) => units.len() - 1,
+            _ => units.len(),
+        };
+
+        for c in char::decode_utf16(units[..complete_units].iter().copied()) {
+            let c = c.map_err(|_| invalid_data("unpaired UTF-16 surrogate"))?;
+            push_char(&mut self.output, c);
+        }
+        Ok(complete_units * 2)
+    }
+}
+
+impl<R: Read> Read for DecodingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.output_index == self.output.len() && !self.fill_output()? {
+            return Ok(0);
+        }
+
+        let available = &self.output[self.output_index..];
+        let length = available.len().min(buf.len());
+        buf[..length].copy_from_slice(&available[..length]);
+        self.output_index += length;
+        Ok(length)
+    }
+}
+
+/// A byte writer that encodes the UTF-8 text written to it into another byte
+/// writer.
+///
+/// The text must be UTF-8, and, when encoding to [`Latin1`](Encoding::Latin1)
+/// or [`Windows1252`](Encoding::Windows1252), its characters must exist in the
+/// encoding. Otherwise an [IO error](std::io::Error) of kind
+/// [`InvalidData`](std::io::ErrorKind::InvalidData) is returned.
+///
+/// Prefer to explicitly call the [`finish`](EncodingWriter::finish) method,
+/// which reports a character left incomplete.
+///
+/// # Example
+///
+/// ```
+/// use ssv::chars::{EncodingWriter, Encoding, Writer};
+///
+/// let mut output = Vec::new();
+/// let mut encoding_writer = EncodingWriter::new(&mut output, Encoding::Utf16Le).with_bom(true);
+///
+/// let mut writer = Writer::new(&mut encoding_writer);
+/// writer.write_row(["a", "é"])?;
+/// writer.finish()?;
+/// encoding_writer.finish()?;
+///
+/// assert_eq!(output, b"\xFF\xFEa\x00 \x00\xE9\x00\n\x00");
+/// # Ok::<_, ssv::chars::WriteError>(())
+/// ```
+pub struct EncodingWriter<W: Write> {
+    inner: W,
+    encoding: Encoding,
+    bom: bool,
+    started: bool,
+    /// The bytes of an incomplete UTF-8 character.
+    pending: Vec<u8>,
+}
+
+impl<W: Write> EncodingWriter<W> {
+    /// Creates an instance that encodes the text in the given encoding,
+    /// without byte order mark.
+    pub fn new(inner: W, encoding: Encoding) -> Self {
+        EncodingWriter {
+            inner,
+            encoding,
+            bom: false,
+            started: false,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Sets whether the text is preceded by the byte order mark of the
+    /// encoding, if it has one.
+    pub fn with_bom(mut self, bom: bool) -> Self {
+        self.bom = bom;
+        self
+    }
+
+    /// Finalizes the object by flushing the underlying byte writer.
+    ///
+    /// The byte order mark is written even if no text was written.
+    pub fn finish(mut self) -> std::io::Result<()> {
+        self.start()?;
+        if !self.pending.is_empty() {
+            return Err(invalid_data("incomplete UTF-8 character"));
+        }
+        self.inner.flush()
+    }
+
+    fn start(&mut self) -> std::io::Result<()> {
+        if !self.started {
+            self.started = true;
+            if let Some(bom) = self.encoding.bom().filter(|_| self.bom) {
+                self.inner.write_all(bom)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn encode(&self, text: &str) -> std::io::Result<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(text.len());
+        match self.encoding {
+            Encoding::Utf8 => bytes.extend_from_slice(text.as_bytes()),
+            Encoding::Utf16Le => text
+                .encode_utf16()
+                .for_each(|unit| bytes.extend(unit.to_le_bytes())),
+            Encoding::Utf16Be => text
+                .encode_utf16()
+                .for_each(|unit| bytes.extend(unit.to_be_bytes())),
+            Encoding::Latin1 => {
+                for c in text.chars() {
+                    let byte = u8::try_from(c).map_err(|_| unmappable(c, self.encoding))?;
+                    bytes.push(byte);
+                }
+            }
+            Encoding::Windows1252 => {
+                for c in text.chars() {
+                    let byte = match u8::try_from(c) {
+                        Ok(byte) if !(0x80..=0x9F).contains(&byte) => byte,
+                        _ => WINDOWS_1252_HIGH
+                            .iter()
+                            .position(|high| *high == c)
+                            .map(|index| 0x80 + index as u8)
+                            .ok_or_else(|| unmappable(c, self.encoding))?,
+                    };
+                    bytes.push(byte);
+                }
+            }
+        }
+        Ok(bytes)
+    }
+}
+
+impl<W: Write> Write for EncodingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.start()?;
+
+        self.pending.extend_from_slice(buf);
+        let valid_length = match std::str::from_utf8(&self.pending) {
+            Ok(text) => text.len(),
+            Err(error) if error.error_len().is_none() => error.valid_up_to(),
+            Err(error) => {
+                self.pending.clear();
+                return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, error));
+            }
+        };
+
+        let text = std::str::from_utf8(&self.pending[..valid_length]).unwrap();
+        let result = self
+            .encode(text)
+            .and_then(|bytes| self.inner.write_all(&bytes));
+        self.pending.drain(..valid_length);
+        result.map(|_| buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+fn push_char(output: &mut Vec<u8>, c: char) {
+    let mut buffer = [0; 4];
+    output.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+}
+
+fn invalid_data(message: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+fn unmappable(c: char, encoding: Encoding) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        format!("character {c:?} cannot be encoded in {encoding:?}"),
+    )
+}
@@ -0,0 +1,712 @@
+//! Filters rows with predicate expressions.
+
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt::Display;
+use std::io::{Read, Write};
+use std::ops::Deref;
+use std::str::FromStr;
+
+use crate::engine::domain::{Domain, DomainString, DomainStringSlice};
+use crate::syntax::numeric::{is_numeric, parse_numeric};
+
+use super::columns::{ColumnNotFoundError, ColumnRef};
+use super::reader::Reader;
+use super::writer::Writer;
+use super::{ReadResult, ReadWriteError, ReadWriteResult};
+
+/// Filters SSV rows with a predicate expression.
+#[doc = generic_item_warning_doc!("Filter")]
+/// A filter is parsed from an expression that references columns by their name
+/// in the header row, which is the first row.
+///
+/// # Expressions
+///
+/// An expression is built from these elements:
+/// * Operands:
+///   * column names, either bare (letters, digits, `_` and `.`, not starting
+///     with a digit) or enclosed in backticks (`` ` ``);
+///   * strings, enclosed in double quotes (`"`);
+///   * numbers, such as `30`, `-1.5` or `2e3`.
+/// * Comparisons: `=` (or `==`), `!=`, `<`, `<=`, `>` and `>=`. If both values
+///   are numbers, they are compared numerically (a comma (`,`) is accepted as
+///   decimal separator). Otherwise, they are compared as strings.
+/// * Glob matching: `operand ~ "pattern"` and `operand !~ "pattern"`, where `*`
+///   matches any sequence of elements, `?` matches a single element and `\`
+///   escapes the next character. The whole value must match the pattern.
+/// * Checks: `operand is null`, `operand is not null`, `operand is empty` and
+///   `operand is not empty`. A value is null if the row has no value in the
+///   column, and it is empty if it is null or an empty string.
+/// * Boolean logic: `not`, `and` and `or`, in decreasing order of precedence,
+///   and parentheses.
+///
+/// Any comparison or glob matching with a null value is false. Quotes and
+/// backticks are escaped by duplicating them. A column named as a keyword
+/// (`and`, `or`, `not`, `is`, `null` or `empty`) must be enclosed in backticks.
+///
+/// # Example
+///
+/// ```
+/// use ssv::chars::Filter;
+///
+/// let input = "name age\nJohn 35\nJane 25\nBob 40\n";
+/// let mut output = Vec::new();
+///
+/// let filter: Filter = r#"age > 30 and name ~ "J*""#.parse()?;
+/// filter.transform(input.as_bytes(), &mut output)?;
+///
+/// assert_eq!(output, b"name age\nJohn 35\n");
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct Filter<D: Domain> {
+    expression: Expression<D>,
+    columns: Vec<ColumnRef<D>>,
+}
+
+enum Expression<D: Domain> {
+    Or(Box<Expression<D>>, Box<Expression<D>>),
+    And(Box<Expression<D>>, Box<Expression<D>>),
+    Not(Box<Expression<D>>),
+    Compare(Operand<D>, Comparison, Operand<D>),
+    Match(Operand<D>, Vec<GlobItem<D::Element>>),
+    IsNull(Operand<D>),
+    IsEmpty(Operand<D>),
+}
+
+enum Operand<D: Domain> {
+    /// The index of the column in [`Filter::columns`].
+    Column(usize),
+    Literal(D::String),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+enum GlobItem<E> {
+    Element(E),
+    AnyElement,
+    AnySequence,
+}
+
+impl<D: Domain> Filter<D> {
+    /// Parses a filter from an expression.
+    ///
+    /// See the [expression syntax](Filter#expressions).
+    pub fn parse(expression: &str) -> Result<Self, ExpressionError> {
+        Parser::new(expression)?.parse()
+    }
+
+    /// Returns whether a row satisfies the filter, given the header row.
+    pub fn matches(
+        &self,
+        header: &[D::String],
+        row: &[D::String],
+    ) -> Result<bool, ColumnNotFoundError> {
+        Ok(self.evaluate(&self.expression, &self.bind(header)?, row))
+    }
+
+    /// Returns an iterator that returns the header row and the rows that
+    /// satisfy the filter, from the rows returned by the given iterator, such as
+    /// a [`Reader`].
+    pub fn apply<I>(&self, rows: I) -> Filtered<'_, D, I>
+    where
+        I: Iterator<Item = ReadResult<Vec<D::String>>>,
+    {
+        Filtered {
+            filter: self,
+            rows,
+            state: FilteredState::Header,
+        }
+    }
+
+    /// Reads SSV from the given byte reader and writes the header row and the
+    /// rows that satisfy the filter to the given byte writer.
+    ///
+    /// Comment lines are not written.
+    pub fn transform<R: Read, W: Write>(&self, reader: R, writer: W) -> ReadWriteResult<()> {
+        let mut writer = Writer::<D, W>::new(writer);
+        for row in self.apply(Reader::<D, R>::new(reader)) {
+            writer.write_row(row?.iter().map(Deref::deref))?;
+        }
+        writer.finish()?;
+        Ok(())
+    }
+
+    fn bind(&self, header: &[D::String]) -> Result<Vec<usize>, ColumnNotFoundError> {
+        self.columns
+            .iter()
+            .map(|column| column.try_resolve(Some(header)))
+            .collect()
+    }
+
+    fn evaluate(&self, expression: &Expression<D>, indices: &[usize], row: &[D::String]) -> bool {
+        match expression {
+            Expression::Or(left, right) => {
+                self.evaluate(left, indices, row) || self.evaluate(right, indices, row)
+            }
+            Expression::And(left, right) => {
+                self.evaluate(left, indices, row) && self.evaluate(right, indices, row)
+            }
+            Expression::Not(expression) => !self.evaluate(expression, indices, row),
+            Expression::Compare(left, comparison, right) => {
+                match (
+                    Self::value(left, indices, row),
+                    Self::value(right, indices, row),
+                ) {
+                    (Some(left), Some(right)) => comparison.accepts(compare(left, right)),
+                    _ => false,
+                }
+            }
+            Expression::Match(operand, pattern) => Self::value(operand, indices, row)
+                .is_some_and(|value| glob_matches(pattern, &value.elements())),
+            Expression::IsNull(operand) => Self::value(operand, indices, row).is_none(),
+            Expression::IsEmpty(operand) => {
+                Self::value(operand, indices, row).is_none_or(|value| value.as_bytes().is_empty())
+            }
+        }
+    }
+
+    fn value<'a>(
+        operand: &'a Operand<D>,
+        indices: &[usize],
+        row: &'a [D::String],
+    ) -> Option<&'a D::StringSlice> {
+        match operand {
+            Operand::Column(column) => row.get(indices[*column]).map(Deref::deref),
+            Operand::Literal(literal) => Some(literal),
+        }
+    }
+}
+
+impl<D: Domain> FromStr for Filter<D> {
+    type Err = ExpressionError;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        Self::parse(expression)
+    }
+}
+
+impl Comparison {
+    fn accepts(self, ordering: Ordering) -> bool {
+        match self {
+            Comparison::Equal => ordering.is_eq(),
+            Comparison::NotEqual => ordering.is_ne(),
+            Comparison::Less => ordering.is_lt(),
+            Comparison::LessOrEqual => ordering.is_le(),
+            Comparison::Greater => ordering.is_gt(),
+            Comparison::GreaterOrEqual => ordering.is_ge(),
+        }
+    }
+}
+
+fn compare<S: DomainStringSlice<E> + ?Sized, E>(left: &S, right: &S) -> Ordering {
+    let (left, right) = (left.as_bytes(), right.as_bytes());
+    match (parse_numeric(left), parse_numeric(right)) {
+        (Some(left), Some(right)) => left.partial_cmp(&right).unwrap_or(Ordering::Equal),
+        _ => left.cmp(right),
+    }
+}
+
+fn glob_matches<E: Eq>(pattern: &[GlobItem<E>], value: &[E]) -> bool {
+    let (mut pattern_index, mut value_index) = (0, 0);
+    let mut backtrack = None;
+
+    while value_index < value.len() {
+        match pattern.get(pattern_index) {
+            Some(GlobItem::AnySequence) => {
+                backtrack = Some((pattern_index, value_index));
+                pattern_index += 1;
+                continue;
+            }
+            Some(GlobItem::AnyElement) => {
+                pattern_index += 1;
+                value_index += 1;
+                continue;
+            }
+            Some(GlobItem::Element(element)) if *element == value[value_index] => {
+                pattern_index += 1;
+                value_index += 1;
+                continue;
+            }
+            _ => {}
+        }
+
+        match backtrack {
+            Some((star_index, star_value_index)) => {
+                backtrack = Some((star_index, star_value_index + 1));
+                pattern_index = star_index + 1;
+                value_index = star_value_index + 1;
+            }
+            None => return false,
+        }
+    }
+
+    pattern[pattern_index..]
+        .iter()
+        .all(|item| matches!(item, GlobItem::AnySequence))
+}
+
+/// An iterator that applies a [`Filter`] to rows.
+#[doc = generic_item_warning_doc!("Filtered")]
+/// It is returned by the [`Filter::apply`] method. The first row is the header
+/// row, which is returned unchanged.
+pub struct Filtered<'a, D: Domain, I> {
+    filter: &'a Filter<D>,
+    rows: I,
+    state: FilteredState,
+}
+
+enum FilteredState {
+    Header,
+    Bound(Vec<usize>),
+    Failed(Option<ReadWriteError>),
+}
+
+impl<D: Domain, I> Iterator for Filtered<'_, D, I>
+where
+    I: Iterator<Item = ReadResult<Vec<D::String>>>,
+{
+    type Item = ReadWriteResult<Vec<D::String>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let FilteredState::Failed(error) = &mut self.state {
+            return error.take().map(Err);
+        }
+
+        for row in self.rows.by_ref() {
+            let row = match row {
+                Ok(row) => row,
+                Err(error) => return Some(Err(error.into())),
+            };
+
+            match &self.state {
+                FilteredState::Header => match self.filter.bind(&row) {
+                    Ok(indices) => {
+                        self.state = FilteredState::Bound(indices);
+                        return Some(Ok(row));
+                    }
+                    Err(error) => {
+                        self.state = FilteredState::Failed(None);
+                        return Some(Err(error.into()));
+                    }
+                },
+                FilteredState::Bound(indices) => {
+                    if self.filter.evaluate(&self.filter.expression, indices, &row) {
+                        return Some(Ok(row));
+                    }
+                }
+                FilteredState::Failed(_) => unreachable!(),
+            }
+        }
+        None
+    }
+}
+
+/// The error type for parsing a [`Filter`] expression.
+///
+/// The column numbers count chars, starting from 1.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ExpressionError {
+    /// An unexpected character was found.
+    UnexpectedChar(char, usize),
+
+    /// A valid token was found where it is not allowed.
+    UnexpectedToken(String, usize),
+
+    /// The expression ended where more was expected.
+    UnexpectedEnd(usize),
+
+    /// A string or a column name was not closed.
+    UnclosedQuote(usize),
+}
+
+impl Error for ExpressionError {}
+
+impl Display for ExpressionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExpressionError::UnexpectedChar(char, column_number) => {
+                write!(f, "unexpected {char:?} at column {column_number}")
+            }
+            ExpressionError::UnexpectedToken(token, column_number) => {
+                write!(f, "unexpected {token:?} at column {column_number}")
+            }
+            ExpressionError::UnexpectedEnd(column_number) => {
+                write!(f, "unexpected end at column {column_number}")
+            }
+            ExpressionError::UnclosedQuote(column_number) => {
+                write!(f, "unclosed quote at column {column_number}")
+            }
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
+enum TokenKind {
+    Identifier,
+    Name(String),
+    String(String),
+    Number,
+    OpenParenthesis,
+    CloseParenthesis,
+    Comparison(Comparison),
+    Match { negated: bool },
+    End,
+}
+
+struct Token<'a> {
+    kind: TokenKind,
+    text: &'a str,
+    column_number: usize,
+}
+
+const KEYWORDS: [&str; 6] = ["and", "or", "not", "is", "null", "empty"];
+
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    index: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(expression: &'a str) -> Result<Self, ExpressionError> {
+        Ok(Parser {
+            tokens: Lexer::new(expression).tokenize()?,
+            index: 0,
+        })
+    }
+
+    fn parse<D: Domain>(mut self) -> Result<Filter<D>, ExpressionError> {
+        let mut columns = Vec::new();
+        let expression = self.parse_or(&mut columns)?;
+        match self.peek().kind {
+            TokenKind::End => Ok(Filter {
+                expression,
+                columns,
+            }),
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn parse_or<D: Domain>(
+        &mut self,
+        columns: &mut Vec<ColumnRef<D>>,
+    ) -> Result<Expression<D>, ExpressionError> {
+        let mut expression = self.parse_and(columns)?;
+        while self.accept_keyword("or") {
+            let right = self.parse_and(columns)?;
+            expression = Expression::Or(Box::new(expression), Box::new(right));
+        }
+        Ok(expression)
+    }
+
+    fn parse_and<D: Domain>(
+        &mut self,
+        columns: &mut Vec<ColumnRef<D>>,
+    ) -> Result<Expression<D>, ExpressionError> {
+        let mut expression = self.parse_not(columns)?;
+        while self.accept_keyword("and") {
+            let right = self.parse_not(columns)?;
+            expression = Expression::And(Box::new(expression), Box::new(right));
+        }
+        Ok(expression)
+    }
+
+    fn parse_not<D: Domain>(
+        &mut self,
+        columns: &mut Vec<ColumnRef<D>>,
+    ) -> Result<Expression<D>, ExpressionError> {
+        if self.accept_keyword("not") {
+            Ok(Expression::Not(Box::new(self.parse_not(columns)?)))
+        } else {
+            self.parse_primary(columns)
+        }
+    }
+
+    fn parse_primary<D: Domain>(
+        &mut self,
+        columns: &mut Vec<ColumnRef<D>>,
+    ) -> Result<Expression<D>, ExpressionError> {
+        if self.peek().kind == TokenKind::OpenParenthesis {
+            self.index += 1;
+            let expression = self.parse_or(columns)?;
+            return match self.peek().kind {
+                TokenKind::CloseParenthesis => {
+                    self.index += 1;
+                    Ok(expression)
+                }
+                _ => Err(self.unexpected()),
+            };
+        }
+
+        let operand = self.parse_operand(columns)?;
+        match self.peek().kind.clone() {
+            TokenKind::Comparison(comparison) => {
+                self.index += 1;
+                let right = self.parse_operand(columns)?;
+                Ok(Expression::Compare(operand, comparison, right))
+            }
+            TokenKind::Match { negated } => {
+                self.index += 1;
+                let TokenKind::String(pattern) = self.peek().kind.clone() else {
+                    return Err(self.unexpected());
+                };
+                self.index += 1;
+                let expression = Expression::Match(operand, Self::parse_glob::<D>(&pattern));
+                Ok(if negated {
+                    Expression::Not(Box::new(expression))
+                } else {
+                    expression
+                })
+            }
+            TokenKind::Identifier if self.peek().text == "is" => {
+                self.index += 1;
+                let negated = self.accept_keyword("not");
+                let expression = if self.accept_keyword("null") {
+                    Expression::IsNull(operand)
+                } else if self.accept_keyword("empty") {
+                    Expression::IsEmpty(operand)
+                } else {
+                    return Err(self.unexpected());
+                };
+                Ok(if negated {
+                    Expression::Not(Box::new(expression))
+                } else {
+                    expression
+                })
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn parse_operand<D: Domain>(
+        &mut self,
+        columns: &mut Vec<ColumnRef<D>>,
+    ) -> Result<Operand<D>, ExpressionError> {
+        let token = self.peek();
+        let operand = match &token.kind {
+            TokenKind::Identifier if !KEYWORDS.contains(&token.text) => {
+                Self::column(columns, token.text)
+            }
+            TokenKind::Name(name) => Self::column(columns, name),
+            TokenKind::String(string) => Operand::Literal(D::String::from_text(string)),
+            TokenKind::Number => Operand::Literal(D::String::from_text(token.text)),
+            _ => return Err(self.unexpected()),
+        };
+        self.index += 1;
+        Ok(operand)
+    }
+
+    fn column<D: Domain>(columns: &mut Vec<ColumnRef<D>>, name: &str) -> Operand<D> {
+        let column = ColumnRef::Name(D::String::from_text(name));
+        let index = match columns.iter().position(|existing| *existing == column) {
+            Some(index) => index,
+            None => {
+                columns.push(column);
+                columns.len() - 1
+            }
+        };
+        Operand::Column(index)
+    }
+
+    fn parse_glob<D: Domain>(pattern: &str) -> Vec<GlobItem<D::Element>> {
+        let mut items = Vec::new();
+        let mut chars = pattern.chars();
+        while let Some(char) = chars.next() {
+            let literal = match char {
+                '*' => {
+                    items.push(GlobItem::AnySequence);
+                    continue;
+                }
+                '?' => {
+                    items.push(GlobItem::AnyElement);
+                    continue;
+                }
+                '\\' => chars.next().unwrap_or('\\'),
+                _ => char,
+            };
+            let literal = D::String::from_text(literal.encode_utf8(&mut [0; 4]));
+            items.extend(literal.elements().into_iter().map(GlobItem::Element));
+        }
+        items
+    }
+
+    fn accept_keyword(&mut self, keyword: &str) -> bool {
+        let token = self.peek();
+        if token.kind == TokenKind::Identifier && token.text == keyword {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn peek(&self) -> &Token<'a> {
+        &self.tokens[self.index]
+    }
+
+    fn unexpected(&self) -> ExpressionError {
+        let token = self.peek();
+        match token.kind {
+            TokenKind::End => ExpressionError::UnexpectedEnd(token.column_number),
+            _ => ExpressionError::UnexpectedToken(token.text.to_owned(), token.column_number),
+        }
+    }
+}
+
+struct Lexer<'a> {
+    expression: &'a str,
+    chars: Vec<(usize, char)>,
+    index: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(expression: &'a str) -> Self {
+        Lexer {
+            expression,
+            chars: expression.char_indices().collect(),
+            index: 0,
+        }
+    }
+
+    fn tokenize(mut self) -> Result<Vec<Token<'a>>, ExpressionError> {
+        let mut tokens = Vec::new();
+        loop {
+            while self.peek().is_some_and(char::is_whitespace) {
+                self.index += 1;
+            }
+
+            let start = self.index;
+            let Some(char) = self.next() else {
+                tokens.push(Token {
+                    kind: TokenKind::End,
+                    text: "",
+                    column_number: start + 1,
+                });
+                return Ok(tokens);
+            };
+
+            let kind = match char {
+                '(' => TokenKind::OpenParenthesis,
+                ')' => TokenKind::CloseParenthesis,
+                '=' => {
+                    self.accept('=');
+                    TokenKind::Comparison(Comparison::Equal)
+                }
+                '!' if self.accept('=') => TokenKind::Comparison(Comparison::NotEqual),
+                '!' if self.accept('~') => TokenKind::Match { negated: true },
+                '~' => TokenKind::Match { negated: false },
+                '<' if self.accept('=') => TokenKind::Comparison(Comparison::LessOrEqual),
+                '<' => TokenKind::Comparison(Comparison::Less),
+                '>' if self.accept('=') => TokenKind::Comparison(Comparison::GreaterOrEqual),
+                '>' => TokenKind::Comparison(Comparison::Greater),
+                '"' | '`' => {
+                    let text = self.quoted(char, start)?;
+                    if char == '"' {
+                        TokenKind::String(text)
+                    } else {
+                        TokenKind::Name(text)
+                    }
+                }
+                '0'..='9' | '.' | '-' | '+' => {
+                    self.index = start;
+                    self.number()
+                        .ok_or(ExpressionError::UnexpectedChar(char, start + 1))?
+                }
+                _ if char.is_alphabetic() || char == '_' => {
+                    while self
+                        .peek()
+                        .is_some_and(|char| char.is_alphanumeric() || matches!(char, '_' | '.'))
+                    {
+                        self.index += 1;
+                    }
+                    TokenKind::Identifier
+                }
+                _ => return Err(ExpressionError::UnexpectedChar(char, start + 1)),
+            };
+
+            tokens.push(Token {
+                kind,
+                text: self.text(start),
+                column_number: start + 1,
+            });
+        }
+    }
+
+    fn quoted(&mut self, quote: char, start: usize) -> Result<String, ExpressionError> {
+        let mut text = String::new();
+        loop {
+            match self.next() {
+                Some(char) if char == quote => {
+                    if !self.accept(quote) {
+                        return Ok(text);
+                    }
+                    text.push(quote);
+                }
+                Some(char) => text.push(char),
+                None => return Err(ExpressionError::UnclosedQuote(start + 1)),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Option<TokenKind> {
+        let start = self.index;
+        while self.peek().is_some_and(|char| {
+            char.is_ascii_alphanumeric() || matches!(char, '.' | ',' | '+' | '-')
+        }) {
+            let previous = self.index;
+            self.index += 1;
+            // Signs are only allowed at the beginning and after the exponent.
+            if matches!(self.chars[previous].1, '+' | '-')
+                && previous != start
+                && !matches!(self.chars[previous - 1].1, 'e' | 'E')
+            {
+                self.index = previous;
+                break;
+            }
+            // A comma is a decimal separator only if followed by a digit.
+            if self.chars[previous].1 == ','
+                && !self.peek().is_some_and(|char| char.is_ascii_digit())
+            {
+                self.index = previous;
+                break;
+            }
+        }
+
+        is_numeric(self.text(start).as_bytes()).then_some(TokenKind::Number)
+    }
+
+    fn text(&self, start: usize) -> &'a str {
+        let byte_index = |index: usize| {
+            self.chars
+                .get(index)
+                .map_or(self.expression.len(), |(byte_index, _)| *byte_index)
+        };
+        &self.expression[byte_index(start)..byte_index(self.index)]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.index).map(|(_, char)| *char)
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let char = self.peek()?;
+        self.index += 1;
+        Some(char)
+    }
+
+    fn accept(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+}
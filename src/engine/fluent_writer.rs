@@ -1,12 +1,14 @@
 //! Writes SSV using a fluent interface. Automatically inserts delimiters.
 
+use std::collections::BTreeMap;
 use std::io::Write;
 
 use crate::engine::domain::Domain;
 use crate::engine::LineBreak;
+use crate::syntax::writer::{Layout, Sink};
 
-use super::domain::{BytesDomain, DomainStringSlice};
-use super::options::Options;
+use super::domain::DomainStringSlice;
+use super::options::{CommentLineBreakPolicy, Options, QuotingPolicy};
 use super::{WriteError, WriteResult};
 
 /// Has a fluent interface to write SSV to a byte writer.
@@ -36,25 +38,31 @@ use super::{WriteError, WriteResult};
 /// ```
 #[derive(Debug)]
 pub struct FluentWriter<D: Domain, W: Write> {
-    inner: W,
-    state: State,
+    inner: IoSink<W>,
+    layout: Layout,
     options: Options<D>,
+    column_quoting_policies: BTreeMap<usize, QuotingPolicy>,
 }
 
 impl<D: Domain, W: Write> FluentWriter<D, W> {
     /// Creates an instance that writes SSV to the given byte writer.
     pub fn new(inner: W) -> Self {
         FluentWriter {
-            inner,
-            state: State::LineBegin,
+            inner: IoSink(inner),
+            layout: Layout::new(),
             options: Options::new(),
+            column_quoting_policies: BTreeMap::new(),
         }
     }
 
     /// Writes a value.
     ///
-    /// The value is enclosed in quotes if required (check the [rules](crate#rules))
-    /// or if the [`always_quoted`](FluentWriter::always_quoted) option is `true`.
+    /// The value is enclosed in quotes according to the
+    /// [quoting policy](FluentWriter::column_quoting_policy) of the column where the
+    /// value is written. Columns are counted from 0 since the last line-break.
+    /// With the [`QuotingPolicy::Never`] policy, writing a value that requires
+    /// quotes (check the [rules](crate#rules)) fails with
+    /// [`WriteError::QuotesRequired`].
     ///
     /// If the last wroten item was another value, then the
     /// [default spacing](FluentWriter::default_spacing) is automatically written
@@ -81,36 +89,16 @@ impl<D: Domain, W: Write> FluentWriter<D, W> {
     }
 
     fn write_value_raw(mut self, value: &D::StringSlice, quoted: bool) -> WriteResult<Self> {
-        let mut this = match self.state {
-            State::Value => {
-                Self::write_spacing_raw(
-                    &mut self.inner,
-                    self.options.default_spacing().as_bytes(),
-                    &mut self.state,
-                )?;
-                self
-            }
-            State::Comment => self.write_line_break()?,
-            _ => self,
-        };
-
-        let prepared_value = PreparedValue::from(value.as_bytes());
-        let quoted = quoted
-            || prepared_value.must_be_quoted
-            || this.always_quoted()
-            || (this.state == State::LineBegin
-                && prepared_value.bytes.first() == Some(&BytesDomain::HASH));
-
-        if quoted {
-            this.write(&[BytesDomain::QUOTE])?;
-        }
-        this.write(&prepared_value.bytes)?;
-        if quoted {
-            this.write(&[BytesDomain::QUOTE])?;
-        }
-
-        this.state = State::Value;
-        Ok(this)
+        let quoting_policy = self.column_quoting_policy(self.layout.column_index());
+        self.layout.write_value(
+            &mut self.inner,
+            value.as_bytes(),
+            quoted,
+            quoting_policy,
+            self.options.default_spacing().as_bytes(),
+            self.options.default_line_break(),
+        )?;
+        Ok(self)
     }
 
     /// Writes the specified spacing.
@@ -118,28 +106,17 @@ impl<D: Domain, W: Write> FluentWriter<D, W> {
     /// If the last wroten item was a comment, then the
     /// [default line-break](FluentWriter::default_line_break) is automatically
     /// written before this value.
-    pub fn write_spacing(self, spacing: &D::StringSlice) -> WriteResult<Self> {
+    pub fn write_spacing(mut self, spacing: &D::StringSlice) -> WriteResult<Self> {
         if !D::is_valid_spacing(spacing) {
             return Err(WriteError::InvalidSpacing);
         }
 
-        let mut this = match self.state {
-            State::Comment => self.write_line_break()?,
-            _ => self,
-        };
-
-        Self::write_spacing_raw(&mut this.inner, spacing.as_bytes(), &mut this.state)?;
-        Ok(this)
-    }
-
-    fn write_spacing_raw(
-        writer: &mut W,
-        spacing_bytes: &[u8],
-        state: &mut State,
-    ) -> WriteResult<()> {
-        Self::write_raw(writer, spacing_bytes)?;
-        *state = State::Spacing;
-        Ok(())
+        self.layout.write_spacing(
+            &mut self.inner,
+            spacing.as_bytes(),
+            self.options.default_line_break(),
+        )?;
+        Ok(self)
     }
 
     /// Writes the [default line-break](FluentWriter::default_line_break).
@@ -150,13 +127,7 @@ impl<D: Domain, W: Write> FluentWriter<D, W> {
 
     /// Writes the specified line-break.
     pub fn write_this_line_break(mut self, line_break: LineBreak) -> WriteResult<Self> {
-        let bytes: &[u8] = match line_break {
-            LineBreak::Lf => &[BytesDomain::LF],
-            LineBreak::CrLf => &[BytesDomain::CR, BytesDomain::LF],
-        };
-        self.write(bytes)?;
-
-        self.state = State::LineBegin;
+        self.layout.write_line_break(&mut self.inner, line_break)?;
         Ok(self)
     }
 
@@ -167,26 +138,33 @@ impl<D: Domain, W: Write> FluentWriter<D, W> {
     /// If the last wroten item was a value, spacing, or another comment, then
     /// the [default line-break](FluentWriter::default_line_break) is automatically
     /// written before the HASH sign and the comment.
-    pub fn write_comment(self, comment: &D::StringSlice) -> WriteResult<Self> {
-        let mut this = match self.state {
-            State::Value | State::Spacing | State::Comment => self.write_line_break()?,
-            _ => self,
-        };
-
-        this.write(&[BytesDomain::HASH])?;
-        this.write(comment.as_bytes())?;
-
-        this.state = State::Comment;
-        Ok(this)
-    }
-
-    fn write(&mut self, bytes: &[u8]) -> WriteResult<()> {
-        Self::write_raw(&mut self.inner, bytes)
+    ///
+    /// If the comment contains line-breaks, it is handled according to the
+    /// [comment line-break policy](FluentWriter::comment_line_break_policy):
+    /// either it fails with [`WriteError::CommentWithLineBreak`], or each line
+    /// of the comment is written as a separate comment.
+    pub fn write_comment(mut self, comment: &D::StringSlice) -> WriteResult<Self> {
+        self.layout.write_comment(
+            &mut self.inner,
+            comment.as_bytes(),
+            self.options.comment_line_break_policy(),
+            self.options.default_line_break(),
+        )?;
+        Ok(self)
     }
 
-    fn write_raw(writer: &mut W, bytes: &[u8]) -> WriteResult<()> {
-        writer.write_all(bytes)?;
-        Ok(())
+    /// Writes a comment read by a [`Tokenizer`](super::tokenizer::Tokenizer)
+    /// exactly as it is, regardless of the comment line-break policy.
+    ///
+    /// The comment cannot contain LFs, but may end with a CR, so it must not be
+    /// followed by an LF line-break.
+    pub(crate) fn write_verbatim_comment(mut self, comment: &D::StringSlice) -> WriteResult<Self> {
+        self.layout.write_verbatim_comment(
+            &mut self.inner,
+            comment.as_bytes(),
+            self.options.default_line_break(),
+        )?;
+        Ok(self)
     }
 
     /// Finalizes the object by flushing the underlying byte writer.
@@ -194,7 +172,7 @@ impl<D: Domain, W: Write> FluentWriter<D, W> {
     /// Prefer to explicitly call this method instead of letting the [`FluentWriter`]
     /// being dropped.
     pub fn finish(mut self) -> WriteResult<()> {
-        self.inner.flush()?;
+        self.inner.0.flush()?;
         Ok(())
     }
 
@@ -235,20 +213,73 @@ impl<D: Domain, W: Write> FluentWriter<D, W> {
         self
     }
 
-    /// Returns whether the [write_value](FluentWriter::write_value) should
-    /// automatically quote the values.
+    /// Returns the quoting policy used by the method
+    /// [`write_value`](FluentWriter::write_value) for the columns without a
+    /// specific [column quoting policy](FluentWriter::column_quoting_policy).
+    ///
+    /// This is the same as `self.options().quoting_policy()`.
+    pub fn quoting_policy(&self) -> QuotingPolicy {
+        self.options.quoting_policy()
+    }
+
+    /// Sets the [quoting policy](FluentWriter::quoting_policy).
+    ///
+    /// This has the same effect as `self.options_mut().set_quoting_policy(quoting_policy)`.
+    pub fn set_quoting_policy(mut self, quoting_policy: QuotingPolicy) -> Self {
+        self.options.set_quoting_policy(quoting_policy);
+        self
+    }
+
+    /// Returns the quoting policy used by the method
+    /// [`write_value`](FluentWriter::write_value) for the column with the given
+    /// index, starting from 0.
     ///
-    /// This is the same as `self.options().always_quoted()`.
-    pub fn always_quoted(&self) -> bool {
-        self.options.always_quoted()
+    /// If no specific policy was set for the column, then the
+    /// [quoting policy](FluentWriter::quoting_policy) is returned.
+    pub fn column_quoting_policy(&self, column_index: usize) -> QuotingPolicy {
+        self.column_quoting_policies
+            .get(&column_index)
+            .copied()
+            .unwrap_or(self.options.quoting_policy())
+    }
+
+    /// Sets the [quoting policy](FluentWriter::column_quoting_policy) for the
+    /// column with the given index, starting from 0.
+    pub fn set_column_quoting_policy(
+        mut self,
+        column_index: usize,
+        quoting_policy: QuotingPolicy,
+    ) -> Self {
+        self.column_quoting_policies_mut()
+            .insert(column_index, quoting_policy);
+        self
     }
 
-    /// Sets whether the [write_value](FluentWriter::write_value) should
-    /// automatically quote the values.
+    /// Removes the specific quoting policy for the column with the given index,
+    /// starting from 0, so the [quoting policy](FluentWriter::quoting_policy)
+    /// is used for it.
+    pub fn clear_column_quoting_policy(mut self, column_index: usize) -> Self {
+        self.column_quoting_policies_mut().remove(&column_index);
+        self
+    }
+
+    pub(crate) fn column_quoting_policies_mut(&mut self) -> &mut BTreeMap<usize, QuotingPolicy> {
+        &mut self.column_quoting_policies
+    }
+
+    /// Returns how the method [`write_comment`](FluentWriter::write_comment)
+    /// handles comments containing line-breaks.
     ///
-    /// This is the same as `self.options_mut().set_always_quoted(always_quoted)`.
-    pub fn set_always_quoted(mut self, always_quoted: bool) -> Self {
-        self.options.set_always_quoted(always_quoted);
+    /// This is the same as `self.options().comment_line_break_policy()`.
+    pub fn comment_line_break_policy(&self) -> CommentLineBreakPolicy {
+        self.options.comment_line_break_policy()
+    }
+
+    /// Sets the [comment line-break policy](FluentWriter::comment_line_break_policy).
+    ///
+    /// This has the same effect as `self.options_mut().set_comment_line_break_policy(policy)`.
+    pub fn set_comment_line_break_policy(mut self, policy: CommentLineBreakPolicy) -> Self {
+        self.options.set_comment_line_break_policy(policy);
         self
     }
 
@@ -269,42 +300,14 @@ impl<D: Domain, W: Write> FluentWriter<D, W> {
     }
 }
 
-struct PreparedValue {
-    bytes: Vec<u8>,
-    must_be_quoted: bool,
-}
-
-impl PreparedValue {
-    fn from(original_bytes: &[u8]) -> PreparedValue {
-        let mut only_quotes = true;
-        let mut spacing_or_line_break = false;
-
-        let mut bytes = Vec::new();
-        for byte in original_bytes {
-            bytes.push(*byte);
-
-            if *byte == BytesDomain::QUOTE {
-                bytes.push(*byte);
-            } else {
-                only_quotes = false;
+/// Adapts a byte writer to the [`Sink`] used by the [`Layout`].
+#[derive(Debug)]
+struct IoSink<W: Write>(W);
 
-                if *byte == BytesDomain::LF || BytesDomain::is_spacing_element(*byte) {
-                    spacing_or_line_break = true;
-                }
-            }
-        }
+impl<W: Write> Sink for IoSink<W> {
+    type Error = std::io::Error;
 
-        PreparedValue {
-            bytes,
-            must_be_quoted: only_quotes || spacing_or_line_break,
-        }
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.0.write_all(bytes)
     }
 }
-
-#[derive(PartialEq, Eq, Debug)]
-enum State {
-    Value,
-    Spacing,
-    LineBegin,
-    Comment,
-}
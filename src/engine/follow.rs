@@ -0,0 +1,306 @@
+//! Follows SSV files that grow, like `tail -f`.
+
+use std::collections::VecDeque;
+use std::fs::{File, Metadata};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::engine::domain::Domain;
+
+use super::position::Position;
+use super::reader::{Record, RecordReader};
+use super::reverse_reader::States;
+use super::{ReadError, ReadResult};
+
+/// Follows an SSV file that grows, returning its rows as they are appended.
+#[doc = generic_item_warning_doc!("Follower")]
+/// It is an iterator of [`ReadResult`]`<`[`FollowEvent`]`<D>>` values that never
+/// ends, unless an error happens. When the end of the file is reached, it waits
+/// for more data, checking the file at every
+/// [poll interval](Follower::poll_interval). The [`try_next`](Follower::try_next)
+/// method returns without waiting instead.
+///
+/// A row is returned only when its line-break is read, so a partially written
+/// row, including a partially written multi-line quoted value, is kept pending
+/// until it is complete.
+///
+/// The file is checked for truncation and rotation when no more data is
+/// available:
+///
+/// * If the file is shorter than what was read, it was truncated, and it is read
+///   again from its start.
+/// * If the path refers to another file, the file was rotated: the rest of the
+///   old file is read, and the new file is followed from its start. A row left
+///   incomplete in the old file is returned as it is, with an unclosed quoted
+///   value ending at the end of the old file. Rotation is detected only on Unix
+///   platforms.
+///
+/// The positions in errors are counted from the start of the followed file.
+///
+/// If the file doesn't exist, it is waited for.
+///
+/// # Example
+///
+/// ```no_run
+/// use ssv::chars::{FollowEvent, Follower};
+///
+/// # let file_path = "";
+/// for event in Follower::new(file_path) {
+///     match event? {
+///         FollowEvent::Row(row) => println!("{row:?}"),
+///         FollowEvent::Truncated => println!("(truncated)"),
+///         FollowEvent::Rotated => println!("(rotated)"),
+///     }
+/// }
+/// # Ok::<_, ssv::chars::ReadError>(())
+/// ```
+pub struct Follower<D: Domain> {
+    path: PathBuf,
+    poll_interval: Duration,
+    skip_existing: bool,
+    file: Option<FollowedFile>,
+    /// The bytes read after the last complete record.
+    pending: Vec<u8>,
+    /// The length of the complete lines in `pending`, and the state after them.
+    scanned: usize,
+    states: States,
+    /// The number of lines of the file before `pending`.
+    line_offset: usize,
+    events: VecDeque<ReadResult<FollowEvent<D>>>,
+    failed: bool,
+}
+
+impl<D: Domain> Follower<D> {
+    /// Creates an instance that follows the file at the given path from its
+    /// start, polling every second.
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Follower {
+            path: path.as_ref().to_path_buf(),
+            poll_interval: Duration::from_secs(1),
+            skip_existing: false,
+            file: None,
+            pending: Vec::new(),
+            scanned: 0,
+            states: States::BOUNDARY,
+            line_offset: 0,
+            events: VecDeque::new(),
+            failed: false,
+        }
+    }
+
+    /// Sets how long to wait before checking the file again when no more data
+    /// is available.
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Sets whether the rows that are in the file when it is first opened are
+    /// skipped, so that only the rows appended afterwards are returned.
+    ///
+    /// A row that is incomplete when the file is opened is not skipped.
+    pub fn skip_existing(mut self, skip_existing: bool) -> Self {
+        self.skip_existing = skip_existing;
+        self
+    }
+
+    /// Returns the next event if it is available, without waiting.
+    pub fn try_next(&mut self) -> ReadResult<Option<FollowEvent<D>>> {
+        if self.failed {
+            return Ok(None);
+        }
+
+        if self.events.is_empty() {
+            if let Err(error) = self.poll() {
+                self.events.push_back(Err(error));
+            }
+        }
+        match self.events.pop_front() {
+            Some(Err(error)) => {
+                self.failed = true;
+                Err(error)
+            }
+            Some(Ok(event)) => Ok(Some(event)),
+            None => Ok(None),
+        }
+    }
+
+    fn poll(&mut self) -> ReadResult<()> {
+        let Some(file) = &mut self.file else {
+            if let Some(file) = FollowedFile::open(&self.path)? {
+                self.file = Some(file);
+                self.read_available()?;
+                if self.skip_existing {
+                    self.events.clear();
+                    // A rotated file is read from its start
+                    self.skip_existing = false;
+                }
+            }
+            return Ok(());
+        };
+
+        if file.inner.metadata()?.len() < file.offset {
+            file.inner.seek(SeekFrom::Start(0))?;
+            file.offset = 0;
+            self.pending.clear();
+            self.scanned = 0;
+            self.states = States::BOUNDARY;
+            self.line_offset = 0;
+            self.events.push_back(Ok(FollowEvent::Truncated));
+        }
+
+        if self.read_available()? {
+            return Ok(());
+        }
+
+        let rotated = match std::fs::metadata(&self.path) {
+            Ok(metadata) => {
+                identity(&metadata) != self.file.as_ref().and_then(|file| file.identity)
+            }
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => false,
+            Err(error) => return Err(error.into()),
+        };
+        if rotated {
+            let states = self.states.after(&self.pending[self.scanned..]);
+            if states != States::BOUNDARY {
+                // Closes the quoted value, so that the row is returned as it is
+                self.pending.push(b'"');
+            }
+            self.parse(self.pending.len());
+            self.scanned = 0;
+            self.states = States::BOUNDARY;
+            self.line_offset = 0;
+            self.file = None;
+            self.events.push_back(Ok(FollowEvent::Rotated));
+        }
+        Ok(())
+    }
+
+    /// Reads the data available in the file, and queues the rows of the
+    /// complete records.
+    ///
+    /// Returns whether any data was read.
+    fn read_available(&mut self) -> ReadResult<bool> {
+        let Some(file) = &mut self.file else {
+            return Ok(false);
+        };
+
+        let length = file.inner.read_to_end(&mut self.pending)?;
+        file.offset += length as u64;
+
+        let mut complete = 0;
+        while let Some(index) = self.pending[self.scanned..]
+            .iter()
+            .position(|byte| *byte == b'\n')
+        {
+            let line_end = self.scanned + index + 1;
+            self.states = self.states.after(&self.pending[self.scanned..line_end]);
+            self.scanned = line_end;
+            if self.states == States::BOUNDARY {
+                complete = line_end;
+            }
+        }
+        self.parse(complete);
+        self.scanned -= complete;
+
+        Ok(length > 0)
+    }
+
+    /// Queues the rows of the first `length` pending bytes, and removes them.
+    fn parse(&mut self, length: usize) {
+        let shift = |position: Position| Position {
+            line_number: position.line_number + self.line_offset,
+            ..position
+        };
+        for result in RecordReader::<D, _>::new(&self.pending[..length]) {
+            match result {
+                Ok(record) => {
+                    if let Record::Row(row) = record.value {
+                        self.events.push_back(Ok(FollowEvent::Row(row)));
+                    }
+                }
+                Err(error) => {
+                    self.events.push_back(Err(match error {
+                        ReadError::UnpairedQuote(position) => {
+                            ReadError::UnpairedQuote(shift(position))
+                        }
+                        ReadError::UnclosedQuotedValue(position) => {
+                            ReadError::UnclosedQuotedValue(shift(position))
+                        }
+                        error => error,
+                    }));
+                    break;
+                }
+            }
+        }
+        self.line_offset += self.pending[..length]
+            .iter()
+            .filter(|byte| **byte == b'\n')
+            .count();
+        self.pending.drain(..length);
+    }
+}
+
+impl<D: Domain> Iterator for Follower<D> {
+    type Item = ReadResult<FollowEvent<D>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.failed {
+                return None;
+            }
+            match self.try_next() {
+                Ok(Some(event)) => return Some(Ok(event)),
+                Ok(None) => std::thread::sleep(self.poll_interval),
+                Err(error) => return Some(Err(error)),
+            }
+        }
+    }
+}
+
+/// An event returned by a [`Follower`].
+#[doc = generic_item_warning_doc!("FollowEvent")]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum FollowEvent<D: Domain> {
+    /// A row was appended.
+    Row(Vec<D::String>),
+
+    /// The file was truncated, and is read again from its start.
+    Truncated,
+
+    /// The file was rotated, and the new file is read from its start.
+    Rotated,
+}
+
+struct FollowedFile {
+    inner: File,
+    identity: Option<(u64, u64)>,
+    offset: u64,
+}
+
+impl FollowedFile {
+    /// Opens the file, or returns `None` if it doesn't exist.
+    fn open(path: &Path) -> std::io::Result<Option<Self>> {
+        match File::open(path) {
+            Ok(inner) => Ok(Some(FollowedFile {
+                identity: identity(&inner.metadata()?),
+                inner,
+                offset: 0,
+            })),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn identity(metadata: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn identity(_metadata: &Metadata) -> Option<(u64, u64)> {
+    None
+}
@@ -0,0 +1,347 @@
+//! Imports Markdown and fixed-width tables as SSV rows.
+
+use std::error::Error;
+use std::fmt::Display;
+use std::io::{Read, Write};
+use std::ops::Deref;
+
+use crate::engine::domain::{Domain, DomainString};
+
+use super::writer::Writer;
+use super::{ReadError, ReadWriteError};
+
+/// Imports the first GitHub-flavored Markdown table of a text input.
+#[doc = generic_item_warning_doc!("MarkdownImporter")]
+/// A table is a header line followed by a delimiter line, such as
+/// `|---|:---:|--:|`, and by the body lines up to the first blank line. The
+/// leading and trailing pipes of a line are optional, and the values are
+/// trimmed.
+///
+/// Escaped characters, such as `\|`, are unescaped, and `<br>` tags are read as
+/// LF line-breaks. Body rows are completed with empty values, or truncated, to
+/// have as many values as the header row. The header row is the first row,
+/// unless all its values are empty, as in tables rendered without header by a
+/// [`Renderer`](super::render::Renderer).
+///
+/// The input must be UTF-8.
+///
+/// # Example
+///
+/// ```
+/// use ssv::chars::MarkdownImporter;
+///
+/// let input = "\
+/// Some people:
+///
+/// | Name     | Age | Note     |
+/// |----------|----:|----------|
+/// | John Doe |  53 | a \\| b  |
+/// | Mary     |  23 |
+/// ";
+/// let mut output = Vec::new();
+///
+/// MarkdownImporter::new().transform(input.as_bytes(), &mut output)?;
+///
+/// assert_eq!(output, b"Name Age Note\n\"John Doe\" 53 \"a | b\"\nMary 23 \"\"\n");
+/// # Ok::<_, ssv::chars::ImportError>(())
+/// ```
+pub struct MarkdownImporter<D: Domain> {
+    _domain: std::marker::PhantomData<D>,
+}
+
+impl<D: Domain> MarkdownImporter<D> {
+    /// Creates an instance.
+    pub fn new() -> Self {
+        MarkdownImporter {
+            _domain: std::marker::PhantomData,
+        }
+    }
+
+    /// Reads the first Markdown table from the given byte reader, and returns
+    /// its rows.
+    pub fn read<R: Read>(&self, reader: R) -> ImportResult<Vec<Vec<D::String>>> {
+        let text = read_text(reader)?;
+        let lines: Vec<&str> = text.lines().collect();
+
+        let start = lines
+            .windows(2)
+            .position(|pair| {
+                let header = split_markdown_row(pair[0]);
+                let delimiters = split_markdown_row(pair[1]);
+                pair.iter().all(|line| line.contains('|'))
+                    && header.len() == delimiters.len()
+                    && delimiters.iter().all(|cell| is_delimiter(cell))
+            })
+            .ok_or(ImportError::TableNotFound)?;
+
+        let header = split_markdown_row(lines[start]);
+        let column_count = header.len();
+        let mut rows = Vec::new();
+        if header.iter().any(|cell| !cell.is_empty()) {
+            rows.push(header);
+        }
+        for line in &lines[start + 2..] {
+            if line.trim().is_empty() {
+                break;
+            }
+            let mut row = split_markdown_row(line);
+            row.resize_with(column_count, String::new);
+            rows.push(row);
+        }
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| D::String::from_text(&unescape_markdown(cell)))
+                    .collect()
+            })
+            .collect())
+    }
+
+    /// Reads the first Markdown table from the given byte reader, and writes
+    /// its rows as SSV to the given byte writer.
+    pub fn transform<R: Read, W: Write>(&self, reader: R, writer: W) -> ImportResult<()> {
+        write_rows::<D, W>(writer, self.read(reader)?)
+    }
+}
+
+impl<D: Domain> Default for MarkdownImporter<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Splits a line of a Markdown table at the unescaped pipes, without
+/// unescaping the cells.
+fn split_markdown_row(line: &str) -> Vec<String> {
+    let line = line.trim();
+    let line = line.strip_prefix('|').unwrap_or(line);
+
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                cell.push(c);
+                cell.extend(chars.next());
+            }
+            '|' => cells.push(std::mem::take(&mut cell)),
+            _ => cell.push(c),
+        }
+    }
+    if !cell.trim().is_empty() || cells.is_empty() {
+        cells.push(cell);
+    }
+
+    cells.iter().map(|cell| cell.trim().to_string()).collect()
+}
+
+fn is_delimiter(cell: &str) -> bool {
+    let cell = cell.strip_prefix(':').unwrap_or(cell);
+    let cell = cell.strip_suffix(':').unwrap_or(cell);
+    !cell.is_empty() && cell.chars().all(|c| c == '-')
+}
+
+fn unescape_markdown(cell: &str) -> String {
+    let mut unescaped = String::with_capacity(cell.len());
+    let mut rest = cell;
+    while let Some(c) = rest.chars().next() {
+        if let Some(tag) = ["<br>", "<br/>", "<br />"].iter().find(|tag| {
+            rest.get(..tag.len())
+                .is_some_and(|start| start.eq_ignore_ascii_case(tag))
+        }) {
+            unescaped.push('\n');
+            rest = &rest[tag.len()..];
+            continue;
+        }
+
+        rest = &rest[c.len_utf8()..];
+        match (c, rest.chars().next()) {
+            ('\\', Some(next)) if next.is_ascii_punctuation() => {
+                unescaped.push(next);
+                rest = &rest[1..];
+            }
+            _ => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+/// Imports fixed-width columns of text.
+#[doc = generic_item_warning_doc!("FixedWidthImporter")]
+/// Each non-blank line is a row, and the values are the trimmed characters
+/// between the column boundaries, the last column extending to the end of the
+/// line. Rule lines, made only of `-`, `=`, `+` and spaces, are not rows.
+///
+/// The boundaries are the character offsets where the columns start. They can
+/// be [given explicitly](FixedWidthImporter::with_boundaries). Otherwise they
+/// are the starts of the runs of the first rule line, such as the `-` runs
+/// under a header, or, without rule line, the offsets following a space in all
+/// the lines. Auto-detection splits values with spaces at the same offset in
+/// all the lines, so explicit boundaries are safer for such input.
+///
+/// The input must be UTF-8.
+///
+/// # Example
+///
+/// ```
+/// use ssv::chars::FixedWidthImporter;
+///
+/// let input = "\
+/// Name       Age  City
+/// ---------  ---  -----
+/// John Doe    53  Paris
+/// Mary        23
+/// ";
+/// let mut output = Vec::new();
+///
+/// FixedWidthImporter::new().transform(input.as_bytes(), &mut output)?;
+///
+/// assert_eq!(output, b"Name Age City\n\"John Doe\" 53 Paris\nMary 23 \"\"\n");
+/// # Ok::<_, ssv::chars::ImportError>(())
+/// ```
+pub struct FixedWidthImporter<D: Domain> {
+    boundaries: Option<Vec<usize>>,
+    _domain: std::marker::PhantomData<D>,
+}
+
+impl<D: Domain> FixedWidthImporter<D> {
+    /// Creates an instance detecting the column boundaries.
+    pub fn new() -> Self {
+        FixedWidthImporter {
+            boundaries: None,
+            _domain: std::marker::PhantomData,
+        }
+    }
+
+    /// Sets the character offsets where the columns start, instead of
+    /// detecting them.
+    ///
+    /// The characters before the first boundary are ignored.
+    pub fn with_boundaries(mut self, boundaries: impl IntoIterator<Item = usize>) -> Self {
+        let mut boundaries: Vec<_> = boundaries.into_iter().collect();
+        boundaries.sort_unstable();
+        boundaries.dedup();
+        self.boundaries = Some(boundaries);
+        self
+    }
+
+    /// Reads fixed-width columns from the given byte reader, and returns the
+    /// rows.
+    pub fn read<R: Read>(&self, reader: R) -> ImportResult<Vec<Vec<D::String>>> {
+        let text = read_text(reader)?;
+        let lines: Vec<Vec<char>> = text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.chars().collect())
+            .collect();
+        let is_rule = |line: &Vec<char>| {
+            line.iter()
+                .all(|c| matches!(c, '-' | '=' | '+') || c.is_whitespace())
+        };
+
+        let boundaries = match &self.boundaries {
+            Some(boundaries) => boundaries.clone(),
+            None => match lines.iter().find(|line| is_rule(line)) {
+                Some(rule) => run_starts(rule.iter().map(|c| !c.is_whitespace())),
+                None => {
+                    let width = lines.iter().map(Vec::len).max().unwrap_or(0);
+                    run_starts((0..width).map(|offset| {
+                        lines
+                            .iter()
+                            .any(|line| line.get(offset).is_some_and(|c| !c.is_whitespace()))
+                    }))
+                }
+            },
+        };
+
+        Ok(lines
+            .iter()
+            .filter(|line| !is_rule(line))
+            .map(|line| {
+                boundaries
+                    .iter()
+                    .enumerate()
+                    .map(|(index, start)| {
+                        let end = boundaries.get(index + 1).copied().unwrap_or(line.len());
+                        let value: String = line
+                            .get(*start..end.min(line.len()))
+                            .unwrap_or_default()
+                            .iter()
+                            .collect();
+                        D::String::from_text(value.trim())
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+
+    /// Reads fixed-width columns from the given byte reader, and writes the
+    /// rows as SSV to the given byte writer.
+    pub fn transform<R: Read, W: Write>(&self, reader: R, writer: W) -> ImportResult<()> {
+        write_rows::<D, W>(writer, self.read(reader)?)
+    }
+}
+
+impl<D: Domain> Default for FixedWidthImporter<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the offsets where runs of `true` start.
+fn run_starts(filled: impl Iterator<Item = bool>) -> Vec<usize> {
+    let mut starts = Vec::new();
+    let mut previous = false;
+    for (offset, filled) in filled.enumerate() {
+        if filled && !previous {
+            starts.push(offset);
+        }
+        previous = filled;
+    }
+    starts
+}
+
+fn read_text<R: Read>(mut reader: R) -> ImportResult<String> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text).map_err(ReadError::from)?;
+    Ok(text)
+}
+
+fn write_rows<D: Domain, W: Write>(writer: W, rows: Vec<Vec<D::String>>) -> ImportResult<()> {
+    let mut writer = Writer::<D, W>::new(writer);
+    for row in &rows {
+        writer.write_row(row.iter().map(Deref::deref))?;
+    }
+    writer.finish()?;
+    Ok(())
+}
+
+/// A specialized [`Result`] type for import operations.
+pub type ImportResult<T> = Result<T, ImportError>;
+
+/// The error type for import operations.
+#[derive(Debug)]
+pub enum ImportError {
+    /// The input doesn't contain a Markdown table.
+    TableNotFound,
+
+    /// An error happened when reading, including an input that is not UTF-8,
+    /// or when writing.
+    ReadWriteError(ReadWriteError),
+}
+
+impl Error for ImportError {}
+
+impl_from_read_write_error!(ImportError);
+
+impl Display for ImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImportError::TableNotFound => write!(f, "table not found"),
+            ImportError::ReadWriteError(error) => write!(f, "{error}"),
+        }
+    }
+}
@@ -0,0 +1,601 @@
+//! Joins two SSV inputs on key columns.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::Display;
+use std::io::{Read, Write};
+use std::ops::Deref;
+
+use crate::engine::domain::{Domain, DomainString, DomainStringSlice};
+
+use super::columns::ColumnRef;
+use super::position::{Position, WithPosition};
+use super::reader::{Record, RecordReader};
+use super::writer::Writer;
+use super::ReadWriteError;
+
+/// Joins the rows of two SSV inputs (left and right) on key columns.
+#[doc = generic_item_warning_doc!("Joiner")]
+/// Two rows match if the values in all their key columns are equal. Each
+/// output row has all the columns of the left input, followed by the non-key
+/// columns of the right input. Values of an unmatched side are empty, except for
+/// the left key columns of an unmatched right row, which get the values of the
+/// right key columns.
+///
+/// If the joiner [uses a header](Joiner::with_header), the first row of each
+/// input is the header row, and a header row is written. A name that is used in
+/// both sides gets a suffix in each side (see [`Joiner::with_suffixes`]).
+///
+/// Without header rows, the number of columns of each input is given by its
+/// first row. A row with more values than the first row of its input is an
+/// error ([`JoinError::TooManyValues`]). Comment lines are not written.
+///
+/// # Strategies
+///
+/// * [`JoinStrategy::Hash`] (the default) loads the right input in memory,
+///   which should be the smaller one, and streams the left input. The output
+///   follows the order of the left input, followed by the unmatched right rows
+///   in a [full outer](JoinKind::FullOuter) join.
+/// * [`JoinStrategy::Merge`] streams both inputs, which must be sorted
+///   lexically by the key columns, in ascending order. The output is sorted by
+///   the key columns too. If an input is not sorted,
+///   [`JoinError::UnsortedInput`] is returned.
+///
+/// # Example
+///
+/// ```
+/// use ssv::chars::{JoinKind, Joiner};
+///
+/// let customers = "id name\n1 John\n2 Jane\n";
+/// let orders = "order customer\nA 1\nB 1\nC 3\n";
+/// let mut output = Vec::new();
+///
+/// Joiner::new(JoinKind::Left)
+///     .with_header(true)
+///     .on("id", "customer")
+///     .join(customers.as_bytes(), orders.as_bytes(), &mut output)?;
+///
+/// assert_eq!(output, b"id name order\n1 John A\n1 John B\n2 Jane \"\"\n");
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct Joiner<D: Domain> {
+    kind: JoinKind,
+    strategy: JoinStrategy,
+    keys: Vec<(ColumnRef<D>, ColumnRef<D>)>,
+    header: bool,
+    suffixes: (D::String, D::String),
+}
+
+/// The kind of join.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum JoinKind {
+    /// Only matching rows are written.
+    Inner,
+
+    /// Unmatched left rows are written too.
+    Left,
+
+    /// Unmatched left and right rows are written too.
+    FullOuter,
+}
+
+/// How a [`Joiner`] finds the matching rows.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum JoinStrategy {
+    /// The right input is loaded in a hash table.
+    #[default]
+    Hash,
+
+    /// Both inputs are sorted by the key columns and are merged.
+    Merge,
+}
+
+/// One of the inputs of a [`Joiner`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Side {
+    /// The left input.
+    Left,
+
+    /// The right input.
+    Right,
+}
+
+impl<D: Domain> Joiner<D> {
+    /// Creates an instance of the given kind, without keys and without header.
+    pub fn new(kind: JoinKind) -> Self {
+        Joiner {
+            kind,
+            strategy: JoinStrategy::default(),
+            keys: Vec::new(),
+            header: false,
+            suffixes: (
+                D::String::from_text("_left"),
+                D::String::from_text("_right"),
+            ),
+        }
+    }
+
+    /// Sets the strategy.
+    pub fn with_strategy(mut self, strategy: JoinStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Sets whether the first row of each input is a header row.
+    ///
+    /// Keys can reference columns by name only if there are header rows.
+    pub fn with_header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Sets the suffixes added to colliding column names in the header row.
+    ///
+    /// The defaults are `_left` and `_right`.
+    pub fn with_suffixes(mut self, left: &D::StringSlice, right: &D::StringSlice) -> Self {
+        self.suffixes = (D::String::from(left), D::String::from(right));
+        self
+    }
+
+    /// Adds a pair of key columns: one in the left input and one in the right
+    /// input.
+    ///
+    /// At least one pair is needed: joining without keys returns
+    /// [`JoinError::NoKeys`].
+    pub fn on(mut self, left: impl Into<ColumnRef<D>>, right: impl Into<ColumnRef<D>>) -> Self {
+        self.keys.push((left.into(), right.into()));
+        self
+    }
+
+    /// Returns the kind of join.
+    pub fn kind(&self) -> JoinKind {
+        self.kind
+    }
+
+    /// Returns the strategy.
+    pub fn strategy(&self) -> JoinStrategy {
+        self.strategy
+    }
+
+    /// Reads SSV from the left and right byte readers, joins their rows, and
+    /// writes the result to the given byte writer.
+    pub fn join<L: Read, R: Read, W: Write>(&self, left: L, right: R, writer: W) -> JoinResult<()> {
+        if self.keys.is_empty() {
+            return Err(JoinError::NoKeys);
+        }
+
+        let mut left = Rows::<D, L>::new(left, Side::Left);
+        let mut right = Rows::<D, R>::new(right, Side::Right);
+        let mut writer = Writer::<D, W>::new(writer);
+
+        let (left_first, right_first) = (left.peek()?, right.peek()?);
+        let layout = self.layout(left_first, right_first)?;
+        if self.header && (left_first.is_some() || right_first.is_some()) {
+            let header = layout.header::<D>(left.next_row()?, right.next_row()?, &self.suffixes);
+            writer.write_row(header.iter().map(Deref::deref))?;
+        }
+
+        left.width = Some(layout.left_width);
+        right.width = Some(layout.right_width);
+
+        let mut output = Output {
+            writer: &mut writer,
+            layout: &layout,
+        };
+        match self.strategy {
+            JoinStrategy::Hash => self.hash_join(left, right, &mut output)?,
+            JoinStrategy::Merge => self.merge_join(left, right, &mut output)?,
+        }
+
+        writer.finish()?;
+        Ok(())
+    }
+
+    fn layout(
+        &self,
+        left_first: Option<&[D::String]>,
+        right_first: Option<&[D::String]>,
+    ) -> JoinResult<Layout> {
+        let (left_header, right_header) = if self.header {
+            (left_first, right_first)
+        } else {
+            (None, None)
+        };
+
+        let mut left_keys = Vec::new();
+        let mut right_keys = Vec::new();
+        for (left, right) in &self.keys {
+            left_keys.push(Self::resolve(left, left_header, left_first.is_none())?);
+            right_keys.push(Self::resolve(right, right_header, right_first.is_none())?);
+        }
+
+        let width = |first: Option<&[D::String]>, keys: &[usize]| {
+            let max_key = keys.iter().map(|index| index + 1).max().unwrap_or(0);
+            first.map_or(0, <[_]>::len).max(max_key)
+        };
+
+        Ok(Layout {
+            left_width: width(left_first, &left_keys),
+            right_width: width(right_first, &right_keys),
+            left_keys,
+            right_keys,
+        })
+    }
+
+    fn resolve(
+        column: &ColumnRef<D>,
+        header: Option<&[D::String]>,
+        empty: bool,
+    ) -> JoinResult<usize> {
+        if empty {
+            // The columns of an empty input are irrelevant
+            Ok(column.resolve(header).unwrap_or(0))
+        } else {
+            Ok(column.try_resolve(header)?)
+        }
+    }
+
+    fn hash_join<L: Read, R: Read, W: Write>(
+        &self,
+        mut left: Rows<D, L>,
+        right: Rows<D, R>,
+        output: &mut Output<D, W>,
+    ) -> JoinResult<()> {
+        let right_rows = right
+            .map(|row| row.map(|row| row.value))
+            .collect::<JoinResult<Vec<_>>>()?;
+        let mut table: HashMap<Vec<&[u8]>, Vec<usize>> = HashMap::new();
+        for (index, row) in right_rows.iter().enumerate() {
+            let key = output.layout.key::<D>(row, Side::Right);
+            table.entry(key).or_default().push(index);
+        }
+
+        let mut matched = vec![false; right_rows.len()];
+        while let Some(row) = left.next_row()? {
+            let key = output.layout.key::<D>(&row, Side::Left);
+            match table.get(&key) {
+                Some(indices) => {
+                    for index in indices {
+                        matched[*index] = true;
+                        output.write(Some(&row), Some(&right_rows[*index]))?;
+                    }
+                }
+                None if self.kind != JoinKind::Inner => output.write(Some(&row), None)?,
+                None => {}
+            }
+        }
+
+        if self.kind == JoinKind::FullOuter {
+            for (row, matched) in right_rows.iter().zip(matched) {
+                if !matched {
+                    output.write(None, Some(row))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn merge_join<L: Read, R: Read, W: Write>(
+        &self,
+        left: Rows<D, L>,
+        right: Rows<D, R>,
+        output: &mut Output<D, W>,
+    ) -> JoinResult<()> {
+        let layout = output.layout;
+        let mut left = Groups::new(left, Side::Left);
+        let mut right = Groups::new(right, Side::Right);
+
+        let mut left_group = left.next(layout)?;
+        let mut right_group = right.next(layout)?;
+        loop {
+            let ordering = match (&left_group, &right_group) {
+                (None, None) => return Ok(()),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (Some(left_group), Some(right_group)) => layout
+                    .key::<D>(&left_group[0], Side::Left)
+                    .cmp(&layout.key::<D>(&right_group[0], Side::Right)),
+            };
+
+            match ordering {
+                std::cmp::Ordering::Less => {
+                    if self.kind != JoinKind::Inner {
+                        for row in left_group.iter().flatten() {
+                            output.write(Some(row), None)?;
+                        }
+                    }
+                    left_group = left.next(layout)?;
+                }
+                std::cmp::Ordering::Greater => {
+                    if self.kind == JoinKind::FullOuter {
+                        for row in right_group.iter().flatten() {
+                            output.write(None, Some(row))?;
+                        }
+                    }
+                    right_group = right.next(layout)?;
+                }
+                std::cmp::Ordering::Equal => {
+                    for left_row in left_group.iter().flatten() {
+                        for right_row in right_group.iter().flatten() {
+                            output.write(Some(left_row), Some(right_row))?;
+                        }
+                    }
+                    left_group = left.next(layout)?;
+                    right_group = right.next(layout)?;
+                }
+            }
+        }
+    }
+}
+
+struct Layout {
+    left_width: usize,
+    right_width: usize,
+    left_keys: Vec<usize>,
+    right_keys: Vec<usize>,
+}
+
+impl Layout {
+    fn key<'a, D: Domain>(&self, row: &'a [D::String], side: Side) -> Vec<&'a [u8]> {
+        let keys = match side {
+            Side::Left => &self.left_keys,
+            Side::Right => &self.right_keys,
+        };
+        keys.iter()
+            .map(|index| {
+                row.get(*index)
+                    .map_or(&[][..], |value| value.deref().as_bytes())
+            })
+            .collect()
+    }
+
+    fn right_columns(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.right_width).filter(|index| !self.right_keys.contains(index))
+    }
+
+    fn header<D: Domain>(
+        &self,
+        left: Option<Vec<D::String>>,
+        right: Option<Vec<D::String>>,
+        suffixes: &(D::String, D::String),
+    ) -> Vec<D::String> {
+        let left_is_empty = left.is_none();
+        let left = left.unwrap_or_default();
+        let right = right.unwrap_or_default();
+        let name = |row: &[D::String], index: usize| {
+            row.get(index).cloned().unwrap_or_else(D::String::new)
+        };
+
+        let mut left_names: Vec<_> = (0..self.left_width)
+            .map(|index| name(&left, index))
+            .collect();
+        if left_is_empty {
+            for (left_index, right_index) in self.left_keys.iter().zip(&self.right_keys) {
+                left_names[*left_index] = name(&right, *right_index);
+            }
+        }
+        let right_names: Vec<_> = self
+            .right_columns()
+            .map(|index| name(&right, index))
+            .collect();
+
+        let with_suffix = |name: &D::String, suffix: &D::String| D::concat(&[name, suffix]);
+        let mut header: Vec<_> = left_names
+            .iter()
+            .map(|name| {
+                if right_names.contains(name) {
+                    with_suffix(name, &suffixes.0)
+                } else {
+                    name.clone()
+                }
+            })
+            .collect();
+        header.extend(right_names.iter().map(|name| {
+            if left_names.contains(name) {
+                with_suffix(name, &suffixes.1)
+            } else {
+                name.clone()
+            }
+        }));
+        header
+    }
+}
+
+struct Output<'a, D: Domain, W: Write> {
+    writer: &'a mut Writer<D, W>,
+    layout: &'a Layout,
+}
+
+impl<D: Domain, W: Write> Output<'_, D, W> {
+    fn write(&mut self, left: Option<&[D::String]>, right: Option<&[D::String]>) -> JoinResult<()> {
+        fn value<'a, D: Domain>(
+            row: Option<&'a [D::String]>,
+            index: usize,
+            empty: &'a D::String,
+        ) -> &'a D::StringSlice {
+            row.and_then(|row| row.get(index)).unwrap_or(empty)
+        }
+
+        let layout = self.layout;
+        let empty = D::String::new();
+        let value = |row, index| value::<D>(row, index, &empty);
+
+        let mut values: Vec<&D::StringSlice> = (0..layout.left_width)
+            .map(|index| value(left, index))
+            .collect();
+        if left.is_none() {
+            for (left_index, right_index) in layout.left_keys.iter().zip(&layout.right_keys) {
+                values[*left_index] = value(right, *right_index);
+            }
+        }
+        values.extend(layout.right_columns().map(|index| value(right, index)));
+
+        self.writer.write_row(values)?;
+        Ok(())
+    }
+}
+
+/// The rows of an input, skipping comment lines.
+struct Rows<D: Domain, R: Read> {
+    records: RecordReader<D, R>,
+    side: Side,
+    /// The maximum number of values of a row, once the layout is known.
+    width: Option<usize>,
+    peeked: Option<Option<WithPosition<Vec<D::String>>>>,
+}
+
+impl<D: Domain, R: Read> Rows<D, R> {
+    fn new(inner: R, side: Side) -> Self {
+        Rows {
+            records: RecordReader::new(inner),
+            side,
+            width: None,
+            peeked: None,
+        }
+    }
+
+    fn peek(&mut self) -> JoinResult<Option<&[D::String]>> {
+        if self.peeked.is_none() {
+            self.peeked = Some(self.next_with_position()?);
+        }
+        Ok(self
+            .peeked
+            .as_ref()
+            .unwrap()
+            .as_ref()
+            .map(|row| &row.value[..]))
+    }
+
+    fn next_row(&mut self) -> JoinResult<Option<Vec<D::String>>> {
+        Ok(self.next_with_position()?.map(|row| row.value))
+    }
+
+    fn next_with_position(&mut self) -> JoinResult<Option<WithPosition<Vec<D::String>>>> {
+        if let Some(peeked) = self.peeked.take() {
+            return Ok(peeked);
+        }
+        for record in self.records.by_ref() {
+            let record = record?;
+            if let Record::Row(row) = record.value {
+                if self.width.is_some_and(|width| row.len() > width) {
+                    return Err(JoinError::TooManyValues(self.side, record.position));
+                }
+                return Ok(Some(WithPosition {
+                    value: row,
+                    position: record.position,
+                }));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<D: Domain, R: Read> Iterator for Rows<D, R> {
+    type Item = JoinResult<WithPosition<Vec<D::String>>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_with_position().transpose()
+    }
+}
+
+/// Groups of consecutive rows with the same key, from a sorted input.
+struct Groups<D: Domain, R: Read> {
+    rows: Rows<D, R>,
+    side: Side,
+    pending: Option<WithPosition<Vec<D::String>>>,
+    started: bool,
+}
+
+impl<D: Domain, R: Read> Groups<D, R> {
+    fn new(rows: Rows<D, R>, side: Side) -> Self {
+        Groups {
+            rows,
+            side,
+            pending: None,
+            started: false,
+        }
+    }
+
+    fn next(&mut self, layout: &Layout) -> JoinResult<Option<Vec<Vec<D::String>>>> {
+        if !self.started {
+            self.started = true;
+            self.pending = self.rows.next_with_position()?;
+        }
+
+        let Some(first) = self.pending.take() else {
+            return Ok(None);
+        };
+        let mut group = vec![first.value];
+
+        while let Some(row) = self.rows.next_with_position()? {
+            let ordering = layout
+                .key::<D>(&row.value, self.side)
+                .cmp(&layout.key::<D>(&group[0], self.side));
+            match ordering {
+                std::cmp::Ordering::Equal => group.push(row.value),
+                std::cmp::Ordering::Greater => {
+                    self.pending = Some(row);
+                    break;
+                }
+                std::cmp::Ordering::Less => {
+                    return Err(JoinError::UnsortedInput(self.side, row.position))
+                }
+            }
+        }
+
+        Ok(Some(group))
+    }
+}
+
+/// A specialized [`Result`] type for [`Joiner`] operations.
+pub type JoinResult<T> = Result<T, JoinError>;
+
+/// The error type for [`Joiner`] operations.
+#[derive(Debug)]
+pub enum JoinError {
+    /// No key columns were added (see [`Joiner::on`]).
+    NoKeys,
+
+    /// With the [`JoinStrategy::Merge`] strategy, a row whose key is lower than
+    /// the key of the previous row was found at the given position of an
+    /// input.
+    UnsortedInput(Side, Position),
+
+    /// A row with more values than the first row of its input (the header row,
+    /// if any) was found at the given position of an input.
+    TooManyValues(Side, Position),
+
+    /// A key column was not found, or an error happened when reading or
+    /// writing.
+    ReadWriteError(ReadWriteError),
+}
+
+impl Error for JoinError {}
+
+impl_from_read_write_error!(JoinError);
+
+impl Display for JoinError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let side_name = |side: &Side| match side {
+            Side::Left => "left",
+            Side::Right => "right",
+        };
+        match self {
+            JoinError::NoKeys => write!(f, "no key columns"),
+            JoinError::UnsortedInput(side, position) => write!(
+                f,
+                "{} input is not sorted at line {}",
+                side_name(side),
+                position.line_number
+            ),
+            JoinError::TooManyValues(side, position) => write!(
+                f,
+                "{} input has too many values at line {}",
+                side_name(side),
+                position.line_number
+            ),
+            JoinError::ReadWriteError(error) => write!(f, "{error}"),
+        }
+    }
+}
@@ -0,0 +1,561 @@
+//! Checks SSV for style and consistency problems.
+
+use std::cell::Cell;
+use std::collections::BTreeSet;
+use std::fmt::Display;
+use std::io::{self, Read, Write};
+use std::iter::Peekable;
+use std::marker::PhantomData;
+
+use crate::engine::domain::{BytesDomain, Domain, DomainString, DomainStringSlice};
+use crate::engine::LineBreak;
+
+use super::fluent_writer::FluentWriter;
+use super::position::{Position, WithPosition};
+use super::tokenizer::{Token, Tokenizer};
+use super::{ReadResult, ReadWriteResult};
+use crate::syntax::writer::requires_quotes;
+
+/// A lint rule.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Rule {
+    /// Both [`LineBreak::Lf`] and [`LineBreak::CrLf`] are used in the same
+    /// content. The line-breaks differing from the first one are reported.
+    MixedLineBreaks,
+
+    /// Spacing at the end of a line.
+    TrailingSpacing,
+
+    /// Spacing combining SPACE and TAB elements, or spacing using a different
+    /// element than the first spacing in the content.
+    MixedSpacing,
+
+    /// A value enclosed in quotes that does not need to be quoted (see the
+    /// [rules](crate#rules)).
+    UnnecessaryQuotes,
+
+    /// A row with a different number of values than the first row.
+    InconsistentValueCount,
+
+    /// A value containing a non-ASCII whitespace character (e.g. NO-BREAK
+    /// SPACE), which looks like spacing but is part of the value.
+    NonAsciiSpacing,
+}
+
+impl Rule {
+    /// All the rules.
+    pub const ALL: [Rule; 6] = [
+        Rule::MixedLineBreaks,
+        Rule::TrailingSpacing,
+        Rule::MixedSpacing,
+        Rule::UnnecessaryQuotes,
+        Rule::InconsistentValueCount,
+        Rule::NonAsciiSpacing,
+    ];
+
+    /// Returns the identifier of the rule.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Rule::MixedLineBreaks => "mixed-line-breaks",
+            Rule::TrailingSpacing => "trailing-spacing",
+            Rule::MixedSpacing => "mixed-spacing",
+            Rule::UnnecessaryQuotes => "unnecessary-quotes",
+            Rule::InconsistentValueCount => "inconsistent-value-count",
+            Rule::NonAsciiSpacing => "non-ascii-spacing",
+        }
+    }
+
+    /// Returns the rule with the given identifier.
+    pub fn from_id(id: &str) -> Option<Rule> {
+        Rule::ALL.into_iter().find(|rule| rule.id() == id)
+    }
+
+    /// Returns whether [`Linter::fix`] is able to fix the problems reported
+    /// by this rule.
+    pub fn is_fixable(&self) -> bool {
+        !matches!(self, Rule::InconsistentValueCount | Rule::NonAsciiSpacing)
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.id())
+    }
+}
+
+/// A set of enabled [`Rule`]s.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RuleSet {
+    rules: BTreeSet<Rule>,
+}
+
+impl RuleSet {
+    /// Creates an instance with all the rules enabled.
+    pub fn new() -> Self {
+        RuleSet {
+            rules: Rule::ALL.into_iter().collect(),
+        }
+    }
+
+    /// Creates an instance with no rules enabled.
+    pub fn empty() -> Self {
+        RuleSet {
+            rules: BTreeSet::new(),
+        }
+    }
+
+    /// Enables a rule.
+    pub fn enable(&mut self, rule: Rule) {
+        self.rules.insert(rule);
+    }
+
+    /// Disables a rule.
+    pub fn disable(&mut self, rule: Rule) {
+        self.rules.remove(&rule);
+    }
+
+    /// Returns whether a rule is enabled.
+    pub fn is_enabled(&self, rule: Rule) -> bool {
+        self.rules.contains(&rule)
+    }
+
+    /// Returns an iterator over the enabled rules.
+    pub fn iter(&self) -> impl Iterator<Item = Rule> + '_ {
+        self.rules.iter().copied()
+    }
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FromIterator<Rule> for RuleSet {
+    fn from_iter<T: IntoIterator<Item = Rule>>(iter: T) -> Self {
+        RuleSet {
+            rules: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// A problem found by a [`Linter`].
+#[derive(Clone, Copy, Debug)]
+pub struct Finding {
+    /// The rule that reported the problem.
+    pub rule: Rule,
+
+    /// The position where the problem was found.
+    pub position: Position,
+}
+
+/// Checks SSV content for style and consistency problems.
+#[doc = generic_item_warning_doc!("Linter")]
+/// The [`lint`](Linter::lint) method reports the problems found by the enabled
+/// [rules](Linter::rules), and the [`fix`](Linter::fix) method writes a
+/// corrected version of the content.
+///
+/// # Example
+///
+/// ```
+/// use ssv::chars::Linter;
+///
+/// let input = "abc \t def \n\"ghi\" jkl\n";
+///
+/// let linter = Linter::new();
+/// for finding in linter.lint(input.as_bytes())? {
+///     println!(
+///         "{} at {}:{}",
+///         finding.rule,
+///         finding.position.line_number,
+///         finding.position.column_number
+///     );
+/// }
+///
+/// let mut output = Vec::new();
+/// linter.fix(input.as_bytes(), &mut output)?;
+/// assert_eq!(output, b"abc      def\nghi jkl\n");
+/// # Ok::<_, ssv::chars::ReadWriteError>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct Linter<D: Domain> {
+    rules: RuleSet,
+    tab_width: usize,
+    _domain: PhantomData<D>,
+}
+
+impl<D: Domain> Linter<D> {
+    /// Creates an instance with all the rules enabled.
+    pub fn new() -> Self {
+        Linter {
+            rules: RuleSet::new(),
+            tab_width: 8,
+            _domain: PhantomData,
+        }
+    }
+
+    /// Returns the enabled rules.
+    pub fn rules(&self) -> &RuleSet {
+        &self.rules
+    }
+
+    /// Returns a mutable reference to the enabled rules.
+    pub fn rules_mut(&mut self) -> &mut RuleSet {
+        &mut self.rules
+    }
+
+    /// Replaces the enabled rules.
+    pub fn set_rules(&mut self, rules: RuleSet) {
+        self.rules = rules;
+    }
+
+    /// Returns the tab width used by [`fix`](Linter::fix) to convert between
+    /// TABs and SPACEs when fixing [`Rule::MixedSpacing`] problems.
+    pub fn tab_width(&self) -> usize {
+        self.tab_width
+    }
+
+    /// Sets the [tab width](Linter::tab_width).
+    pub fn set_tab_width(&mut self, tab_width: usize) {
+        self.tab_width = tab_width.max(1);
+    }
+
+    /// Returns the problems found in the SSV content read from the given byte
+    /// reader.
+    pub fn lint<R: Read>(&self, reader: R) -> ReadResult<Vec<Finding>> {
+        let mut tokens = Tokenizer::<D, R>::new(reader).peekable();
+        let mut analyzer = Analyzer::new(&self.rules);
+        let mut findings = Vec::new();
+
+        while let Some(token) = Self::next_token(&mut tokens)? {
+            let next = Self::peek_token(&mut tokens);
+            findings.extend(analyzer.check(&token, next));
+        }
+        findings.extend(analyzer.finish());
+
+        Ok(findings)
+    }
+
+    /// Writes to the given byte writer a corrected version of the SSV content
+    /// read from the given byte reader.
+    ///
+    /// Only the problems reported by enabled rules that are
+    /// [fixable](Rule::is_fixable) are corrected:
+    /// * line-breaks are replaced by the first line-break in the content,
+    ///   except a CRLF after a comment that ends with a CR, which would
+    ///   otherwise be read without it;
+    /// * trailing spacing is removed;
+    /// * spacing differing from the first spacing in the content is rewritten
+    ///   with its element: TABs are replaced by SPACEs up to the next
+    ///   [tab stop](Linter::tab_width) or, if the first spacing is made of
+    ///   TABs, SPACEs are replaced by TABs;
+    /// * unnecessary quotes are removed.
+    ///
+    /// Returns the problems that were not corrected.
+    pub fn fix<R: Read, W: Write>(&self, reader: R, writer: W) -> ReadWriteResult<Vec<Finding>> {
+        let mut tokens = Tokenizer::<D, R>::new(reader).peekable();
+        let mut analyzer = Analyzer::new(&self.rules);
+        let mut remaining = Vec::new();
+        let column = Cell::new(0);
+        let mut fluent = FluentWriter::<D, _>::new(ColumnTracker {
+            inner: writer,
+            column: &column,
+            tab_width: self.tab_width,
+        });
+
+        let mut after_comment_with_cr = false;
+        while let Some(token) = Self::next_token(&mut tokens)? {
+            let next = Self::peek_token(&mut tokens);
+            let findings = analyzer.check(&token, next);
+            let found = |rule| findings.iter().any(|finding| finding.rule == rule);
+            let keeps_line_break = after_comment_with_cr;
+            after_comment_with_cr = matches!(
+                &token.value,
+                Token::Comment(comment) if comment.as_bytes().last() == Some(&BytesDomain::CR)
+            );
+
+            fluent = match token.value {
+                Token::UnquotedValue(value) => fluent.write_value(&value)?,
+                Token::QuotedValue(value) => {
+                    if found(Rule::UnnecessaryQuotes) {
+                        fluent.write_value(&value)?
+                    } else {
+                        fluent.write_quoted_value(&value)?
+                    }
+                }
+                Token::Spacing(spacing) => {
+                    if found(Rule::TrailingSpacing) {
+                        fluent
+                    } else if found(Rule::MixedSpacing) {
+                        let spacing = match analyzer.first_spacing_kind {
+                            Some(SpacingKind::Tabs) => self.to_tabs(&spacing, column.get()),
+                            _ => self.expand_tabs(&spacing, column.get()),
+                        };
+                        fluent.write_spacing(&spacing)?
+                    } else {
+                        fluent.write_spacing(&spacing)?
+                    }
+                }
+                Token::LineBreak(line_break) => {
+                    let line_break = if found(Rule::MixedLineBreaks) && !keeps_line_break {
+                        analyzer.first_line_break.unwrap_or(line_break)
+                    } else {
+                        line_break
+                    };
+                    fluent.write_this_line_break(line_break)?
+                }
+                Token::Comment(comment) => fluent.write_verbatim_comment(&comment)?,
+            };
+
+            remaining.extend(findings.into_iter().filter(|finding| {
+                !finding.rule.is_fixable()
+                    || (keeps_line_break && finding.rule == Rule::MixedLineBreaks)
+            }));
+        }
+        remaining.extend(analyzer.finish());
+        fluent.finish()?;
+
+        Ok(remaining)
+    }
+
+    /// Replaces the TABs in spacing written at the given output column (counted
+    /// from 0) by SPACEs.
+    fn expand_tabs(&self, spacing: &D::StringSlice, column: usize) -> D::String {
+        let width = self.spacing_end(spacing, column) - column;
+        D::String::from_text(&" ".repeat(width))
+    }
+
+    /// Replaces spacing written at the given output column (counted from 0) by
+    /// at least one TAB, reaching the same tab stop when possible.
+    fn to_tabs(&self, spacing: &D::StringSlice, column: usize) -> D::String {
+        let end = self.spacing_end(spacing, column);
+        let tabs = (end / self.tab_width - column / self.tab_width).max(1);
+        D::String::from_text(&"\t".repeat(tabs))
+    }
+
+    fn spacing_end(&self, spacing: &D::StringSlice, column: usize) -> usize {
+        spacing.as_bytes().iter().fold(column, |column, byte| {
+            advance(column, *byte, self.tab_width)
+        })
+    }
+
+    fn next_token<R: Read>(
+        tokens: &mut Peekable<Tokenizer<D, R>>,
+    ) -> ReadResult<Option<WithPosition<Token<D>>>> {
+        tokens.next().transpose()
+    }
+
+    fn peek_token<R: Read>(tokens: &mut Peekable<Tokenizer<D, R>>) -> Option<&Token<D>> {
+        match tokens.peek() {
+            Some(Ok(token)) => Some(&token.value),
+            _ => None,
+        }
+    }
+}
+
+impl<D: Domain> Default for Linter<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A byte writer that keeps track of the column (counted from 0) where the
+/// next character will be written.
+struct ColumnTracker<'c, W: Write> {
+    inner: W,
+    column: &'c Cell<usize>,
+    tab_width: usize,
+}
+
+impl<W: Write> Write for ColumnTracker<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        let column = buf[..written]
+            .iter()
+            .fold(self.column.get(), |column, byte| {
+                advance(column, *byte, self.tab_width)
+            });
+        self.column.set(column);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Returns the column after writing the byte at the given column. UTF-8
+/// continuation bytes do not advance the column.
+fn advance(column: usize, byte: u8, tab_width: usize) -> usize {
+    match byte {
+        b'\n' => 0,
+        b'\t' => column + tab_width - column % tab_width,
+        _ if byte & 0xC0 == 0x80 => column,
+        _ => column + 1,
+    }
+}
+
+struct Analyzer<'a> {
+    rules: &'a RuleSet,
+    first_line_break: Option<LineBreak>,
+    first_spacing_kind: Option<SpacingKind>,
+    expected_value_count: Option<usize>,
+    line: Line,
+}
+
+enum Line {
+    Begin,
+    Row {
+        position: Position,
+        value_count: usize,
+    },
+    Comment,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SpacingKind {
+    Spaces,
+    Tabs,
+    Mixed,
+}
+
+impl<'a> Analyzer<'a> {
+    fn new(rules: &'a RuleSet) -> Self {
+        Analyzer {
+            rules,
+            first_line_break: None,
+            first_spacing_kind: None,
+            expected_value_count: None,
+            line: Line::Begin,
+        }
+    }
+
+    fn check<D: Domain>(
+        &mut self,
+        token: &WithPosition<Token<D>>,
+        next: Option<&Token<D>>,
+    ) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        let mut report = |rule| {
+            if self.rules.is_enabled(rule) {
+                findings.push(Finding {
+                    rule,
+                    position: token.position,
+                });
+            }
+        };
+
+        match &token.value {
+            Token::UnquotedValue(value) | Token::QuotedValue(value) => {
+                let bytes = value.as_bytes();
+                if let Token::QuotedValue(_) = token.value {
+                    if !requires_quotes(bytes, matches!(self.line, Line::Begin)) {
+                        report(Rule::UnnecessaryQuotes);
+                    }
+                }
+                if contains_non_ascii_spacing(bytes) {
+                    report(Rule::NonAsciiSpacing);
+                }
+            }
+            Token::Spacing(spacing) => {
+                if matches!(next, None | Some(Token::LineBreak(_))) {
+                    report(Rule::TrailingSpacing);
+                }
+
+                let kind = SpacingKind::of(spacing.as_bytes());
+                if kind == SpacingKind::Mixed
+                    || kind != *self.first_spacing_kind.get_or_insert(kind)
+                {
+                    report(Rule::MixedSpacing);
+                }
+            }
+            Token::LineBreak(line_break) => {
+                let first_line_break = *self.first_line_break.get_or_insert(*line_break);
+                if *line_break != first_line_break {
+                    report(Rule::MixedLineBreaks);
+                }
+            }
+            Token::Comment(_) => {}
+        }
+
+        self.line = match (std::mem::replace(&mut self.line, Line::Begin), &token.value) {
+            (Line::Begin, Token::Comment(_)) => Line::Comment,
+            (line, Token::LineBreak(_)) => {
+                findings.extend(self.finish_line(line, token.position));
+                Line::Begin
+            }
+            (Line::Begin, value) => Line::Row {
+                position: token.position,
+                value_count: Self::value_count(value),
+            },
+            (
+                Line::Row {
+                    position,
+                    value_count,
+                },
+                value,
+            ) => Line::Row {
+                position,
+                value_count: value_count + Self::value_count(value),
+            },
+            (Line::Comment, _) => Line::Comment,
+        };
+
+        findings
+    }
+
+    fn finish(&mut self) -> Vec<Finding> {
+        match std::mem::replace(&mut self.line, Line::Begin) {
+            line @ Line::Row { position, .. } => self.finish_line(line, position),
+            Line::Begin | Line::Comment => Vec::new(),
+        }
+    }
+
+    fn finish_line(&mut self, line: Line, line_break_position: Position) -> Vec<Finding> {
+        let (position, value_count) = match line {
+            Line::Begin => (line_break_position, 0),
+            Line::Row {
+                position,
+                value_count,
+            } => (position, value_count),
+            Line::Comment => return Vec::new(),
+        };
+
+        let expected_value_count = *self.expected_value_count.get_or_insert(value_count);
+        if value_count != expected_value_count
+            && self.rules.is_enabled(Rule::InconsistentValueCount)
+        {
+            vec![Finding {
+                rule: Rule::InconsistentValueCount,
+                position,
+            }]
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn value_count<D: Domain>(token: &Token<D>) -> usize {
+        match token {
+            Token::UnquotedValue(_) | Token::QuotedValue(_) => 1,
+            _ => 0,
+        }
+    }
+}
+
+impl SpacingKind {
+    fn of(spacing: &[u8]) -> Self {
+        let tabs = spacing.contains(&b'\t');
+        let spaces = spacing.contains(&b' ');
+        match (spaces, tabs) {
+            (true, true) => SpacingKind::Mixed,
+            (false, true) => SpacingKind::Tabs,
+            _ => SpacingKind::Spaces,
+        }
+    }
+}
+
+fn contains_non_ascii_spacing(bytes: &[u8]) -> bool {
+    String::from_utf8_lossy(bytes)
+        .chars()
+        .any(|char| char.is_whitespace() && !char.is_ascii())
+}
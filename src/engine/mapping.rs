@@ -0,0 +1,413 @@
+//! Maps SSV rows to and from structs.
+//!
+//! A struct implementing [`FromSsvRow`] is read from the rows of a
+//! [`StructReader`], and a struct implementing [`ToSsvRow`] is written as a
+//! row by a [`StructWriter`]. The values are parsed with [`FromStr`] and
+//! formatted with [`Display`].
+//!
+//! With the `derive` feature, both traits can be derived for structs with
+//! `#[derive(FromSsvRow, ToSsvRow)]`. Each field is mapped to the column at its
+//! position in the struct, and is configured with `#[ssv(...)]` attributes:
+//!
+//! * `column = "Name"` - maps the field to the column with the name in the
+//!   header row, which is also the name written in the header row. Without it,
+//!   the name of the field is written.
+//! * `optional` - the field is optional. Fields of type `Option<T>` are
+//!   optional without this attribute, which is needed only when the type is an
+//!   alias of `Option`. A missing or empty value, or a column not found in the
+//!   header row, is read as `None`, and `None` is written as an empty value.
+//! * `default` or `default = "path"` - the field is optional, and a missing or
+//!   empty value is read as [`Default::default()`], or as the result of the
+//!   function at the given path.
+//! * `quote = "policy"` - the [`QuotingPolicy`] of the column when writing:
+//!   `"minimal"`, `"always"`, `"non_numeric"` or `"never"`.
+//!
+//! # Example
+//!
+#![cfg_attr(feature = "derive", doc = "```")]
+#![cfg_attr(not(feature = "derive"), doc = "```ignore")]
+//! use ssv::chars::{FromSsvRow, StructReader, StructWriter, ToSsvRow};
+//!
+//! #[derive(FromSsvRow, ToSsvRow, PartialEq, Debug)]
+//! struct Person {
+//!     #[ssv(column = "Name")]
+//!     name: String,
+//!     #[ssv(column = "Age")]
+//!     age: u32,
+//!     #[ssv(column = "Note", quote = "always")]
+//!     note: Option<String>,
+//! }
+//!
+//! let input = "Age Name Note\n53 \"John Doe\" Joe\n23 Mary\n";
+//! let people = StructReader::<_, Person>::new(input.as_bytes())
+//!     .with_header(true)
+//!     .collect::<Result<Vec<_>, _>>()?;
+//!
+//! assert_eq!(people[1], Person { name: "Mary".into(), age: 23, note: None });
+//!
+//! let mut output = Vec::new();
+//! let mut writer = StructWriter::<_, Person>::new(&mut output).with_header(true);
+//! writer.write_all(&people)?;
+//! writer.finish()?;
+//!
+//! assert_eq!(output, b"Name Age Note\n\"John Doe\" 53 \"Joe\"\nMary 23 \"\"\n");
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+
+use std::error::Error;
+use std::fmt::Display;
+use std::io::{Read, Write};
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::str::FromStr;
+
+use crate::engine::domain::{Domain, DomainString, DomainStringSlice};
+
+use super::columns::ColumnRef;
+use super::options::QuotingPolicy;
+use super::reader::Reader;
+use super::writer::Writer;
+use super::{ReadWriteError, WriteResult};
+
+#[cfg(feature = "derive")]
+pub use ssv_derive::{FromSsvRow, ToSsvRow};
+
+/// The column a field is mapped to.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FieldColumn {
+    /// The name of the field.
+    pub field: &'static str,
+
+    /// The name of the column in the header row, or `None` if the field is
+    /// mapped to the column at its position.
+    pub name: Option<&'static str>,
+
+    /// Whether the field is required. The values of an optional field are
+    /// `None` if its column is not found in the header row.
+    pub required: bool,
+}
+
+/// A type whose values are read from SSV rows.
+///
+/// It can be derived with the `derive` feature (see the [module](self) docs).
+pub trait FromSsvRow: Sized {
+    /// The columns of the fields, in the order of the fields.
+    const COLUMNS: &'static [FieldColumn];
+
+    /// Creates a value from the values of the [columns](Self::COLUMNS) of the
+    /// fields, which are `None` for the columns that are missing in the row.
+    fn from_ssv_values(values: &[Option<&str>]) -> Result<Self, FieldError>;
+}
+
+/// A type whose values are written as SSV rows.
+///
+/// It can be derived with the `derive` feature (see the [module](self) docs).
+pub trait ToSsvRow {
+    /// The names of the columns, written in the header row.
+    const HEADER: &'static [&'static str];
+
+    /// The quoting policies of the columns, or `None` for the columns that
+    /// follow the policy of the writer.
+    const QUOTING_POLICIES: &'static [Option<QuotingPolicy>];
+
+    /// Returns the values of the row.
+    fn to_ssv_values(&self) -> Vec<String>;
+
+    /// Writes the values as a row with [`Writer::write_row`].
+    fn write_ssv_row<D: Domain, W: Write>(&self, writer: &mut Writer<D, W>) -> WriteResult<()> {
+        let values: Vec<_> = self
+            .to_ssv_values()
+            .iter()
+            .map(|value| D::String::from_text(value))
+            .collect();
+        writer.write_row(values.iter().map(Deref::deref))
+    }
+}
+
+/// Parses the value of a required field.
+///
+/// It is used by the derived [`FromSsvRow`] implementations.
+pub fn parse_value<T: FromStr>(field: &'static str, value: Option<&str>) -> Result<T, FieldError>
+where
+    T::Err: Display,
+{
+    let value = value.ok_or(FieldError::MissingValue(field))?;
+    value
+        .parse()
+        .map_err(|error: T::Err| FieldError::InvalidValue(field, error.to_string()))
+}
+
+/// Parses the value of an optional field, returning `None` if the value is
+/// missing or empty.
+///
+/// It is used by the derived [`FromSsvRow`] implementations.
+pub fn parse_optional_value<T: FromStr>(
+    field: &'static str,
+    value: Option<&str>,
+) -> Result<Option<T>, FieldError>
+where
+    T::Err: Display,
+{
+    match value {
+        None | Some("") => Ok(None),
+        Some(_) => parse_value(field, value).map(Some),
+    }
+}
+
+/// Formats the value of an optional field, returning an empty value for `None`.
+///
+/// It is used by the derived [`ToSsvRow`] implementations.
+pub fn format_optional_value<T: Display>(value: &Option<T>) -> String {
+    match value {
+        Some(value) => value.to_string(),
+        None => String::new(),
+    }
+}
+
+/// Reads values of a type implementing [`FromSsvRow`] from SSV rows.
+#[doc = generic_item_warning_doc!("StructReader")]
+/// If the reader [uses a header](StructReader::with_header), the first row is
+/// the header row, which is used to find the columns of the fields mapped by
+/// name.
+pub struct StructReader<D: Domain, R: Read, T: FromSsvRow> {
+    reader: Reader<D, R>,
+    header: bool,
+    indexes: Option<Vec<Option<usize>>>,
+    row_number: usize,
+    failed: bool,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<D: Domain, R: Read, T: FromSsvRow> StructReader<D, R, T> {
+    /// Creates an instance without header that reads SSV from the given byte
+    /// reader.
+    pub fn new(inner: R) -> Self {
+        StructReader {
+            reader: Reader::new(inner),
+            header: false,
+            indexes: None,
+            row_number: 0,
+            failed: false,
+            marker: PhantomData,
+        }
+    }
+
+    /// Sets whether the first row is a header row.
+    ///
+    /// Fields can be mapped to columns by name only if there is a header row.
+    pub fn with_header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    fn bind(&mut self) -> MappingResult<Vec<Option<usize>>> {
+        let header = if self.header {
+            match self.reader.next() {
+                Some(header) => {
+                    self.row_number += 1;
+                    Some(header?)
+                }
+                None => None,
+            }
+        } else {
+            None
+        };
+
+        T::COLUMNS
+            .iter()
+            .enumerate()
+            .map(|(index, column)| {
+                let Some(name) = column.name else {
+                    return Ok(Some(index));
+                };
+                let column_ref = ColumnRef::<D>::from(name);
+                if column.required {
+                    Ok(Some(column_ref.try_resolve(header.as_deref())?))
+                } else {
+                    Ok(column_ref.resolve(header.as_deref()))
+                }
+            })
+            .collect()
+    }
+
+    fn read_next(&mut self) -> MappingResult<Option<T>> {
+        let indexes = match &self.indexes {
+            Some(indexes) => indexes,
+            None => {
+                let indexes = self.bind()?;
+                self.indexes.insert(indexes)
+            }
+        };
+
+        let Some(row) = self.reader.next().transpose()? else {
+            return Ok(None);
+        };
+        self.row_number += 1;
+
+        let values = indexes
+            .iter()
+            .map(|index| {
+                index
+                    .and_then(|index| row.get(index))
+                    .map(|value| std::str::from_utf8(value.as_bytes()))
+                    .transpose()
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| MappingError::InvalidUtf8(self.row_number))?;
+
+        T::from_ssv_values(&values)
+            .map(Some)
+            .map_err(|error| MappingError::FieldError(self.row_number, error))
+    }
+}
+
+impl<D: Domain, R: Read, T: FromSsvRow> Iterator for StructReader<D, R, T> {
+    type Item = MappingResult<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let result = self.read_next();
+        // The reading can't continue if the columns could not be found
+        self.failed = result.is_err() && self.indexes.is_none();
+        result.transpose()
+    }
+}
+
+impl<D: Domain, R: Read, T: FromSsvRow> FusedIterator for StructReader<D, R, T> {}
+
+/// Writes values of a type implementing [`ToSsvRow`] as SSV rows.
+#[doc = generic_item_warning_doc!("StructWriter")]
+/// If the writer [uses a header](StructWriter::with_header), a header row with
+/// the [names of the columns](ToSsvRow::HEADER) is written before the first
+/// row, even if no row is written. The [quoting policies](ToSsvRow::QUOTING_POLICIES)
+/// of the columns don't apply to the header row.
+pub struct StructWriter<D: Domain, W: Write, T: ToSsvRow> {
+    writer: Writer<D, W>,
+    header: bool,
+    started: bool,
+    marker: PhantomData<fn(&T)>,
+}
+
+impl<D: Domain, W: Write, T: ToSsvRow> StructWriter<D, W, T> {
+    /// Creates an instance without header that writes SSV to the given byte
+    /// writer.
+    pub fn new(inner: W) -> Self {
+        StructWriter {
+            writer: Writer::new(inner),
+            header: false,
+            started: false,
+            marker: PhantomData,
+        }
+    }
+
+    /// Sets whether a header row is written.
+    pub fn with_header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Writes a value as a row.
+    pub fn write(&mut self, value: &T) -> WriteResult<()> {
+        self.start()?;
+        value.write_ssv_row(&mut self.writer)
+    }
+
+    /// Writes several values as rows.
+    pub fn write_all<'a>(&mut self, values: impl IntoIterator<Item = &'a T>) -> WriteResult<()>
+    where
+        T: 'a,
+    {
+        for value in values {
+            self.write(value)?;
+        }
+        Ok(())
+    }
+
+    /// Finalizes the object by flushing the underlying byte writer.
+    pub fn finish(mut self) -> WriteResult<()> {
+        self.start()?;
+        self.writer.finish()
+    }
+
+    fn start(&mut self) -> WriteResult<()> {
+        if !self.started {
+            self.started = true;
+            if self.header {
+                let header: Vec<_> = T::HEADER
+                    .iter()
+                    .map(|name| D::String::from_text(name))
+                    .collect();
+                self.writer.write_row(header.iter().map(Deref::deref))?;
+            }
+            for (index, policy) in T::QUOTING_POLICIES.iter().enumerate() {
+                if let Some(policy) = policy {
+                    self.writer.set_column_quoting_policy(index, *policy);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The error type for the values of fields.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum FieldError {
+    /// A required field has no value. It has the name of the field.
+    MissingValue(&'static str),
+
+    /// A value could not be parsed. It has the name of the field and the parse
+    /// error message.
+    InvalidValue(&'static str, String),
+}
+
+impl Error for FieldError {}
+
+impl Display for FieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FieldError::MissingValue(field) => write!(f, "missing value for field {field}"),
+            FieldError::InvalidValue(field, message) => {
+                write!(f, "invalid value for field {field}: {message}")
+            }
+        }
+    }
+}
+
+/// A specialized [`Result`] type for [`StructReader`] operations.
+pub type MappingResult<T> = Result<T, MappingError>;
+
+/// The error type for [`StructReader`] operations.
+#[derive(Debug)]
+pub enum MappingError {
+    /// A value is not valid UTF-8. It has the row number, starting from 1 and
+    /// including the header row.
+    InvalidUtf8(usize),
+
+    /// The value of a field is missing or invalid. It has the row number,
+    /// starting from 1 and including the header row.
+    FieldError(usize, FieldError),
+
+    /// The column of a field was not found, or an error happened when reading.
+    ReadWriteError(ReadWriteError),
+}
+
+impl Error for MappingError {}
+
+impl_from_read_write_error!(MappingError);
+
+impl Display for MappingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MappingError::InvalidUtf8(row_number) => {
+                write!(f, "invalid UTF-8 in row {row_number}")
+            }
+            MappingError::FieldError(row_number, field_error) => {
+                write!(f, "{field_error} in row {row_number}")
+            }
+            MappingError::ReadWriteError(error) => write!(f, "{error}"),
+        }
+    }
+}
@@ -0,0 +1,411 @@
+//! Three-way merge of SSV rows.
+
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Write};
+use std::ops::Deref;
+
+use crate::engine::domain::{Domain, DomainString, DomainStringSlice};
+
+use super::alignment::{self, Line};
+use super::columns::ColumnRef;
+use super::reader::{Record, RecordReader};
+use super::ReadWriteResult;
+
+/// Merges the changes made to the rows of a base SSV input in two other inputs,
+/// named "ours" and "theirs".
+#[doc = generic_item_warning_doc!("Merger")]
+/// Rows are matched by the values in the [key columns](Merger::key), or by all
+/// their values if there are no key columns. If the merger
+/// [uses a header](Merger::with_header), the header rows are matched to each
+/// other. Values are compared after parsing, so differences in spacing and
+/// quoting are ignored.
+///
+/// Rows changed on a single side are merged cell by cell: a cell takes the
+/// value of the side that changed it. Only a cell changed differently on both
+/// sides, or a row deleted on a side and changed on the other, is a conflict.
+/// A conflict is written between conflict markers, like the ones written by
+/// git:
+///
+/// ```text
+/// <<<<<<< ours
+/// 1 John Paris
+/// =======
+/// 1 John Lyon
+/// >>>>>>> theirs
+/// ```
+///
+/// Comments are kept with the rows that follow them, and are deleted with
+/// them. The comments of a row that is in both "ours" and "theirs" are merged
+/// like a cell, and comments at the end of the inputs like the comments of a
+/// row: comments changed differently on both sides are a conflict.
+///
+/// The output follows the order of the rows in "ours", with rows added in
+/// "theirs" placed after the row that precedes them in "theirs", and after the
+/// rows added in "ours" at the same place. The values of each column are
+/// aligned, and line-breaks are written as LF.
+///
+/// # Example
+///
+/// ```
+/// use ssv::chars::Merger;
+///
+/// let base = "id name city\n1 John Paris\n2 Jane Rome\n";
+/// let ours = "id name  city\n1 Johnny Paris\n2 Jane   Rome\n";
+/// let theirs = "id name city\n1 John Lyon\n2 Jane Rome\n3 Bob Oslo\n";
+/// let mut output = Vec::new();
+///
+/// let conflicts = Merger::new().with_header(true).key("id").merge(
+///     base.as_bytes(),
+///     ours.as_bytes(),
+///     theirs.as_bytes(),
+///     &mut output,
+/// )?;
+///
+/// assert_eq!(conflicts, 0);
+/// assert_eq!(output, b"id name   city\n1  Johnny Lyon\n2  Jane   Rome\n3  Bob    Oslo\n");
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct Merger<D: Domain> {
+    keys: Vec<ColumnRef<D>>,
+    header: bool,
+}
+
+impl<D: Domain> Merger<D> {
+    /// Creates an instance without key columns and without header.
+    pub fn new() -> Self {
+        Merger {
+            keys: Vec::new(),
+            header: false,
+        }
+    }
+
+    /// Sets whether the first row of each input is a header row.
+    ///
+    /// Columns can be referenced by name only if there are header rows.
+    pub fn with_header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Adds a key column.
+    pub fn key(mut self, column: impl Into<ColumnRef<D>>) -> Self {
+        self.keys.push(column.into());
+        self
+    }
+
+    /// Reads SSV from the given byte readers, merges the rows, and writes the
+    /// result to the given byte writer.
+    ///
+    /// Returns the number of conflicts.
+    pub fn merge<B: Read, O: Read, T: Read, W: Write>(
+        &self,
+        base: B,
+        ours: O,
+        theirs: T,
+        writer: W,
+    ) -> ReadWriteResult<usize> {
+        let base = self.read(base)?;
+        let ours = self.read(ours)?;
+        let theirs = self.read(theirs)?;
+
+        let base_rows: HashMap<_, _> = base
+            .entries
+            .into_iter()
+            .map(|entry| (entry.key, (entry.row, entry.comments)))
+            .collect();
+        let ours_keys: HashSet<_> = ours.entries.iter().map(|entry| &entry.key).collect();
+        let mut theirs_rows = HashMap::new();
+
+        // Rows of "theirs" that are not in "ours", by the key of the preceding
+        // row of "theirs" that is in "ours"
+        let mut insertions: HashMap<Option<Key>, Vec<Insertion<D>>> = HashMap::new();
+        let mut anchor = None;
+        for entry in theirs.entries {
+            if ours_keys.contains(&entry.key) {
+                anchor = Some(entry.key.clone());
+                theirs_rows.insert(entry.key, (entry.row, entry.comments));
+                continue;
+            }
+
+            let merged = match base_rows.get(&entry.key) {
+                None => Merged::Row(entry.row),
+                Some((base_row, _)) if *base_row == entry.row => continue,
+                Some(_) => Merged::Conflict(None, Some(entry.row)),
+            };
+            insertions
+                .entry(anchor.clone())
+                .or_default()
+                .push((Comments::Lines(entry.comments), merged));
+        }
+
+        // The insertions are written before the next row that is in both "ours"
+        // and "theirs", so that rows added on both sides at the same place are
+        // written with the rows of "ours" first
+        let mut output = Output::new();
+        let mut pending = insertions.remove(&None);
+        for entry in ours.entries {
+            let (base_row, base_comments) = match base_rows.get(&entry.key) {
+                Some((row, comments)) => (Some(row), &comments[..]),
+                None => (None, &[][..]),
+            };
+            let theirs_row = theirs_rows.remove(&entry.key);
+            if theirs_row.is_some() {
+                output.extend(pending.take());
+            }
+            let (comments, merged) = match theirs_row {
+                Some((theirs_row, theirs_comments)) => (
+                    merge_comments(base_comments, entry.comments, theirs_comments),
+                    merge_cells(base_row, entry.row, theirs_row),
+                ),
+                None => (
+                    Comments::Lines(entry.comments),
+                    match base_row {
+                        None => Merged::Row(entry.row),
+                        Some(base_row) if *base_row == entry.row => Merged::Deleted,
+                        Some(_) => Merged::Conflict(Some(entry.row), None),
+                    },
+                ),
+            };
+
+            output.push(comments, merged);
+            if let Some(insertions) = insertions.remove(&Some(entry.key)) {
+                pending.get_or_insert_with(Vec::new).extend(insertions);
+            }
+        }
+        output.extend(pending);
+        output.push_comments(merge_comments(
+            &base.trailing_comments,
+            ours.trailing_comments,
+            theirs.trailing_comments,
+        ));
+
+        alignment::write_aligned(writer, &output.lines)?;
+        Ok(output.conflicts)
+    }
+
+    fn read<R: Read>(&self, reader: R) -> ReadWriteResult<Input<D>> {
+        let mut input = Input {
+            entries: Vec::new(),
+            trailing_comments: Vec::new(),
+        };
+        let mut comments = Vec::new();
+        let mut key_columns = None;
+        let mut occurrences = HashMap::new();
+
+        for record in RecordReader::<D, R>::new(reader) {
+            let row = match record?.value {
+                Record::Row(row) => row,
+                Record::Comment(comment) => {
+                    comments.push(comment);
+                    continue;
+                }
+            };
+
+            let values = match &key_columns {
+                None if self.header => {
+                    key_columns = Some(self.resolve(Some(&row))?);
+                    None
+                }
+                None => {
+                    let columns = key_columns.insert(self.resolve(None)?);
+                    Some(key_values::<D>(&row, columns))
+                }
+                Some(columns) => Some(key_values::<D>(&row, columns)),
+            };
+            let occurrence = occurrences.entry(values.clone()).or_insert(0);
+            let key = Key(values, *occurrence);
+            *occurrence += 1;
+
+            input.entries.push(Entry {
+                key,
+                row,
+                comments: std::mem::take(&mut comments),
+            });
+        }
+        input.trailing_comments = comments;
+
+        Ok(input)
+    }
+
+    fn resolve(&self, header: Option<&[D::String]>) -> ReadWriteResult<Option<Vec<usize>>> {
+        if self.keys.is_empty() {
+            return Ok(None);
+        }
+        self.keys
+            .iter()
+            .map(|column| Ok(column.try_resolve(header)?))
+            .collect::<ReadWriteResult<_>>()
+            .map(Some)
+    }
+}
+
+impl<D: Domain> Default for Merger<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Identifies a row by its key values (`None` for the header row) and by the
+/// occurrence of these key values.
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct Key(Option<Vec<Vec<u8>>>, usize);
+
+struct Input<D: Domain> {
+    entries: Vec<Entry<D>>,
+    trailing_comments: Vec<D::String>,
+}
+
+struct Entry<D: Domain> {
+    key: Key,
+    row: Vec<D::String>,
+    comments: Vec<D::String>,
+}
+
+fn key_values<D: Domain>(row: &[D::String], columns: &Option<Vec<usize>>) -> Vec<Vec<u8>> {
+    let value = |value: &D::String| value.deref().as_bytes().to_vec();
+    match columns {
+        Some(columns) => columns
+            .iter()
+            .map(|index| row.get(*index).map_or(Vec::new(), value))
+            .collect(),
+        None => row.iter().map(value).collect(),
+    }
+}
+
+enum Merged<D: Domain> {
+    Row(Vec<D::String>),
+    Deleted,
+    Conflict(Option<Vec<D::String>>, Option<Vec<D::String>>),
+}
+
+fn merge_cells<D: Domain>(
+    base: Option<&Vec<D::String>>,
+    ours: Vec<D::String>,
+    theirs: Vec<D::String>,
+) -> Merged<D> {
+    let width = ours.len().max(theirs.len()).max(base.map_or(0, Vec::len));
+    let mut merged = Vec::with_capacity(width);
+    let mut conflicting = false;
+    for index in 0..width {
+        let base = base.and_then(|base| base.get(index));
+        let ours = ours.get(index);
+        let theirs = theirs.get(index);
+        let (ours, theirs) = if ours == theirs || theirs == base {
+            (ours, ours)
+        } else if ours == base {
+            (theirs, theirs)
+        } else {
+            conflicting = true;
+            (ours, theirs)
+        };
+        merged.push((ours.cloned(), theirs.cloned()));
+    }
+
+    let row = |values: Vec<Option<D::String>>| {
+        let mut values: Vec<_> = values
+            .into_iter()
+            .rev()
+            .skip_while(Option::is_none)
+            .collect();
+        values.reverse();
+        values
+            .into_iter()
+            .map(|value| value.unwrap_or_else(D::String::new))
+            .collect::<Vec<_>>()
+    };
+    let (ours, theirs): (Vec<_>, Vec<_>) = merged.into_iter().unzip();
+    if conflicting {
+        Merged::Conflict(Some(row(ours)), Some(row(theirs)))
+    } else {
+        Merged::Row(row(ours))
+    }
+}
+
+/// A row of "theirs" that is not in "ours", with its comments.
+type Insertion<D> = (Comments<D>, Merged<D>);
+
+/// The comments preceding a row, or a conflict between the comments of "ours"
+/// and "theirs".
+enum Comments<D: Domain> {
+    Lines(Vec<D::String>),
+    Conflict(Vec<D::String>, Vec<D::String>),
+}
+
+fn merge_comments<D: Domain>(
+    base: &[D::String],
+    ours: Vec<D::String>,
+    theirs: Vec<D::String>,
+) -> Comments<D> {
+    if ours == theirs || theirs == base {
+        Comments::Lines(ours)
+    } else if ours == base {
+        Comments::Lines(theirs)
+    } else {
+        Comments::Conflict(ours, theirs)
+    }
+}
+
+struct Output<D: Domain> {
+    lines: Vec<Line<D>>,
+    conflicts: usize,
+}
+
+impl<D: Domain> Output<D> {
+    fn new() -> Self {
+        Output {
+            lines: Vec::new(),
+            conflicts: 0,
+        }
+    }
+
+    fn extend(&mut self, merged: Option<Vec<Insertion<D>>>) {
+        for (comments, merged) in merged.into_iter().flatten() {
+            self.push(comments, merged);
+        }
+    }
+
+    fn push(&mut self, comments: Comments<D>, merged: Merged<D>) {
+        match merged {
+            Merged::Row(row) => {
+                self.push_comments(comments);
+                self.lines.push(Line::Row(row));
+            }
+            Merged::Deleted => {}
+            Merged::Conflict(ours, theirs) => {
+                self.push_comments(comments);
+                self.push_conflict(ours.map(Line::Row), theirs.map(Line::Row));
+            }
+        }
+    }
+
+    fn push_comments(&mut self, comments: Comments<D>) {
+        match comments {
+            Comments::Lines(comments) => self.lines.extend(comments.into_iter().map(Line::Comment)),
+            Comments::Conflict(ours, theirs) => self.push_conflict(
+                ours.into_iter().map(Line::Comment),
+                theirs.into_iter().map(Line::Comment),
+            ),
+        }
+    }
+
+    fn push_conflict(
+        &mut self,
+        ours: impl IntoIterator<Item = Line<D>>,
+        theirs: impl IntoIterator<Item = Line<D>>,
+    ) {
+        let marker = |texts: &[&str]| {
+            Line::Unaligned(
+                texts
+                    .iter()
+                    .map(|text| D::String::from_text(text))
+                    .collect(),
+            )
+        };
+        self.lines.push(marker(&["<<<<<<<", "ours"]));
+        self.lines.extend(ours);
+        self.lines.push(marker(&["======="]));
+        self.lines.extend(theirs);
+        self.lines.push(marker(&[">>>>>>>", "theirs"]));
+        self.conflicts += 1;
+    }
+}
@@ -7,6 +7,8 @@ use crate::engine::LineBreak;
 
 use super::{WriteError, WriteResult};
 
+pub use crate::syntax::writer::{CommentLineBreakPolicy, QuotingPolicy};
+
 /// The options for writing SSV content.
 #[doc = generic_item_warning_doc!("Options")]
 /// See the docs for [`FluentWriter`](crate::engine::fluent_writer::FluentWriter)
@@ -15,7 +17,8 @@ use super::{WriteError, WriteResult};
 pub struct Options<D: Domain> {
     default_spacing: D::String,
     default_line_break: LineBreak,
-    always_quoted: bool,
+    quoting_policy: QuotingPolicy,
+    comment_line_break_policy: CommentLineBreakPolicy,
 }
 
 impl<D: Domain> Options<D> {
@@ -24,7 +27,8 @@ impl<D: Domain> Options<D> {
         Options {
             default_spacing: D::String::from_element(D::SPACE),
             default_line_break: LineBreak::Lf,
-            always_quoted: false,
+            quoting_policy: QuotingPolicy::Minimal,
+            comment_line_break_policy: CommentLineBreakPolicy::Reject,
         }
     }
 
@@ -53,14 +57,26 @@ impl<D: Domain> Options<D> {
         self.default_line_break = line_break;
     }
 
-    /// Returns whether the values are always quoted.
-    pub fn always_quoted(&self) -> bool {
-        self.always_quoted
+    /// Returns the quoting policy used for the columns without a specific
+    /// column quoting policy (see
+    /// [`FluentWriter::column_quoting_policy`](crate::engine::fluent_writer::FluentWriter::column_quoting_policy)).
+    pub fn quoting_policy(&self) -> QuotingPolicy {
+        self.quoting_policy
+    }
+
+    /// Sets the [quoting policy](Options::quoting_policy).
+    pub fn set_quoting_policy(&mut self, quoting_policy: QuotingPolicy) {
+        self.quoting_policy = quoting_policy;
     }
 
-    /// Sets whether the values are always quoted.
-    pub fn set_always_quoted(&mut self, always_quoted: bool) {
-        self.always_quoted = always_quoted;
+    /// Returns how comments containing line-breaks are handled.
+    pub fn comment_line_break_policy(&self) -> CommentLineBreakPolicy {
+        self.comment_line_break_policy
+    }
+
+    /// Sets the [comment line-break policy](Options::comment_line_break_policy).
+    pub fn set_comment_line_break_policy(&mut self, policy: CommentLineBreakPolicy) {
+        self.comment_line_break_policy = policy;
     }
 }
 
@@ -77,19 +93,27 @@ mod tests {
     use crate::engine::{LineBreak, WriteError};
     use crate::tests::support::TAB;
 
-    use super::Options;
+    use super::{CommentLineBreakPolicy, Options, QuotingPolicy};
 
     #[test]
     fn initial_values() {
         let options: Options<BytesDomain> = Options::new();
         assert_eq!(options.default_spacing(), b" ");
         assert_eq!(options.default_line_break(), LineBreak::Lf);
-        assert_eq!(options.always_quoted(), false);
+        assert_eq!(options.quoting_policy(), QuotingPolicy::Minimal);
+        assert_eq!(
+            options.comment_line_break_policy(),
+            CommentLineBreakPolicy::Reject
+        );
 
         let options: Options<CharsDomain> = Options::new();
         assert_eq!(options.default_spacing(), " ");
         assert_eq!(options.default_line_break(), LineBreak::Lf);
-        assert_eq!(options.always_quoted(), false);
+        assert_eq!(options.quoting_policy(), QuotingPolicy::Minimal);
+        assert_eq!(
+            options.comment_line_break_policy(),
+            CommentLineBreakPolicy::Reject
+        );
     }
 
     #[test]
@@ -139,13 +163,30 @@ mod tests {
     }
 
     #[test]
-    fn set_always_quoted() {
+    fn set_quoting_policy() {
+        let mut options: Options<BytesDomain> = Options::new();
+        options.set_quoting_policy(QuotingPolicy::Always);
+        assert_eq!(options.quoting_policy(), QuotingPolicy::Always);
+
+        let mut options: Options<CharsDomain> = Options::new();
+        options.set_quoting_policy(QuotingPolicy::Never);
+        assert_eq!(options.quoting_policy(), QuotingPolicy::Never);
+    }
+
+    #[test]
+    fn set_comment_line_break_policy() {
         let mut options: Options<BytesDomain> = Options::new();
-        options.set_always_quoted(true);
-        assert_eq!(options.always_quoted(), true);
+        options.set_comment_line_break_policy(CommentLineBreakPolicy::Split);
+        assert_eq!(
+            options.comment_line_break_policy(),
+            CommentLineBreakPolicy::Split
+        );
 
         let mut options: Options<CharsDomain> = Options::new();
-        options.set_always_quoted(true);
-        assert_eq!(options.always_quoted(), true);
+        options.set_comment_line_break_policy(CommentLineBreakPolicy::Split);
+        assert_eq!(
+            options.comment_line_break_policy(),
+            CommentLineBreakPolicy::Split
+        );
     }
 }
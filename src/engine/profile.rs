@@ -0,0 +1,770 @@
+//! Infers the types of SSV columns and computes statistics about their values.
+
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::error::Error;
+use std::fmt::Display;
+use std::hash::Hasher;
+use std::io::{Read, Write};
+use std::ops::Deref;
+use std::str::FromStr;
+
+use crate::engine::domain::{Domain, DomainString, DomainStringSlice};
+
+use super::position::Position;
+use super::reader::{Record, RecordReader};
+use super::writer::Writer;
+use super::{ReadResult, ReadWriteError, WriteResult};
+
+/// Computes a [`Profile`] of the columns of SSV rows in a single pass.
+#[doc = generic_item_warning_doc!("Profiler")]
+/// # Example
+///
+/// ```
+/// use ssv::chars::{ColumnType, DecimalSeparator, Profiler, Reader};
+///
+/// let input = "id price date\n1 2,5 2024-01-31\n2 \"\" 2024-02-01\n3 10 2024-02-29\n";
+///
+/// let profile = Profiler::new()
+///     .with_header(true)
+///     .profile(Reader::new(input.as_bytes()))?;
+///
+/// assert_eq!(profile.row_count, 3);
+/// let price = &profile.columns[1];
+/// assert_eq!(price.name.as_deref(), Some("price"));
+/// assert_eq!(price.column_type, ColumnType::Decimal(DecimalSeparator::Comma));
+/// assert_eq!(price.empty_count, 1);
+/// assert_eq!(price.max.as_deref(), Some("10"));
+/// assert_eq!(profile.columns[2].column_type, ColumnType::Date);
+/// # Ok::<_, ssv::chars::ReadError>(())
+/// ```
+pub struct Profiler<D: Domain> {
+    header: bool,
+    _domain: std::marker::PhantomData<D>,
+}
+
+impl<D: Domain> Profiler<D> {
+    /// Creates an instance without header.
+    pub fn new() -> Self {
+        Profiler {
+            header: false,
+            _domain: std::marker::PhantomData,
+        }
+    }
+
+    /// Sets whether the first row is a header row, whose values are the names
+    /// of the columns.
+    pub fn with_header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Computes the profile of the rows returned by the given iterator, such
+    /// as a [`Reader`](super::reader::Reader).
+    pub fn profile<I>(&self, rows: I) -> ReadResult<Profile<D>>
+    where
+        I: IntoIterator<Item = ReadResult<Vec<D::String>>>,
+    {
+        let mut rows = rows.into_iter();
+        let names = if self.header {
+            rows.next().transpose()?
+        } else {
+            None
+        };
+
+        let mut columns: Vec<ColumnStats<D>> = Vec::new();
+        let mut row_count = 0;
+        for row in rows {
+            let row = row?;
+            while columns.len() < row.len() {
+                columns.push(ColumnStats::new(row_count));
+            }
+            for (index, stats) in columns.iter_mut().enumerate() {
+                match row.get(index) {
+                    Some(value) => stats.add(value),
+                    None => stats.null_count += 1,
+                }
+            }
+            row_count += 1;
+        }
+
+        let names = names.unwrap_or_default();
+        while columns.len() < names.len() {
+            columns.push(ColumnStats::new(row_count));
+        }
+        Ok(Profile {
+            row_count,
+            columns: columns
+                .into_iter()
+                .enumerate()
+                .map(|(index, stats)| stats.finish(names.get(index).cloned()))
+                .collect(),
+        })
+    }
+}
+
+impl<D: Domain> Default for Profiler<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The profile of the columns of SSV rows, computed by a [`Profiler`].
+#[doc = generic_item_warning_doc!("Profile")]
+#[derive(Clone, PartialEq, Debug)]
+pub struct Profile<D: Domain> {
+    /// The number of rows, not counting the header row.
+    pub row_count: usize,
+
+    /// The profiles of the columns.
+    pub columns: Vec<ColumnProfile<D>>,
+}
+
+impl<D: Domain> Profile<D> {
+    /// Returns a [`Schema`] with the names and the inferred types of the
+    /// columns.
+    ///
+    /// A column is nullable if it has null or empty values.
+    pub fn schema(&self) -> Schema<D> {
+        Schema {
+            columns: self
+                .columns
+                .iter()
+                .map(|column| ColumnSchema {
+                    name: column.name.clone().unwrap_or_else(D::String::new),
+                    column_type: column.column_type,
+                    nullable: column.null_count > 0 || column.empty_count > 0,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The profile of a column, computed by a [`Profiler`].
+#[doc = generic_item_warning_doc!("ColumnProfile")]
+#[derive(Clone, PartialEq, Debug)]
+pub struct ColumnProfile<D: Domain> {
+    /// The name of the column in the header row, if any.
+    pub name: Option<D::String>,
+
+    /// The most specific type of all the values that are not empty.
+    ///
+    /// It is [`ColumnType::Text`] if all the values are empty.
+    pub column_type: ColumnType,
+
+    /// The number of rows that do not have a value in the column.
+    pub null_count: usize,
+
+    /// The number of empty values.
+    pub empty_count: usize,
+
+    /// The minimum value that is not empty, compared according to the
+    /// [column type](ColumnProfile::column_type).
+    pub min: Option<D::String>,
+
+    /// The maximum value that is not empty, compared according to the
+    /// [column type](ColumnProfile::column_type).
+    pub max: Option<D::String>,
+
+    /// An estimate of the number of distinct values, including the empty
+    /// value.
+    ///
+    /// The estimate is computed with the HyperLogLog algorithm, using 4 KiB
+    /// per column, and has a typical error of about 1.6%. Small counts are
+    /// nearly exact.
+    pub distinct_estimate: usize,
+
+    /// The value with the most elements. If several values have the most
+    /// elements, it is the first of them.
+    pub longest: Option<D::String>,
+
+    row_count: usize,
+}
+
+impl<D: Domain> ColumnProfile<D> {
+    /// The ratio of rows that do not have a value in the column.
+    pub fn null_ratio(&self) -> f64 {
+        ratio(self.null_count, self.row_count)
+    }
+
+    /// The ratio of rows that have an empty value in the column.
+    pub fn empty_ratio(&self) -> f64 {
+        ratio(self.empty_count, self.row_count)
+    }
+}
+
+fn ratio(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f64 / total as f64
+    }
+}
+
+/// The type of the values of a column.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ColumnType {
+    /// `true`, `false`, `yes` or `no`, in any case.
+    Boolean,
+
+    /// Digits, optionally preceded by a sign (`+` or `-`).
+    Integer,
+
+    /// An integer, or digits with the decimal separator between them,
+    /// optionally preceded by a sign.
+    Decimal(DecimalSeparator),
+
+    /// A date in the `YYYY-MM-DD` format.
+    Date,
+
+    /// Any value.
+    Text,
+}
+
+/// A decimal separator.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DecimalSeparator {
+    /// Point (`.`).
+    Point,
+
+    /// Comma (`,`).
+    Comma,
+}
+
+impl DecimalSeparator {
+    fn char(self) -> char {
+        match self {
+            DecimalSeparator::Point => '.',
+            DecimalSeparator::Comma => ',',
+        }
+    }
+}
+
+impl ColumnType {
+    const INFERRED: [ColumnType; 5] = [
+        ColumnType::Boolean,
+        ColumnType::Integer,
+        ColumnType::Decimal(DecimalSeparator::Point),
+        ColumnType::Decimal(DecimalSeparator::Comma),
+        ColumnType::Date,
+    ];
+
+    /// Returns whether the value has this type.
+    ///
+    /// Empty values do not have any type other than [`ColumnType::Text`].
+    pub fn matches(self, value: &str) -> bool {
+        match self {
+            ColumnType::Boolean => ["true", "false", "yes", "no"]
+                .iter()
+                .any(|boolean| value.eq_ignore_ascii_case(boolean)),
+            ColumnType::Integer => is_integer(value),
+            ColumnType::Decimal(separator) => {
+                is_integer(value)
+                    || value
+                        .split_once(separator.char())
+                        .is_some_and(|(integer, fraction)| {
+                            is_integer(integer) && is_digits(fraction)
+                        })
+            }
+            ColumnType::Date => is_date(value),
+            ColumnType::Text => true,
+        }
+    }
+
+    fn parse_number(self, value: &str) -> Option<f64> {
+        match self {
+            ColumnType::Integer | ColumnType::Decimal(DecimalSeparator::Point) => {
+                value.parse().ok()
+            }
+            ColumnType::Decimal(DecimalSeparator::Comma) => value.replace(',', ".").parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+impl Display for ColumnType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ColumnType::Boolean => "boolean",
+            ColumnType::Integer => "integer",
+            ColumnType::Decimal(DecimalSeparator::Point) => "decimal",
+            ColumnType::Decimal(DecimalSeparator::Comma) => "decimal-comma",
+            ColumnType::Date => "date",
+            ColumnType::Text => "text",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for ColumnType {
+    type Err = ();
+
+    /// Parses the names written by the [`Display`] implementation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ColumnType::INFERRED
+            .into_iter()
+            .chain([ColumnType::Text])
+            .find(|column_type| column_type.to_string() == s)
+            .ok_or(())
+    }
+}
+
+fn is_digits(text: &str) -> bool {
+    !text.is_empty() && text.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+fn is_integer(text: &str) -> bool {
+    is_digits(text.strip_prefix(['+', '-']).unwrap_or(text))
+}
+
+fn is_date(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    if bytes.len() != 10 || bytes[4] != b'-' || bytes[7] != b'-' {
+        return false;
+    }
+    let number = |range: std::ops::Range<usize>| {
+        let digits = &text[range];
+        is_digits(digits).then(|| digits.parse::<u32>().unwrap_or_default())
+    };
+    match (number(0..4), number(5..7), number(8..10)) {
+        (Some(year), Some(month), Some(day)) => {
+            let days = match month {
+                2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+                2 => 28,
+                4 | 6 | 9 | 11 => 30,
+                1..=12 => 31,
+                _ => return false,
+            };
+            (1..=days).contains(&day)
+        }
+        _ => false,
+    }
+}
+
+/// A number and the value it was parsed from.
+type Extreme<D> = (f64, <D as Domain>::String);
+
+struct ColumnStats<D: Domain> {
+    null_count: usize,
+    empty_count: usize,
+    /// Whether all the values that are not empty have each of
+    /// [`ColumnType::INFERRED`].
+    candidates: [bool; 5],
+    lexical: Option<(D::String, D::String)>,
+    /// Minimum and maximum with the decimal point and with the decimal comma.
+    numeric: [Option<(Extreme<D>, Extreme<D>)>; 2],
+    distinct: DistinctEstimator,
+    longest: Option<(usize, D::String)>,
+}
+
+impl<D: Domain> ColumnStats<D> {
+    fn new(null_count: usize) -> Self {
+        ColumnStats {
+            null_count,
+            empty_count: 0,
+            candidates: [true; 5],
+            lexical: None,
+            numeric: [None, None],
+            distinct: DistinctEstimator::new(),
+            longest: None,
+        }
+    }
+
+    fn add(&mut self, value: &D::String) {
+        let bytes = value.deref().as_bytes();
+        self.distinct.add(bytes);
+
+        let length = value.deref().elements().len();
+        if self
+            .longest
+            .as_ref()
+            .is_none_or(|(longest, _)| length > *longest)
+        {
+            self.longest = Some((length, value.clone()));
+        }
+
+        if bytes.is_empty() {
+            self.empty_count += 1;
+            return;
+        }
+
+        let text = String::from_utf8_lossy(bytes);
+        for (candidate, column_type) in self.candidates.iter_mut().zip(ColumnType::INFERRED) {
+            *candidate = *candidate && column_type.matches(&text);
+        }
+
+        match &mut self.lexical {
+            None => self.lexical = Some((value.clone(), value.clone())),
+            Some((min, max)) => {
+                if bytes < min.deref().as_bytes() {
+                    *min = value.clone();
+                }
+                if bytes > max.deref().as_bytes() {
+                    *max = value.clone();
+                }
+            }
+        }
+
+        for (extremes, separator) in self
+            .numeric
+            .iter_mut()
+            .zip([DecimalSeparator::Point, DecimalSeparator::Comma])
+        {
+            let column_type = ColumnType::Decimal(separator);
+            if !column_type.matches(&text) {
+                continue;
+            }
+            let Some(number) = column_type.parse_number(&text) else {
+                continue;
+            };
+            match extremes {
+                None => *extremes = Some(((number, value.clone()), (number, value.clone()))),
+                Some((min, max)) => {
+                    if number.partial_cmp(&min.0) == Some(Ordering::Less) {
+                        *min = (number, value.clone());
+                    }
+                    if number.partial_cmp(&max.0) == Some(Ordering::Greater) {
+                        *max = (number, value.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    fn finish(self, name: Option<D::String>) -> ColumnProfile<D> {
+        let has_values = self.lexical.is_some();
+        let column_type = ColumnType::INFERRED
+            .into_iter()
+            .zip(self.candidates)
+            .find(|(_, candidate)| has_values && *candidate)
+            .map_or(ColumnType::Text, |(column_type, _)| column_type);
+
+        let [point, comma] = self.numeric;
+        let (min, max) = match column_type {
+            ColumnType::Integer | ColumnType::Decimal(DecimalSeparator::Point) => {
+                point.map(|(min, max)| (min.1, max.1)).unzip()
+            }
+            ColumnType::Decimal(DecimalSeparator::Comma) => {
+                comma.map(|(min, max)| (min.1, max.1)).unzip()
+            }
+            _ => self.lexical.unzip(),
+        };
+
+        let row_count = self.null_count + self.distinct.count;
+        ColumnProfile {
+            name,
+            column_type,
+            null_count: self.null_count,
+            empty_count: self.empty_count,
+            min,
+            max,
+            distinct_estimate: self.distinct.estimate(),
+            longest: self.longest.map(|(_, value)| value),
+            row_count,
+        }
+    }
+}
+
+/// Estimates the number of distinct values with the HyperLogLog algorithm.
+struct DistinctEstimator {
+    registers: Vec<u8>,
+    count: usize,
+}
+
+impl DistinctEstimator {
+    const PRECISION: u32 = 12;
+
+    fn new() -> Self {
+        DistinctEstimator {
+            registers: vec![0; 1 << Self::PRECISION],
+            count: 0,
+        }
+    }
+
+    fn add(&mut self, bytes: &[u8]) {
+        let mut hasher = DefaultHasher::new();
+        hasher.write(bytes);
+        let hash = hasher.finish();
+
+        let index = (hash >> (64 - Self::PRECISION)) as usize;
+        let rank = ((hash << Self::PRECISION).leading_zeros() + 1).min(64 - Self::PRECISION + 1);
+        self.registers[index] = self.registers[index].max(rank as u8);
+        self.count += 1;
+    }
+
+    fn estimate(&self) -> usize {
+        let m = self.registers.len() as f64;
+        let sum: f64 = self
+            .registers
+            .iter()
+            .map(|register| 2f64.powi(-i32::from(*register)))
+            .sum();
+        let raw = 0.7213 / (1.0 + 1.079 / m) * m * m / sum;
+
+        let zeros = self
+            .registers
+            .iter()
+            .filter(|register| **register == 0)
+            .count();
+        let estimate = if raw <= 2.5 * m && zeros > 0 {
+            m * (m / zeros as f64).ln()
+        } else {
+            raw
+        };
+        (estimate.round() as usize).min(self.count)
+    }
+}
+
+/// The names, types and nullability of columns, to validate SSV content.
+#[doc = generic_item_warning_doc!("Schema")]
+/// A schema is usually obtained from a [`Profile`], and can be written as SSV
+/// and read back. The SSV has a header row with the columns `name`, `type` and
+/// `nullable`, and a row per column. The types are written as `boolean`,
+/// `integer`, `decimal`, `decimal-comma`, `date` or `text`, and the
+/// nullability as `true` or `false`.
+///
+/// If all the names are empty, the validated content is expected not to have a
+/// header row.
+///
+/// # Example
+///
+/// ```
+/// use ssv::chars::{Profiler, Reader, ViolationKind};
+///
+/// let input = "id name\n1 John\n2 Jane\n";
+/// let schema = Profiler::new().with_header(true).profile(Reader::new(input.as_bytes()))?.schema();
+///
+/// let mut output = Vec::new();
+/// schema.write(&mut output)?;
+/// assert_eq!(output, b"name type nullable\nid integer false\nname text false\n");
+///
+/// let violations = schema.validate("id name\nx John\n3\n".as_bytes())?;
+/// assert_eq!(violations.len(), 2);
+/// assert!(matches!(violations[0].kind, ViolationKind::TypeMismatch(_)));
+/// assert!(matches!(violations[1].kind, ViolationKind::MissingValue));
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Schema<D: Domain> {
+    /// The schemas of the columns.
+    pub columns: Vec<ColumnSchema<D>>,
+}
+
+/// The name, type and nullability of a column in a [`Schema`].
+#[doc = generic_item_warning_doc!("ColumnSchema")]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ColumnSchema<D: Domain> {
+    /// The name of the column, or an empty value if it is unknown.
+    pub name: D::String,
+
+    /// The type of the values that are not empty.
+    pub column_type: ColumnType,
+
+    /// Whether values can be empty or missing.
+    pub nullable: bool,
+}
+
+impl<D: Domain> Schema<D> {
+    const HEADER: [&'static str; 3] = ["name", "type", "nullable"];
+
+    /// Reads a schema written as SSV from the given byte reader.
+    pub fn read<R: Read>(reader: R) -> SchemaResult<Self> {
+        let mut records = RecordReader::<D, R>::new(reader).filter_map(|record| match record {
+            Ok(record) => match record.value {
+                Record::Row(row) => Some(Ok((row, record.position))),
+                Record::Comment(_) => None,
+            },
+            Err(error) => Some(Err(error)),
+        });
+
+        let text =
+            |value: &D::String| String::from_utf8_lossy(value.deref().as_bytes()).into_owned();
+        match records.next().transpose()? {
+            Some((header, _)) if header.iter().map(text).eq(Self::HEADER) => {}
+            Some((_, position)) => return Err(SchemaError::InvalidRow(position)),
+            None => {
+                return Err(SchemaError::InvalidRow(Position {
+                    line_number: 1,
+                    column_number: 1,
+                }))
+            }
+        }
+
+        let mut columns = Vec::new();
+        for record in records {
+            let (row, position) = record?;
+            let [name, column_type, nullable] =
+                <[D::String; 3]>::try_from(row).map_err(|_| SchemaError::InvalidRow(position))?;
+            columns.push(ColumnSchema {
+                name,
+                column_type: text(&column_type)
+                    .parse()
+                    .map_err(|_| SchemaError::InvalidRow(position))?,
+                nullable: match text(&nullable).as_str() {
+                    "true" => true,
+                    "false" => false,
+                    _ => return Err(SchemaError::InvalidRow(position)),
+                },
+            });
+        }
+
+        Ok(Schema { columns })
+    }
+
+    /// Writes the schema as SSV to the given byte writer.
+    pub fn write<W: Write>(&self, writer: W) -> WriteResult<()> {
+        let mut writer = Writer::<D, W>::new(writer);
+        let text = |text: &str| D::String::from_text(text);
+
+        writer.write_row(Self::HEADER.map(text).iter().map(Deref::deref))?;
+        for column in &self.columns {
+            let row = [
+                column.name.clone(),
+                text(&column.column_type.to_string()),
+                text(if column.nullable { "true" } else { "false" }),
+            ];
+            writer.write_row(row.iter().map(Deref::deref))?;
+        }
+        writer.finish()
+    }
+
+    /// Reads SSV from the given byte reader and returns the violations of the
+    /// schema.
+    ///
+    /// If the schema has names, the first row must be a header row with these
+    /// names.
+    pub fn validate<R: Read>(&self, reader: R) -> SchemaResult<Vec<Violation<D>>> {
+        let mut violations = Vec::new();
+        let mut header = self
+            .columns
+            .iter()
+            .any(|column| !column.name.deref().as_bytes().is_empty());
+
+        for record in RecordReader::<D, R>::new(reader) {
+            let record = record?;
+            let Record::Row(row) = record.value else {
+                continue;
+            };
+            let mut violation = |column, kind| {
+                violations.push(Violation {
+                    position: record.position,
+                    column,
+                    kind,
+                });
+            };
+
+            if header {
+                header = false;
+                for (index, column) in self.columns.iter().enumerate() {
+                    let found = row.get(index);
+                    if found != Some(&column.name) {
+                        violation(
+                            index,
+                            ViolationKind::HeaderMismatch {
+                                expected: column.name.clone(),
+                                found: found.cloned(),
+                            },
+                        );
+                    }
+                }
+                for (index, name) in row.iter().enumerate().skip(self.columns.len()) {
+                    violation(index, ViolationKind::UnexpectedValue(name.clone()));
+                }
+                continue;
+            }
+
+            for (index, column) in self.columns.iter().enumerate() {
+                match row.get(index) {
+                    None => {
+                        if !column.nullable {
+                            violation(index, ViolationKind::MissingValue);
+                        }
+                    }
+                    Some(value) if value.deref().as_bytes().is_empty() => {
+                        if !column.nullable {
+                            violation(index, ViolationKind::MissingValue);
+                        }
+                    }
+                    Some(value) => {
+                        let text = String::from_utf8_lossy(value.deref().as_bytes());
+                        if !column.column_type.matches(&text) {
+                            violation(index, ViolationKind::TypeMismatch(value.clone()));
+                        }
+                    }
+                }
+            }
+            for (index, value) in row.iter().enumerate().skip(self.columns.len()) {
+                violation(index, ViolationKind::UnexpectedValue(value.clone()));
+            }
+        }
+
+        Ok(violations)
+    }
+}
+
+/// A violation of a [`Schema`].
+#[doc = generic_item_warning_doc!("Violation")]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Violation<D: Domain> {
+    /// The position of the row.
+    pub position: Position,
+
+    /// The column index.
+    pub column: usize,
+
+    /// What is wrong.
+    pub kind: ViolationKind<D>,
+}
+
+/// The kind of a [`Violation`].
+#[doc = generic_item_warning_doc!("ViolationKind")]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ViolationKind<D: Domain> {
+    /// The header row does not have the expected name.
+    HeaderMismatch {
+        /// The name in the schema.
+        expected: D::String,
+
+        /// The name in the header row, if any.
+        found: Option<D::String>,
+    },
+
+    /// A value is empty or missing in a column that is not nullable.
+    MissingValue,
+
+    /// A value does not have the type of the column.
+    TypeMismatch(D::String),
+
+    /// A value is in a column that is not in the schema.
+    UnexpectedValue(D::String),
+}
+
+/// A specialized [`Result`] type for [`Schema`] operations.
+pub type SchemaResult<T> = Result<T, SchemaError>;
+
+/// The error type for [`Schema`] operations.
+#[derive(Debug)]
+pub enum SchemaError {
+    /// A row of a schema is not valid.
+    InvalidRow(Position),
+
+    /// An error happened when reading or writing.
+    ReadWriteError(ReadWriteError),
+}
+
+impl Error for SchemaError {}
+
+impl_from_read_write_error!(SchemaError);
+
+impl Display for SchemaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchemaError::InvalidRow(position) => write!(
+                f,
+                "invalid schema row at {}:{}",
+                position.line_number, position.column_number
+            ),
+            SchemaError::ReadWriteError(error) => write!(f, "{error}"),
+        }
+    }
+}
@@ -0,0 +1,633 @@
+//! Selects, reorders, renames and computes columns.
+
+use std::error::Error;
+use std::fmt::Display;
+use std::io::{Read, Write};
+use std::iter::Peekable;
+use std::str::{CharIndices, FromStr};
+
+use crate::engine::domain::{Domain, DomainString};
+
+use super::columns::{ColumnNotFoundError, ColumnRef};
+use super::position::WithPosition;
+use super::reader::{Record, RecordReader};
+use super::writer::Writer;
+use super::{ReadResult, ReadWriteError, ReadWriteResult};
+
+/// Selects, reorders, renames and computes columns of SSV rows.
+#[doc = generic_item_warning_doc!("Projection")]
+/// A projection is a list of output columns, which are built with the methods
+/// [`all`](Projection::all), [`select`](Projection::select),
+/// [`select_as`](Projection::select_as) and [`compute`](Projection::compute),
+/// or parsed from a [spec string](Projection::parse).
+///
+/// If the projection [uses a header](Projection::with_header), the first row is
+/// the header row, which is used to find the columns referenced by name, and a
+/// header row with the names of the output columns is written. Otherwise,
+/// columns can only be referenced by index.
+///
+/// Comment lines pass through unchanged. Values in columns beyond the end of a
+/// row are empty.
+///
+/// # Example
+///
+/// ```
+/// use ssv::chars::Projection;
+///
+/// let input = "id first last\n# comment\n1 John Doe\n";
+/// let mut output = Vec::new();
+///
+/// let projection: Projection = "name=first+' '+last, 0".parse()?;
+/// projection.with_header(true).transform(input.as_bytes(), &mut output)?;
+///
+/// assert_eq!(output, b"name id\n# comment\n\"John Doe\" 1\n");
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct Projection<D: Domain> {
+    items: Vec<Item<D>>,
+    excluded: Vec<ColumnRef<D>>,
+    header: bool,
+}
+
+/// A function that computes a value from the values of the source columns.
+type Compute<D> = Box<dyn Fn(&[&<D as Domain>::StringSlice]) -> <D as Domain>::String>;
+
+enum Item<D: Domain> {
+    AllColumns,
+    Column {
+        source: ColumnRef<D>,
+        name: Option<D::String>,
+    },
+    Computed {
+        name: D::String,
+        sources: Vec<ColumnRef<D>>,
+        compute: Compute<D>,
+    },
+}
+
+impl<D: Domain> Projection<D> {
+    /// Creates an instance without output columns and without header.
+    pub fn new() -> Self {
+        Projection {
+            items: Vec::new(),
+            excluded: Vec::new(),
+            header: false,
+        }
+    }
+
+    /// Sets whether the first row is a header row.
+    pub fn with_header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Returns whether the first row is a header row.
+    pub fn has_header(&self) -> bool {
+        self.header
+    }
+
+    /// Adds all the input columns, in order, except the
+    /// [excluded](Projection::exclude) ones.
+    pub fn all(mut self) -> Self {
+        self.items.push(Item::AllColumns);
+        self
+    }
+
+    /// Excludes a column from the columns added by [`all`](Projection::all).
+    pub fn exclude(mut self, column: impl Into<ColumnRef<D>>) -> Self {
+        self.excluded.push(column.into());
+        self
+    }
+
+    /// Adds an input column, keeping its name in the header.
+    pub fn select(mut self, column: impl Into<ColumnRef<D>>) -> Self {
+        self.items.push(Item::Column {
+            source: column.into(),
+            name: None,
+        });
+        self
+    }
+
+    /// Adds an input column with a new name in the header.
+    pub fn select_as(mut self, column: impl Into<ColumnRef<D>>, name: &D::StringSlice) -> Self {
+        self.items.push(Item::Column {
+            source: column.into(),
+            name: Some(D::String::from(name)),
+        });
+        self
+    }
+
+    /// Adds a column whose values are computed from the values of the source
+    /// columns.
+    ///
+    /// The function receives the values of the source columns in the same
+    /// order as in `sources`.
+    pub fn compute<F>(
+        mut self,
+        name: &D::StringSlice,
+        sources: Vec<ColumnRef<D>>,
+        compute: F,
+    ) -> Self
+    where
+        F: Fn(&[&D::StringSlice]) -> D::String + 'static,
+    {
+        self.items.push(Item::Computed {
+            name: D::String::from(name),
+            sources,
+            compute: Box::new(compute),
+        });
+        self
+    }
+
+    /// Parses a projection from a spec string.
+    ///
+    /// The spec is a comma-separated list of items:
+    /// * `column` - adds an input column;
+    /// * `name=column` - adds an input column with a new name;
+    /// * `name=operand+operand+...` - adds a column whose values are the
+    ///   concatenation of the operands, which are columns or literals;
+    /// * `*` - adds all the input columns;
+    /// * `-column` - excludes a column from the columns added by `*`.
+    ///
+    /// A column is referenced by index (only digits, starting from 0) or by
+    /// name. Names containing special characters (`,`, `=`, `+`, `*`, `-`,
+    /// quotes or whitespace) or only digits must be enclosed in double quotes
+    /// (`"`). Literals are enclosed in single quotes (`'`). Quotes are escaped
+    /// by duplicating them. Whitespace between items and operands is ignored.
+    ///
+    /// The returned projection does not [use a header](Projection::with_header).
+    pub fn parse(spec: &str) -> Result<Self, SpecError> {
+        SpecParser::new(spec).parse()
+    }
+
+    /// Returns an iterator that applies the projection to the records returned
+    /// by the given iterator, such as a [`RecordReader`].
+    pub fn apply<I>(&self, records: I) -> Projected<'_, D, I>
+    where
+        I: Iterator<Item = ReadResult<WithPosition<Record<D>>>>,
+    {
+        Projected {
+            projection: self,
+            records,
+            state: if self.header {
+                ProjectedState::Header
+            } else {
+                self.bind(None).into()
+            },
+        }
+    }
+
+    /// Reads SSV from the given byte reader, applies the projection, and writes
+    /// the result to the given byte writer.
+    pub fn transform<R: Read, W: Write>(&self, reader: R, writer: W) -> ReadWriteResult<()> {
+        let mut writer = Writer::new(writer);
+        for record in self.apply(RecordReader::new(reader)) {
+            writer.write_record(&record?)?;
+        }
+        writer.finish()?;
+        Ok(())
+    }
+
+    fn bind(&self, header: Option<&[D::String]>) -> ReadWriteResult<Bound<'_, D>> {
+        let resolve = |column: &ColumnRef<D>| column.try_resolve(header);
+        let name_of = |index: usize, column: &ColumnRef<D>| {
+            header
+                .and_then(|header| header.get(index))
+                .cloned()
+                .ok_or_else(|| ColumnNotFoundError(column.to_string()))
+        };
+
+        let excluded = self
+            .excluded
+            .iter()
+            .map(resolve)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut items = Vec::new();
+        let mut names = Vec::new();
+        for item in &self.items {
+            match item {
+                Item::AllColumns => {
+                    items.push(BoundItem::AllColumns);
+                    if let Some(header) = header {
+                        names.extend(
+                            header
+                                .iter()
+                                .enumerate()
+                                .filter(|(index, _)| !excluded.contains(index))
+                                .map(|(_, name)| name.clone()),
+                        );
+                    }
+                }
+                Item::Column { source, name } => {
+                    let index = resolve(source)?;
+                    items.push(BoundItem::Column(index));
+                    if header.is_some() {
+                        names.push(match name {
+                            Some(name) => name.clone(),
+                            None => name_of(index, source)?,
+                        });
+                    }
+                }
+                Item::Computed {
+                    name,
+                    sources,
+                    compute,
+                } => {
+                    let sources = sources.iter().map(resolve).collect::<Result<_, _>>()?;
+                    items.push(BoundItem::Computed { sources, compute });
+                    names.push(name.clone());
+                }
+            }
+        }
+
+        Ok(Bound {
+            items,
+            excluded,
+            width: header.map(|header| header.len()),
+            header: header.map(|_| names),
+        })
+    }
+}
+
+impl<D: Domain> Default for Projection<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D: Domain> FromStr for Projection<D> {
+    type Err = SpecError;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        Self::parse(spec)
+    }
+}
+
+struct Bound<'a, D: Domain> {
+    items: Vec<BoundItem<'a, D>>,
+    excluded: Vec<usize>,
+    /// The number of columns in the header row, which the
+    /// [`AllColumns`](BoundItem::AllColumns) item outputs regardless of the
+    /// length of each row.
+    width: Option<usize>,
+    header: Option<Vec<D::String>>,
+}
+
+enum BoundItem<'a, D: Domain> {
+    AllColumns,
+    Column(usize),
+    Computed {
+        sources: Vec<usize>,
+        compute: &'a Compute<D>,
+    },
+}
+
+impl<D: Domain> Bound<'_, D> {
+    fn project(&self, row: &[D::String]) -> Vec<D::String> {
+        let empty = D::String::new();
+        let value_at = |index: usize| row.get(index).unwrap_or(&empty).clone();
+
+        let mut projected = Vec::new();
+        for item in &self.items {
+            match item {
+                BoundItem::AllColumns => projected.extend(
+                    (0..self.width.unwrap_or(row.len()))
+                        .filter(|index| !self.excluded.contains(index))
+                        .map(value_at),
+                ),
+                BoundItem::Column(index) => projected.push(value_at(*index)),
+                BoundItem::Computed { sources, compute } => {
+                    let values: Vec<_> = sources
+                        .iter()
+                        .map(|index| row.get(*index).unwrap_or(&empty))
+                        .collect();
+                    let values: Vec<&D::StringSlice> =
+                        values.iter().map(|value| &***value).collect();
+                    projected.push(compute(&values));
+                }
+            }
+        }
+        projected
+    }
+}
+
+/// An iterator that applies a [`Projection`] to records.
+#[doc = generic_item_warning_doc!("Projected")]
+/// It is returned by the [`Projection::apply`] method.
+pub struct Projected<'a, D: Domain, I> {
+    projection: &'a Projection<D>,
+    records: I,
+    state: ProjectedState<'a, D>,
+}
+
+enum ProjectedState<'a, D: Domain> {
+    Header,
+    Bound(Bound<'a, D>),
+    Failed(Option<ReadWriteError>),
+}
+
+impl<'a, D: Domain> From<ReadWriteResult<Bound<'a, D>>> for ProjectedState<'a, D> {
+    fn from(result: ReadWriteResult<Bound<'a, D>>) -> Self {
+        match result {
+            Ok(bound) => ProjectedState::Bound(bound),
+            Err(error) => ProjectedState::Failed(Some(error)),
+        }
+    }
+}
+
+impl<D: Domain, I> Iterator for Projected<'_, D, I>
+where
+    I: Iterator<Item = ReadResult<WithPosition<Record<D>>>>,
+{
+    type Item = ReadWriteResult<Record<D>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let ProjectedState::Failed(error) = &mut self.state {
+            return error.take().map(Err);
+        }
+
+        let record = match self.records.next()? {
+            Ok(record) => record.value,
+            Err(error) => return Some(Err(error.into())),
+        };
+
+        let row = match record {
+            Record::Row(row) => row,
+            comment @ Record::Comment(_) => return Some(Ok(comment)),
+        };
+
+        match &self.state {
+            ProjectedState::Header => {
+                self.state = self.projection.bind(Some(&row)).into();
+                match &mut self.state {
+                    ProjectedState::Bound(bound) => {
+                        Some(Ok(Record::Row(bound.header.take().unwrap_or_default())))
+                    }
+                    ProjectedState::Failed(error) => error.take().map(Err),
+                    ProjectedState::Header => unreachable!(),
+                }
+            }
+            ProjectedState::Bound(bound) => Some(Ok(Record::Row(bound.project(&row)))),
+            ProjectedState::Failed(_) => unreachable!(),
+        }
+    }
+}
+
+/// The error type for parsing a [`Projection`] spec string.
+///
+/// The column numbers count chars, starting from 1.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SpecError {
+    /// An unexpected character was found.
+    UnexpectedChar(char, usize),
+
+    /// The spec ended where more was expected.
+    UnexpectedEnd(usize),
+
+    /// A quoted name or literal was not closed.
+    UnclosedQuote(usize),
+}
+
+impl Error for SpecError {}
+
+impl Display for SpecError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpecError::UnexpectedChar(char, column_number) => {
+                write!(f, "unexpected {char:?} at column {column_number}")
+            }
+            SpecError::UnexpectedEnd(column_number) => {
+                write!(f, "unexpected end at column {column_number}")
+            }
+            SpecError::UnclosedQuote(column_number) => {
+                write!(f, "unclosed quote at column {column_number}")
+            }
+        }
+    }
+}
+
+struct SpecParser<'a> {
+    chars: Peekable<CharIndices<'a>>,
+    spec: &'a str,
+}
+
+enum Operand {
+    Column { text: String, quoted: bool },
+    Literal(String),
+}
+
+impl<'a> SpecParser<'a> {
+    fn new(spec: &'a str) -> Self {
+        SpecParser {
+            chars: spec.char_indices().peekable(),
+            spec,
+        }
+    }
+
+    fn parse<D: Domain>(mut self) -> Result<Projection<D>, SpecError> {
+        let mut projection = Projection::new();
+        loop {
+            projection = self.parse_item(projection)?;
+
+            self.skip_whitespace();
+            match self.chars.next() {
+                None => return Ok(projection),
+                Some((_, ',')) => {}
+                Some((index, char)) => {
+                    return Err(SpecError::UnexpectedChar(char, self.column_number(index)))
+                }
+            }
+        }
+    }
+
+    fn parse_item<D: Domain>(
+        &mut self,
+        projection: Projection<D>,
+    ) -> Result<Projection<D>, SpecError> {
+        self.skip_whitespace();
+        match self.chars.peek() {
+            Some((_, '*')) => {
+                self.chars.next();
+                return Ok(projection.all());
+            }
+            Some((_, '-')) => {
+                self.chars.next();
+                self.skip_whitespace();
+                let column = self.parse_column()?;
+                return Ok(projection.exclude(column));
+            }
+            _ => {}
+        }
+
+        let (first_index, first) = self.parse_operand()?;
+        self.skip_whitespace();
+        if let Some((_, '=')) = self.chars.peek() {
+            self.chars.next();
+            let name = match first {
+                Operand::Column { text, .. } => D::String::from_text(&text),
+                Operand::Literal(_) => {
+                    return Err(SpecError::UnexpectedChar(
+                        '\'',
+                        self.column_number(first_index),
+                    ))
+                }
+            };
+
+            let mut operands = Vec::new();
+            loop {
+                self.skip_whitespace();
+                operands.push(self.parse_operand()?.1);
+                self.skip_whitespace();
+                match self.chars.peek() {
+                    Some((_, '+')) => {
+                        self.chars.next();
+                    }
+                    _ => break,
+                }
+            }
+
+            Ok(match <[Operand; 1]>::try_from(operands) {
+                Ok([Operand::Column { text, quoted }]) => {
+                    projection.select_as(Self::column_ref::<D>(text, quoted), &name)
+                }
+                Ok(operands) => Self::compute(projection, name, operands.into()),
+                Err(operands) => Self::compute(projection, name, operands),
+            })
+        } else {
+            match first {
+                Operand::Column { text, quoted } => {
+                    Ok(projection.select(Self::column_ref::<D>(text, quoted)))
+                }
+                Operand::Literal(_) => Err(SpecError::UnexpectedChar(
+                    '\'',
+                    self.column_number(first_index),
+                )),
+            }
+        }
+    }
+
+    fn compute<D: Domain>(
+        projection: Projection<D>,
+        name: D::String,
+        operands: Vec<Operand>,
+    ) -> Projection<D> {
+        enum Part<D: Domain> {
+            Source(usize),
+            Literal(D::String),
+        }
+
+        let mut sources = Vec::new();
+        let parts: Vec<Part<D>> = operands
+            .into_iter()
+            .map(|operand| match operand {
+                Operand::Column { text, quoted } => {
+                    sources.push(Self::column_ref::<D>(text, quoted));
+                    Part::Source(sources.len() - 1)
+                }
+                Operand::Literal(text) => Part::Literal(D::String::from_text(&text)),
+            })
+            .collect();
+
+        projection.compute(&name, sources, move |values| {
+            let parts: Vec<&D::StringSlice> = parts
+                .iter()
+                .map(|part| match part {
+                    Part::Source(index) => values[*index],
+                    Part::Literal(literal) => &**literal,
+                })
+                .collect();
+            D::concat(&parts)
+        })
+    }
+
+    fn parse_column<D: Domain>(&mut self) -> Result<ColumnRef<D>, SpecError> {
+        match self.parse_operand()? {
+            (_, Operand::Column { text, quoted }) => Ok(Self::column_ref(text, quoted)),
+            (index, Operand::Literal(_)) => {
+                Err(SpecError::UnexpectedChar('\'', self.column_number(index)))
+            }
+        }
+    }
+
+    fn column_ref<D: Domain>(text: String, quoted: bool) -> ColumnRef<D> {
+        match text.parse() {
+            Ok(index) if !quoted && text.bytes().all(|byte| byte.is_ascii_digit()) => {
+                ColumnRef::Index(index)
+            }
+            _ => ColumnRef::Name(D::String::from_text(&text)),
+        }
+    }
+
+    fn parse_operand(&mut self) -> Result<(usize, Operand), SpecError> {
+        match self.chars.peek().copied() {
+            Some((index, '"')) => {
+                let text = self.parse_quoted('"')?;
+                Ok((index, Operand::Column { text, quoted: true }))
+            }
+            Some((index, '\'')) => {
+                let text = self.parse_quoted('\'')?;
+                Ok((index, Operand::Literal(text)))
+            }
+            Some((index, char)) if !Self::is_special(char) => {
+                let mut text = String::new();
+                while let Some((_, char)) = self.chars.peek().copied() {
+                    if Self::is_special(char) {
+                        break;
+                    }
+                    text.push(char);
+                    self.chars.next();
+                }
+                Ok((
+                    index,
+                    Operand::Column {
+                        text,
+                        quoted: false,
+                    },
+                ))
+            }
+            Some((index, char)) => Err(SpecError::UnexpectedChar(char, self.column_number(index))),
+            None => Err(SpecError::UnexpectedEnd(
+                self.column_number(self.spec.len()),
+            )),
+        }
+    }
+
+    fn parse_quoted(&mut self, quote: char) -> Result<String, SpecError> {
+        let (start, _) = self.chars.next().unwrap();
+        let mut text = String::new();
+        loop {
+            match self.chars.next() {
+                Some((_, char)) if char == quote => {
+                    if let Some((_, next)) = self.chars.peek() {
+                        if *next == quote {
+                            self.chars.next();
+                            text.push(quote);
+                            continue;
+                        }
+                    }
+                    return Ok(text);
+                }
+                Some((_, char)) => text.push(char),
+                None => return Err(SpecError::UnclosedQuote(self.column_number(start))),
+            }
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some((_, char)) = self.chars.peek() {
+            if !char.is_whitespace() {
+                break;
+            }
+            self.chars.next();
+        }
+    }
+
+    fn is_special(char: char) -> bool {
+        matches!(char, ',' | '=' | '+' | '*' | '-' | '"' | '\'') || char.is_whitespace()
+    }
+
+    fn column_number(&self, byte_index: usize) -> usize {
+        self.spec[..byte_index].chars().count() + 1
+    }
+}
@@ -5,6 +5,7 @@ use std::iter::FusedIterator;
 
 use crate::engine::domain::Domain;
 
+use super::position::{Position, WithPosition};
 use super::tokenizer::{Token, Tokenizer};
 use super::ReadResult;
 
@@ -31,65 +32,150 @@ use super::ReadResult;
 /// # Ok::<_, ssv::chars::ReadError>(())
 /// ```
 pub struct Reader<D: Domain, R: Read> {
-    tokenizer: Tokenizer<D, R>,
-    state: Option<State<D>>,
+    records: RecordReader<D, R>,
 }
 
 impl<D: Domain, R: Read> Reader<D, R> {
     /// Creates an instance that reads SSV from the given byte reader.
     pub fn new(inner: R) -> Self {
         Reader {
+            records: RecordReader::new(inner),
+        }
+    }
+}
+
+impl<D: Domain, R: Read> Iterator for Reader<D, R> {
+    type Item = ReadResult<Vec<D::String>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for result in self.records.by_ref() {
+            match result {
+                Ok(WithPosition {
+                    value: Record::Row(row),
+                    ..
+                }) => return Some(Ok(row)),
+                Ok(_) => {}
+                Err(error) => return Some(Err(error)),
+            }
+        }
+        None
+    }
+}
+
+impl<D: Domain, R: Read> FusedIterator for Reader<D, R> {}
+
+/// An SSV record: either a row of values or a comment line.
+#[doc = generic_item_warning_doc!("Record")]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Record<D: Domain> {
+    /// A row of values.
+    Row(Vec<D::String>),
+
+    /// A comment line, without the HASH sign (`#`).
+    Comment(D::String),
+}
+
+/// Reads SSV records (rows and comment lines) from a byte reader.
+#[doc = generic_item_warning_doc!("RecordReader")]
+/// It is an iterator of [`ReadResult`]`<`[`WithPosition`]`<`[`Record`]`<D>>>`
+/// values. The position is the position of the first token in the line.
+///
+/// Unlike the [`Reader`], comment lines are not skipped.
+///
+/// # Example
+///
+/// ```
+/// use ssv::chars::{Record, RecordReader};
+///
+/// let input = "# comment\nvalue value";
+///
+/// for result in RecordReader::new(input.as_bytes()) {
+///     match result?.value {
+///         Record::Row(row) => println!("Row: {row:?}"),
+///         Record::Comment(comment) => println!("Comment: {comment:?}"),
+///     }
+/// }
+/// # Ok::<_, ssv::chars::ReadError>(())
+/// ```
+pub struct RecordReader<D: Domain, R: Read> {
+    tokenizer: Tokenizer<D, R>,
+    state: Option<State<D>>,
+}
+
+impl<D: Domain, R: Read> RecordReader<D, R> {
+    /// Creates an instance that reads SSV from the given byte reader.
+    pub fn new(inner: R) -> Self {
+        RecordReader {
             tokenizer: Tokenizer::new(inner),
             state: Some(State::Begin),
         }
     }
 
-    fn process(&mut self, token: Token<D>, state: State<D>) -> ProcessResult<D> {
+    fn process(&mut self, token: WithPosition<Token<D>>, state: State<D>) -> ProcessResult<D> {
+        let position = token.position;
         match state {
-            State::Begin => match token {
+            State::Begin => match token.value {
                 Token::UnquotedValue(value) | Token::QuotedValue(value) => {
-                    ProcessResult::NextState(State::Row(vec![value]))
+                    ProcessResult::NextState(State::Row(position, vec![value]))
+                }
+                Token::Spacing(_) => ProcessResult::NextState(State::Row(position, Vec::new())),
+                Token::LineBreak(_) => ProcessResult::ReturnRecord(WithPosition {
+                    value: Record::Row(Vec::new()),
+                    position,
+                }),
+                Token::Comment(comment) => {
+                    ProcessResult::NextState(State::Comment(position, comment))
                 }
-                Token::Spacing(_) => ProcessResult::NextState(State::Row(Vec::new())),
-                Token::LineBreak(_) => ProcessResult::ReturnRow(Vec::new()),
-                Token::Comment(_) => ProcessResult::NextState(State::Comment),
             },
-            State::Row(mut row) => match token {
+            State::Row(row_position, mut row) => match token.value {
                 Token::UnquotedValue(value) | Token::QuotedValue(value) => {
                     row.push(value);
-                    ProcessResult::NextState(State::Row(row))
+                    ProcessResult::NextState(State::Row(row_position, row))
                 }
-                Token::Spacing(_) => ProcessResult::NextState(State::Row(row)),
-                Token::LineBreak(_) => ProcessResult::ReturnRow(row),
+                Token::Spacing(_) => ProcessResult::NextState(State::Row(row_position, row)),
+                Token::LineBreak(_) => ProcessResult::ReturnRecord(WithPosition {
+                    value: Record::Row(row),
+                    position: row_position,
+                }),
                 Token::Comment(_) => unreachable!(),
             },
-            State::Comment => {
-                matches!(token, Token::LineBreak(_));
-                ProcessResult::NextState(State::Begin)
+            State::Comment(comment_position, comment) => {
+                debug_assert!(matches!(token.value, Token::LineBreak(_)));
+                ProcessResult::ReturnRecord(WithPosition {
+                    value: Record::Comment(comment),
+                    position: comment_position,
+                })
             }
         }
     }
 
-    fn finish(&mut self, state: State<D>) -> Option<Vec<D::String>> {
+    fn finish(&mut self, state: State<D>) -> Option<WithPosition<Record<D>>> {
         match state {
-            State::Row(row) => Some(row),
-            State::Begin | State::Comment => None,
+            State::Row(position, row) => Some(WithPosition {
+                value: Record::Row(row),
+                position,
+            }),
+            State::Comment(position, comment) => Some(WithPosition {
+                value: Record::Comment(comment),
+                position,
+            }),
+            State::Begin => None,
         }
     }
 }
 
-impl<D: Domain, R: Read> Iterator for Reader<D, R> {
-    type Item = ReadResult<Vec<D::String>>;
+impl<D: Domain, R: Read> Iterator for RecordReader<D, R> {
+    type Item = ReadResult<WithPosition<Record<D>>>;
 
     fn next(&mut self) -> Option<Self::Item> {
         let mut state = self.state.take()?;
 
         while let Some(result) = self.tokenizer.next() {
             match result {
-                Ok(token) => match self.process(token.value, state) {
-                    ProcessResult::ReturnRow(row) => {
+                Ok(token) => match self.process(token, state) {
+                    ProcessResult::ReturnRecord(record) => {
                         self.state = Some(State::Begin);
-                        return Some(Ok(row));
+                        return Some(Ok(record));
                     }
                     ProcessResult::NextState(next_state) => state = next_state,
                 },
@@ -104,15 +190,15 @@ impl<D: Domain, R: Read> Iterator for Reader<D, R> {
     }
 }
 
-impl<D: Domain, R: Read> FusedIterator for Reader<D, R> {}
+impl<D: Domain, R: Read> FusedIterator for RecordReader<D, R> {}
 
 enum State<D: Domain> {
     Begin,
-    Row(Vec<D::String>),
-    Comment,
+    Row(Position, Vec<D::String>),
+    Comment(Position, D::String),
 }
 
 enum ProcessResult<D: Domain> {
-    ReturnRow(Vec<D::String>),
+    ReturnRecord(WithPosition<Record<D>>),
     NextState(State<D>),
 }
@@ -0,0 +1,382 @@
+//! Renders SSV rows as Markdown, HTML or text tables.
+
+use std::io::Write;
+use std::ops::Deref;
+
+use crate::engine::domain::{Domain, DomainStringSlice};
+use crate::syntax::numeric::is_numeric;
+
+use super::{ReadResult, ReadWriteResult, WriteError};
+
+/// Renders SSV rows as a table in a [`TableFormat`].
+#[doc = generic_item_warning_doc!("Renderer")]
+/// A column whose values are all numbers, ignoring empty values and the
+/// header, is right-aligned. Rows with fewer values than others are completed
+/// with empty cells. Values which are not valid UTF-8 are converted lossily,
+/// and the width of a value is its number of characters.
+///
+/// # Example
+///
+/// ```
+/// use ssv::chars::{Reader, Renderer, TableFormat};
+///
+/// let input = "\"#\" Name Age Note\n1 \"John Doe\" 53 \"a.k.a. \"\"Joe\"\"\"\n77 Mary 23\n";
+/// let mut output = Vec::new();
+///
+/// Renderer::new(TableFormat::Markdown)
+///     .with_header(true)
+///     .render(Reader::new(input.as_bytes()), &mut output)?;
+///
+/// assert_eq!(
+///     String::from_utf8(output).unwrap(),
+///     "\
+/// | #  | Name     | Age | Note         |
+/// |---:|----------|----:|--------------|
+/// |  1 | John Doe |  53 | a.k.a. \"Joe\" |
+/// | 77 | Mary     |  23 |              |
+/// "
+/// );
+/// # Ok::<_, ssv::chars::ReadWriteError>(())
+/// ```
+pub struct Renderer<D: Domain> {
+    format: TableFormat,
+    header: bool,
+    _domain: std::marker::PhantomData<D>,
+}
+
+impl<D: Domain> Renderer<D> {
+    /// Creates an instance rendering tables in the given format, without
+    /// header.
+    pub fn new(format: TableFormat) -> Self {
+        Renderer {
+            format,
+            header: false,
+            _domain: std::marker::PhantomData,
+        }
+    }
+
+    /// Sets whether the first row is a header row, which is rendered as the
+    /// header of the table.
+    ///
+    /// Without header row, Markdown tables have an empty header, as required
+    /// by GitHub-flavored Markdown.
+    pub fn with_header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Renders the rows returned by the given iterator, such as a
+    /// [`Reader`](super::reader::Reader), and writes the table to the given
+    /// byte writer.
+    pub fn render<I, W>(&self, rows: I, mut writer: W) -> ReadWriteResult<()>
+    where
+        I: IntoIterator<Item = ReadResult<Vec<D::String>>>,
+        W: Write,
+    {
+        let text = |row: Vec<D::String>| -> Vec<String> {
+            row.iter()
+                .map(|value| String::from_utf8_lossy(value.deref().as_bytes()).into_owned())
+                .collect()
+        };
+
+        let mut rows = rows.into_iter();
+        let header = if self.header {
+            rows.next().transpose()?.map(text)
+        } else {
+            None
+        };
+        let rows = rows
+            .map(|row| row.map(text))
+            .collect::<ReadResult<Vec<_>>>()?;
+
+        let table = Table::new(header, rows);
+        match self.format {
+            TableFormat::Markdown => table.write_markdown(&mut writer),
+            TableFormat::Html => table.write_html(&mut writer),
+            TableFormat::Text(style) => table.write_text(&mut writer, style.borders()),
+        }
+        .and_then(|()| writer.flush())
+        .map_err(WriteError::from)?;
+        Ok(())
+    }
+}
+
+/// The format of the tables rendered by a [`Renderer`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum TableFormat {
+    /// A GitHub-flavored Markdown table. Pipes and characters with a meaning
+    /// in Markdown are escaped, and line-breaks are written as `<br>`.
+    #[default]
+    Markdown,
+
+    /// An HTML `<table>` element. Special HTML characters are escaped, and
+    /// line-breaks are written as `<br>`.
+    Html,
+
+    /// A text table with borders, for terminals. Values with line-breaks span
+    /// several lines.
+    Text(BorderStyle),
+}
+
+/// The characters used to draw the borders of [text tables](TableFormat::Text).
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum BorderStyle {
+    /// Box-drawing characters, such as `┌`, `─` and `│`.
+    #[default]
+    Unicode,
+
+    /// The ASCII characters `+`, `-` and `|`.
+    Ascii,
+}
+
+impl BorderStyle {
+    fn borders(self) -> Borders {
+        match self {
+            BorderStyle::Unicode => Borders {
+                horizontal: '─',
+                vertical: '│',
+                top: ['┌', '┬', '┐'],
+                middle: ['├', '┼', '┤'],
+                bottom: ['└', '┴', '┘'],
+            },
+            BorderStyle::Ascii => Borders {
+                horizontal: '-',
+                vertical: '|',
+                top: ['+'; 3],
+                middle: ['+'; 3],
+                bottom: ['+'; 3],
+            },
+        }
+    }
+}
+
+/// The characters of the borders of a text table, with the left, inner and
+/// right corners of the top, middle and bottom lines.
+struct Borders {
+    horizontal: char,
+    vertical: char,
+    top: [char; 3],
+    middle: [char; 3],
+    bottom: [char; 3],
+}
+
+struct Table {
+    header: Option<Vec<String>>,
+    rows: Vec<Vec<String>>,
+    numeric: Vec<bool>,
+}
+
+impl Table {
+    fn new(header: Option<Vec<String>>, mut rows: Vec<Vec<String>>) -> Self {
+        let column_count = rows.iter().chain(&header).map(Vec::len).max().unwrap_or(0);
+        let header = header.map(|mut header| {
+            header.resize_with(column_count, String::new);
+            header
+        });
+        for row in rows.iter_mut() {
+            row.resize_with(column_count, String::new);
+        }
+
+        let numeric = (0..column_count)
+            .map(|index| {
+                let mut values = rows
+                    .iter()
+                    .map(|row| &row[index])
+                    .filter(|value| !value.is_empty())
+                    .peekable();
+                values.peek().is_some() && values.all(|value| is_numeric(value.as_bytes()))
+            })
+            .collect();
+
+        Table {
+            header,
+            rows,
+            numeric,
+        }
+    }
+
+    fn write_markdown<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        if self.numeric.is_empty() {
+            return Ok(());
+        }
+
+        let escape = |row: &Vec<String>| -> Vec<String> {
+            row.iter().map(|value| escape_markdown(value)).collect()
+        };
+        let header = match &self.header {
+            Some(header) => escape(header),
+            None => vec![String::new(); self.numeric.len()],
+        };
+        let rows: Vec<_> = self.rows.iter().map(escape).collect();
+        let widths = column_widths(std::iter::once(&header).chain(&rows), |value| {
+            value.chars().count()
+        });
+
+        let left = vec![false; self.numeric.len()];
+        write_line(writer, '|', &header, &widths, &left)?;
+        write!(writer, "|")?;
+        for (width, numeric) in widths.iter().zip(&self.numeric) {
+            match numeric {
+                true => write!(writer, "{}:|", "-".repeat(width + 1))?,
+                false => write!(writer, "{}|", "-".repeat(width + 2))?,
+            }
+        }
+        writeln!(writer)?;
+        for row in &rows {
+            write_line(writer, '|', row, &widths, &self.numeric)?;
+        }
+        Ok(())
+    }
+
+    fn write_html<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        let write_row = |writer: &mut W, row: &[String], tag: &str| {
+            write!(writer, "<tr>")?;
+            for (value, numeric) in row.iter().zip(&self.numeric) {
+                let style = match numeric {
+                    true => " style=\"text-align: right\"",
+                    false => "",
+                };
+                write!(writer, "<{tag}{style}>{}</{tag}>", escape_html(value))?;
+            }
+            writeln!(writer, "</tr>")
+        };
+
+        writeln!(writer, "<table>")?;
+        if let Some(header) = &self.header {
+            writeln!(writer, "<thead>")?;
+            write_row(writer, header, "th")?;
+            writeln!(writer, "</thead>")?;
+        }
+        if !self.rows.is_empty() {
+            writeln!(writer, "<tbody>")?;
+            for row in &self.rows {
+                write_row(writer, row, "td")?;
+            }
+            writeln!(writer, "</tbody>")?;
+        }
+        writeln!(writer, "</table>")
+    }
+
+    fn write_text<W: Write>(&self, writer: &mut W, borders: Borders) -> std::io::Result<()> {
+        if self.numeric.is_empty() {
+            return Ok(());
+        }
+
+        let widths = column_widths(self.header.iter().chain(&self.rows), |value| {
+            lines(value)
+                .map(|line| line.chars().count())
+                .max()
+                .unwrap_or(0)
+        });
+        let write_border = |writer: &mut W, [left, inner, right]: [char; 3]| {
+            let segments: Vec<String> = widths
+                .iter()
+                .map(|width| borders.horizontal.to_string().repeat(width + 2))
+                .collect();
+            writeln!(writer, "{left}{}{right}", segments.join(&inner.to_string()))
+        };
+        let write_row = |writer: &mut W, row: &[String], numeric: &[bool]| -> std::io::Result<()> {
+            let mut cells: Vec<_> = row.iter().map(|value| lines(value)).collect();
+            loop {
+                let line: Vec<_> = cells.iter_mut().map(Iterator::next).collect();
+                if line.iter().all(Option::is_none) {
+                    return Ok(());
+                }
+                let line: Vec<_> = line
+                    .into_iter()
+                    .map(|line| line.unwrap_or_default())
+                    .collect();
+                write_line(writer, borders.vertical, &line, &widths, numeric)?;
+            }
+        };
+
+        write_border(writer, borders.top)?;
+        if let Some(header) = &self.header {
+            write_row(writer, header, &vec![false; self.numeric.len()])?;
+            write_border(writer, borders.middle)?;
+        }
+        for row in &self.rows {
+            write_row(writer, row, &self.numeric)?;
+        }
+        write_border(writer, borders.bottom)
+    }
+}
+
+fn column_widths<'a>(
+    rows: impl Iterator<Item = &'a Vec<String>>,
+    width: impl Fn(&str) -> usize,
+) -> Vec<usize> {
+    let mut widths = Vec::new();
+    for row in rows {
+        widths.resize(widths.len().max(row.len()), 0);
+        for (column_width, value) in widths.iter_mut().zip(row) {
+            *column_width = (*column_width).max(width(value));
+        }
+    }
+    widths
+}
+
+/// Writes a line of cells padded to the column widths, on the left if they are
+/// right-aligned.
+fn write_line<W: Write, S: AsRef<str>>(
+    writer: &mut W,
+    vertical: char,
+    cells: &[S],
+    widths: &[usize],
+    right_aligned: &[bool],
+) -> std::io::Result<()> {
+    write!(writer, "{vertical}")?;
+    for ((cell, width), right_aligned) in cells.iter().zip(widths).zip(right_aligned) {
+        let cell = cell.as_ref();
+        let padding = " ".repeat(width - cell.chars().count());
+        match right_aligned {
+            true => write!(writer, " {padding}{cell} {vertical}")?,
+            false => write!(writer, " {cell}{padding} {vertical}")?,
+        }
+    }
+    writeln!(writer)
+}
+
+/// Returns the lines of a value, with at least one line.
+fn lines(value: &str) -> impl Iterator<Item = &str> {
+    value
+        .split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .flat_map(|line| line.split('\r'))
+}
+
+fn escape_markdown(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for (index, line) in lines(value).enumerate() {
+        if index > 0 {
+            escaped.push_str("<br>");
+        }
+        for c in line.chars() {
+            if matches!(c, '\\' | '|' | '`' | '*' | '_' | '<' | '[' | ']') {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for (index, line) in lines(value).enumerate() {
+        if index > 0 {
+            escaped.push_str("<br>");
+        }
+        for c in line.chars() {
+            match c {
+                '&' => escaped.push_str("&amp;"),
+                '<' => escaped.push_str("&lt;"),
+                '>' => escaped.push_str("&gt;"),
+                '"' => escaped.push_str("&quot;"),
+                '\'' => escaped.push_str("&#39;"),
+                _ => escaped.push(c),
+            }
+        }
+    }
+    escaped
+}
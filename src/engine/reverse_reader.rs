@@ -0,0 +1,241 @@
+//! Reads SSV rows from the end of the input to its start.
+
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::iter::FusedIterator;
+
+use crate::engine::domain::Domain;
+
+use super::reader::{Record, RecordReader};
+use super::ReadResult;
+
+const CHUNK_SIZE: u64 = 8 * 1024;
+
+/// Reads SSV rows from a seekable byte reader, from the last row to the first.
+#[doc = generic_item_warning_doc!("ReverseReader")]
+/// It is an iterator of SSV rows, like a [`Reader`](super::reader::Reader), but
+/// the rows are returned in reverse order, and only the end of the input is read
+/// to return the last rows.
+///
+/// The input is scanned backward line by line. Whether a line-break ends a row
+/// or is in a quoted value is found by counting the quotes after it. A line
+/// starting with a HASH sign (`#`) and having an odd number of quotes may be
+/// either a comment line or part of a quoted value, so when such a line is
+/// found, the input is scanned from its start up to the line to know which it
+/// is.
+///
+/// The positions in the errors are relative to the row where they happen: line
+/// 1 is the first line of the row.
+///
+/// # Example
+///
+/// ```
+/// use std::io::Cursor;
+///
+/// use ssv::chars::ReverseReader;
+///
+/// let input = "first row\n# comment\n\"multi-line\nvalue\" x\nlast row\n";
+///
+/// let mut reader = ReverseReader::new(Cursor::new(input));
+///
+/// assert_eq!(reader.next().unwrap()?, ["last", "row"]);
+/// assert_eq!(reader.next().unwrap()?, ["multi-line\nvalue", "x"]);
+/// assert_eq!(reader.next().unwrap()?, ["first", "row"]);
+/// assert!(reader.next().is_none());
+/// # Ok::<_, ssv::chars::ReadError>(())
+/// ```
+pub struct ReverseReader<D: Domain, R: Read + Seek> {
+    inner: R,
+    /// The bytes that were read and not returned yet, from `buffer_start` to
+    /// `end`.
+    buffer: Vec<u8>,
+    buffer_start: u64,
+    /// The end of the part of the input that was not returned yet, which is a
+    /// row boundary. It is `None` before the first read.
+    end: Option<u64>,
+    failed: bool,
+    _domain: std::marker::PhantomData<D>,
+}
+
+impl<D: Domain, R: Read + Seek> ReverseReader<D, R> {
+    /// Creates an instance that reads SSV from the given seekable byte reader,
+    /// from its start to its end.
+    pub fn new(inner: R) -> Self {
+        ReverseReader {
+            inner,
+            buffer: Vec::new(),
+            buffer_start: 0,
+            end: None,
+            failed: false,
+            _domain: std::marker::PhantomData,
+        }
+    }
+
+    fn read_row(&mut self) -> ReadResult<Option<Vec<D::String>>> {
+        let mut end = match self.end {
+            Some(end) => end,
+            None => {
+                let end = self.inner.seek(SeekFrom::End(0))?;
+                self.buffer_start = end;
+                end
+            }
+        };
+
+        while end > 0 {
+            let start = self.record_start(end)?;
+            let offset = (start - self.buffer_start) as usize;
+            let record = RecordReader::<D, _>::new(&self.buffer[offset..])
+                .next()
+                .transpose()?;
+            self.buffer.truncate(offset);
+            end = start;
+            self.end = Some(end);
+
+            if let Some(record) = record {
+                match record.value {
+                    Record::Row(row) => return Ok(Some(row)),
+                    Record::Comment(_) => {}
+                }
+            }
+        }
+
+        self.end = Some(0);
+        Ok(None)
+    }
+
+    /// Returns the start of the record that ends at the given row boundary.
+    fn record_start(&mut self, end: u64) -> std::io::Result<u64> {
+        let mut states = States::BOUNDARY;
+        let mut line_end = end;
+        loop {
+            let line_start = self.line_start(line_end)?;
+            let offset = (line_start - self.buffer_start) as usize;
+            let length = (line_end - line_start) as usize;
+            states = states.before(&self.buffer[offset..offset + length]);
+
+            if line_start == 0 {
+                return Ok(0);
+            }
+            let boundary = match states {
+                States::BOUNDARY => true,
+                States::QUOTED => false,
+                _ => self.is_boundary(line_start)?,
+            };
+            if boundary {
+                return Ok(line_start);
+            }
+            states = States::QUOTED;
+            line_end = line_start;
+        }
+    }
+
+    /// Returns the start of the line that ends at the given offset, which is
+    /// after a LF or at the end of the input.
+    fn line_start(&mut self, line_end: u64) -> std::io::Result<u64> {
+        // The LF that ends the line is not searched
+        let mut searched_end = line_end - 1;
+        loop {
+            if searched_end > self.buffer_start {
+                let searched = &self.buffer[..(searched_end - self.buffer_start) as usize];
+                if let Some(index) = searched.iter().rposition(|byte| *byte == b'\n') {
+                    return Ok(self.buffer_start + index as u64 + 1);
+                }
+                searched_end = self.buffer_start;
+            }
+            if self.buffer_start == 0 {
+                return Ok(0);
+            }
+            self.load_chunk()?;
+        }
+    }
+
+    /// Reads the chunk of the input before the buffer, and prepends it to the
+    /// buffer.
+    fn load_chunk(&mut self) -> std::io::Result<()> {
+        let start = self.buffer_start.saturating_sub(CHUNK_SIZE);
+        let mut chunk = vec![0; (self.buffer_start - start) as usize];
+        self.inner.seek(SeekFrom::Start(start))?;
+        self.inner.read_exact(&mut chunk)?;
+        chunk.append(&mut self.buffer);
+        self.buffer = chunk;
+        self.buffer_start = start;
+        Ok(())
+    }
+
+    /// Scans the input from its start to find out whether the line that starts
+    /// at the given offset starts a record.
+    fn is_boundary(&mut self, line_start: u64) -> std::io::Result<bool> {
+        self.inner.seek(SeekFrom::Start(0))?;
+        let mut reader = BufReader::new((&mut self.inner).take(line_start));
+
+        let mut states = States::BOUNDARY;
+        let mut line = Vec::new();
+        while reader.read_until(b'\n', &mut line)? > 0 {
+            states = states.after(&line);
+            line.clear();
+        }
+        Ok(states == States::BOUNDARY)
+    }
+}
+
+impl<D: Domain, R: Read + Seek> Iterator for ReverseReader<D, R> {
+    type Item = ReadResult<Vec<D::String>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+
+        let result = self.read_row();
+        self.failed = result.is_err();
+        result.transpose()
+    }
+}
+
+impl<D: Domain, R: Read + Seek> FusedIterator for ReverseReader<D, R> {}
+
+/// The possible states at the start or end of a line: at a record boundary,
+/// or in a quoted value.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) struct States {
+    boundary: bool,
+    quoted: bool,
+}
+
+impl States {
+    pub(crate) const BOUNDARY: States = States {
+        boundary: true,
+        quoted: false,
+    };
+
+    const QUOTED: States = States {
+        boundary: false,
+        quoted: true,
+    };
+
+    /// Returns the state at the end of the line, given the state at its start.
+    pub(crate) fn after(self, line: &[u8]) -> States {
+        let odd_quotes = line.iter().filter(|byte| **byte == b'"').count() % 2 == 1;
+        let is_comment = self.boundary && line.first() == Some(&b'#');
+        if is_comment || !odd_quotes {
+            self
+        } else {
+            States {
+                boundary: self.quoted,
+                quoted: self.boundary,
+            }
+        }
+    }
+
+    /// Returns the possible states at the start of the line, given the
+    /// possible states at its end.
+    fn before(self, line: &[u8]) -> States {
+        let contains = |state: States| {
+            let after = state.after(line);
+            (after.boundary && self.boundary) || (after.quoted && self.quoted)
+        };
+        States {
+            boundary: contains(States::BOUNDARY),
+            quoted: contains(States::QUOTED),
+        }
+    }
+}
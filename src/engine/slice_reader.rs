@@ -0,0 +1,395 @@
+//! Reads SSV from in-memory buffers and memory-mapped files without copying
+//! the values.
+
+use std::borrow::Cow;
+use std::iter::FusedIterator;
+#[cfg(feature = "mmap")]
+use std::path::Path;
+
+use crate::engine::domain::Domain;
+
+use super::position::{Position, WithPosition};
+use super::{ReadError, ReadResult};
+
+/// A value read by a [`SliceReader`] or a [`SliceRecordReader`].
+///
+/// It borrows from the input, unless it had to be unescaped because it contains
+/// duplicated quotes.
+pub type SliceValue<'a, D> = Cow<'a, <D as Domain>::StringSlice>;
+
+/// Reads SSV rows from a byte slice.
+#[doc = generic_item_warning_doc!("SliceReader")]
+/// It is like a [`Reader`](super::reader::Reader), but the values borrow from the
+/// slice when possible, and it is an iterator of
+/// [`ReadResult`]`<`[`Vec`]`<`[`SliceValue`]`>>` values.
+///
+/// # Example
+///
+/// ```
+/// use ssv::chars::SliceReader;
+///
+/// let input = b"value \"quoted value\"\n\"with \"\"quotes\"\"\"";
+///
+/// let rows: Vec<_> = SliceReader::new(input).collect::<Result<_, _>>()?;
+///
+/// assert_eq!(rows, [vec!["value", "quoted value"], vec!["with \"quotes\""]]);
+/// # Ok::<_, ssv::chars::ReadError>(())
+/// ```
+pub struct SliceReader<'a, D: Domain> {
+    records: SliceRecordReader<'a, D>,
+}
+
+impl<'a, D: Domain> SliceReader<'a, D> {
+    /// Creates an instance that reads SSV from the given byte slice.
+    pub fn new(input: &'a [u8]) -> Self {
+        SliceReader {
+            records: SliceRecordReader::new(input),
+        }
+    }
+}
+
+impl<'a, D: Domain> Iterator for SliceReader<'a, D> {
+    type Item = ReadResult<Vec<SliceValue<'a, D>>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for result in self.records.by_ref() {
+            match result {
+                Ok(WithPosition {
+                    value: SliceRecord::Row(row),
+                    ..
+                }) => return Some(Ok(row)),
+                Ok(_) => {}
+                Err(error) => return Some(Err(error)),
+            }
+        }
+        None
+    }
+}
+
+impl<'a, D: Domain> FusedIterator for SliceReader<'a, D> {}
+
+/// An SSV record read by a [`SliceRecordReader`]: either a row of values or a
+/// comment line.
+#[doc = generic_item_warning_doc!("SliceRecord")]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SliceRecord<'a, D: Domain> {
+    /// A row of values.
+    Row(Vec<SliceValue<'a, D>>),
+
+    /// A comment line, without the HASH sign (`#`).
+    Comment(SliceValue<'a, D>),
+}
+
+/// Reads SSV records (rows and comment lines) from a byte slice.
+#[doc = generic_item_warning_doc!("SliceRecordReader")]
+/// It is like a [`RecordReader`](super::reader::RecordReader), with the same
+/// positions and errors, but the values borrow from the slice when possible.
+///
+/// # Example
+///
+/// ```
+/// use ssv::chars::{SliceRecord, SliceRecordReader};
+///
+/// let input = b"# comment\nvalue value";
+///
+/// for result in SliceRecordReader::new(input) {
+///     match result?.value {
+///         SliceRecord::Row(row) => println!("Row: {row:?}"),
+///         SliceRecord::Comment(comment) => println!("Comment: {comment:?}"),
+///     }
+/// }
+/// # Ok::<_, ssv::chars::ReadError>(())
+/// ```
+pub struct SliceRecordReader<'a, D: Domain> {
+    input: &'a [u8],
+    index: usize,
+    line_number: usize,
+    line_start: usize,
+    failed: bool,
+    _domain: std::marker::PhantomData<D>,
+}
+
+impl<'a, D: Domain> SliceRecordReader<'a, D> {
+    /// Creates an instance that reads SSV from the given byte slice.
+    pub fn new(input: &'a [u8]) -> Self {
+        SliceRecordReader {
+            input,
+            index: 0,
+            line_number: 1,
+            line_start: 0,
+            failed: false,
+            _domain: std::marker::PhantomData,
+        }
+    }
+
+    fn read_record(&mut self) -> ReadResult<WithPosition<SliceRecord<'a, D>>> {
+        let position = self.position_at(self.index);
+
+        if self.input[self.index] == b'#' {
+            let start = self.index + 1;
+            let end = self.line_end(start);
+            let comment = D::slice_from_bytes(&self.input[start..end])?;
+            self.skip_line_break(end);
+            return Ok(WithPosition {
+                value: SliceRecord::Comment(Cow::Borrowed(comment)),
+                position,
+            });
+        }
+
+        let mut row = Vec::new();
+        loop {
+            match self.input.get(self.index) {
+                None => break,
+                Some(b' ' | b'\t') => self.index += 1,
+                Some(_) if self.is_line_break(self.index) => {
+                    self.skip_line_break(self.index);
+                    break;
+                }
+                Some(b'"') => row.push(self.read_quoted_value()?),
+                Some(_) => {
+                    let start = self.index;
+                    row.push(self.read_unquoted_value(start, 0)?);
+                }
+            }
+        }
+
+        Ok(WithPosition {
+            value: SliceRecord::Row(row),
+            position,
+        })
+    }
+
+    /// Reads a value that starts with a quote. Like the
+    /// [`Tokenizer`](super::tokenizer::Tokenizer), an even number of leading
+    /// quotes followed by something other than a separator starts an unquoted
+    /// value.
+    fn read_quoted_value(&mut self) -> ReadResult<SliceValue<'a, D>> {
+        let start = self.index;
+        let count = self.input[start..]
+            .iter()
+            .take_while(|byte| **byte == b'"')
+            .count();
+        let after_quotes = start + count;
+
+        if count % 2 == 0 {
+            if self.is_separator(after_quotes) {
+                self.index = after_quotes;
+                let value = vec![b'"'; (count - 2) / 2];
+                return Ok(Cow::Owned(D::slice_from_bytes(&value)?.to_owned()));
+            }
+            return self.read_unquoted_value(after_quotes, count / 2);
+        }
+
+        let mut escaped = count > 1;
+        let mut index = after_quotes;
+        loop {
+            match self.input.get(index) {
+                None => {
+                    return Err(ReadError::UnclosedQuotedValue(
+                        self.position_at(self.input.len()),
+                    ))
+                }
+                Some(b'"') if self.input.get(index + 1) == Some(&b'"') => {
+                    escaped = true;
+                    index += 2;
+                }
+                Some(b'"') => {
+                    if !self.is_separator(index + 1) {
+                        return Err(ReadError::UnpairedQuote(self.position_at(index)));
+                    }
+                    break;
+                }
+                Some(_) => index += 1,
+            }
+        }
+
+        let content = &self.input[start + 1..index];
+        self.advance(index + 1);
+        self.value(content, escaped)
+    }
+
+    /// Reads an unquoted value starting at `start`, prefixed by the given
+    /// number of quotes.
+    fn read_unquoted_value(
+        &mut self,
+        start: usize,
+        prefix_quotes: usize,
+    ) -> ReadResult<SliceValue<'a, D>> {
+        let mut escaped = prefix_quotes > 0;
+        let mut index = start;
+        while !self.is_separator(index) {
+            if self.input[index] == b'"' {
+                if self.input.get(index + 1) != Some(&b'"') {
+                    return Err(ReadError::UnpairedQuote(self.position_at(index)));
+                }
+                escaped = true;
+                index += 1;
+            }
+            index += 1;
+        }
+
+        let content = &self.input[start - 2 * prefix_quotes..index];
+        self.advance(index);
+        self.value(content, escaped)
+    }
+
+    /// Converts the content of a value, replacing duplicated quotes if it is
+    /// escaped.
+    fn value(&self, content: &'a [u8], escaped: bool) -> ReadResult<SliceValue<'a, D>> {
+        if !escaped {
+            return Ok(Cow::Borrowed(D::slice_from_bytes(content)?));
+        }
+
+        let mut value = Vec::with_capacity(content.len());
+        let mut bytes = content.iter();
+        while let Some(byte) = bytes.next() {
+            value.push(*byte);
+            if *byte == b'"' {
+                bytes.next();
+            }
+        }
+        Ok(Cow::Owned(D::slice_from_bytes(&value)?.to_owned()))
+    }
+
+    fn is_separator(&self, index: usize) -> bool {
+        match self.input.get(index) {
+            None | Some(b' ' | b'\t') => true,
+            Some(_) => self.is_line_break(index),
+        }
+    }
+
+    fn is_line_break(&self, index: usize) -> bool {
+        match self.input.get(index) {
+            Some(b'\n') => true,
+            Some(b'\r') => self.input.get(index + 1) == Some(&b'\n'),
+            _ => false,
+        }
+    }
+
+    fn line_end(&self, start: usize) -> usize {
+        (start..self.input.len())
+            .find(|index| self.is_line_break(*index))
+            .unwrap_or(self.input.len())
+    }
+
+    fn skip_line_break(&mut self, index: usize) {
+        let end = match self.input.get(index) {
+            Some(b'\r') => index + 2,
+            Some(_) => index + 1,
+            None => index,
+        };
+        self.advance(end);
+    }
+
+    /// Moves to the given index, keeping track of the lines.
+    fn advance(&mut self, index: usize) {
+        for offset in self.index..index {
+            if self.input[offset] == b'\n' {
+                self.line_number += 1;
+                self.line_start = offset + 1;
+            }
+        }
+        self.index = index;
+    }
+
+    /// Returns the position of the element at the given index, which must not
+    /// be before the current index.
+    fn position_at(&self, index: usize) -> Position {
+        if index == self.line_start {
+            return Position {
+                line_number: self.line_number,
+                column_number: 1,
+            };
+        }
+
+        let mut line_number = self.line_number;
+        let mut line_start = self.line_start;
+        for offset in self.index..index {
+            if self.input[offset] == b'\n' {
+                line_number += 1;
+                line_start = offset + 1;
+            }
+        }
+
+        Position {
+            line_number,
+            column_number: D::element_iterator(&self.input[line_start..index]).count() + 1,
+        }
+    }
+}
+
+impl<'a, D: Domain> Iterator for SliceRecordReader<'a, D> {
+    type Item = ReadResult<WithPosition<SliceRecord<'a, D>>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.index >= self.input.len() {
+            return None;
+        }
+
+        let result = self.read_record();
+        self.failed = result.is_err();
+        Some(result)
+    }
+}
+
+impl<'a, D: Domain> FusedIterator for SliceRecordReader<'a, D> {}
+
+/// A memory-mapped file to read SSV from.
+#[doc = generic_item_warning_doc!("MappedFile")]
+/// The rows and records are read directly from the mapped memory by a
+/// [`SliceReader`] or a [`SliceRecordReader`], which avoids copying the file
+/// contents and most of the values.
+///
+/// # Example
+///
+/// ```no_run
+/// # let file_path = "";
+/// let file = unsafe { ssv::chars::map_file(file_path)? };
+///
+/// # #[allow(unused_variables)]
+/// for row in file.rows() {
+///     // Use `row`
+/// }
+/// # Ok::<_, ssv::chars::ReadError>(())
+/// ```
+#[cfg(feature = "mmap")]
+pub struct MappedFile<D: Domain> {
+    mmap: memmap2::Mmap,
+    _domain: std::marker::PhantomData<D>,
+}
+
+#[cfg(feature = "mmap")]
+impl<D: Domain> MappedFile<D> {
+    /// Maps the file at the given path into memory.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated, by this or another process,
+    /// while it is mapped. Otherwise the contents of the values may change, or
+    /// reading them may crash the process.
+    pub unsafe fn open<P: AsRef<Path>>(path: P) -> ReadResult<Self> {
+        let file = std::fs::File::open(path)?;
+        // SAFETY: the caller guarantees that the file is not modified while
+        // it is mapped.
+        let mmap = unsafe { memmap2::Mmap::map(&file)? };
+        Ok(MappedFile {
+            mmap,
+            _domain: std::marker::PhantomData,
+        })
+    }
+
+    /// Returns the contents of the file.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.mmap
+    }
+
+    /// Returns a [`SliceReader`] over the contents of the file.
+    pub fn rows(&self) -> SliceReader<'_, D> {
+        SliceReader::new(&self.mmap)
+    }
+
+    /// Returns a [`SliceRecordReader`] over the contents of the file.
+    pub fn records(&self) -> SliceRecordReader<'_, D> {
+        SliceRecordReader::new(&self.mmap)
+    }
+}
@@ -0,0 +1,573 @@
+//! Sorts SSV content that may be larger than the available memory.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::fs::File;
+use std::io::{BufWriter, Read, Write};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+
+use crate::engine::domain::{Domain, DomainStringSlice};
+use crate::syntax::numeric::parse_numeric;
+
+use super::columns::ColumnRef;
+use super::reader::{Record, RecordReader};
+use super::writer::Writer;
+use super::{ReadError, ReadWriteResult, WriteError};
+
+/// Sorts SSV rows by key columns, spilling sorted runs to temporary files when
+/// the content does not fit in a run.
+#[doc = generic_item_warning_doc!("Sorter")]
+/// The rows are read in runs of up to [`run_size`](Sorter::run_size) rows. Each
+/// run is sorted in memory and, if there is more than one run, written as SSV
+/// to a temporary file. Then the runs are merged back to the output. If there
+/// are more runs than the [merge fan-in](Sorter::merge_fan_in), they are first
+/// merged in groups into fewer, longer runs, as many times as needed, so the
+/// number of files open at once stays bounded. The temporary files are removed
+/// when no longer needed.
+///
+/// The sort is stable: rows with equal keys keep their relative order.
+///
+/// Comment lines before the first row stay at the beginning, followed by the
+/// header row if the sorter [uses a header](Sorter::with_header). Comment lines
+/// after the last row stay at the end. Any other comment lines move together
+/// with the row that follows them.
+///
+/// If no key is given, the rows are compared by all their values, lexically.
+///
+/// # Example
+///
+/// ```
+/// use ssv::chars::{Collation, SortKey, Sorter};
+///
+/// let input = "# people\nname age\nJohn 35\nAnn 9\nBob 35\n";
+/// let mut output = Vec::new();
+///
+/// Sorter::new()
+///     .with_header(true)
+///     .by(SortKey::descending("age").with_collation(Collation::Numeric))
+///     .by(SortKey::ascending("name"))
+///     .sort(input.as_bytes(), &mut output)?;
+///
+/// assert_eq!(output, b"# people\nname age\nBob 35\nJohn 35\nAnn 9\n");
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct Sorter<D: Domain> {
+    keys: Vec<SortKey<D>>,
+    header: bool,
+    run_size: usize,
+    merge_fan_in: usize,
+    temp_dir: Option<PathBuf>,
+}
+
+impl<D: Domain> Sorter<D> {
+    /// The default number of rows in a run.
+    pub const DEFAULT_RUN_SIZE: usize = 100_000;
+
+    /// The default maximum number of runs that are merged at once.
+    pub const DEFAULT_MERGE_FAN_IN: usize = 64;
+
+    /// Creates an instance without keys and without header.
+    pub fn new() -> Self {
+        Sorter {
+            keys: Vec::new(),
+            header: false,
+            run_size: Self::DEFAULT_RUN_SIZE,
+            merge_fan_in: Self::DEFAULT_MERGE_FAN_IN,
+            temp_dir: None,
+        }
+    }
+
+    /// Sets whether the first row is a header row.
+    ///
+    /// Keys can reference columns by name only if there is a header row.
+    pub fn with_header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Adds a key. Keys added first have higher priority.
+    pub fn by(mut self, key: SortKey<D>) -> Self {
+        self.keys.push(key);
+        self
+    }
+
+    /// Sets the maximum number of rows that are sorted in memory.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `run_size` is zero.
+    pub fn run_size(mut self, run_size: usize) -> Self {
+        assert!(run_size > 0, "the run size must be greater than zero");
+        self.run_size = run_size;
+        self
+    }
+
+    /// Sets the maximum number of runs that are merged at once, which is also
+    /// the maximum number of temporary files open at once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `merge_fan_in` is less than two.
+    pub fn merge_fan_in(mut self, merge_fan_in: usize) -> Self {
+        assert!(
+            merge_fan_in > 1,
+            "the merge fan-in must be greater than one"
+        );
+        self.merge_fan_in = merge_fan_in;
+        self
+    }
+
+    /// Sets the directory where the temporary files are created.
+    ///
+    /// The default is the directory returned by [`std::env::temp_dir`].
+    pub fn temp_dir<P: AsRef<Path>>(mut self, temp_dir: P) -> Self {
+        self.temp_dir = Some(temp_dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Reads SSV from the given byte reader, sorts the rows, and writes them to
+    /// the given byte writer.
+    ///
+    /// An [IO error](std::io::Error) when creating a temporary file is returned
+    /// as a [`WriteError`], and when opening it as a [`ReadError`].
+    pub fn sort<R: Read, W: Write>(&self, reader: R, writer: W) -> ReadWriteResult<()> {
+        let mut records = RecordReader::<D, R>::new(reader);
+        let mut writer = Writer::<D, W>::new(writer);
+
+        let mut first_row = None;
+        for record in records.by_ref() {
+            match record?.value {
+                Record::Comment(comment) => writer.write_comment_line(&comment)?,
+                Record::Row(row) => {
+                    first_row = Some(row);
+                    break;
+                }
+            }
+        }
+
+        let mut run = Vec::new();
+        let keys = match (self.header, first_row) {
+            (true, Some(header)) => {
+                let keys = self.bind(Some(&header))?;
+                writer.write_row(header.iter().map(Deref::deref))?;
+                keys
+            }
+            (false, Some(row)) => {
+                run.push(Entry::new(Vec::new(), row));
+                self.bind(None)?
+            }
+            (_, None) => Vec::new(),
+        };
+        let comparator = Comparator { keys };
+
+        let mut runs = Vec::new();
+        let mut comments = Vec::new();
+        for record in records {
+            match record?.value {
+                Record::Comment(comment) => comments.push(comment),
+                Record::Row(row) => {
+                    run.push(Entry::new(std::mem::take(&mut comments), row));
+                    if run.len() >= self.run_size {
+                        comparator.sort(&mut run);
+                        runs.push(self.spill(&run)?);
+                        run.clear();
+                    }
+                }
+            }
+        }
+        comparator.sort(&mut run);
+
+        if runs.is_empty() {
+            for entry in &run {
+                entry.write(&mut writer)?;
+            }
+        } else {
+            if !run.is_empty() {
+                runs.push(self.spill(&run)?);
+            }
+            drop(run);
+            while runs.len() > self.merge_fan_in {
+                runs = runs
+                    .chunks(self.merge_fan_in)
+                    .map(|chunk| self.write_run(|writer| self.merge(chunk, &comparator, writer)))
+                    .collect::<ReadWriteResult<_>>()?;
+            }
+            self.merge(&runs, &comparator, &mut writer)?;
+        }
+
+        for comment in comments {
+            writer.write_comment_line(&comment)?;
+        }
+        writer.finish()?;
+        Ok(())
+    }
+
+    fn bind(&self, header: Option<&[D::String]>) -> ReadWriteResult<Vec<BoundKey>> {
+        self.keys
+            .iter()
+            .map(|key| {
+                let index = key.column.try_resolve(header)?;
+                Ok(BoundKey {
+                    index,
+                    direction: key.direction,
+                    collation: key.collation,
+                })
+            })
+            .collect()
+    }
+
+    fn spill(&self, run: &[Entry<D>]) -> ReadWriteResult<TempFile> {
+        self.write_run(|writer| {
+            for entry in run {
+                entry.write(writer)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn write_run<F>(&self, write: F) -> ReadWriteResult<TempFile>
+    where
+        F: FnOnce(&mut Writer<D, BufWriter<File>>) -> ReadWriteResult<()>,
+    {
+        let temp_file = TempFile::create(self.temp_dir.as_deref()).map_err(WriteError::from)?;
+        let file = File::create(&temp_file.path).map_err(WriteError::from)?;
+        let mut writer = Writer::<D, _>::new(BufWriter::new(file));
+        write(&mut writer)?;
+        writer.finish()?;
+        Ok(temp_file)
+    }
+
+    fn merge<W: Write>(
+        &self,
+        runs: &[TempFile],
+        comparator: &Comparator,
+        writer: &mut Writer<D, W>,
+    ) -> ReadWriteResult<()> {
+        let mut readers = runs
+            .iter()
+            .map(|run| {
+                let file = File::open(&run.path).map_err(ReadError::from)?;
+                Ok(RunReader {
+                    records: RecordReader::new(file),
+                })
+            })
+            .collect::<ReadWriteResult<Vec<_>>>()?;
+
+        let mut heap = BinaryHeap::new();
+        for (run_index, reader) in readers.iter_mut().enumerate() {
+            if let Some(entry) = reader.next_entry()? {
+                heap.push(HeapItem {
+                    entry,
+                    run_index,
+                    comparator,
+                });
+            }
+        }
+
+        while let Some(HeapItem {
+            entry, run_index, ..
+        }) = heap.pop()
+        {
+            entry.write(writer)?;
+            if let Some(entry) = readers[run_index].next_entry()? {
+                heap.push(HeapItem {
+                    entry,
+                    run_index,
+                    comparator,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<D: Domain> Default for Sorter<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A key to sort rows.
+#[doc = generic_item_warning_doc!("SortKey")]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SortKey<D: Domain> {
+    column: ColumnRef<D>,
+    direction: Direction,
+    collation: Collation,
+}
+
+impl<D: Domain> SortKey<D> {
+    /// Creates a key in ascending order, with [lexical](Collation::Lexical)
+    /// collation.
+    pub fn ascending(column: impl Into<ColumnRef<D>>) -> Self {
+        SortKey {
+            column: column.into(),
+            direction: Direction::Ascending,
+            collation: Collation::default(),
+        }
+    }
+
+    /// Creates a key in descending order, with [lexical](Collation::Lexical)
+    /// collation.
+    pub fn descending(column: impl Into<ColumnRef<D>>) -> Self {
+        SortKey {
+            column: column.into(),
+            direction: Direction::Descending,
+            collation: Collation::default(),
+        }
+    }
+
+    /// Sets the collation.
+    pub fn with_collation(mut self, collation: Collation) -> Self {
+        self.collation = collation;
+        self
+    }
+
+    /// Returns the column.
+    pub fn column(&self) -> &ColumnRef<D> {
+        &self.column
+    }
+
+    /// Returns the direction.
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Returns the collation.
+    pub fn collation(&self) -> Collation {
+        self.collation
+    }
+}
+
+/// The direction of a [`SortKey`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Direction {
+    /// From the lowest to the highest value.
+    Ascending,
+
+    /// From the highest to the lowest value.
+    Descending,
+}
+
+/// How the values of a [`SortKey`] are compared.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Collation {
+    /// Values are compared element by element.
+    #[default]
+    Lexical,
+
+    /// Values are compared as numbers (a comma (`,`) is accepted as decimal
+    /// separator). Values that are not numbers come after the numbers, and are
+    /// compared lexically.
+    Numeric,
+
+    /// Sequences of ASCII digits are compared by their numeric values, and the
+    /// remaining parts are compared lexically. E.g.: `"file9"` comes before
+    /// `"file10"`.
+    Natural,
+}
+
+impl Collation {
+    fn compare(self, left: &[u8], right: &[u8]) -> Ordering {
+        match self {
+            Collation::Lexical => left.cmp(right),
+            Collation::Numeric => match (parse_numeric(left), parse_numeric(right)) {
+                (Some(left), Some(right)) => left.partial_cmp(&right).unwrap_or(Ordering::Equal),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => left.cmp(right),
+            },
+            Collation::Natural => Self::compare_natural(left, right),
+        }
+    }
+
+    fn compare_natural(mut left: &[u8], mut right: &[u8]) -> Ordering {
+        fn split_digits(bytes: &[u8]) -> (&[u8], &[u8]) {
+            let count = bytes
+                .iter()
+                .take_while(|byte| byte.is_ascii_digit())
+                .count();
+            bytes.split_at(count)
+        }
+
+        loop {
+            match (left.first(), right.first()) {
+                (None, None) => return Ordering::Equal,
+                (None, Some(_)) => return Ordering::Less,
+                (Some(_), None) => return Ordering::Greater,
+                (Some(l), Some(r)) if l.is_ascii_digit() && r.is_ascii_digit() => {
+                    let (left_digits, left_rest) = split_digits(left);
+                    let (right_digits, right_rest) = split_digits(right);
+
+                    let trim = |digits: &'_ [u8]| -> usize {
+                        digits.iter().take_while(|digit| **digit == b'0').count()
+                    };
+                    let left_significant = &left_digits[trim(left_digits)..];
+                    let right_significant = &right_digits[trim(right_digits)..];
+
+                    let ordering = left_significant
+                        .len()
+                        .cmp(&right_significant.len())
+                        .then_with(|| left_significant.cmp(right_significant))
+                        .then_with(|| left_digits.len().cmp(&right_digits.len()));
+                    if ordering.is_ne() {
+                        return ordering;
+                    }
+
+                    left = left_rest;
+                    right = right_rest;
+                }
+                (Some(l), Some(r)) => {
+                    if l != r {
+                        return l.cmp(r);
+                    }
+                    left = &left[1..];
+                    right = &right[1..];
+                }
+            }
+        }
+    }
+}
+
+struct BoundKey {
+    index: usize,
+    direction: Direction,
+    collation: Collation,
+}
+
+struct Comparator {
+    keys: Vec<BoundKey>,
+}
+
+impl Comparator {
+    fn sort<D: Domain>(&self, run: &mut [Entry<D>]) {
+        run.sort_by(|left, right| self.compare::<D>(&left.row, &right.row));
+    }
+
+    fn compare<D: Domain>(&self, left: &[D::String], right: &[D::String]) -> Ordering {
+        fn value<D: Domain>(row: &[D::String], index: usize) -> &[u8] {
+            row.get(index).map_or(&[], |value| value.deref().as_bytes())
+        }
+
+        if self.keys.is_empty() {
+            let length = left.len().max(right.len());
+            return (0..length)
+                .map(|index| value::<D>(left, index).cmp(value::<D>(right, index)))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal);
+        }
+
+        self.keys
+            .iter()
+            .map(|key| {
+                let ordering = key
+                    .collation
+                    .compare(value::<D>(left, key.index), value::<D>(right, key.index));
+                match key.direction {
+                    Direction::Ascending => ordering,
+                    Direction::Descending => ordering.reverse(),
+                }
+            })
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+struct Entry<D: Domain> {
+    comments: Vec<D::String>,
+    row: Vec<D::String>,
+}
+
+impl<D: Domain> Entry<D> {
+    fn new(comments: Vec<D::String>, row: Vec<D::String>) -> Self {
+        Entry { comments, row }
+    }
+
+    fn write<W: Write>(&self, writer: &mut Writer<D, W>) -> ReadWriteResult<()> {
+        for comment in &self.comments {
+            writer.write_comment_line(comment)?;
+        }
+        writer.write_row(self.row.iter().map(Deref::deref))?;
+        Ok(())
+    }
+}
+
+struct RunReader<D: Domain> {
+    records: RecordReader<D, File>,
+}
+
+impl<D: Domain> RunReader<D> {
+    fn next_entry(&mut self) -> ReadWriteResult<Option<Entry<D>>> {
+        let mut comments = Vec::new();
+        for record in self.records.by_ref() {
+            match record?.value {
+                Record::Comment(comment) => comments.push(comment),
+                Record::Row(row) => return Ok(Some(Entry::new(comments, row))),
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// An entry in the merge heap. The order is reversed, so the [`BinaryHeap`]
+/// returns the lowest entry first, and entries from earlier runs first when
+/// equal, which keeps the sort stable.
+struct HeapItem<'a, D: Domain> {
+    entry: Entry<D>,
+    run_index: usize,
+    comparator: &'a Comparator,
+}
+
+impl<D: Domain> Ord for HeapItem<'_, D> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.comparator
+            .compare::<D>(&self.entry.row, &other.entry.row)
+            .then(self.run_index.cmp(&other.run_index))
+            .reverse()
+    }
+}
+
+impl<D: Domain> PartialOrd for HeapItem<'_, D> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<D: Domain> PartialEq for HeapItem<'_, D> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl<D: Domain> Eq for HeapItem<'_, D> {}
+
+/// A temporary file that is removed when dropped.
+struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    fn create(dir: Option<&Path>) -> std::io::Result<Self> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        let dir = dir.map_or_else(std::env::temp_dir, Path::to_path_buf);
+        loop {
+            let count = COUNTER.fetch_add(1, AtomicOrdering::Relaxed);
+            let path = dir.join(format!("ssv-sort-{}-{count}.ssv", std::process::id()));
+            match File::create_new(&path) {
+                Ok(_) => return Ok(TempFile { path }),
+                Err(error) if error.kind() == std::io::ErrorKind::AlreadyExists => continue,
+                Err(error) => return Err(error),
+            }
+        }
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
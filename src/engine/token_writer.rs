@@ -0,0 +1,180 @@
+//! Writes SSV token-by-token.
+
+use std::io::Write;
+
+use crate::engine::domain::{Domain, DomainStringSlice};
+use crate::engine::LineBreak;
+
+use super::domain::BytesDomain;
+use super::tokenizer::Token;
+use super::{WriteError, WriteResult};
+use crate::syntax::writer::PreparedValue;
+
+/// Writes SSV tokens to a byte writer.
+#[doc = generic_item_warning_doc!("TokenWriter")]
+/// The tokens are written exactly as they are, so the output of a
+/// [`Tokenizer`](crate::engine::tokenizer::Tokenizer) can be written back
+/// preserving the formatting. No delimiter is automatically inserted.
+///
+/// The sequence of tokens is validated, so the content can be read back
+/// resulting in the same tokens. A token is rejected with
+/// [`WriteError::UnexpectedToken`] if:
+/// * a value follows another value or a comment without a line-break in between;
+/// * spacing follows another spacing or a comment;
+/// * a comment is not at the beginning of a line;
+/// * a [`LineBreak::Lf`] follows an unquoted value or a comment ending with CR
+///   (byte value/codepoint 13), which would be read as a [`LineBreak::CrLf`].
+///
+/// An [`UnquotedValue`](Token::UnquotedValue) that requires quotes (check the
+/// [rules](crate#rules)) is rejected with [`WriteError::QuotesRequired`], and a
+/// [`Comment`](Token::Comment) containing a line-break is rejected with
+/// [`WriteError::CommentWithLineBreak`].
+///
+/// A rejected token is not written, and the object can still be used.
+///
+/// # Example
+///
+/// ```
+/// use ssv::chars::{Token, Tokenizer, TokenWriter};
+///
+/// let input = "# comment\nabc   \"def\"\n";
+/// let mut output = Vec::new();
+///
+/// let mut token_writer = TokenWriter::new(&mut output);
+/// for token in Tokenizer::new(input.as_bytes()) {
+///     let token = token?.value;
+///     if !matches!(token, Token::Comment(_)) {
+///         token_writer.write_token(&token)?;
+///     }
+/// }
+/// token_writer.finish()?;
+///
+/// assert_eq!(output, b"\nabc   \"def\"\n");
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct TokenWriter<D: Domain, W: Write> {
+    inner: W,
+    state: State,
+    _domain: std::marker::PhantomData<D>,
+}
+
+impl<D: Domain, W: Write> TokenWriter<D, W> {
+    /// Creates an instance that writes SSV to the given byte writer.
+    pub fn new(inner: W) -> Self {
+        TokenWriter {
+            inner,
+            state: State::LineBegin,
+            _domain: std::marker::PhantomData,
+        }
+    }
+
+    /// Writes several tokens.
+    pub fn write_tokens<'a>(
+        &mut self,
+        tokens: impl IntoIterator<Item = &'a Token<D>>,
+    ) -> WriteResult<()>
+    where
+        D: 'a,
+    {
+        for token in tokens {
+            self.write_token(token)?;
+        }
+        Ok(())
+    }
+
+    /// Writes a single token.
+    pub fn write_token(&mut self, token: &Token<D>) -> WriteResult<()> {
+        match token {
+            Token::UnquotedValue(value) => {
+                self.expect(matches!(self.state, State::LineBegin | State::Spacing))?;
+                let prepared_value = PreparedValue::from(value.as_bytes());
+                if prepared_value.requires_quotes(self.state == State::LineBegin) {
+                    return Err(WriteError::QuotesRequired);
+                }
+
+                self.write(&prepared_value.bytes)?;
+                self.state = State::Value {
+                    ends_with_cr: prepared_value.bytes.last() == Some(&BytesDomain::CR),
+                };
+            }
+            Token::QuotedValue(value) => {
+                self.expect(matches!(self.state, State::LineBegin | State::Spacing))?;
+                let prepared_value = PreparedValue::from(value.as_bytes());
+
+                self.write(&[BytesDomain::QUOTE])?;
+                self.write(&prepared_value.bytes)?;
+                self.write(&[BytesDomain::QUOTE])?;
+                self.state = State::Value {
+                    ends_with_cr: false,
+                };
+            }
+            Token::Spacing(spacing) => {
+                self.expect(matches!(self.state, State::LineBegin | State::Value { .. }))?;
+                if !D::is_valid_spacing(spacing) {
+                    return Err(WriteError::InvalidSpacing);
+                }
+
+                self.write(spacing.as_bytes())?;
+                self.state = State::Spacing;
+            }
+            Token::LineBreak(line_break) => {
+                let bytes: &[u8] = match line_break {
+                    LineBreak::Lf => {
+                        self.expect(!matches!(
+                            self.state,
+                            State::Value { ends_with_cr: true }
+                                | State::Comment { ends_with_cr: true }
+                        ))?;
+                        &[BytesDomain::LF]
+                    }
+                    LineBreak::CrLf => &[BytesDomain::CR, BytesDomain::LF],
+                };
+
+                self.write(bytes)?;
+                self.state = State::LineBegin;
+            }
+            Token::Comment(comment) => {
+                self.expect(self.state == State::LineBegin)?;
+                let bytes = comment.as_bytes();
+                if bytes.contains(&BytesDomain::LF) {
+                    return Err(WriteError::CommentWithLineBreak);
+                }
+
+                self.write(&[BytesDomain::HASH])?;
+                self.write(bytes)?;
+                self.state = State::Comment {
+                    ends_with_cr: bytes.last() == Some(&BytesDomain::CR),
+                };
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Finalizes the object by flushing the underlying byte writer.
+    pub fn finish(mut self) -> WriteResult<()> {
+        self.inner.flush()?;
+        Ok(())
+    }
+
+    fn expect(&self, valid: bool) -> WriteResult<()> {
+        if valid {
+            Ok(())
+        } else {
+            Err(WriteError::UnexpectedToken)
+        }
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> WriteResult<()> {
+        self.inner.write_all(bytes)?;
+        Ok(())
+    }
+}
+
+#[derive(PartialEq, Eq, Debug)]
+enum State {
+    LineBegin,
+    Value { ends_with_cr: bool },
+    Spacing,
+    Comment { ends_with_cr: bool },
+}
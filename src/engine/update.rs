@@ -0,0 +1,402 @@
+//! Updates cells of SSV content in place, preserving its layout.
+
+use std::error::Error;
+use std::fmt::Display;
+use std::io::{Read, Write};
+use std::ops::Deref;
+use std::path::Path;
+
+use crate::engine::domain::{Domain, DomainString, DomainStringSlice};
+
+use super::columns::ColumnRef;
+use super::token_writer::TokenWriter;
+use super::tokenizer::{Token, Tokenizer};
+use super::{LineBreak, ReadError, ReadWriteError, WriteError};
+use crate::syntax::writer::{requires_quotes, PreparedValue};
+
+/// Updates cells of SSV content, keeping everything else as it is.
+#[doc = generic_item_warning_doc!("Updater")]
+/// The rows to update are the rows whose value in the [key column](Updater::key)
+/// is the given key. Only the updated cells are rewritten: the spacing,
+/// comments, line-breaks and quoting of the other cells are kept. The new value
+/// is enclosed in quotes if the old one was, or if it requires them.
+///
+/// If the next column of an updated row is aligned with the same column of
+/// other rows, with spaces, the spacing after the new value is adjusted to keep
+/// it aligned. When the new value is too wide, the spacing is widened in all the
+/// aligned rows, so that the narrowest spacing between the two columns is kept.
+///
+/// Rows that don't have the updated column are completed with empty values.
+///
+/// # Example
+///
+/// ```
+/// use ssv::chars::Updater;
+///
+/// let input = "\
+/// ## Settings
+/// name  value      note
+/// port  8080       \"HTTP port\"
+/// host  localhost  -
+/// ";
+/// let mut output = Vec::new();
+///
+/// Updater::new()
+///     .with_header(true)
+///     .key("name")
+///     .update(input.as_bytes(), &mut output, "host", "value", "example.com")?;
+///
+/// assert_eq!(
+///     String::from_utf8(output).unwrap(),
+///     "\
+/// ## Settings
+/// name  value        note
+/// port  8080         \"HTTP port\"
+/// host  example.com  -
+/// "
+/// );
+/// # Ok::<_, ssv::chars::UpdateError>(())
+/// ```
+pub struct Updater<D: Domain> {
+    key: ColumnRef<D>,
+    header: bool,
+}
+
+impl<D: Domain> Updater<D> {
+    /// Creates an instance using the first column as the key column, without
+    /// header.
+    pub fn new() -> Self {
+        Updater {
+            key: ColumnRef::Index(0),
+            header: false,
+        }
+    }
+
+    /// Sets whether the first row is a header row.
+    ///
+    /// Columns can be referenced by name only if there is a header row, which
+    /// is never updated.
+    pub fn with_header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Sets the column holding the keys of the rows.
+    pub fn key(mut self, column: impl Into<ColumnRef<D>>) -> Self {
+        self.key = column.into();
+        self
+    }
+
+    /// Reads SSV from the given byte reader, and writes it to the given byte
+    /// writer with the cells updated.
+    ///
+    /// The cells are in the given column of the rows having the given key.
+    pub fn update<R: Read, W: Write>(
+        &self,
+        reader: R,
+        writer: W,
+        key: &D::StringSlice,
+        column: impl Into<ColumnRef<D>>,
+        new_value: &D::StringSlice,
+    ) -> UpdateResult<()> {
+        let mut records = read_records::<D, R>(reader)?;
+        self.update_records(&mut records, key, &column.into(), new_value)?;
+
+        let mut token_writer = TokenWriter::<D, W>::new(writer);
+        for record in &records {
+            token_writer.write_tokens(&record.tokens)?;
+            if let Some(line_break) = record.line_break {
+                token_writer.write_token(&Token::LineBreak(line_break))?;
+            }
+        }
+        token_writer.finish()?;
+        Ok(())
+    }
+
+    /// Updates the cells of the file at the given path, replacing it
+    /// atomically.
+    ///
+    /// The cells are in the given column of the rows having the given key. The
+    /// file is left untouched if an error happens.
+    pub fn update_file<P: AsRef<Path>>(
+        &self,
+        path: P,
+        key: &D::StringSlice,
+        column: impl Into<ColumnRef<D>>,
+        new_value: &D::StringSlice,
+    ) -> UpdateResult<()> {
+        let input = std::fs::read(path.as_ref()).map_err(ReadError::from)?;
+        let column = column.into();
+        super::replace_file(path.as_ref(), |file| {
+            self.update(input.as_slice(), file, key, column, new_value)
+        })
+    }
+
+    fn update_records(
+        &self,
+        records: &mut [SourceRecord<D>],
+        key: &D::StringSlice,
+        column: &ColumnRef<D>,
+        new_value: &D::StringSlice,
+    ) -> UpdateResult<()> {
+        let mut rows = records
+            .iter()
+            .enumerate()
+            .filter(|(_, record)| record.value_indices().next().is_some())
+            .map(|(index, _)| index);
+
+        let header_index = if self.header { rows.next() } else { None };
+        let header = header_index.map(|index| records[index].values());
+        let resolve = |column: &ColumnRef<D>| {
+            column
+                .try_resolve(header.as_deref())
+                .map_err(UpdateError::from)
+        };
+        let key_index = resolve(&self.key)?;
+        let column_index = resolve(column)?;
+
+        let updated: Vec<usize> = rows
+            .filter(|index| {
+                records[*index]
+                    .values()
+                    .get(key_index)
+                    .is_some_and(|value| value.deref() == key)
+            })
+            .collect();
+        if updated.is_empty() {
+            return Err(UpdateError::RowNotFound);
+        }
+
+        for index in updated {
+            update_cell(records, index, column_index, new_value)?;
+        }
+        Ok(())
+    }
+}
+
+impl<D: Domain> Default for Updater<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A record as it was read: its tokens, and the line-break ending it, if any.
+struct SourceRecord<D: Domain> {
+    tokens: Vec<Token<D>>,
+    line_break: Option<LineBreak>,
+}
+
+impl<D: Domain> SourceRecord<D> {
+    /// Returns the indices of the value tokens.
+    fn value_indices(&self) -> impl Iterator<Item = usize> + '_ {
+        self.tokens
+            .iter()
+            .enumerate()
+            .filter(|(_, token)| matches!(token, Token::UnquotedValue(_) | Token::QuotedValue(_)))
+            .map(|(index, _)| index)
+    }
+
+    fn values(&self) -> Vec<D::String> {
+        self.tokens
+            .iter()
+            .filter_map(|token| match token {
+                Token::UnquotedValue(value) | Token::QuotedValue(value) => Some(value.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the offset, in elements, where the token with the given index
+    /// starts, or `None` if a previous token contains a line-break.
+    fn offset(&self, token_index: usize) -> Option<usize> {
+        let mut offset = 0;
+        for token in &self.tokens[..token_index] {
+            let bytes = written_bytes(token);
+            if bytes.contains(&b'\n') {
+                return None;
+            }
+            offset += D::slice_from_bytes(&bytes).ok()?.elements().len();
+        }
+        Some(offset)
+    }
+
+    /// Returns the index of the spacing token before the value in the given
+    /// column, and where the value starts, if the spacing is only spaces.
+    fn aligned_column(&self, column_index: usize) -> Option<(usize, usize)> {
+        let value_index = self.value_indices().nth(column_index)?;
+        let spacing_index = value_index.checked_sub(1)?;
+        match &self.tokens[spacing_index] {
+            Token::Spacing(spacing) if spacing.as_bytes().iter().all(|byte| *byte == b' ') => {
+                Some((spacing_index, self.offset(value_index)?))
+            }
+            _ => None,
+        }
+    }
+}
+
+fn read_records<D: Domain, R: Read>(reader: R) -> UpdateResult<Vec<SourceRecord<D>>> {
+    let mut records = Vec::new();
+    let mut tokens = Vec::new();
+    for token in Tokenizer::<D, R>::new(reader) {
+        match token?.value {
+            Token::LineBreak(line_break) => records.push(SourceRecord {
+                tokens: std::mem::take(&mut tokens),
+                line_break: Some(line_break),
+            }),
+            token => tokens.push(token),
+        }
+    }
+    if !tokens.is_empty() {
+        records.push(SourceRecord {
+            tokens,
+            line_break: None,
+        });
+    }
+    Ok(records)
+}
+
+/// Replaces the value in the given column of the given record, and adjusts
+/// the spacing after it to keep the next column aligned.
+fn update_cell<D: Domain>(
+    records: &mut [SourceRecord<D>],
+    record_index: usize,
+    column_index: usize,
+    new_value: &D::StringSlice,
+) -> UpdateResult<()> {
+    let record = &mut records[record_index];
+    let value_count = record.value_indices().count();
+    if column_index >= value_count {
+        for index in value_count..=column_index {
+            if !matches!(record.tokens.last(), None | Some(Token::Spacing(_))) {
+                record.tokens.push(Token::Spacing(spaces::<D>(1)));
+            }
+            let value = if index == column_index {
+                new_token::<D>(new_value, false, record.tokens.is_empty())
+            } else {
+                Token::QuotedValue(D::String::new())
+            };
+            record.tokens.push(value);
+        }
+        return Ok(());
+    }
+
+    let value_index = record.value_indices().nth(column_index).unwrap();
+    let was_quoted = matches!(record.tokens[value_index], Token::QuotedValue(_));
+    let next = record.aligned_column(column_index + 1);
+    record.tokens[value_index] = new_token::<D>(new_value, was_quoted, value_index == 0);
+
+    let Some((spacing_index, next_offset)) = next else {
+        return Ok(());
+    };
+    let aligned: Vec<(usize, usize)> = records
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| *index != record_index)
+        .filter_map(|(index, record)| {
+            let (spacing_index, offset) = record.aligned_column(column_index + 1)?;
+            (offset == next_offset).then_some((index, spacing_index))
+        })
+        .collect();
+    if aligned.is_empty() {
+        return Ok(());
+    }
+
+    // The narrowest spacing of the aligned rows is kept
+    let spacing_length =
+        |record: &SourceRecord<D>, spacing_index: usize| match &record.tokens[spacing_index] {
+            Token::Spacing(spacing) => spacing.as_bytes().len(),
+            _ => 1,
+        };
+    let gap = aligned
+        .iter()
+        .map(|(index, spacing_index)| spacing_length(&records[*index], *spacing_index))
+        .chain([spacing_length(&records[record_index], spacing_index)])
+        .min()
+        .unwrap_or(1);
+
+    let record = &mut records[record_index];
+    // A new value with a line-break can't be aligned
+    let Some(value_end) = record.offset(spacing_index) else {
+        return Ok(());
+    };
+    if value_end + gap <= next_offset {
+        record.tokens[spacing_index] = Token::Spacing(spaces::<D>(next_offset - value_end));
+    } else {
+        record.tokens[spacing_index] = Token::Spacing(spaces::<D>(gap));
+        let widening = value_end + gap - next_offset;
+        for (index, spacing_index) in aligned {
+            if let Token::Spacing(spacing) = &mut records[index].tokens[spacing_index] {
+                for _ in 0..widening {
+                    spacing.push(D::SPACE);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn new_token<D: Domain>(value: &D::StringSlice, quoted: bool, at_line_begin: bool) -> Token<D> {
+    let bytes = value.as_bytes();
+    // An unquoted value ending with CR would form a CRLF with the line-break
+    if quoted || requires_quotes(bytes, at_line_begin) || bytes.last() == Some(&b'\r') {
+        Token::QuotedValue(D::String::from(value))
+    } else {
+        Token::UnquotedValue(D::String::from(value))
+    }
+}
+
+fn spaces<D: Domain>(count: usize) -> D::String {
+    let mut spacing = D::String::new();
+    for _ in 0..count {
+        spacing.push(D::SPACE);
+    }
+    spacing
+}
+
+/// Returns the bytes of the token as written by a [`TokenWriter`].
+fn written_bytes<D: Domain>(token: &Token<D>) -> Vec<u8> {
+    match token {
+        Token::UnquotedValue(value) => PreparedValue::from(value.as_bytes()).bytes,
+        Token::QuotedValue(value) => {
+            let mut bytes = vec![b'"'];
+            bytes.extend(PreparedValue::from(value.as_bytes()).bytes);
+            bytes.push(b'"');
+            bytes
+        }
+        Token::Spacing(spacing) => spacing.as_bytes().to_vec(),
+        Token::LineBreak(_) => vec![b'\n'],
+        Token::Comment(comment) => [b"#", comment.as_bytes()].concat(),
+    }
+}
+
+/// A specialized [`Result`] type for [`Updater`] operations.
+pub type UpdateResult<T> = Result<T, UpdateError>;
+
+/// The error type for [`Updater`] operations.
+#[derive(Debug)]
+pub enum UpdateError {
+    /// No row has the given key.
+    RowNotFound,
+
+    /// A column was not found, or an error happened when reading or writing.
+    ReadWriteError(ReadWriteError),
+}
+
+impl Error for UpdateError {}
+
+impl_from_read_write_error!(UpdateError);
+
+impl From<std::io::Error> for UpdateError {
+    fn from(io_error: std::io::Error) -> Self {
+        WriteError::IoError(io_error).into()
+    }
+}
+
+impl Display for UpdateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UpdateError::RowNotFound => write!(f, "row not found"),
+            UpdateError::ReadWriteError(error) => write!(f, "{error}"),
+        }
+    }
+}
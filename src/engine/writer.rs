@@ -4,8 +4,9 @@ use std::io::Write;
 use crate::engine::domain::Domain;
 
 use super::fluent_writer::FluentWriter;
-use super::options::Options;
-use super::WriteResult;
+use super::options::{Options, QuotingPolicy};
+use super::reader::Record;
+use super::{WriteError, WriteResult};
 
 /// Follows a row-oriented structure to write SSV to a byte writer.
 #[doc = generic_item_warning_doc!("Writer")]
@@ -14,7 +15,8 @@ use super::WriteResult;
 /// govern how the items are written:
 /// * the values inside a row are separated by the [default spacing](Options::default_spacing);
 /// * all rows are ended with the [default line-break](Options::default_line_break);
-/// * if [`always_quoted`](Options::always_quoted) is `true`, all values are automatically quoted.
+/// * the values are quoted according to the [quoting policy](Writer::column_quoting_policy)
+///   of their columns.
 ///
 /// It also has the method [`new_row`](Writer::new_row), which returns an object
 /// to write the values in the context of a row.
@@ -48,6 +50,7 @@ use super::WriteResult;
 /// ```
 pub struct Writer<D: Domain, W: Write> {
     fluent: Option<FluentWriter<D, W>>,
+    drop_error: Option<WriteError>,
 }
 
 const INVALID_WRITER_MESSAGE: &str = "the Writer is invalid due to a previous error";
@@ -57,6 +60,7 @@ impl<D: Domain, W: Write> Writer<D, W> {
     pub fn new(writer: W) -> Self {
         Writer {
             fluent: Some(FluentWriter::new(writer)),
+            drop_error: None,
         }
     }
 
@@ -120,6 +124,9 @@ impl<D: Domain, W: Write> Writer<D, W> {
     ///
     /// The comment is automatically preceded by the HASH sign (`#`) and followed
     /// by the [default line-break](Options::default_line_break).
+    ///
+    /// A comment containing line-breaks is handled according to the
+    /// [comment line-break policy](Options::comment_line_break_policy).
     pub fn write_comment_line(&mut self, comment: &D::StringSlice) -> WriteResult<()> {
         let row_writer = self.new_row();
         row_writer
@@ -128,48 +135,91 @@ impl<D: Domain, W: Write> Writer<D, W> {
         row_writer.finish()
     }
 
+    /// Writes a [`Record`], either as a row or as a comment line.
+    pub fn write_record(&mut self, record: &Record<D>) -> WriteResult<()> {
+        match record {
+            Record::Row(row) => self.write_row(row.iter().map(|value| &**value)),
+            Record::Comment(comment) => self.write_comment_line(comment),
+        }
+    }
+
     /// Finalizes the object by flushing the underlying byte writer.
     ///
     /// Prefer to explicitly call this method instead of letting the [`Writer`]
     /// being dropped.
     pub fn finish(mut self) -> WriteResult<()> {
-        self.take_fluent().finish()
+        self.take_fluent()?.finish()
     }
 
     fn use_fluent<F>(&mut self, f: F) -> WriteResult<()>
     where
         F: FnOnce(FluentWriter<D, W>) -> WriteResult<FluentWriter<D, W>>,
     {
-        let fluent = self.take_fluent();
+        let fluent = self.take_fluent()?;
         let fluent = f(fluent)?;
         self.fluent = Some(fluent);
         Ok(())
     }
 
-    fn take_fluent(&mut self) -> FluentWriter<D, W> {
-        self.fluent.take().expect(INVALID_WRITER_MESSAGE)
+    fn take_fluent(&mut self) -> WriteResult<FluentWriter<D, W>> {
+        if let Some(error) = self.drop_error.take() {
+            return Err(error);
+        }
+        Ok(self.fluent.take().expect(INVALID_WRITER_MESSAGE))
+    }
+
+    fn fluent(&self) -> &FluentWriter<D, W> {
+        self.fluent.as_ref().expect(INVALID_WRITER_MESSAGE)
+    }
+
+    fn fluent_mut(&mut self) -> &mut FluentWriter<D, W> {
+        self.fluent.as_mut().expect(INVALID_WRITER_MESSAGE)
     }
 
     /// Returns a reference to the associated [`Options`] object.
     pub fn options(&self) -> &Options<D> {
-        self.fluent
-            .as_ref()
-            .expect(INVALID_WRITER_MESSAGE)
-            .options()
+        self.fluent().options()
     }
 
     /// Returns a mutable reference to the associated [`Options`] object.
     pub fn options_mut(&mut self) -> &mut Options<D> {
-        self.fluent
-            .as_mut()
-            .expect(INVALID_WRITER_MESSAGE)
-            .options_mut()
+        self.fluent_mut().options_mut()
     }
 
     /// Replaces the associated [`Options`] object.
     pub fn set_options(&mut self, options: Options<D>) -> WriteResult<()> {
         self.use_fluent(|fluent| fluent.set_options(options))
     }
+
+    /// Returns the quoting policy used for the column with the given index,
+    /// starting from 0.
+    ///
+    /// If no specific policy was set for the column, then the
+    /// [quoting policy](Options::quoting_policy) is returned.
+    pub fn column_quoting_policy(&self, column_index: usize) -> QuotingPolicy {
+        self.fluent().column_quoting_policy(column_index)
+    }
+
+    /// Sets the [quoting policy](Writer::column_quoting_policy) for the column
+    /// with the given index, starting from 0.
+    pub fn set_column_quoting_policy(
+        &mut self,
+        column_index: usize,
+        quoting_policy: QuotingPolicy,
+    ) {
+        self.fluent_mut()
+            .column_quoting_policies_mut()
+            .insert(column_index, quoting_policy);
+    }
+
+    /// Removes the specific quoting policy for the column with the given index,
+    /// starting from 0, so the [quoting policy](Options::quoting_policy) is used
+    /// for it.
+    pub fn clear_column_quoting_policy(&mut self, column_index: usize) {
+        self.fluent_mut()
+            .column_quoting_policies_mut()
+            .remove(&column_index);
+    }
 }
 
 /// An object that writes values in the context of a row.
@@ -178,9 +228,11 @@ impl<D: Domain, W: Write> Writer<D, W> {
 /// [`options`](Writer::options) object in the same way.
 ///
 /// The [default line-break](Options::default_line_break) is automatically
-/// written when [`RowWriter`] is dropped. Eventual writing errors will be
-/// ignored. Prefer to explicitly call the [`finish`](Writer::finish) method
-/// instead of letting the [`RowWriter`] being dropped.
+/// written when [`RowWriter`] is dropped. An eventual writing error is returned
+/// by the next writing method called on the originating [`Writer`], or by its
+/// [`finish`](Writer::finish) method. Prefer to explicitly call the
+/// [`finish`](RowWriter::finish) method instead of letting the [`RowWriter`]
+/// being dropped.
 ///
 /// # Invalid state after erroring
 ///
@@ -255,6 +307,10 @@ impl<'a, D: Domain, W: Write> RowWriter<'a, D, W> {
 
 impl<'a, D: Domain, W: Write> Drop for RowWriter<'a, D, W> {
     fn drop(&mut self) {
-        self.finish_row().unwrap();
+        if self.writer.fluent.is_some() {
+            if let Err(error) = self.finish_row() {
+                self.writer.drop_error = Some(error);
+            }
+        }
     }
 }
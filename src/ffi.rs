@@ -0,0 +1,604 @@
+//! C ABI bindings, enabled by the `ffi` feature.
+//!
+//! The functions in this module read and write SSV in the
+//! [bytes domain](crate::bytes) through opaque handles:
+//!
+//! * an [`SsvReader`] returns the rows of a file or of a memory buffer, each row
+//!   as an array of [`SsvValue`]s pointing to the bytes of its values;
+//! * an [`SsvWriter`] writes rows to a file or to a memory buffer.
+//!
+//! Files are decompressed and compressed like in
+//! [`read_file`](crate::engine::read_file) and
+//! [`write_file`](crate::engine::write_file).
+//!
+//! Every failing function reports an [`SsvStatus`] code, and fills an optional
+//! [`SsvError`] with the position of syntax errors and the OS error code of IO
+//! errors.
+//!
+//! The `ssv-ffi` crate in the `ffi` directory of the repository builds these
+//! functions as a C shared library, and has the `ssv.h` header that declares
+//! them.
+
+use std::ffi::{c_char, c_int, CStr};
+use std::fs::File;
+use std::io::{BufWriter, Cursor, Write};
+use std::ptr;
+
+use crate::engine::compression::{CompressingWriter, Compression};
+use crate::engine::domain::BytesDomain;
+use crate::engine::reader::Reader;
+use crate::engine::ReadError;
+use crate::syntax::writer::{Sink, SinkWriteError, SinkWriter};
+
+/// The result of a function.
+///
+/// Errors have negative codes.
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SsvStatus {
+    /// The function succeeded.
+    Ok = 0,
+
+    /// There are no more rows to read.
+    End = 1,
+
+    /// A quote in a value was not duplicated (see the [rules](crate#rules)).
+    UnpairedQuote = -1,
+
+    /// The input ended before reaching the closing quote of a quoted value.
+    UnclosedQuotedValue = -2,
+
+    /// An IO error happened when reading or writing.
+    IoError = -3,
+
+    /// A value containing non-spacing elements was tried to be used as spacing.
+    InvalidSpacing = -4,
+
+    /// A value requiring quotes was tried to be written without them.
+    QuotesRequired = -5,
+
+    /// A comment containing a line-break was tried to be written.
+    CommentWithLineBreak = -6,
+
+    /// A required pointer was null, or a path was not valid UTF-8.
+    InvalidArgument = -7,
+
+    /// A row was tried to be written after the writer was finished.
+    Finished = -8,
+}
+
+impl SsvStatus {
+    const ALL: [SsvStatus; 10] = [
+        SsvStatus::Ok,
+        SsvStatus::End,
+        SsvStatus::UnpairedQuote,
+        SsvStatus::UnclosedQuotedValue,
+        SsvStatus::IoError,
+        SsvStatus::InvalidSpacing,
+        SsvStatus::QuotesRequired,
+        SsvStatus::CommentWithLineBreak,
+        SsvStatus::InvalidArgument,
+        SsvStatus::Finished,
+    ];
+
+    /// Returns the status with the given code, if there is one.
+    fn from_code(code: c_int) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|status| *status as c_int == code)
+    }
+}
+
+/// The details of an error.
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SsvError {
+    /// The error code.
+    pub status: SsvStatus,
+
+    /// The line number of a syntax error, starting from 1, or 0 for other
+    /// errors.
+    pub line_number: usize,
+
+    /// The column number of a syntax error, in bytes and starting from 1, or 0
+    /// for other errors.
+    pub column_number: usize,
+
+    /// The OS error code of an IO error, such as an `errno` value, or 0 if
+    /// there is none.
+    pub os_error: c_int,
+}
+
+impl SsvError {
+    fn new(status: SsvStatus) -> Self {
+        SsvError {
+            status,
+            line_number: 0,
+            column_number: 0,
+            os_error: 0,
+        }
+    }
+
+    fn report(self, error: *mut SsvError) -> SsvStatus {
+        if !error.is_null() {
+            // SAFETY: the callers receive `error` from C, which must point to a
+            // writable `SsvError` if it is not null.
+            unsafe { error.write(self) };
+        }
+        self.status
+    }
+}
+
+impl From<ReadError> for SsvError {
+    fn from(read_error: ReadError) -> Self {
+        let (status, position) = match read_error {
+            ReadError::UnpairedQuote(position) => (SsvStatus::UnpairedQuote, position),
+            ReadError::UnclosedQuotedValue(position) => (SsvStatus::UnclosedQuotedValue, position),
+            ReadError::IoError(io_error) => return io_error.into(),
+        };
+        SsvError {
+            line_number: position.line_number,
+            column_number: position.column_number,
+            ..SsvError::new(status)
+        }
+    }
+}
+
+impl From<SinkWriteError<std::io::Error>> for SsvError {
+    fn from(sink_write_error: SinkWriteError<std::io::Error>) -> Self {
+        match sink_write_error {
+            SinkWriteError::InvalidSpacing => SsvError::new(SsvStatus::InvalidSpacing),
+            SinkWriteError::QuotesRequired => SsvError::new(SsvStatus::QuotesRequired),
+            SinkWriteError::CommentWithLineBreak => SsvError::new(SsvStatus::CommentWithLineBreak),
+            SinkWriteError::SinkError(io_error) => io_error.into(),
+        }
+    }
+}
+
+impl From<std::io::Error> for SsvError {
+    fn from(io_error: std::io::Error) -> Self {
+        SsvError {
+            os_error: io_error.raw_os_error().unwrap_or(0),
+            ..SsvError::new(SsvStatus::IoError)
+        }
+    }
+}
+
+/// A value, as a pointer to its bytes and their count.
+///
+/// The bytes are not terminated by a NUL byte, and may contain NUL bytes.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct SsvValue {
+    /// The bytes of the value. It may be null only if `length` is 0.
+    pub data: *const u8,
+
+    /// The number of bytes.
+    pub length: usize,
+}
+
+impl SsvValue {
+    /// # Safety
+    ///
+    /// `data` must be null with a zero `length`, or point to `length` readable
+    /// bytes.
+    unsafe fn as_bytes<'a>(&self) -> &'a [u8] {
+        if self.length == 0 {
+            &[]
+        } else {
+            // SAFETY: guaranteed by the caller.
+            unsafe { std::slice::from_raw_parts(self.data, self.length) }
+        }
+    }
+}
+
+/// An opaque handle that reads rows.
+pub struct SsvReader {
+    rows: Box<dyn Iterator<Item = Result<Vec<Vec<u8>>, ReadError>>>,
+    row: Vec<Vec<u8>>,
+    values: Vec<SsvValue>,
+}
+
+impl SsvReader {
+    fn new(rows: impl Iterator<Item = Result<Vec<Vec<u8>>, ReadError>> + 'static) -> Box<Self> {
+        Box::new(SsvReader {
+            rows: Box::new(rows),
+            row: Vec::new(),
+            values: Vec::new(),
+        })
+    }
+
+    fn next_row(&mut self) -> Result<bool, SsvError> {
+        self.values.clear();
+        match self.rows.next() {
+            Some(row) => {
+                self.row = row?;
+                self.values.extend(self.row.iter().map(|value| SsvValue {
+                    data: value.as_ptr(),
+                    length: value.len(),
+                }));
+                Ok(true)
+            }
+            None => {
+                self.row.clear();
+                Ok(false)
+            }
+        }
+    }
+}
+
+/// An opaque handle that writes rows.
+pub struct SsvWriter {
+    /// `None` after the writer is finished.
+    writer: Option<SinkWriter<BytesDomain, Output>>,
+
+    /// The content of a memory writer after it is finished.
+    finished_buffer: Option<Vec<u8>>,
+}
+
+impl SsvWriter {
+    fn new(output: Output) -> Box<Self> {
+        Box::new(SsvWriter {
+            writer: Some(SinkWriter::new(output)),
+            finished_buffer: None,
+        })
+    }
+
+    fn write_row(&mut self, row: &[SsvValue]) -> Result<(), SsvError> {
+        let writer = self
+            .writer
+            .as_mut()
+            .ok_or(SsvError::new(SsvStatus::Finished))?;
+        for value in row {
+            if value.data.is_null() && value.length > 0 {
+                return Err(SsvError::new(SsvStatus::InvalidArgument));
+            }
+            // SAFETY: the caller of `ssv_writer_write_row` guarantees that the
+            // non-null pointers point to `length` readable bytes.
+            writer.write_value(unsafe { value.as_bytes() })?;
+        }
+        writer.write_line_break()?;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), SsvError> {
+        let writer = self
+            .writer
+            .take()
+            .ok_or(SsvError::new(SsvStatus::Finished))?;
+        match writer.into_inner() {
+            Output::File(file) => file.finish()?,
+            Output::Buffer(buffer) => self.finished_buffer = Some(buffer),
+        }
+        Ok(())
+    }
+
+    fn buffer(&self) -> Option<&[u8]> {
+        match &self.writer {
+            Some(writer) => match writer.get_ref() {
+                Output::File(_) => None,
+                Output::Buffer(buffer) => Some(buffer),
+            },
+            None => self.finished_buffer.as_deref(),
+        }
+    }
+}
+
+enum Output {
+    File(CompressingWriter<BufWriter<File>>),
+    Buffer(Vec<u8>),
+}
+
+impl Sink for Output {
+    type Error = std::io::Error;
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        match self {
+            Output::File(file) => file.write_all(bytes),
+            Output::Buffer(buffer) => {
+                buffer.extend_from_slice(bytes);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// # Safety
+///
+/// `path` must be null or point to a NUL-terminated string.
+unsafe fn path_from_c<'a>(path: *const c_char) -> Result<&'a str, SsvError> {
+    if path.is_null() {
+        return Err(SsvError::new(SsvStatus::InvalidArgument));
+    }
+    // SAFETY: guaranteed by the caller.
+    unsafe { CStr::from_ptr(path) }
+        .to_str()
+        .map_err(|_| SsvError::new(SsvStatus::InvalidArgument))
+}
+
+/// Returns a static NUL-terminated description of a status code.
+///
+/// The code is taken as an integer, so that codes not returned by this version
+/// get a generic description instead of being undefined behavior.
+#[no_mangle]
+pub extern "C" fn ssv_status_message(status: c_int) -> *const c_char {
+    let Some(status) = SsvStatus::from_code(status) else {
+        return c"unknown status".as_ptr();
+    };
+    let message: &CStr = match status {
+        SsvStatus::Ok => c"ok",
+        SsvStatus::End => c"no more rows",
+        SsvStatus::UnpairedQuote => c"unpaired quote",
+        SsvStatus::UnclosedQuotedValue => c"unclosed quoted value",
+        SsvStatus::IoError => c"IO error",
+        SsvStatus::InvalidSpacing => c"invalid spacing",
+        SsvStatus::QuotesRequired => c"value requires quotes",
+        SsvStatus::CommentWithLineBreak => c"comment contains a line-break",
+        SsvStatus::InvalidArgument => c"invalid argument",
+        SsvStatus::Finished => c"writer already finished",
+    };
+    message.as_ptr()
+}
+
+/// Opens a file to read its rows.
+///
+/// Returns null on failure, filling `error` if it is not null.
+///
+/// # Safety
+///
+/// `path` must be null or point to a NUL-terminated UTF-8 string. `error` must
+/// be null or point to a writable [`SsvError`].
+#[no_mangle]
+pub unsafe extern "C" fn ssv_reader_open_file(
+    path: *const c_char,
+    error: *mut SsvError,
+) -> *mut SsvReader {
+    let result = (|| -> Result<_, SsvError> {
+        // SAFETY: guaranteed by the caller.
+        let path = unsafe { path_from_c(path) }?;
+        let reader = crate::engine::read_file::<BytesDomain, _>(path)?;
+        Ok(SsvReader::new(reader))
+    })();
+    match result {
+        Ok(reader) => Box::into_raw(reader),
+        Err(ssv_error) => {
+            ssv_error.report(error);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Creates a reader of the rows in a memory buffer.
+///
+/// The buffer is copied, so it can be released right after this call. Returns
+/// null if `data` is null and `length` is not 0, filling `error` if it is not
+/// null.
+///
+/// # Safety
+///
+/// `data` must be null with a zero `length`, or point to `length` readable
+/// bytes. `error` must be null or point to a writable [`SsvError`].
+#[no_mangle]
+pub unsafe extern "C" fn ssv_reader_from_buffer(
+    data: *const u8,
+    length: usize,
+    error: *mut SsvError,
+) -> *mut SsvReader {
+    if data.is_null() && length > 0 {
+        SsvError::new(SsvStatus::InvalidArgument).report(error);
+        return ptr::null_mut();
+    }
+    // SAFETY: guaranteed by the caller.
+    let buffer = unsafe { SsvValue { data, length }.as_bytes() }.to_vec();
+    let reader: Reader<BytesDomain, _> = Reader::new(Cursor::new(buffer));
+    Box::into_raw(SsvReader::new(reader))
+}
+
+/// Reads the next row.
+///
+/// On [`SsvStatus::Ok`], `values` points to an array of `count` values, which
+/// are valid until the next call or until the reader is freed. Returns
+/// [`SsvStatus::End`] when there are no more rows, and an error code on
+/// failure, filling `error` if it is not null. Reading after an error returns
+/// [`SsvStatus::End`].
+///
+/// # Safety
+///
+/// `reader` must be null or a handle returned by one of the `ssv_reader_*`
+/// functions and not yet freed. `values` and `count` must be null or point to
+/// writable variables. `error` must be null or point to a writable
+/// [`SsvError`].
+#[no_mangle]
+pub unsafe extern "C" fn ssv_reader_next_row(
+    reader: *mut SsvReader,
+    values: *mut *const SsvValue,
+    count: *mut usize,
+    error: *mut SsvError,
+) -> SsvStatus {
+    if values.is_null() || count.is_null() {
+        return SsvError::new(SsvStatus::InvalidArgument).report(error);
+    }
+    // SAFETY: guaranteed by the caller.
+    let Some(reader) = (unsafe { reader.as_mut() }) else {
+        return SsvError::new(SsvStatus::InvalidArgument).report(error);
+    };
+    match reader.next_row() {
+        Ok(has_row) => {
+            // SAFETY: guaranteed by the caller.
+            unsafe {
+                values.write(reader.values.as_ptr());
+                count.write(reader.values.len());
+            }
+            if has_row {
+                SsvStatus::Ok
+            } else {
+                SsvStatus::End
+            }
+        }
+        Err(ssv_error) => ssv_error.report(error),
+    }
+}
+
+/// Frees a reader. Does nothing if `reader` is null.
+///
+/// # Safety
+///
+/// `reader` must be null or a handle returned by one of the `ssv_reader_*`
+/// functions and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn ssv_reader_free(reader: *mut SsvReader) {
+    if !reader.is_null() {
+        // SAFETY: guaranteed by the caller.
+        drop(unsafe { Box::from_raw(reader) });
+    }
+}
+
+/// Creates a file, or truncates it if it exists, to write rows to it.
+///
+/// The file is compressed if its extension matches a compression format (see
+/// [`Compression::from_extension`]). Returns null on failure, filling `error`
+/// if it is not null.
+///
+/// # Safety
+///
+/// `path` must be null or point to a NUL-terminated UTF-8 string. `error` must
+/// be null or point to a writable [`SsvError`].
+#[no_mangle]
+pub unsafe extern "C" fn ssv_writer_create_file(
+    path: *const c_char,
+    error: *mut SsvError,
+) -> *mut SsvWriter {
+    let result = (|| -> Result<_, SsvError> {
+        // SAFETY: guaranteed by the caller.
+        let path = unsafe { path_from_c(path) }?;
+        let file = BufWriter::new(File::create(path)?);
+        let file = CompressingWriter::new(file, Compression::from_extension(path))?;
+        Ok(SsvWriter::new(Output::File(file)))
+    })();
+    match result {
+        Ok(writer) => Box::into_raw(writer),
+        Err(ssv_error) => {
+            ssv_error.report(error);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Creates a writer of rows to a memory buffer, which is returned by
+/// [`ssv_writer_buffer`].
+#[no_mangle]
+pub extern "C" fn ssv_writer_new_buffer() -> *mut SsvWriter {
+    Box::into_raw(SsvWriter::new(Output::Buffer(Vec::new())))
+}
+
+/// Writes a row of `count` values.
+///
+/// The values are quoted only when required. Returns an error code on failure,
+/// filling `error` if it is not null.
+///
+/// # Safety
+///
+/// `writer` must be null or a handle returned by one of the `ssv_writer_*`
+/// functions and not yet freed. `values` must be null with a zero `count`, or
+/// point to `count` values, whose `data` must be null with a zero `length`, or
+/// point to `length` readable bytes. `error` must be null or point to a
+/// writable [`SsvError`].
+#[no_mangle]
+pub unsafe extern "C" fn ssv_writer_write_row(
+    writer: *mut SsvWriter,
+    values: *const SsvValue,
+    count: usize,
+    error: *mut SsvError,
+) -> SsvStatus {
+    // SAFETY: guaranteed by the caller.
+    let Some(writer) = (unsafe { writer.as_mut() }) else {
+        return SsvError::new(SsvStatus::InvalidArgument).report(error);
+    };
+    let row = if count == 0 {
+        &[]
+    } else if values.is_null() {
+        return SsvError::new(SsvStatus::InvalidArgument).report(error);
+    } else {
+        // SAFETY: guaranteed by the caller.
+        unsafe { std::slice::from_raw_parts(values, count) }
+    };
+    match writer.write_row(row) {
+        Ok(()) => SsvStatus::Ok,
+        Err(ssv_error) => ssv_error.report(error),
+    }
+}
+
+/// Finishes the writing, ending the compressed stream and flushing the file of
+/// a file writer.
+///
+/// No rows can be written after this call. Returns an error code on failure,
+/// filling `error` if it is not null.
+///
+/// # Safety
+///
+/// `writer` must be null or a handle returned by one of the `ssv_writer_*`
+/// functions and not yet freed. `error` must be null or point to a writable
+/// [`SsvError`].
+#[no_mangle]
+pub unsafe extern "C" fn ssv_writer_finish(
+    writer: *mut SsvWriter,
+    error: *mut SsvError,
+) -> SsvStatus {
+    // SAFETY: guaranteed by the caller.
+    let Some(writer) = (unsafe { writer.as_mut() }) else {
+        return SsvError::new(SsvStatus::InvalidArgument).report(error);
+    };
+    match writer.finish() {
+        Ok(()) => SsvStatus::Ok,
+        Err(ssv_error) => ssv_error.report(error),
+    }
+}
+
+/// Returns the content written by a writer created with
+/// [`ssv_writer_new_buffer`].
+///
+/// `data` and `length` receive the content, which is valid until the next
+/// row is written or until the writer is freed. Returns
+/// [`SsvStatus::InvalidArgument`] for a file writer.
+///
+/// # Safety
+///
+/// `writer` must be null or a handle returned by one of the `ssv_writer_*`
+/// functions and not yet freed. `data` and `length` must be null or point to
+/// writable variables.
+#[no_mangle]
+pub unsafe extern "C" fn ssv_writer_buffer(
+    writer: *const SsvWriter,
+    data: *mut *const u8,
+    length: *mut usize,
+) -> SsvStatus {
+    // SAFETY: guaranteed by the caller.
+    let buffer = unsafe { writer.as_ref() }.and_then(SsvWriter::buffer);
+    match buffer {
+        Some(buffer) if !data.is_null() && !length.is_null() => {
+            // SAFETY: guaranteed by the caller.
+            unsafe {
+                data.write(buffer.as_ptr());
+                length.write(buffer.len());
+            }
+            SsvStatus::Ok
+        }
+        _ => SsvStatus::InvalidArgument,
+    }
+}
+
+/// Frees a writer. Does nothing if `writer` is null.
+///
+/// A file writer that was not finished is finished, ignoring eventual errors.
+///
+/// # Safety
+///
+/// `writer` must be null or a handle returned by one of the `ssv_writer_*`
+/// functions and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn ssv_writer_free(writer: *mut SsvWriter) {
+    if !writer.is_null() {
+        // SAFETY: guaranteed by the caller.
+        drop(unsafe { Box::from_raw(writer) });
+    }
+}
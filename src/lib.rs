@@ -1,3 +1,4 @@
+#![cfg_attr(not(feature = "std"), no_std)]
 #![warn(missing_docs)]
 #![doc(test(attr(deny(warnings))))]
 
@@ -11,11 +12,11 @@
 //!
 //! * Values are separated by a sequence of at least one spacing element.
 //!   * A spacing element is either a SPACE (byte value/codepoint 32) or a TAB
-//! (byte value/codepoint 9).
+//!     (byte value/codepoint 9).
 //!   * The first value in a row can be preceded by spacing, which is ignored.
 //!   * The last value in a row can be succeeded by spacing, which is ignored.
 //! * Rows of values are separated by line-breaks. A line-break is an LF (byte
-//! value/codepoint 10) optionally preceded by CR (byte value/codepoint 13).
+//!   value/codepoint 10) optionally preceded by CR (byte value/codepoint 13).
 //! * Values may be enclosed in quotes (`"`).
 //! * Values *must* be enclosed in quotes in the following cases:
 //!   * the value is empty;
@@ -25,7 +26,7 @@
 //!   * the value is the first thing in a row and starts with a HASH sign (`#`).
 //! * Values containing quotes are encoded by duplicating the quotes.
 //! * A line starting with the HASH sign (`#`) is ignored until the next
-//! line-break (or end of the content). Such line is considered a comment line.
+//!   line-break (or end of the content). Such line is considered a comment line.
 //!
 //!
 //! ## Example
@@ -58,37 +59,170 @@
 //! for types in the [`engine`] module. Code using this crate should not have
 //! references to the [`engine`] module, only to the specializations modules.
 //!
+//! The exception is code that reads and writes its own value types, such as
+//! small-string-optimized or shared strings. It can implement the
+//! [`Domain`](crate::engine::domain::Domain) trait, as explained in the
+//! [`domain`](crate::engine::domain) module, and use the generic types in the
+//! [`engine`] module with its domain.
+//!
 //! # Reading SSV
 //!
 //! Given a byte reader (a value implementing the [`std::io::Read`] trait),
 //! SSV can be read with:
 //!
 //! * [`Tokenizer`](crate::engine::tokenizer::Tokenizer) - an iterator that
-//! validates and returns tokens, including spacing, line-breaks and comments.
+//!   validates and returns tokens, including spacing, line-breaks and comments.
 //! * [`Reader`](crate::engine::reader::Reader) - an iterator that returns rows.
-//! Each row is a [`Vec`](std::vec::Vec) of values.
+//!   Each row is a [`Vec`](std::vec::Vec) of values.
 //! * [`read`](crate::engine::read) - a utility function that creates a
-//! [`Reader`](crate::engine::reader::Reader) object.
+//!   [`Reader`](crate::engine::reader::Reader) object.
 //!
 //! There is also the [`read_file`](crate::engine::read_file) function that reads
 //! from a file given its path.
 //!
+//! A [`ReverseReader`](crate::engine::reverse_reader::ReverseReader) returns the
+//! rows of a seekable reader from the last one to the first one, reading only
+//! the end of the input to return the last rows.
+//!
+//! A [`Follower`](crate::engine::follow::Follower) follows a file that grows,
+//! like `tail -f`, returning its rows as they are appended, and detecting when
+//! the file is truncated or rotated.
+//!
+//! Input that is already in memory can be read without copying the values with
+//! a [`SliceReader`](crate::engine::slice_reader::SliceReader) or a
+//! [`SliceRecordReader`](crate::engine::slice_reader::SliceRecordReader). With
+//! the `mmap` feature, the `map_file` function maps a
+//! large file into memory to read it this way.
+//!
 //! # Writing SSV
 //!
 //! Given a byte writer (a value implementing the [`std::io::Write`] trait),
 //! SSV can be written with:
 //!
 //! * [`FluentWriter`](crate::engine::fluent_writer::FluentWriter) - an object
-//! that writes items with a fluent interface. Delimiters such as spacing and
-//! line-breaks are automatically written when required.
+//!   that writes items with a fluent interface. Delimiters such as spacing and
+//!   line-breaks are automatically written when required.
 //! * [`Writer`](crate::engine::writer::Writer) - an object that writes in a
-//! row-oriented way.
+//!   row-oriented way.
+//! * [`TokenWriter`](crate::engine::token_writer::TokenWriter) - an object that
+//!   writes tokens exactly as they are, such as the ones returned by the
+//!   [`Tokenizer`](crate::engine::tokenizer::Tokenizer).
 //! * [`write`](crate::engine::write) - a utility function that uses a
-//! [`Writer`](crate::engine::writer::Writer) object to write SSV content.
+//!   [`Writer`](crate::engine::writer::Writer) object to write SSV content.
 //!
 //! There is also the [`write_file`](crate::engine::write_file) function that
-//! writes to a file given its path.
+//! writes to a file given its path, and the
+//! [`write_file_with_mode`](crate::engine::write_file_with_mode) function that
+//! can instead replace the file atomically or append to it (see
+//! [`WriteMode`](crate::engine::WriteMode)).
+//!
+//! With the `gzip` and `zstd` features, [`read_file`](crate::engine::read_file)
+//! decompresses files detected by their magic bytes, and the writing functions
+//! compress files with the `.gz`, `.zst` or `.zstd` extensions. The
+//! [`DecompressingReader`](crate::engine::compression::DecompressingReader) and
+//! [`CompressingWriter`](crate::engine::compression::CompressingWriter) wrappers
+//! stream compressed content from and to any byte reader or writer.
+//!
+//! The [chars domain](crate::chars) reads and writes UTF-8. A
+//! [`DecodingReader`](crate::engine::encoding::DecodingReader) and an
+//! [`EncodingWriter`](crate::engine::encoding::EncodingWriter) convert from and
+//! to UTF-16, Latin-1 and Windows-1252, handling byte order marks, and
+//! [`read_file_with_encoding`](crate::engine::read_file_with_encoding) reads a
+//! file in a given encoding.
+//!
+//! # Linting SSV
+//!
+//! The [`Linter`](crate::engine::lint::Linter) reports style and consistency
+//! problems, such as mixed line-breaks, trailing spacing or unnecessary quotes,
+//! and can write a corrected version of the content.
+//!
+//! # Updating SSV
+//!
+//! An [`Updater`](crate::engine::update::Updater) replaces cells of the rows
+//! found by a key, keeping the spacing, comments and line-breaks of the content,
+//! and the alignment of the columns. Files are replaced atomically.
+//!
+//! # Transforming SSV
+//!
+//! A [`Projection`](crate::engine::projection::Projection) selects, reorders,
+//! renames and computes columns, either built programmatically or parsed from
+//! a spec string such as `"id, name=first+' '+last, *, -tmp"`. It works on the
+//! records returned by a [`RecordReader`](crate::engine::reader::RecordReader),
+//! so comment lines are preserved.
+//!
+//! A [`Filter`](crate::engine::filter::Filter) keeps the rows that satisfy a
+//! predicate expression, such as `age > 30 and name ~ "J*"`.
+//!
+//! A [`Sorter`](crate::engine::sort::Sorter) sorts rows by one or more key
+//! columns, and handles content larger than the available memory by spilling
+//! sorted runs to temporary files.
+//!
+//! A [`Joiner`](crate::engine::join::Joiner) joins the rows of two inputs on
+//! key columns, with inner, left or full outer joins.
+//!
+//! An [`Aggregator`](crate::engine::aggregate::Aggregator) computes counts,
+//! sums, minimums, maximums, means and distinct counts per group of rows, with
+//! numbers parsed according to a decimal point or decimal comma format.
+//!
+//! # Mapping SSV to structs
+//!
+//! A [`StructReader`](crate::engine::mapping::StructReader) reads the rows as
+//! values of a type implementing
+//! [`FromSsvRow`](crate::engine::mapping::FromSsvRow), with fields mapped to
+//! columns by position or by header name, and a
+//! [`StructWriter`](crate::engine::mapping::StructWriter) writes values of a
+//! type implementing [`ToSsvRow`](crate::engine::mapping::ToSsvRow) as rows.
+//! With the `derive` feature, both traits can be derived for structs.
+//!
+//! # Comparing SSV
+//!
+//! A [`Differ`](crate::engine::diff::Differ) compares the parsed rows of two
+//! inputs, matched by key columns or by sequence alignment, and reports the
+//! added, removed and changed rows with the positions of the changed cells.
+//! Differences in spacing and quoting are ignored.
+//!
+//! A [`Merger`](crate::engine::merge::Merger) does a three-way merge of rows
+//! matched by key columns, and reports a conflict only when the same cell was
+//! changed on both sides. The `ssv-git` binary uses it as a git merge driver,
+//! and [`align`](crate::engine::align) as a git textconv driver.
+//!
+//! # Profiling SSV
+//!
+//! A [`Profiler`](crate::engine::profile::Profiler) infers the type of each
+//! column and computes statistics about its values in a single pass. The
+//! inferred types can be exported as a [`Schema`](crate::engine::profile::Schema),
+//! which is written as SSV and validates other content.
+//!
+//! # Rendering SSV
+//!
+//! A [`Renderer`](crate::engine::render::Renderer) writes rows as a
+//! GitHub-flavored Markdown table, like the one in the [example](#example), as
+//! an HTML table, or as a text table with Unicode or ASCII borders. Numeric
+//! columns are right-aligned.
+//!
+//! Conversely, a [`MarkdownImporter`](crate::engine::import::MarkdownImporter)
+//! reads GitHub-flavored Markdown tables, and a
+//! [`FixedWidthImporter`](crate::engine::import::FixedWidthImporter) reads
+//! fixed-width columns of text with explicit or detected boundaries, to write
+//! their rows as SSV.
+//!
+//! # `no_std` support
+//!
+//! Everything but the [`syntax`] module requires the `std` feature, which is
+//! enabled by default. Without it, the crate is `no_std` and only requires the
+//! `alloc` crate. The [`syntax`] module has the tokenizer state machine and the
+//! quoting rules, reading from a `&[u8]` or a `&str` and writing to a
+//! [`Sink`](crate::syntax::writer::Sink), such as a
+//! [`core::fmt::Write`] object.
+//!
+//! # C bindings
+//!
+//! With the `ffi` feature, the `ffi` module has C ABI functions that read and
+//! write SSV through opaque handles. The `ssv-ffi` crate in the `ffi` directory
+//! of the repository builds them as a C shared library, and has the `ssv.h`
+//! header that declares them.
 
+#[cfg(feature = "std")]
 macro_rules! generic_item_warning_doc {
     ($item_name:literal) => {
         concat!(
@@ -112,6 +246,7 @@ macro_rules! generic_item_warning_doc {
     };
 }
 
+#[cfg(feature = "std")]
 macro_rules! generic_item_link_doc {
     ($item_name:literal) => {
         concat!(
@@ -124,6 +259,7 @@ See the doc for the generic item [`crate::engine::",
     };
 }
 
+#[cfg(feature = "std")]
 macro_rules! generic_item_delegation_doc {
     ($item_name:literal) => {
         concat!(
@@ -136,20 +272,34 @@ It simply delegates to the generic item [`crate::engine::",
     };
 }
 
+extern crate alloc;
+
+#[cfg(feature = "std")]
 pub mod engine;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod syntax;
 
+#[cfg(feature = "std")]
 macro_rules! domain_module {
     ($name:ident, $domain:ty, $doc:literal) => {
         #[doc = $doc]
         pub mod $name {
-            use std::fs::File;
             use std::io::Read;
             use std::io::Write;
             use std::path::Path;
 
             use crate::engine::domain::Domain;
 
+            pub use crate::engine::compression::CompressingWriter;
+            pub use crate::engine::compression::Compression;
+            pub use crate::engine::compression::DecompressingReader;
+            pub use crate::engine::encoding::DecodingReader;
+            pub use crate::engine::encoding::Encoding;
+            pub use crate::engine::encoding::EncodingWriter;
+            pub use crate::engine::FileReader;
             pub use crate::engine::LineBreak;
+            pub use crate::engine::WriteMode;
 
             pub use crate::engine::position::Position;
             pub use crate::engine::position::WithPosition;
@@ -168,13 +318,82 @@ macro_rules! domain_module {
             #[doc = generic_item_link_doc!("reader::Reader")]
             pub type Reader<R> = super::engine::reader::Reader<$domain, R>;
 
+            /// An SSV record: either a row of values or a comment line.
+            #[doc = generic_item_link_doc!("reader::Record")]
+            pub type Record = super::engine::reader::Record<$domain>;
+
+            /// Reads SSV records (rows and comment lines) from a value that
+            /// implements the [`Read`] trait.
+            #[doc = generic_item_link_doc!("reader::RecordReader")]
+            pub type RecordReader<R> = super::engine::reader::RecordReader<$domain, R>;
+
+            /// Reads SSV rows from a value that implements the [`Read`] and
+            /// [`Seek`](std::io::Seek) traits, from the last row to the first.
+            #[doc = generic_item_link_doc!("reverse_reader::ReverseReader")]
+            pub type ReverseReader<R> = super::engine::reverse_reader::ReverseReader<$domain, R>;
+
+            /// Follows an SSV file that grows, returning its rows as they are
+            /// appended.
+            #[doc = generic_item_link_doc!("follow::Follower")]
+            pub type Follower = super::engine::follow::Follower<$domain>;
+
+            /// An event returned by a [`Follower`].
+            #[doc = generic_item_link_doc!("follow::FollowEvent")]
+            pub type FollowEvent = super::engine::follow::FollowEvent<$domain>;
+
             /// Reads SSV from a file.
             #[doc = generic_item_delegation_doc!("read_file")]
             #[inline]
-            pub fn read_file<P: AsRef<Path>>(path: P) -> ReadResult<Reader<File>> {
+            pub fn read_file<P: AsRef<Path>>(path: P) -> ReadResult<Reader<FileReader>> {
                 crate::engine::read_file(path)
             }
 
+            /// Reads SSV from a file in the given text encoding.
+            #[doc = generic_item_delegation_doc!("read_file_with_encoding")]
+            #[inline]
+            pub fn read_file_with_encoding<P: AsRef<Path>>(
+                path: P,
+                encoding: Encoding,
+            ) -> ReadResult<Reader<DecodingReader<FileReader>>> {
+                crate::engine::read_file_with_encoding(path, encoding)
+            }
+
+            /// A value read from a byte slice, which borrows from the slice when
+            /// possible.
+            #[doc = generic_item_link_doc!("slice_reader::SliceValue")]
+            pub type SliceValue<'a> = super::engine::slice_reader::SliceValue<'a, $domain>;
+
+            /// Reads SSV rows from a byte slice.
+            #[doc = generic_item_link_doc!("slice_reader::SliceReader")]
+            pub type SliceReader<'a> = super::engine::slice_reader::SliceReader<'a, $domain>;
+
+            /// An SSV record read from a byte slice.
+            #[doc = generic_item_link_doc!("slice_reader::SliceRecord")]
+            pub type SliceRecord<'a> = super::engine::slice_reader::SliceRecord<'a, $domain>;
+
+            /// Reads SSV records (rows and comment lines) from a byte slice.
+            #[doc = generic_item_link_doc!("slice_reader::SliceRecordReader")]
+            pub type SliceRecordReader<'a> =
+                super::engine::slice_reader::SliceRecordReader<'a, $domain>;
+
+            /// A memory-mapped file to read SSV from.
+            #[doc = generic_item_link_doc!("slice_reader::MappedFile")]
+            #[cfg(feature = "mmap")]
+            pub type MappedFile = super::engine::slice_reader::MappedFile<$domain>;
+
+            /// Maps a file into memory to read SSV from it.
+            #[doc = generic_item_delegation_doc!("map_file")]
+            ///
+            /// # Safety
+            ///
+            /// The file must not be modified or truncated while it is mapped.
+            #[cfg(feature = "mmap")]
+            #[inline]
+            pub unsafe fn map_file<P: AsRef<Path>>(path: P) -> ReadResult<MappedFile> {
+                // SAFETY: the caller upholds the contract of `map_file`.
+                unsafe { crate::engine::map_file(path) }
+            }
+
             /// Reads SSV from a reader.
             #[doc = generic_item_delegation_doc!("read")]
             #[inline]
@@ -185,6 +404,12 @@ macro_rules! domain_module {
             pub use crate::engine::WriteError;
             pub use crate::engine::WriteResult;
 
+            pub use crate::engine::ReadWriteError;
+            pub use crate::engine::ReadWriteResult;
+
+            pub use crate::engine::options::CommentLineBreakPolicy;
+            pub use crate::engine::options::QuotingPolicy;
+
             /// The options for writing SSV content.
             #[doc = generic_item_link_doc!("options::Options")]
             pub type Options = super::engine::options::Options<$domain>;
@@ -198,10 +423,189 @@ macro_rules! domain_module {
             #[doc = generic_item_link_doc!("writer::Writer")]
             pub type Writer<W> = super::engine::writer::Writer<$domain, W>;
 
+            /// Writes SSV tokens to a value that implements the [`Write`] trait.
+            #[doc = generic_item_link_doc!("token_writer::TokenWriter")]
+            pub type TokenWriter<W> = super::engine::token_writer::TokenWriter<$domain, W>;
+
             /// An object that writes values in the context of a row.
             #[doc = generic_item_link_doc!("writer::RowWriter")]
             pub type RowWriter<'a, W> = super::engine::writer::RowWriter<'a, $domain, W>;
 
+            pub use crate::engine::lint::Finding;
+            pub use crate::engine::lint::Rule;
+            pub use crate::engine::lint::RuleSet;
+
+            /// Checks SSV content for style and consistency problems.
+            #[doc = generic_item_link_doc!("lint::Linter")]
+            pub type Linter = super::engine::lint::Linter<$domain>;
+
+            pub use crate::engine::columns::ColumnNotFoundError;
+            pub use crate::engine::projection::SpecError;
+
+            /// A reference to a column, either by index or by name.
+            #[doc = generic_item_link_doc!("columns::ColumnRef")]
+            pub type ColumnRef = super::engine::columns::ColumnRef<$domain>;
+
+            /// Selects, reorders, renames and computes columns of SSV rows.
+            #[doc = generic_item_link_doc!("projection::Projection")]
+            pub type Projection = super::engine::projection::Projection<$domain>;
+
+            /// An iterator that applies a projection to records.
+            #[doc = generic_item_link_doc!("projection::Projected")]
+            pub type Projected<'a, I> = super::engine::projection::Projected<'a, $domain, I>;
+
+            pub use crate::engine::filter::ExpressionError;
+
+            /// Filters SSV rows with a predicate expression.
+            #[doc = generic_item_link_doc!("filter::Filter")]
+            pub type Filter = super::engine::filter::Filter<$domain>;
+
+            /// An iterator that applies a filter to rows.
+            #[doc = generic_item_link_doc!("filter::Filtered")]
+            pub type Filtered<'a, I> = super::engine::filter::Filtered<'a, $domain, I>;
+
+            pub use crate::engine::sort::Collation;
+            pub use crate::engine::sort::Direction;
+
+            /// Sorts SSV rows, spilling sorted runs to temporary files.
+            #[doc = generic_item_link_doc!("sort::Sorter")]
+            pub type Sorter = super::engine::sort::Sorter<$domain>;
+
+            /// A key to sort rows.
+            #[doc = generic_item_link_doc!("sort::SortKey")]
+            pub type SortKey = super::engine::sort::SortKey<$domain>;
+
+            pub use crate::engine::join::JoinError;
+            pub use crate::engine::join::JoinKind;
+            pub use crate::engine::join::JoinResult;
+            pub use crate::engine::join::JoinStrategy;
+            pub use crate::engine::join::Side;
+
+            /// Joins the rows of two SSV inputs on key columns.
+            #[doc = generic_item_link_doc!("join::Joiner")]
+            pub type Joiner = super::engine::join::Joiner<$domain>;
+
+            pub use crate::engine::aggregate::AggregateError;
+            pub use crate::engine::aggregate::AggregateResult;
+            pub use crate::engine::aggregate::GroupingStrategy;
+            pub use crate::engine::aggregate::NumberFormat;
+
+            /// Computes aggregations of SSV rows per group.
+            #[doc = generic_item_link_doc!("aggregate::Aggregator")]
+            pub type Aggregator = super::engine::aggregate::Aggregator<$domain>;
+
+            /// An aggregation computed by an aggregator.
+            #[doc = generic_item_link_doc!("aggregate::Aggregation")]
+            pub type Aggregation = super::engine::aggregate::Aggregation<$domain>;
+
+            /// An iterator that returns aggregated rows.
+            #[doc = generic_item_link_doc!("aggregate::Aggregated")]
+            pub type Aggregated<'a, I> = super::engine::aggregate::Aggregated<'a, $domain, I>;
+
+            pub use crate::engine::mapping::FieldColumn;
+            pub use crate::engine::mapping::FieldError;
+            pub use crate::engine::mapping::FromSsvRow;
+            pub use crate::engine::mapping::MappingError;
+            pub use crate::engine::mapping::MappingResult;
+            pub use crate::engine::mapping::ToSsvRow;
+
+            /// Reads values of a type implementing [`FromSsvRow`] from SSV rows.
+            #[doc = generic_item_link_doc!("mapping::StructReader")]
+            pub type StructReader<R, T> = super::engine::mapping::StructReader<$domain, R, T>;
+
+            /// Writes values of a type implementing [`ToSsvRow`] as SSV rows.
+            #[doc = generic_item_link_doc!("mapping::StructWriter")]
+            pub type StructWriter<W, T> = super::engine::mapping::StructWriter<$domain, W, T>;
+
+            pub use crate::engine::update::UpdateError;
+            pub use crate::engine::update::UpdateResult;
+
+            /// Updates cells of SSV content, keeping everything else as it is.
+            #[doc = generic_item_link_doc!("update::Updater")]
+            pub type Updater = super::engine::update::Updater<$domain>;
+
+            /// Compares the rows of two SSV inputs cell by cell.
+            #[doc = generic_item_link_doc!("diff::Differ")]
+            pub type Differ = super::engine::diff::Differ<$domain>;
+
+            /// The result of comparing two SSV inputs.
+            #[doc = generic_item_link_doc!("diff::Diff")]
+            pub type Diff = super::engine::diff::Diff<$domain>;
+
+            /// A difference found by a differ.
+            #[doc = generic_item_link_doc!("diff::Difference")]
+            pub type Difference = super::engine::diff::Difference<$domain>;
+
+            /// A row compared by a differ.
+            #[doc = generic_item_link_doc!("diff::DiffRow")]
+            pub type DiffRow = super::engine::diff::DiffRow<$domain>;
+
+            /// A cell that differs between matched rows.
+            #[doc = generic_item_link_doc!("diff::CellDifference")]
+            pub type CellDifference = super::engine::diff::CellDifference<$domain>;
+
+            /// Merges the changes made to the rows of a base SSV input in two other inputs.
+            #[doc = generic_item_link_doc!("merge::Merger")]
+            pub type Merger = super::engine::merge::Merger<$domain>;
+
+            pub use crate::engine::profile::ColumnType;
+            pub use crate::engine::profile::DecimalSeparator;
+            pub use crate::engine::profile::SchemaError;
+            pub use crate::engine::profile::SchemaResult;
+
+            /// Computes a profile of the columns of SSV rows.
+            #[doc = generic_item_link_doc!("profile::Profiler")]
+            pub type Profiler = super::engine::profile::Profiler<$domain>;
+
+            /// The profile of the columns of SSV rows.
+            #[doc = generic_item_link_doc!("profile::Profile")]
+            pub type Profile = super::engine::profile::Profile<$domain>;
+
+            /// The profile of a column.
+            #[doc = generic_item_link_doc!("profile::ColumnProfile")]
+            pub type ColumnProfile = super::engine::profile::ColumnProfile<$domain>;
+
+            /// The names, types and nullability of columns.
+            #[doc = generic_item_link_doc!("profile::Schema")]
+            pub type Schema = super::engine::profile::Schema<$domain>;
+
+            /// The name, type and nullability of a column.
+            #[doc = generic_item_link_doc!("profile::ColumnSchema")]
+            pub type ColumnSchema = super::engine::profile::ColumnSchema<$domain>;
+
+            /// A violation of a schema.
+            #[doc = generic_item_link_doc!("profile::Violation")]
+            pub type Violation = super::engine::profile::Violation<$domain>;
+
+            /// The kind of a violation of a schema.
+            #[doc = generic_item_link_doc!("profile::ViolationKind")]
+            pub type ViolationKind = super::engine::profile::ViolationKind<$domain>;
+
+            pub use crate::engine::render::BorderStyle;
+            pub use crate::engine::render::TableFormat;
+
+            /// Renders SSV rows as a Markdown, HTML or text table.
+            #[doc = generic_item_link_doc!("render::Renderer")]
+            pub type Renderer = super::engine::render::Renderer<$domain>;
+
+            pub use crate::engine::import::ImportError;
+            pub use crate::engine::import::ImportResult;
+
+            /// Imports the first Markdown table of a text input.
+            #[doc = generic_item_link_doc!("import::MarkdownImporter")]
+            pub type MarkdownImporter = super::engine::import::MarkdownImporter<$domain>;
+
+            /// Imports fixed-width columns of text.
+            #[doc = generic_item_link_doc!("import::FixedWidthImporter")]
+            pub type FixedWidthImporter = super::engine::import::FixedWidthImporter<$domain>;
+
+            /// Reads SSV and writes it with the values of each column aligned.
+            #[doc = generic_item_delegation_doc!("align")]
+            #[inline]
+            pub fn align<R: Read, W: Write>(reader: R, writer: W) -> ReadWriteResult<()> {
+                crate::engine::align::<$domain, R, W>(reader, writer)
+            }
+
             /// Writes SSV to a file.
             #[doc = generic_item_delegation_doc!("write_file")]
             #[inline]
@@ -217,6 +621,22 @@ macro_rules! domain_module {
                 crate::engine::write_file::<'a, $domain, P>(path, rows)
             }
 
+            /// Writes SSV to a file in the given mode.
+            #[doc = generic_item_delegation_doc!("write_file_with_mode")]
+            #[inline]
+            pub fn write_file_with_mode<'a, P: AsRef<Path>>(
+                path: P,
+                mode: WriteMode,
+                rows: impl IntoIterator<
+                    Item = impl IntoIterator<Item = &'a <$domain as Domain>::StringSlice>,
+                >,
+            ) -> WriteResult<()>
+            where
+                <$domain as Domain>::StringSlice: 'a,
+            {
+                crate::engine::write_file_with_mode::<'a, $domain, P>(path, mode, rows)
+            }
+
             /// Writes SSV to a writer.
             #[doc = generic_item_delegation_doc!("write")]
             #[inline]
@@ -235,16 +655,18 @@ macro_rules! domain_module {
     };
 }
 
+#[cfg(feature = "std")]
 domain_module!(
     chars,
     super::engine::domain::CharsDomain,
     "Types and functions to operate on `char`/`String`/`&str`."
 );
+#[cfg(feature = "std")]
 domain_module!(
     bytes,
     super::engine::domain::BytesDomain,
     "Types and functions to operate on `u8`/`Vec<u8>`/`&[u8]`."
 );
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests;
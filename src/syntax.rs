@@ -0,0 +1,123 @@
+//! The SSV syntax, independent of `std::io`.
+//!
+//! This module is available without the `std` feature, and only requires the
+//! `alloc` crate. It has:
+//!
+//! * [`BaseDomain`](domain::BaseDomain) - the element and value types.
+//! * [`Tokenizer`](tokenizer::Tokenizer) - an iterator that returns the tokens
+//!   of elements from a `&[u8]` or a `&str`, or from any other iterator.
+//! * [`SinkWriter`](writer::SinkWriter) - an object that writes values,
+//!   spacing, line-breaks and comments to a [`Sink`](writer::Sink), which is
+//!   like [`core::fmt::Write`] but for bytes.
+//!
+#![cfg_attr(
+    feature = "std",
+    doc = "With the `std` feature, every [`Domain`](crate::engine::domain::Domain) implements"
+)]
+#![cfg_attr(
+    feature = "std",
+    doc = "[`BaseDomain`](domain::BaseDomain), and the [`engine`](crate::engine) module"
+)]
+#![cfg_attr(
+    feature = "std",
+    doc = "implements its tokenizer and writers on top of these."
+)]
+//!
+//! # Example
+//!
+//! ```
+//! use ssv::syntax::domain::CharsDomain;
+//! use ssv::syntax::tokenizer::{Token, Tokenizer};
+//! use ssv::syntax::writer::{FmtSink, SinkWriter};
+//!
+//! let mut output = String::new();
+//! let mut writer = SinkWriter::<CharsDomain, _>::new(FmtSink(&mut output));
+//!
+//! for result in Tokenizer::from_text("a  \"b c\"\n") {
+//!     match result?.value {
+//!         Token::UnquotedValue(value) | Token::QuotedValue(value) => {
+//!             writer.write_value(&value.to_uppercase())?;
+//!         }
+//!         Token::LineBreak(line_break) => writer.write_this_line_break(line_break)?,
+//!         _ => {}
+//!     }
+//! }
+//!
+//! assert_eq!(output, "A \"B C\"\n");
+//! # Ok::<_, Box<dyn core::error::Error>>(())
+//! ```
+
+use core::fmt::Display;
+
+pub mod domain;
+pub(crate) mod numeric;
+pub mod tokenizer;
+pub mod writer;
+
+/// Line-break types.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LineBreak {
+    /// Line-break consisting only of LF (byte value/codepoint 10).
+    Lf,
+
+    /// Line-break consisting of CR (byte value/codepoint 13) and LF (byte value/codepoint 10).
+    CrLf,
+}
+
+/// The position of a token.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Position {
+    /// The line number where the token is found, starting from 1.
+    pub line_number: usize,
+
+    /// The column number where the token is found, starting from 1.
+    ///
+    /// Columns may be counted in bytes or chars, depending on the domain.
+    pub column_number: usize,
+}
+
+/// A value associated to a [`Position`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct WithPosition<T> {
+    /// The value.
+    pub value: T,
+
+    /// The [`Position`].
+    pub position: Position,
+}
+
+/// The error type for invalid SSV syntax.
+///
+/// It is returned by the [`Tokenizer`](tokenizer::Tokenizer) of this module.
+#[cfg_attr(
+    feature = "std",
+    doc = "It is converted to a [`ReadError`](crate::engine::ReadError) by the readers of the"
+)]
+#[cfg_attr(feature = "std", doc = "[`engine`](crate::engine) module.")]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SyntaxError {
+    /// A quote in a value was not duplicated (see the [rules](crate#rules)).
+    UnpairedQuote(Position),
+
+    /// The input ended before reaching the closing quote of a quoted value.
+    UnclosedQuotedValue(Position),
+}
+
+impl core::error::Error for SyntaxError {}
+
+impl Display for SyntaxError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SyntaxError::UnpairedQuote(position) => write!(
+                f,
+                "unpaired quote at {}:{}",
+                position.line_number, position.column_number
+            ),
+            SyntaxError::UnclosedQuotedValue(position) => write!(
+                f,
+                "unclosed quoted value {}:{}",
+                position.line_number, position.column_number
+            ),
+        }
+    }
+}
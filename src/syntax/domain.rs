@@ -0,0 +1,235 @@
+//! The [`BaseDomain`] trait, which defines the element and value types of the
+//! SSV syntax.
+//!
+#![cfg_attr(
+    feature = "std",
+    doc = "With the `std` feature, it is implemented for every"
+)]
+#![cfg_attr(
+    feature = "std",
+    doc = "[`Domain`](crate::engine::domain::Domain), which also has the conversions from byte"
+)]
+#![cfg_attr(
+    feature = "std",
+    doc = "readers. See the [`domain`](crate::engine::domain) module of the engine for how to"
+)]
+#![cfg_attr(feature = "std", doc = "implement it.")]
+
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Debug;
+use core::ops::Deref;
+
+/// The element and value types of the SSV syntax.
+pub trait BaseDomain: Eq + Clone + Debug + 'static {
+    /// The type of the elements of the values, such as a byte or a character.
+    type Element: Copy + Eq + Debug;
+
+    /// The owned value type.
+    type String: DomainString<Self::Element>
+        + Deref<Target = Self::StringSlice>
+        + for<'a> From<&'a Self::StringSlice>;
+
+    /// The borrowed value type.
+    type StringSlice: DomainStringSlice<Self::Element> + ToOwned<Owned: Debug> + Eq + Debug + ?Sized;
+
+    /// The LF element (byte value/codepoint 10).
+    const LF: Self::Element;
+
+    /// The CR element (byte value/codepoint 13).
+    const CR: Self::Element;
+
+    /// The QUOTE element (`"`).
+    const QUOTE: Self::Element;
+
+    /// The SPACE element (byte value/codepoint 32).
+    const SPACE: Self::Element;
+
+    /// The HASH element (`#`).
+    const HASH: Self::Element;
+
+    /// Returns whether the element is a SPACE or a TAB.
+    fn is_spacing_element(element: Self::Element) -> bool;
+
+    /// Returns whether the value is not empty and contains only spacing
+    /// elements.
+    fn is_valid_spacing(spacing: &Self::StringSlice) -> bool;
+
+    /// Returns the concatenation of the values.
+    fn concat(parts: &[&Self::StringSlice]) -> Self::String;
+}
+
+/// The operations on the owned value type of a domain whose elements are of
+/// type `E`.
+pub trait DomainString<E>: Sized + Clone + Eq + Debug {
+    /// Creates an empty value.
+    fn new() -> Self;
+
+    /// Appends an element to the end of the value.
+    fn push(&mut self, element: E);
+
+    /// Creates a value consisting of the given number of QUOTE elements.
+    fn quotes(length: usize) -> Self;
+
+    /// Creates a value from a text, such as a column name or a number.
+    fn from_text(text: &str) -> Self;
+
+    /// Creates a value consisting of a single element.
+    fn from_element(element: E) -> Self {
+        let mut string = Self::new();
+        string.push(element);
+        string
+    }
+}
+
+/// The operations on the borrowed value type of a domain whose elements are of
+/// type `E`.
+pub trait DomainStringSlice<E> {
+    /// Returns the encoding of the value as written to a byte writer.
+    fn as_bytes(&self) -> &[u8];
+
+    /// Returns the elements of the value.
+    fn elements(&self) -> Vec<E>;
+}
+
+/// The domain whose elements are bytes, and whose values are [`Vec<u8>`] and
+/// `[u8]`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BytesDomain;
+
+/// The items of the [`BaseDomain`] and
+/// [`Domain`](crate::engine::domain::Domain) implementations for
+/// [`BytesDomain`].
+macro_rules! bytes_domain_items {
+    ($trait:ident) => {
+        type Element = u8;
+        type String = Vec<u8>;
+        type StringSlice = [u8];
+
+        const LF: Self::Element = b'\n';
+        const CR: Self::Element = b'\r';
+        const QUOTE: Self::Element = b'"';
+        const SPACE: Self::Element = b' ';
+        const HASH: Self::Element = b'#';
+
+        fn is_spacing_element(element: Self::Element) -> bool {
+            matches!(element, b' ' | b'\t')
+        }
+
+        fn is_valid_spacing(spacing: &Self::StringSlice) -> bool {
+            !spacing.is_empty()
+                && spacing
+                    .iter()
+                    .all(|byte| <Self as $trait>::is_spacing_element(*byte))
+        }
+
+        fn concat(parts: &[&Self::StringSlice]) -> Self::String {
+            parts.concat()
+        }
+    };
+}
+#[cfg(feature = "std")]
+pub(crate) use bytes_domain_items;
+
+#[cfg(not(feature = "std"))]
+impl BaseDomain for BytesDomain {
+    bytes_domain_items!(BaseDomain);
+}
+
+impl DomainString<u8> for Vec<u8> {
+    fn new() -> Self {
+        Vec::new()
+    }
+
+    fn push(&mut self, element: u8) {
+        Vec::push(self, element);
+    }
+
+    fn quotes(length: usize) -> Self {
+        [BytesDomain::QUOTE].repeat(length)
+    }
+
+    fn from_text(text: &str) -> Self {
+        text.as_bytes().to_vec()
+    }
+}
+
+impl DomainStringSlice<u8> for [u8] {
+    fn as_bytes(&self) -> &[u8] {
+        self
+    }
+
+    fn elements(&self) -> Vec<u8> {
+        self.to_vec()
+    }
+}
+
+/// The domain whose elements are characters, and whose values are [`String`]
+/// and [`str`], read and written as UTF-8.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CharsDomain;
+
+/// The items of the [`BaseDomain`] and
+/// [`Domain`](crate::engine::domain::Domain) implementations for
+/// [`CharsDomain`].
+macro_rules! chars_domain_items {
+    ($trait:ident) => {
+        type Element = char;
+        type String = String;
+        type StringSlice = str;
+
+        const LF: Self::Element = '\n';
+        const CR: Self::Element = '\r';
+        const QUOTE: Self::Element = '"';
+        const SPACE: Self::Element = ' ';
+        const HASH: Self::Element = '#';
+
+        fn is_spacing_element(element: Self::Element) -> bool {
+            matches!(element, ' ' | '\t')
+        }
+
+        fn is_valid_spacing(spacing: &Self::StringSlice) -> bool {
+            !spacing.is_empty() && spacing.chars().all(<Self as $trait>::is_spacing_element)
+        }
+
+        fn concat(parts: &[&Self::StringSlice]) -> Self::String {
+            parts.concat()
+        }
+    };
+}
+#[cfg(feature = "std")]
+pub(crate) use chars_domain_items;
+
+#[cfg(not(feature = "std"))]
+impl BaseDomain for CharsDomain {
+    chars_domain_items!(BaseDomain);
+}
+
+impl DomainString<char> for String {
+    fn new() -> Self {
+        String::new()
+    }
+
+    fn push(&mut self, element: char) {
+        String::push(self, element);
+    }
+
+    fn quotes(length: usize) -> Self {
+        Self::from_element(CharsDomain::QUOTE).repeat(length)
+    }
+
+    fn from_text(text: &str) -> Self {
+        text.to_owned()
+    }
+}
+
+impl DomainStringSlice<char> for str {
+    fn as_bytes(&self) -> &[u8] {
+        self.as_bytes()
+    }
+
+    fn elements(&self) -> Vec<char> {
+        self.chars().collect()
+    }
+}
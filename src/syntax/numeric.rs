@@ -0,0 +1,86 @@
+//! Recognizes the numbers used by [`QuotingPolicy::NonNumeric`](super::writer::QuotingPolicy::NonNumeric)
+//! and by the numeric comparisons of the [`engine`](crate::engine) operations.
+//!
+//! A number is an optional sign (`+` or `-`) followed by digits with an
+//! optional decimal separator (`.` or `,`) and an optional exponent (e.g. `e-3`).
+
+/// Returns whether the value is a number.
+pub(crate) fn is_numeric(bytes: &[u8]) -> bool {
+    let mut bytes = bytes
+        .strip_prefix(b"+")
+        .or(bytes.strip_prefix(b"-"))
+        .unwrap_or(bytes);
+
+    fn take_digits(bytes: &mut &[u8]) -> usize {
+        let count = bytes
+            .iter()
+            .take_while(|byte| byte.is_ascii_digit())
+            .count();
+        *bytes = &bytes[count..];
+        count
+    }
+
+    let mut digits = 0;
+
+    digits += take_digits(&mut bytes);
+    if let Some(rest) = bytes.strip_prefix(b".").or(bytes.strip_prefix(b",")) {
+        bytes = rest;
+        digits += take_digits(&mut bytes);
+    }
+    if digits == 0 {
+        return false;
+    }
+
+    if let Some(rest) = bytes.strip_prefix(b"e").or(bytes.strip_prefix(b"E")) {
+        bytes = rest
+            .strip_prefix(b"+")
+            .or(rest.strip_prefix(b"-"))
+            .unwrap_or(rest);
+        if take_digits(&mut bytes) == 0 {
+            return false;
+        }
+    }
+
+    bytes.is_empty()
+}
+
+/// Returns the value of a number, or `None` if the value is not a
+/// [number](is_numeric).
+#[cfg(feature = "std")]
+pub(crate) fn parse_numeric(bytes: &[u8]) -> Option<f64> {
+    if is_numeric(bytes) {
+        let text = alloc::string::String::from_utf8_lossy(bytes).replace(',', ".");
+        text.parse().ok()
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_numeric;
+    #[cfg(feature = "std")]
+    use super::parse_numeric;
+
+    #[test]
+    fn numbers() {
+        for value in [
+            "0", "123", "-4", "+5", "1.5", "1,5", ".5", "5.", "-1,5e3", "2E+10",
+        ] {
+            assert!(is_numeric(value.as_bytes()), "{value}");
+        }
+        for value in [
+            "", "-", ".", "1.2.3", "1e", "e3", "abc", "12a", "1 2", "0x10",
+        ] {
+            assert!(!is_numeric(value.as_bytes()), "{value}");
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn parse() {
+        assert_eq!(parse_numeric(b"-1,5e3"), Some(-1500.0));
+        assert_eq!(parse_numeric(b"5."), Some(5.0));
+        assert_eq!(parse_numeric(b"0x10"), None);
+    }
+}
@@ -0,0 +1,408 @@
+//! Reads SSV token-by-token from elements.
+
+use core::iter::{Copied, FusedIterator, Peekable};
+use core::slice::Iter;
+use core::str::Chars;
+
+use super::domain::{BaseDomain, BytesDomain, CharsDomain, DomainString};
+use super::{LineBreak, Position, SyntaxError, WithPosition};
+
+/// An SSV token.
+#[cfg_attr(feature = "std", doc = generic_item_warning_doc!("Token"))]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Token<D: BaseDomain> {
+    /// A value that is not enclosed in quotes.
+    UnquotedValue(D::String),
+
+    /// A value that is enclosed in quotes.
+    QuotedValue(D::String),
+
+    /// Spacing.
+    Spacing(D::String),
+
+    /// Line-break.
+    LineBreak(LineBreak),
+
+    /// Comment, without the HASH sign (`#`).
+    Comment(D::String),
+}
+
+/// Reads SSV tokens from an iterator of elements.
+///
+/// It is an iterator of
+/// `Result<`[`WithPosition`]`<`[`Token`]`<D>>, `[`SyntaxError`]`>` values. The
+/// [`from_bytes`](Tokenizer::from_bytes) and
+/// [`from_text`](Tokenizer::from_text) functions create instances that read from
+/// a `&[u8]` and from a `&str`.
+///
+/// # Example
+///
+/// ```
+/// use ssv::syntax::tokenizer::{Token, Tokenizer};
+///
+/// let mut tokenizer = Tokenizer::from_bytes(b"value");
+///
+/// let token = tokenizer.next().unwrap()?;
+/// assert_eq!(token.value, Token::UnquotedValue(b"value".to_vec()));
+/// assert!(tokenizer.next().is_none());
+/// # Ok::<_, ssv::syntax::SyntaxError>(())
+/// ```
+pub struct Tokenizer<D: BaseDomain, I: Iterator<Item = D::Element>> {
+    elements: Peekable<I>,
+    machine: Machine<D>,
+}
+
+impl<D: BaseDomain, I: Iterator<Item = D::Element>> Tokenizer<D, I> {
+    /// Creates an instance that reads SSV from the given elements.
+    pub fn new(elements: I) -> Self {
+        Tokenizer {
+            elements: elements.peekable(),
+            machine: Machine::new(),
+        }
+    }
+}
+
+impl<'a> Tokenizer<BytesDomain, Copied<Iter<'a, u8>>> {
+    /// Creates an instance that reads SSV from the given bytes.
+    pub fn from_bytes(input: &'a [u8]) -> Self {
+        Tokenizer::new(input.iter().copied())
+    }
+}
+
+impl<'a> Tokenizer<CharsDomain, Chars<'a>> {
+    /// Creates an instance that reads SSV from the given text.
+    pub fn from_text(input: &'a str) -> Self {
+        Tokenizer::new(input.chars())
+    }
+}
+
+impl<D: BaseDomain, I: Iterator<Item = D::Element>> Iterator for Tokenizer<D, I> {
+    type Item = Result<WithPosition<Token<D>>, SyntaxError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.machine.is_finished() {
+            return None;
+        }
+
+        while let Some(element) = self.elements.next() {
+            let elements = &mut self.elements;
+            let next_element_is_lf = || elements.peek() == Some(&D::LF);
+            if let Some(result) = self.machine.process(element, next_element_is_lf) {
+                return Some(result);
+            }
+        }
+
+        self.machine.finish()
+    }
+}
+
+impl<D: BaseDomain, I: Iterator<Item = D::Element>> FusedIterator for Tokenizer<D, I> {}
+
+/// The state machine of the tokenizers, which is given one element at a time.
+pub(crate) struct Machine<D: BaseDomain> {
+    state: Option<State<D>>,
+    position: Position,
+    current_token_position: Position,
+}
+
+impl<D: BaseDomain> Machine<D> {
+    pub(crate) fn new() -> Self {
+        Machine {
+            state: Some(State::Begin),
+            position: Position {
+                line_number: 1,
+                column_number: 0,
+            },
+            current_token_position: Position {
+                line_number: 0,
+                column_number: 0,
+            },
+        }
+    }
+
+    /// Returns whether no more tokens are returned, either because the end of
+    /// the elements was processed or because an error happened.
+    pub(crate) fn is_finished(&self) -> bool {
+        self.state.is_none()
+    }
+
+    /// Stops processing after an error that happened outside of the machine.
+    #[cfg(feature = "std")]
+    pub(crate) fn fail(&mut self) {
+        self.state = None;
+    }
+
+    /// Processes the next element, returning a token if one is complete.
+    ///
+    /// The `next_element_is_lf` function is called only when it is required to
+    /// decide if a CR element is part of a line-break.
+    pub(crate) fn process(
+        &mut self,
+        element: D::Element,
+        next_element_is_lf: impl FnOnce() -> bool,
+    ) -> Option<Result<WithPosition<Token<D>>, SyntaxError>> {
+        let state = self.state.take()?;
+
+        self.position.column_number += 1;
+        if state == State::Begin {
+            self.current_token_position = self.position;
+        }
+
+        match self.transition(element, state, next_element_is_lf) {
+            Ok((next_state, token)) => {
+                let token = self.include_current_token_position(token);
+
+                if token.is_some() && next_state != State::Begin {
+                    self.current_token_position = self.position;
+                }
+
+                if element == D::LF {
+                    self.position.line_number += 1;
+                    self.position.column_number = 0;
+                }
+
+                self.state = Some(next_state);
+                token
+            }
+            Err(error) => Some(Err(error)),
+        }
+    }
+
+    /// Processes the end of the elements, returning the last token.
+    pub(crate) fn finish(&mut self) -> Option<Result<WithPosition<Token<D>>, SyntaxError>> {
+        let state = self.state.take()?;
+        match self.last_token(state) {
+            Ok(token) => self.include_current_token_position(token),
+            Err(error) => Some(Err(error)),
+        }
+    }
+
+    fn transition(
+        &mut self,
+        element: D::Element,
+        state: State<D>,
+        next_element_is_lf: impl FnOnce() -> bool,
+    ) -> Result<(State<D>, Option<Token<D>>), SyntaxError> {
+        let is_cr_in_line_break = |element: D::Element| element == D::CR && next_element_is_lf();
+
+        let next_state = match state {
+            State::Begin => {
+                if D::is_spacing_element(element) {
+                    State::Spacing(D::String::from_element(element))
+                } else if element == D::QUOTE {
+                    State::QuotesPrefix(1)
+                } else if element == D::LF {
+                    return Ok((state, Some(Token::LineBreak(LineBreak::Lf))));
+                } else if is_cr_in_line_break(element) {
+                    State::CrInLineBreak
+                } else if element == D::HASH {
+                    State::Comment(D::String::new())
+                } else {
+                    let value = D::String::from_element(element);
+                    State::UnquotedValue(value)
+                }
+            }
+            State::UnquotedValue(mut value) => {
+                if element == D::QUOTE {
+                    State::QuoteInUnquotedValue(value)
+                } else {
+                    let next_state = if D::is_spacing_element(element) {
+                        Some(State::Spacing(D::String::from_element(element)))
+                    } else if element == D::LF {
+                        Some(State::LfLineBreak)
+                    } else if is_cr_in_line_break(element) {
+                        Some(State::CrInLineBreak)
+                    } else {
+                        None
+                    };
+
+                    if let Some(next_state) = next_state {
+                        return Ok((next_state, Some(Token::UnquotedValue(value))));
+                    } else {
+                        value.push(element);
+                        State::UnquotedValue(value)
+                    }
+                }
+            }
+            State::QuoteInUnquotedValue(mut value) => {
+                if element == D::QUOTE {
+                    value.push(element);
+                    State::UnquotedValue(value)
+                } else {
+                    let mut position = self.position;
+                    position.column_number -= 1;
+                    return Err(SyntaxError::UnpairedQuote(position));
+                }
+            }
+            State::QuotesPrefix(count) => {
+                if element == D::QUOTE {
+                    State::QuotesPrefix(count + 1)
+                } else if count % 2 == 0 {
+                    let next_state_after_quoted_value = if D::is_spacing_element(element) {
+                        Some(State::Spacing(D::String::from_element(element)))
+                    } else if element == D::LF {
+                        Some(State::LfLineBreak)
+                    } else if is_cr_in_line_break(element) {
+                        Some(State::CrInLineBreak)
+                    } else {
+                        None
+                    };
+
+                    if let Some(next_state) = next_state_after_quoted_value {
+                        let value = D::String::quotes((count - 2) / 2);
+                        return Ok((next_state, Some(Token::QuotedValue(value))));
+                    } else {
+                        let mut value = D::String::quotes(count / 2);
+                        value.push(element);
+                        State::UnquotedValue(value)
+                    }
+                } else {
+                    let mut value = D::String::quotes((count - 1) / 2);
+                    value.push(element);
+                    State::QuotedValue(value)
+                }
+            }
+            State::QuotedValue(mut value) => {
+                if element == D::QUOTE {
+                    State::QuoteInQuotedValue(value)
+                } else {
+                    value.push(element);
+                    State::QuotedValue(value)
+                }
+            }
+            State::QuoteInQuotedValue(mut value) => {
+                if element == D::QUOTE {
+                    value.push(element);
+                    State::QuotedValue(value)
+                } else {
+                    let next_state_after_quoted_value = if D::is_spacing_element(element) {
+                        Some(State::Spacing(D::String::from_element(element)))
+                    } else if element == D::LF {
+                        Some(State::LfLineBreak)
+                    } else if is_cr_in_line_break(element) {
+                        Some(State::CrInLineBreak)
+                    } else {
+                        None
+                    };
+
+                    if let Some(next_state) = next_state_after_quoted_value {
+                        return Ok((next_state, Some(Token::QuotedValue(value))));
+                    } else {
+                        let mut position = self.position;
+                        position.column_number -= 1;
+                        return Err(SyntaxError::UnpairedQuote(position));
+                    }
+                }
+            }
+            State::Spacing(mut spacing) => {
+                if D::is_spacing_element(element) {
+                    spacing.push(element);
+                    State::Spacing(spacing)
+                } else {
+                    let next_state = if element == D::QUOTE {
+                        State::QuotesPrefix(1)
+                    } else if element == D::LF {
+                        State::LfLineBreak
+                    } else if is_cr_in_line_break(element) {
+                        State::CrInLineBreak
+                    } else {
+                        State::UnquotedValue(D::String::from_element(element))
+                    };
+                    return Ok((next_state, Some(Token::Spacing(spacing))));
+                }
+            }
+            State::LfLineBreak => {
+                let next_state = if D::is_spacing_element(element) {
+                    State::Spacing(D::String::from_element(element))
+                } else if element == D::QUOTE {
+                    State::QuotesPrefix(1)
+                } else if element == D::LF {
+                    State::LfLineBreak
+                } else if is_cr_in_line_break(element) {
+                    State::CrInLineBreak
+                } else if element == D::HASH {
+                    State::Comment(D::String::new())
+                } else {
+                    State::UnquotedValue(D::String::from_element(element))
+                };
+                return Ok((next_state, Some(Token::LineBreak(LineBreak::Lf))));
+            }
+            State::CrInLineBreak => {
+                assert_eq!(element, D::LF);
+                return Ok((State::Begin, Some(Token::LineBreak(LineBreak::CrLf))));
+            }
+            State::Comment(mut comment) => {
+                let next_line_break_state = if element == D::LF {
+                    Some(State::LfLineBreak)
+                } else if is_cr_in_line_break(element) {
+                    Some(State::CrInLineBreak)
+                } else {
+                    None
+                };
+
+                if let Some(next_state) = next_line_break_state {
+                    return Ok((next_state, Some(Token::Comment(comment))));
+                } else {
+                    comment.push(element);
+                    State::Comment(comment)
+                }
+            }
+        };
+
+        Ok((next_state, None))
+    }
+
+    fn last_token(&self, state: State<D>) -> Result<Option<Token<D>>, SyntaxError> {
+        match state {
+            State::Begin => Ok(None),
+            State::UnquotedValue(value) => Ok(Some(Token::UnquotedValue(value))),
+            State::QuoteInUnquotedValue(_) => Err(SyntaxError::UnpairedQuote(self.position)),
+            State::QuotesPrefix(count) => {
+                if count % 2 == 0 {
+                    let value = D::String::quotes((count - 2) / 2);
+                    Ok(Some(Token::QuotedValue(value)))
+                } else {
+                    let mut position = self.position;
+                    position.column_number += 1;
+                    Err(SyntaxError::UnclosedQuotedValue(position))
+                }
+            }
+            State::QuotedValue(_) => {
+                let mut position = self.position;
+                position.column_number += 1;
+                Err(SyntaxError::UnclosedQuotedValue(position))
+            }
+            State::QuoteInQuotedValue(value) => Ok(Some(Token::QuotedValue(value))),
+            State::Spacing(spacing) => Ok(Some(Token::Spacing(spacing))),
+            State::LfLineBreak => Ok(Some(Token::LineBreak(LineBreak::Lf))),
+            State::CrInLineBreak => unreachable!(),
+            State::Comment(comment) => Ok(Some(Token::Comment(comment))),
+        }
+    }
+
+    fn include_current_token_position(
+        &self,
+        token: Option<Token<D>>,
+    ) -> Option<Result<WithPosition<Token<D>>, SyntaxError>> {
+        token.map(|token| {
+            Ok(WithPosition {
+                value: token,
+                position: self.current_token_position,
+            })
+        })
+    }
+}
+
+#[derive(PartialEq, Eq)]
+enum State<D: BaseDomain> {
+    Begin,
+    UnquotedValue(D::String),
+    QuoteInUnquotedValue(D::String),
+    QuotesPrefix(usize),
+    QuotedValue(D::String),
+    QuoteInQuotedValue(D::String),
+    Spacing(D::String),
+    LfLineBreak,
+    CrInLineBreak,
+    Comment(D::String),
+}
@@ -0,0 +1,558 @@
+//! Writes SSV to a byte sink. Automatically inserts delimiters.
+
+use alloc::vec::Vec;
+use core::convert::Infallible;
+use core::fmt::{Debug, Display};
+use core::ops::Deref;
+
+use super::domain::{BaseDomain, BytesDomain, DomainString, DomainStringSlice};
+use super::numeric::is_numeric;
+use super::LineBreak;
+
+/// Defines when values are enclosed in quotes.
+///
+/// The policy is applied by the methods that do not explicitly ask for quotes.
+#[cfg_attr(
+    feature = "std",
+    doc = "E.g.: [`FluentWriter::write_value`](crate::engine::fluent_writer::FluentWriter::write_value)"
+)]
+#[cfg_attr(
+    feature = "std",
+    doc = "and the [`Writer`](crate::engine::writer::Writer) methods."
+)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum QuotingPolicy {
+    /// Values are enclosed in quotes only if required (check the [rules](crate#rules)).
+    #[default]
+    Minimal,
+
+    /// Values are always enclosed in quotes.
+    Always,
+
+    /// Values are enclosed in quotes if required or if they are not numbers.
+    ///
+    /// A number is an optional sign (`+` or `-`) followed by digits with an
+    /// optional decimal separator (`.` or `,`) and an optional exponent
+    /// (e.g. `e-3`).
+    NonNumeric,
+
+    /// Values are never enclosed in quotes. Writing a value that requires
+    /// quotes fails.
+    #[cfg_attr(
+        feature = "std",
+        doc = "The writers of the engine fail with [`WriteError::QuotesRequired`](crate::engine::WriteError::QuotesRequired)."
+    )]
+    Never,
+}
+
+/// Defines how comments containing line-breaks are handled.
+///
+/// A line-break inside a comment would end the comment line early, and the
+/// rest of the comment would be read as values. A CR at the end of a comment
+/// is also a line-break if an LF line-break is written after the comment,
+/// because they would form a CRLF.
+///
+/// Note that the default policy, [`Reject`](CommentLineBreakPolicy::Reject),
+/// changes the behavior of the writers, which used to write such comments
+/// verbatim. Content that was written without errors before may now fail.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum CommentLineBreakPolicy {
+    /// Writing the comment fails.
+    #[cfg_attr(
+        feature = "std",
+        doc = "The writers of the engine fail with [`WriteError::CommentWithLineBreak`](crate::engine::WriteError::CommentWithLineBreak)."
+    )]
+    #[default]
+    Reject,
+
+    /// The comment is split at the line-breaks, and each part is written as a
+    /// separate comment line.
+    Split,
+}
+
+/// A destination of bytes, like [`core::fmt::Write`] is a destination of text.
+pub trait Sink {
+    /// The error type of the [`write_bytes`](Sink::write_bytes) method.
+    type Error;
+
+    /// Writes all the bytes.
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Self::Error>;
+}
+
+impl Sink for Vec<u8> {
+    type Error = Infallible;
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.extend_from_slice(bytes);
+        Ok(())
+    }
+}
+
+impl<S: Sink + ?Sized> Sink for &mut S {
+    type Error = S::Error;
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        (**self).write_bytes(bytes)
+    }
+}
+
+/// A [`Sink`] that writes to a [`core::fmt::Write`] object, such as an
+/// [`alloc::string::String`] or a [`Formatter`](core::fmt::Formatter).
+///
+/// Bytes that are not valid UTF-8 fail with [`core::fmt::Error`].
+#[derive(Debug)]
+pub struct FmtSink<W: core::fmt::Write>(pub W);
+
+impl<W: core::fmt::Write> Sink for FmtSink<W> {
+    type Error = core::fmt::Error;
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        let text = core::str::from_utf8(bytes).map_err(|_| core::fmt::Error)?;
+        self.0.write_str(text)
+    }
+}
+
+/// The error type for the [`SinkWriter`] operations.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SinkWriteError<E> {
+    /// A value containing non-spacing elements was tried to be used as spacing.
+    InvalidSpacing,
+
+    /// A value requiring quotes was tried to be written with the
+    /// [`QuotingPolicy::Never`] policy.
+    QuotesRequired,
+
+    /// A comment containing a line-break was tried to be written with the
+    /// [`CommentLineBreakPolicy::Reject`] policy.
+    CommentWithLineBreak,
+
+    /// An error happened when using the underlying [`Sink`].
+    SinkError(E),
+}
+
+impl<E: Debug + Display> core::error::Error for SinkWriteError<E> {}
+
+impl<E: Display> Display for SinkWriteError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SinkWriteError::InvalidSpacing => write!(f, "invalid spacing"),
+            SinkWriteError::QuotesRequired => write!(f, "value requires quotes"),
+            SinkWriteError::CommentWithLineBreak => write!(f, "comment contains a line-break"),
+            SinkWriteError::SinkError(error) => write!(f, "sink error: {error}"),
+        }
+    }
+}
+
+/// Has an interface to write SSV to a [`Sink`].
+///
+/// Spacing and line-breaks are automatically written as required.
+#[cfg_attr(
+    feature = "std",
+    doc = "The [`FluentWriter`](crate::engine::fluent_writer::FluentWriter) does the same for a"
+)]
+#[cfg_attr(feature = "std", doc = "byte writer.")]
+///
+/// # Example
+///
+/// ```
+/// use ssv::syntax::domain::BytesDomain;
+/// use ssv::syntax::writer::SinkWriter;
+///
+/// let mut writer = SinkWriter::<BytesDomain, _>::new(Vec::new());
+///
+/// writer.write_value(b"value")?;
+/// writer.write_value(b"another value")?; // automatic spacing
+/// writer.write_line_break()?;
+/// writer.write_comment(b" comment")?;
+///
+/// assert_eq!(writer.into_inner(), b"value \"another value\"\n# comment");
+/// # Ok::<_, ssv::syntax::writer::SinkWriteError<core::convert::Infallible>>(())
+/// ```
+#[derive(Debug)]
+pub struct SinkWriter<D: BaseDomain, S: Sink> {
+    sink: S,
+    layout: Layout,
+    default_spacing: D::String,
+    default_line_break: LineBreak,
+    quoting_policy: QuotingPolicy,
+    comment_line_break_policy: CommentLineBreakPolicy,
+}
+
+impl<D: BaseDomain, S: Sink> SinkWriter<D, S> {
+    /// Creates an instance that writes SSV to the given sink.
+    pub fn new(sink: S) -> Self {
+        SinkWriter {
+            sink,
+            layout: Layout::new(),
+            default_spacing: D::String::from_element(D::SPACE),
+            default_line_break: LineBreak::Lf,
+            quoting_policy: QuotingPolicy::Minimal,
+            comment_line_break_policy: CommentLineBreakPolicy::Reject,
+        }
+    }
+
+    /// Writes a value.
+    ///
+    /// The value is enclosed in quotes according to the
+    /// [quoting policy](SinkWriter::set_quoting_policy). With the
+    /// [`QuotingPolicy::Never`] policy, writing a value that requires quotes
+    /// (check the [rules](crate#rules)) fails with
+    /// [`SinkWriteError::QuotesRequired`].
+    ///
+    /// If the last written item was another value, then the
+    /// [default spacing](SinkWriter::set_default_spacing) is automatically
+    /// written before this value. If it was a comment, then the
+    /// [default line-break](SinkWriter::set_default_line_break) is
+    /// automatically written before this value.
+    pub fn write_value(&mut self, value: &D::StringSlice) -> Result<(), SinkWriteError<S::Error>> {
+        self.write_value_raw(value, false)
+    }
+
+    /// Writes a value enclosed in quotes.
+    ///
+    /// Spacing and line-breaks are automatically written before it like in
+    /// [`write_value`](SinkWriter::write_value).
+    pub fn write_quoted_value(
+        &mut self,
+        value: &D::StringSlice,
+    ) -> Result<(), SinkWriteError<S::Error>> {
+        self.write_value_raw(value, true)
+    }
+
+    fn write_value_raw(
+        &mut self,
+        value: &D::StringSlice,
+        quoted: bool,
+    ) -> Result<(), SinkWriteError<S::Error>> {
+        self.layout.write_value(
+            &mut self.sink,
+            value.as_bytes(),
+            quoted,
+            self.quoting_policy,
+            self.default_spacing.as_bytes(),
+            self.default_line_break,
+        )
+    }
+
+    /// Writes the specified spacing.
+    ///
+    /// If the last written item was a comment, then the
+    /// [default line-break](SinkWriter::set_default_line_break) is automatically
+    /// written before the spacing.
+    pub fn write_spacing(
+        &mut self,
+        spacing: &D::StringSlice,
+    ) -> Result<(), SinkWriteError<S::Error>> {
+        if !D::is_valid_spacing(spacing) {
+            return Err(SinkWriteError::InvalidSpacing);
+        }
+
+        self.layout
+            .write_spacing(&mut self.sink, spacing.as_bytes(), self.default_line_break)
+    }
+
+    /// Writes the [default line-break](SinkWriter::set_default_line_break).
+    pub fn write_line_break(&mut self) -> Result<(), SinkWriteError<S::Error>> {
+        self.write_this_line_break(self.default_line_break)
+    }
+
+    /// Writes the specified line-break.
+    pub fn write_this_line_break(
+        &mut self,
+        line_break: LineBreak,
+    ) -> Result<(), SinkWriteError<S::Error>> {
+        self.layout.write_line_break(&mut self.sink, line_break)
+    }
+
+    /// Writes the comment.
+    ///
+    /// The HASH sign (`#`) is written before the comment. If the last written
+    /// item was a value, spacing, or another comment, then the
+    /// [default line-break](SinkWriter::set_default_line_break) is
+    /// automatically written before it.
+    ///
+    /// If the comment contains line-breaks, it is handled according to the
+    /// [comment line-break policy](SinkWriter::set_comment_line_break_policy).
+    pub fn write_comment(
+        &mut self,
+        comment: &D::StringSlice,
+    ) -> Result<(), SinkWriteError<S::Error>> {
+        self.layout.write_comment(
+            &mut self.sink,
+            comment.as_bytes(),
+            self.comment_line_break_policy,
+            self.default_line_break,
+        )
+    }
+
+    /// Sets the spacing written between values, which is a single SPACE by
+    /// default.
+    pub fn set_default_spacing(
+        &mut self,
+        spacing: D::String,
+    ) -> Result<(), SinkWriteError<S::Error>> {
+        if !D::is_valid_spacing(spacing.deref()) {
+            return Err(SinkWriteError::InvalidSpacing);
+        }
+
+        self.default_spacing = spacing;
+        Ok(())
+    }
+
+    /// Sets the line-break written automatically and by
+    /// [`write_line_break`](SinkWriter::write_line_break), which is LF by
+    /// default.
+    pub fn set_default_line_break(&mut self, line_break: LineBreak) {
+        self.default_line_break = line_break;
+    }
+
+    /// Sets the policy that defines when values are enclosed in quotes.
+    pub fn set_quoting_policy(&mut self, quoting_policy: QuotingPolicy) {
+        self.quoting_policy = quoting_policy;
+    }
+
+    /// Sets how comments containing line-breaks are handled.
+    pub fn set_comment_line_break_policy(&mut self, policy: CommentLineBreakPolicy) {
+        self.comment_line_break_policy = policy;
+    }
+
+    /// Returns a reference to the underlying sink.
+    pub fn get_ref(&self) -> &S {
+        &self.sink
+    }
+
+    /// Returns the underlying sink.
+    pub fn into_inner(self) -> S {
+        self.sink
+    }
+}
+
+/// Keeps track of the last written item to automatically insert delimiters.
+#[derive(Debug)]
+pub(crate) struct Layout {
+    state: State,
+    column_index: usize,
+    /// The policy of the last written comment, if it ends with a CR, which
+    /// forms a CRLF if an LF line-break is written after it.
+    trailing_cr: Option<CommentLineBreakPolicy>,
+}
+
+impl Layout {
+    pub(crate) fn new() -> Self {
+        Layout {
+            state: State::LineBegin,
+            column_index: 0,
+            trailing_cr: None,
+        }
+    }
+
+    /// Returns the index of the column of the next value, counted from 0 since
+    /// the last line-break.
+    #[cfg(feature = "std")]
+    pub(crate) fn column_index(&self) -> usize {
+        self.column_index
+    }
+
+    pub(crate) fn write_value<S: Sink>(
+        &mut self,
+        sink: &mut S,
+        value: &[u8],
+        quoted: bool,
+        quoting_policy: QuotingPolicy,
+        default_spacing: &[u8],
+        default_line_break: LineBreak,
+    ) -> Result<(), SinkWriteError<S::Error>> {
+        let prepared_value = PreparedValue::from(value);
+        let required =
+            prepared_value.requires_quotes(matches!(self.state, State::LineBegin | State::Comment));
+        let quoted = quoted
+            || match quoting_policy {
+                QuotingPolicy::Minimal => required,
+                QuotingPolicy::Always => true,
+                QuotingPolicy::NonNumeric => required || !is_numeric(value),
+                QuotingPolicy::Never if required => return Err(SinkWriteError::QuotesRequired),
+                QuotingPolicy::Never => false,
+            };
+
+        match self.state {
+            State::Value => self.write_spacing(sink, default_spacing, default_line_break)?,
+            State::Comment => self.write_line_break(sink, default_line_break)?,
+            _ => {}
+        }
+
+        if quoted {
+            write(sink, &[BytesDomain::QUOTE])?;
+        }
+        write(sink, &prepared_value.bytes)?;
+        if quoted {
+            write(sink, &[BytesDomain::QUOTE])?;
+        }
+
+        self.state = State::Value;
+        self.column_index += 1;
+        Ok(())
+    }
+
+    /// Writes the spacing, which must be valid.
+    pub(crate) fn write_spacing<S: Sink>(
+        &mut self,
+        sink: &mut S,
+        spacing: &[u8],
+        default_line_break: LineBreak,
+    ) -> Result<(), SinkWriteError<S::Error>> {
+        if self.state == State::Comment {
+            self.write_line_break(sink, default_line_break)?;
+        }
+
+        write(sink, spacing)?;
+        self.state = State::Spacing;
+        Ok(())
+    }
+
+    pub(crate) fn write_line_break<S: Sink>(
+        &mut self,
+        sink: &mut S,
+        line_break: LineBreak,
+    ) -> Result<(), SinkWriteError<S::Error>> {
+        let bytes: &[u8] = match line_break {
+            LineBreak::Lf => &[BytesDomain::LF],
+            LineBreak::CrLf => &[BytesDomain::CR, BytesDomain::LF],
+        };
+        if line_break == LineBreak::Lf {
+            match self.trailing_cr {
+                Some(CommentLineBreakPolicy::Reject) => {
+                    return Err(SinkWriteError::CommentWithLineBreak);
+                }
+                Some(CommentLineBreakPolicy::Split) => {
+                    // The CRLF ends the comment line, and the empty part after
+                    // the CR is written as another comment line.
+                    write(sink, bytes)?;
+                    write(sink, &[BytesDomain::HASH])?;
+                }
+                None => {}
+            }
+        }
+        write(sink, bytes)?;
+
+        self.state = State::LineBegin;
+        self.column_index = 0;
+        self.trailing_cr = None;
+        Ok(())
+    }
+
+    pub(crate) fn write_comment<S: Sink>(
+        &mut self,
+        sink: &mut S,
+        comment: &[u8],
+        policy: CommentLineBreakPolicy,
+        default_line_break: LineBreak,
+    ) -> Result<(), SinkWriteError<S::Error>> {
+        let lines = comment_lines(comment);
+        if lines.len() > 1 && policy == CommentLineBreakPolicy::Reject {
+            return Err(SinkWriteError::CommentWithLineBreak);
+        }
+
+        for line in lines {
+            if self.state != State::LineBegin {
+                self.write_line_break(sink, default_line_break)?;
+            }
+
+            write(sink, &[BytesDomain::HASH])?;
+            write(sink, line)?;
+            self.state = State::Comment;
+        }
+        if comment.last() == Some(&BytesDomain::CR) {
+            self.trailing_cr = Some(policy);
+        }
+        Ok(())
+    }
+
+    /// Writes a comment read by a tokenizer exactly as it is.
+    ///
+    /// The comment must not contain LFs, and if it ends with a CR, it must not
+    /// be followed by an LF line-break.
+    #[cfg(feature = "std")]
+    pub(crate) fn write_verbatim_comment<S: Sink>(
+        &mut self,
+        sink: &mut S,
+        comment: &[u8],
+        default_line_break: LineBreak,
+    ) -> Result<(), SinkWriteError<S::Error>> {
+        if self.state != State::LineBegin {
+            self.write_line_break(sink, default_line_break)?;
+        }
+
+        write(sink, &[BytesDomain::HASH])?;
+        write(sink, comment)?;
+        self.state = State::Comment;
+        Ok(())
+    }
+}
+
+fn write<S: Sink>(sink: &mut S, bytes: &[u8]) -> Result<(), SinkWriteError<S::Error>> {
+    sink.write_bytes(bytes).map_err(SinkWriteError::SinkError)
+}
+
+#[derive(PartialEq, Eq, Debug)]
+enum State {
+    Value,
+    Spacing,
+    LineBegin,
+    Comment,
+}
+
+pub(crate) struct PreparedValue {
+    pub(crate) bytes: Vec<u8>,
+    must_be_quoted: bool,
+}
+
+impl PreparedValue {
+    pub(crate) fn from(original_bytes: &[u8]) -> PreparedValue {
+        let mut only_quotes = true;
+        let mut spacing_or_line_break = false;
+
+        let mut bytes = Vec::new();
+        for byte in original_bytes {
+            bytes.push(*byte);
+
+            if *byte == BytesDomain::QUOTE {
+                bytes.push(*byte);
+            } else {
+                only_quotes = false;
+
+                if *byte == BytesDomain::LF || BytesDomain::is_spacing_element(*byte) {
+                    spacing_or_line_break = true;
+                }
+            }
+        }
+
+        PreparedValue {
+            bytes,
+            must_be_quoted: only_quotes || spacing_or_line_break,
+        }
+    }
+
+    pub(crate) fn requires_quotes(&self, at_line_begin: bool) -> bool {
+        self.must_be_quoted || (at_line_begin && self.bytes.first() == Some(&BytesDomain::HASH))
+    }
+}
+
+/// Splits the comment at its line-breaks (LF or CRLF), which would otherwise
+/// end the comment line early.
+fn comment_lines(comment: &[u8]) -> Vec<&[u8]> {
+    let mut lines: Vec<&[u8]> = comment.split(|byte| *byte == BytesDomain::LF).collect();
+    let last_index = lines.len() - 1;
+    for line in &mut lines[..last_index] {
+        if let Some(stripped) = line.strip_suffix(&[BytesDomain::CR]) {
+            *line = stripped;
+        }
+    }
+    lines
+}
+
+/// Returns whether the value must be enclosed in quotes according to the
+/// [rules](crate#rules).
+#[cfg(feature = "std")]
+pub(crate) fn requires_quotes(value: &[u8], at_line_begin: bool) -> bool {
+    PreparedValue::from(value).requires_quotes(at_line_begin)
+}
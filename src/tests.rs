@@ -1,13 +1,37 @@
 use crate::engine::domain::CharsDomain;
 use crate::engine::domain::{BytesDomain, DomainStringSlice};
 use crate::engine::reader::Reader;
+use crate::engine::writer::Writer;
 use crate::engine::ReadResult;
-use crate::tests::support::LF;
+use crate::tests::support::{CRLF, LF, Q};
 use crate::{bformat, domain_format, domain_format_ref};
 
+mod aggregate;
+mod compression;
+mod diff;
+mod domain;
+mod encoding;
+#[cfg(feature = "ffi")]
+mod ffi;
+mod filter;
 mod fluent_writer;
+mod follow;
+mod import;
+mod join;
+mod lint;
+mod mapping;
+mod merge;
+mod profile;
+mod projection;
 mod reader;
+mod render;
+mod reverse_reader;
+mod slice_reader;
+mod sort;
+mod syntax;
+mod token_writer;
 mod tokenizer;
+mod update;
 mod writer;
 
 pub mod support;
@@ -51,3 +75,112 @@ fn write() {
     test_with_domain!(BytesDomain);
     test_with_domain!(CharsDomain);
 }
+
+#[test]
+fn align() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            let input = bformat!("a  {Q}b c{Q} d{LF}# comment{CRLF}eee f{LF}{LF}g{LF}");
+            let mut output = Vec::new();
+
+            super::engine::align::<$domain, _, _>(input.as_slice(), &mut output).unwrap();
+
+            assert_eq!(
+                String::from_utf8(output).unwrap(),
+                String::from_utf8(bformat!(
+                    "a   {Q}b c{Q} d{LF}# comment{LF}eee f{LF}{LF}g{LF}"
+                ))
+                .unwrap()
+            );
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
+
+#[test]
+fn write_file_with_mode() {
+    use crate::engine::WriteMode;
+
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            let path = std::env::temp_dir().join(format!(
+                "ssv-write-file-{}-{}.ssv",
+                stringify!($domain),
+                std::process::id()
+            ));
+            let rows = domain_format!($domain, [["a", "b c"]]);
+            let write = |mode| {
+                super::engine::write_file_with_mode::<$domain, _>(
+                    &path,
+                    mode,
+                    rows.iter()
+                        .map(|row| row.iter().map(std::ops::Deref::deref)),
+                )
+            };
+
+            write(WriteMode::Append).unwrap();
+            assert_eq!(std::fs::read(&path).unwrap(), bformat!("a {Q}b c{Q}{LF}"));
+
+            write(WriteMode::Truncate).unwrap();
+            assert_eq!(std::fs::read(&path).unwrap(), bformat!("a {Q}b c{Q}{LF}"));
+
+            std::fs::write(&path, bformat!("x{CRLF}y")).unwrap();
+            write(WriteMode::Append).unwrap();
+            assert_eq!(
+                std::fs::read(&path).unwrap(),
+                bformat!("x{CRLF}y{CRLF}a {Q}b c{Q}{CRLF}")
+            );
+
+            std::fs::write(&path, bformat!("x{LF}# y{LF}")).unwrap();
+            write(WriteMode::Append).unwrap();
+            assert_eq!(
+                std::fs::read(&path).unwrap(),
+                bformat!("x{LF}# y{LF}a {Q}b c{Q}{LF}")
+            );
+
+            std::fs::write(&path, bformat!("x")).unwrap();
+            write(WriteMode::Append).unwrap();
+            assert_eq!(
+                std::fs::read(&path).unwrap(),
+                bformat!("x{LF}a {Q}b c{Q}{LF}")
+            );
+
+            write(WriteMode::Atomic).unwrap();
+            assert_eq!(std::fs::read(&path).unwrap(), bformat!("a {Q}b c{Q}{LF}"));
+
+            let failing_rows = domain_format!($domain, [["a"], ["b c"]]);
+            let mut options = crate::engine::options::Options::<$domain>::new();
+            options.set_quoting_policy(crate::engine::options::QuotingPolicy::Never);
+            let result = crate::engine::replace_file(&path, |file| {
+                let mut writer = Writer::<$domain, _>::new(file);
+                writer.set_options(options)?;
+                writer.write_rows(
+                    failing_rows
+                        .iter()
+                        .map(|row| row.iter().map(std::ops::Deref::deref)),
+                )
+            });
+            assert!(matches!(
+                result,
+                Err(crate::engine::WriteError::QuotesRequired)
+            ));
+            assert_eq!(std::fs::read(&path).unwrap(), bformat!("a {Q}b c{Q}{LF}"));
+            let directory_entries = std::fs::read_dir(std::env::temp_dir())
+                .unwrap()
+                .filter(|entry| {
+                    let name = entry.as_ref().unwrap().file_name();
+                    let name = name.to_string_lossy();
+                    name.starts_with(&format!(".{}", path.file_name().unwrap().to_string_lossy()))
+                })
+                .count();
+            assert_eq!(directory_entries, 0);
+
+            std::fs::remove_file(&path).unwrap();
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
@@ -0,0 +1,316 @@
+use crate::engine::aggregate::{
+    AggregateError, Aggregation, Aggregator, GroupingStrategy, NumberFormat,
+};
+use crate::engine::columns::{ColumnNotFoundError, ColumnRef};
+use crate::engine::domain::{BytesDomain, CharsDomain};
+use crate::engine::reader::Reader;
+use crate::engine::ReadWriteError;
+use crate::tests::support::{LF, Q};
+use crate::{bformat, domain_format, domain_format_ref};
+
+const SALES: &str = "city product amount\nParis tea 1.5\nRome tea 2\nParis cake 3\nParis tea x\n";
+const SORTED_SALES: &str =
+    "city product amount\nParis tea 1.5\nParis cake 3\nParis tea x\nRome tea 2\n";
+
+macro_rules! assert_aggregation {
+    ($aggregator:expr, $input:expr, $expected_output:literal $(,)?) => {
+        _assert_aggregation_domain!(BytesDomain, $aggregator, $input, $expected_output);
+        _assert_aggregation_domain!(CharsDomain, $aggregator, $input, $expected_output);
+    };
+}
+
+macro_rules! _assert_aggregation_domain {
+    ($domain:ident, $aggregator:expr, $input:expr, $expected_output:literal) => {
+        let aggregator: Aggregator<$domain> = $aggregator;
+        let mut output = Vec::new();
+
+        aggregator
+            .transform($input.as_bytes(), &mut output)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            String::from_utf8(bformat!($expected_output)).unwrap()
+        );
+    };
+}
+
+#[test]
+fn functions() {
+    assert_aggregation!(
+        Aggregator::new()
+            .with_header(true)
+            .group_by("city")
+            .with(Aggregation::count())
+            .with(Aggregation::sum("amount"))
+            .with(Aggregation::min("amount"))
+            .with(Aggregation::max("amount"))
+            .with(Aggregation::mean("amount"))
+            .with(Aggregation::distinct_count("product")),
+        SALES,
+        "city count sum(amount) min(amount) max(amount) mean(amount) distinct(product){LF}\
+         Paris 3 4.5 1.5 x 2.25 2{LF}\
+         Rome 1 2 2 2 2 1{LF}",
+    );
+}
+
+#[test]
+fn strategies() {
+    for strategy in [GroupingStrategy::Hash, GroupingStrategy::Sorted] {
+        assert_aggregation!(
+            Aggregator::new()
+                .with_header(true)
+                .with_strategy(strategy)
+                .group_by("city")
+                .with(Aggregation::count()),
+            SORTED_SALES,
+            "city count{LF}Paris 3{LF}Rome 1{LF}",
+        );
+    }
+}
+
+#[test]
+fn unsorted_input() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            for (header, expected_row_number) in [(true, 4), (false, 2)] {
+                let error = Aggregator::<$domain>::new()
+                    .with_header(header)
+                    .with_strategy(GroupingStrategy::Sorted)
+                    .group_by(0)
+                    .with(Aggregation::count())
+                    .transform(SALES.as_bytes(), Vec::new())
+                    .unwrap_err();
+                assert!(
+                    matches!(error, AggregateError::UnsortedInput(row_number) if row_number == expected_row_number),
+                    "wrong error: {:?}",
+                    error
+                );
+            }
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
+
+#[test]
+fn non_utf8_values() {
+    let mut output = Vec::new();
+    Aggregator::<BytesDomain>::new()
+        .group_by(0)
+        .with(Aggregation::min(1))
+        .with(Aggregation::max(1))
+        .transform(&b"\xff \xfe\na 1\n\xff \xfd\n"[..], &mut output)
+        .unwrap();
+    assert_eq!(output, b"\xff \xfd \xfe\na 1 1\n");
+}
+
+#[test]
+fn multiple_group_columns() {
+    for strategy in [GroupingStrategy::Hash, GroupingStrategy::Sorted] {
+        assert_aggregation!(
+            Aggregator::new()
+                .with_strategy(strategy)
+                .group_by(0)
+                .group_by(1)
+                .with(Aggregation::sum(2)),
+            "a x 1\na x 2\na y 3\nb\n",
+            "a x 3{LF}a y 3{LF}b {Q}{Q} 0{LF}",
+        );
+    }
+}
+
+#[test]
+fn without_group_columns() {
+    for strategy in [GroupingStrategy::Hash, GroupingStrategy::Sorted] {
+        assert_aggregation!(
+            Aggregator::new()
+                .with_strategy(strategy)
+                .with(Aggregation::count())
+                .with(Aggregation::mean(0))
+                .with(Aggregation::min(0)),
+            "3\n1\n",
+            "2 2 1{LF}",
+        );
+        assert_aggregation!(
+            Aggregator::new()
+                .with_strategy(strategy)
+                .with(Aggregation::count())
+                .with(Aggregation::sum(0))
+                .with(Aggregation::mean(0))
+                .with(Aggregation::max(0)),
+            "",
+            "0 0 {Q}{Q} {Q}{Q}{LF}",
+        );
+    }
+}
+
+#[test]
+fn number_formats() {
+    assert_aggregation!(
+        Aggregator::new()
+            .with_number_format(NumberFormat::DECIMAL_COMMA)
+            .with(Aggregation::sum(0))
+            .with(Aggregation::max(0)),
+        "1,5\n1.000\n2.5\n",
+        "1001,5 2.5{LF}",
+    );
+    assert_aggregation!(
+        Aggregator::new()
+            .with(Aggregation::sum(0))
+            .with(Aggregation::min(0)),
+        "1,5\n1,000\n2.5\n",
+        "1002.5 2.5{LF}",
+    );
+
+    let comma = NumberFormat::DECIMAL_COMMA;
+    assert_eq!(comma.parse("-1.234.567,5"), Some(-1234567.5));
+    assert_eq!(comma.parse("1e3"), Some(1000.0));
+    assert_eq!(comma.parse("12.34"), None);
+    assert_eq!(comma.parse(".123"), None);
+    assert_eq!(comma.parse(" 1"), None);
+    assert_eq!(comma.parse(""), None);
+    assert_eq!(comma.format(-0.5), "-0,5");
+    assert_eq!(comma.format(3.0), "3");
+}
+
+#[test]
+fn sum_precision() {
+    assert_aggregation!(
+        Aggregator::new().with(Aggregation::sum(0)),
+        "0.1\n0.2\n",
+        "0.3{LF}",
+    );
+    assert_aggregation!(
+        Aggregator::new().with(Aggregation::sum(0)),
+        "0.25\n1\n-0.05\n",
+        "1.20{LF}",
+    );
+    assert_aggregation!(
+        Aggregator::new().with(Aggregation::sum(0)),
+        "1.5e-3\n2e2\n",
+        "200.0015{LF}",
+    );
+    assert_aggregation!(
+        Aggregator::new().with(Aggregation::sum(0)),
+        "9007199254740993\n2\n",
+        "9007199254740995{LF}",
+    );
+    assert_aggregation!(
+        Aggregator::new().with(Aggregation::sum(0)),
+        "170141183460469231731687303715884105727\n1\n",
+        "170141183460469231731687303715884105728{LF}",
+    );
+}
+
+#[test]
+fn names() {
+    assert_aggregation!(
+        Aggregator::new()
+            .with_header(true)
+            .group_by(1)
+            .with(Aggregation::count())
+            .with(Aggregation::max(2)),
+        "x y z\n1 a 2\n",
+        "y count max(z){LF}a 1 2{LF}",
+    );
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            let mut output = Vec::new();
+            Aggregator::<$domain>::new()
+                .with_header(true)
+                .with(Aggregation::count().named(domain_format_ref!($domain, "rows")))
+                .transform("x\n1\n".as_bytes(), &mut output)
+                .unwrap();
+            assert_eq!(output, bformat!("rows{LF}1{LF}"));
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
+
+#[test]
+fn apply() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            let aggregator = Aggregator::<$domain>::new()
+                .with_header(true)
+                .with_strategy(GroupingStrategy::Sorted)
+                .group_by("city")
+                .with(Aggregation::distinct_count("product"));
+            let rows: Vec<_> = aggregator
+                .apply(Reader::<$domain, _>::new(SORTED_SALES.as_bytes()))
+                .map(Result::unwrap)
+                .collect();
+
+            assert_eq!(
+                rows,
+                domain_format!(
+                    $domain,
+                    [["city", "distinct(product)"], ["Paris", "2"], ["Rome", "1"]]
+                )
+            );
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
+
+#[test]
+fn too_many_groups() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            let aggregator = Aggregator::<$domain>::new()
+                .with_header(true)
+                .max_groups(2)
+                .group_by("product")
+                .with(Aggregation::count());
+            aggregator.transform(SALES.as_bytes(), Vec::new()).unwrap();
+
+            let error = aggregator
+                .group_by("city")
+                .transform(SALES.as_bytes(), Vec::new())
+                .unwrap_err();
+            assert!(
+                matches!(error, AggregateError::TooManyGroups(2)),
+                "wrong error: {:?}",
+                error
+            );
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
+
+#[test]
+fn column_not_found() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            for aggregator in [
+                Aggregator::<$domain>::new()
+                    .with_header(true)
+                    .group_by(ColumnRef::from("x")),
+                Aggregator::<$domain>::new().with(Aggregation::sum("x")),
+            ] {
+                let error = aggregator
+                    .transform(SALES.as_bytes(), Vec::new())
+                    .unwrap_err();
+                if let AggregateError::ReadWriteError(ReadWriteError::ColumnNotFound(
+                    ColumnNotFoundError(column),
+                )) = error
+                {
+                    assert_eq!(column, "\"x\"");
+                } else {
+                    panic!("wrong error: {:?}", error);
+                }
+            }
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
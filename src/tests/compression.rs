@@ -0,0 +1,196 @@
+use std::io::{Read, Write};
+
+use crate::engine::compression::{CompressingWriter, Compression, DecompressingReader};
+use crate::engine::domain::{BytesDomain, CharsDomain};
+use crate::engine::reader::Reader;
+use crate::engine::WriteMode;
+use crate::tests::support::{CRLF, LF, Q};
+use crate::{bformat, domain_format};
+
+fn decompress(input: &[u8]) -> std::io::Result<(Compression, Vec<u8>)> {
+    let mut reader = DecompressingReader::new(input)?;
+    let mut output = Vec::new();
+    reader.read_to_end(&mut output)?;
+    Ok((reader.compression(), output))
+}
+
+fn compress(compression: Compression, input: &[u8]) -> Vec<u8> {
+    let mut output = Vec::new();
+    let mut writer = CompressingWriter::new(&mut output, compression).unwrap();
+    writer.write_all(input).unwrap();
+    writer.finish().unwrap();
+    output
+}
+
+#[test]
+fn compression_from_extension() {
+    assert_eq!(Compression::from_extension("a.ssv.gz"), Compression::Gzip);
+    assert_eq!(Compression::from_extension("a.GZ"), Compression::Gzip);
+    assert_eq!(Compression::from_extension("a.ssv.zst"), Compression::Zstd);
+    assert_eq!(Compression::from_extension("a.zstd"), Compression::Zstd);
+    assert_eq!(Compression::from_extension("a.ssv"), Compression::None);
+    assert_eq!(Compression::from_extension("gz"), Compression::None);
+}
+
+#[test]
+fn compression_from_magic_bytes() {
+    assert_eq!(
+        Compression::from_magic_bytes(b"\x1F\x8B\x08"),
+        Compression::Gzip
+    );
+    assert_eq!(
+        Compression::from_magic_bytes(b"\x28\xB5\x2F\xFD\x00"),
+        Compression::Zstd
+    );
+    assert_eq!(
+        Compression::from_magic_bytes(b"\x28\xB5\x2F"),
+        Compression::None
+    );
+    assert_eq!(Compression::from_magic_bytes(b"a b"), Compression::None);
+    assert_eq!(Compression::from_magic_bytes(b""), Compression::None);
+}
+
+#[test]
+fn uncompressed() {
+    for input in [&b""[..], b"a", b"a b", b"a b c\n", b"\x1F"] {
+        assert_eq!(
+            decompress(input).unwrap(),
+            (Compression::None, input.to_vec())
+        );
+        assert_eq!(compress(Compression::None, input), input);
+    }
+}
+
+#[cfg(not(feature = "gzip"))]
+#[test]
+fn gzip_unsupported() {
+    let error = decompress(b"\x1F\x8B\x08\x00").unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::Unsupported);
+    let error = CompressingWriter::new(Vec::new(), Compression::Gzip)
+        .err()
+        .unwrap();
+    assert_eq!(error.kind(), std::io::ErrorKind::Unsupported);
+}
+
+#[cfg(not(feature = "zstd"))]
+#[test]
+fn zstd_unsupported() {
+    let error = decompress(b"\x28\xB5\x2F\xFD\x00").unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::Unsupported);
+    let error = CompressingWriter::new(Vec::new(), Compression::Zstd)
+        .err()
+        .unwrap();
+    assert_eq!(error.kind(), std::io::ErrorKind::Unsupported);
+}
+
+fn supported_compressions() -> Vec<(Compression, &'static str)> {
+    let mut compressions = Vec::new();
+    if cfg!(feature = "gzip") {
+        compressions.push((Compression::Gzip, "gz"));
+    }
+    if cfg!(feature = "zstd") {
+        compressions.push((Compression::Zstd, "zst"));
+    }
+    compressions
+}
+
+#[test]
+fn streams() {
+    for (compression, _) in supported_compressions() {
+        let compressed = compress(compression, b"a b\n\"c\nd\" e\n");
+        assert_ne!(compressed, b"a b\n\"c\nd\" e\n");
+
+        let concatenated = [compressed, compress(compression, b"f\n")].concat();
+        let reader =
+            Reader::<CharsDomain, _>::new(DecompressingReader::new(&concatenated[..]).unwrap());
+        let rows: Vec<_> = reader.collect::<Result<_, _>>().unwrap();
+        assert_eq!(rows, [vec!["a", "b"], vec!["c\nd", "e"], vec!["f"]]);
+
+        assert_eq!(
+            decompress(&compress(compression, b"")).unwrap(),
+            (compression, Vec::new())
+        );
+    }
+}
+
+#[test]
+fn finish_ends_once() {
+    struct FlushCounter(usize);
+
+    impl Write for FlushCounter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            self.0 += 1;
+            Ok(())
+        }
+    }
+
+    let mut counter = FlushCounter(0);
+    CompressingWriter::new(&mut counter, Compression::None)
+        .unwrap()
+        .finish()
+        .unwrap();
+    assert_eq!(counter.0, 1);
+
+    drop(CompressingWriter::new(&mut counter, Compression::None).unwrap());
+    assert_eq!(counter.0, 2);
+}
+
+#[test]
+fn files() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            for (compression, extension) in supported_compressions() {
+                let path = std::env::temp_dir().join(format!(
+                    "ssv-compression-{}-{}.ssv.{extension}",
+                    stringify!($domain),
+                    std::process::id()
+                ));
+                let rows = domain_format!($domain, [["a", "b c"]]);
+                let write = |mode| {
+                    crate::engine::write_file_with_mode::<$domain, _>(
+                        &path,
+                        mode,
+                        rows.iter()
+                            .map(|row| row.iter().map(std::ops::Deref::deref)),
+                    )
+                };
+                let read = || {
+                    let mut content = Vec::new();
+                    let mut file = std::fs::File::open(&path).unwrap();
+                    file.read_to_end(&mut content).unwrap();
+                    let (detected, content) = decompress(&content).unwrap();
+                    assert_eq!(detected, compression);
+                    content
+                };
+
+                write(WriteMode::Append).unwrap();
+                assert_eq!(read(), bformat!("a {Q}b c{Q}{LF}"));
+
+                write(WriteMode::Truncate).unwrap();
+                assert_eq!(read(), bformat!("a {Q}b c{Q}{LF}"));
+
+                write(WriteMode::Atomic).unwrap();
+                assert_eq!(read(), bformat!("a {Q}b c{Q}{LF}"));
+
+                std::fs::write(&path, compress(compression, &bformat!("x{CRLF}y"))).unwrap();
+                write(WriteMode::Append).unwrap();
+                assert_eq!(read(), bformat!("x{CRLF}y{CRLF}a {Q}b c{Q}{CRLF}"));
+
+                let rows: Vec<_> = crate::engine::read_file::<$domain, _>(&path)
+                    .unwrap()
+                    .collect::<Result<_, _>>()
+                    .unwrap();
+                assert_eq!(rows, domain_format!($domain, [["x"], ["y"], ["a", "b c"]]));
+
+                std::fs::remove_file(&path).unwrap();
+            }
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
@@ -0,0 +1,250 @@
+use crate::engine::columns::{ColumnNotFoundError, ColumnRef};
+use crate::engine::diff::{Differ, Difference, MAX_SEQUENCE_CELLS};
+use crate::engine::domain::{BytesDomain, CharsDomain};
+use crate::engine::position::Position;
+use crate::engine::ReadWriteError;
+use crate::tests::support::{LF, Q};
+use crate::{bformat, domain_format};
+
+const LEFT: &str = "id name city\n1 John Paris\n2 Jane Rome\n3 Bob Oslo\n";
+
+macro_rules! assert_diff {
+    ($differ:expr, $left:expr, $right:expr, $expected_report:literal $(,)?) => {
+        _assert_diff_domain!(BytesDomain, $differ, $left, $right, $expected_report);
+        _assert_diff_domain!(CharsDomain, $differ, $left, $right, $expected_report);
+    };
+}
+
+macro_rules! _assert_diff_domain {
+    ($domain:ident, $differ:expr, $left:expr, $right:expr, $expected_report:literal) => {
+        let differ: Differ<$domain> = $differ;
+
+        let diff = differ.diff($left.as_bytes(), $right.as_bytes()).unwrap();
+
+        assert_eq!(diff.to_string(), format!($expected_report));
+    };
+}
+
+#[test]
+fn by_keys() {
+    assert_diff!(
+        Differ::new().with_header(true).key("id"),
+        LEFT,
+        "id name city\n3 Bob Oslo\n1 John Lyon\n4 Ann Nice\n",
+        "changed row at 2:1 -> 3:1{LF}\
+         \x20 city: Paris (2:8) -> Lyon (3:8){LF}\
+         removed row at 3:1: 2 Jane Rome{LF}\
+         added row at 4:1: 4 Ann Nice{LF}",
+    );
+    assert_diff!(
+        Differ::new().key(0).key(1),
+        "a 1 x\na 1 y\nb 2 z\n",
+        "a 1 y\nb 2 z\na 1 x\n",
+        "changed row at 1:1 -> 1:1{LF}\
+         \x20 2: x (1:5) -> y (1:5){LF}\
+         changed row at 2:1 -> 3:1{LF}\
+         \x20 2: y (2:5) -> x (3:5){LF}",
+    );
+}
+
+#[test]
+fn by_sequence() {
+    assert_diff!(
+        Differ::new().with_header(true),
+        LEFT,
+        "id name city\n0 Ann Nice\n1 John Paris\n2 Jane Lyon\n",
+        "added row at 2:1: 0 Ann Nice{LF}\
+         changed row at 3:1 -> 4:1{LF}\
+         \x20 city: Rome (3:8) -> Lyon (4:8){LF}\
+         removed row at 4:1: 3 Bob Oslo{LF}",
+    );
+    assert_diff!(
+        Differ::new(),
+        "a\nb\nc\nd\n",
+        "a\nx\ny\nd\ne\n",
+        "changed row at 2:1 -> 2:1{LF}\
+         \x20 0: b (2:1) -> x (2:1){LF}\
+         changed row at 3:1 -> 3:1{LF}\
+         \x20 0: c (3:1) -> y (3:1){LF}\
+         added row at 5:1: e{LF}",
+    );
+}
+
+#[test]
+fn layout_is_ignored() {
+    assert_diff!(
+        Differ::new(),
+        "a   b\n# comment\n\"c d\" e\n",
+        "\n  \"a\" b  \n\"c d\"    \"e\"\r\n",
+        "",
+    );
+    assert_diff!(
+        Differ::new().key(0),
+        "a   b\n# comment\n\"c d\" e\n",
+        "\"c d\"    \"e\"\r\n  \"a\" b  \n",
+        "",
+    );
+}
+
+#[test]
+fn by_sequence_above_the_size_limit() {
+    let inputs = |rows: usize| -> (String, String) {
+        let rows: String = (0..rows).map(|index| format!("{index}\n")).collect();
+        (format!("x\n{rows}y\n"), format!("z\nq\n{rows}w\n"))
+    };
+    let count_changed = |diff: &crate::engine::diff::Diff<BytesDomain>| {
+        diff.differences()
+            .iter()
+            .filter(|difference| matches!(difference, Difference::Changed { .. }))
+            .count()
+    };
+
+    let (left, right) = inputs(10);
+    let diff = Differ::<BytesDomain>::new()
+        .diff(left.as_bytes(), right.as_bytes())
+        .unwrap();
+    assert_eq!(diff.differences().len(), 3);
+    assert_eq!(count_changed(&diff), 2);
+
+    // The rows are not aligned, so they are shifted by the added row
+    let rows = MAX_SEQUENCE_CELLS.isqrt();
+    let (left, right) = inputs(rows);
+    let diff = Differ::<BytesDomain>::new()
+        .diff(left.as_bytes(), right.as_bytes())
+        .unwrap();
+    assert_eq!(diff.differences().len(), rows + 3);
+    assert_eq!(count_changed(&diff), rows + 2);
+}
+
+#[test]
+fn missing_cells() {
+    assert_diff!(
+        Differ::new(),
+        "a b \"\"\n",
+        "a\n",
+        "changed row at 1:1 -> 1:1{LF}\
+         \x20 1: b (1:3) -> (missing){LF}\
+         \x20 2: {Q}{Q} (1:5) -> (missing){LF}",
+    );
+}
+
+#[test]
+fn headers() {
+    assert_diff!(
+        Differ::new().with_header(true).key("id"),
+        "id name\n1 John\n",
+        "name id\nJohn 1\n",
+        "changed row at 1:1 -> 1:1{LF}\
+         \x20 id: id (1:1) -> name (1:1){LF}\
+         \x20 name: name (1:4) -> id (1:6){LF}\
+         changed row at 2:1 -> 2:1{LF}\
+         \x20 id: 1 (2:1) -> John (2:1){LF}\
+         \x20 name: John (2:3) -> 1 (2:6){LF}",
+    );
+    assert_diff!(
+        Differ::new().with_header(true),
+        "",
+        "x\n1\n",
+        "added row at 1:1: x{LF}added row at 2:1: 1{LF}",
+    );
+}
+
+#[test]
+fn differences() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            let diff = Differ::<$domain>::new()
+                .key(0)
+                .diff("a 1\nb 2\n".as_bytes(), "b 3\n".as_bytes())
+                .unwrap();
+
+            assert_eq!(diff.differences().len(), 2);
+            if let Difference::Changed { left, right, cells } = &diff.differences()[1] {
+                assert_eq!(left.position.line_number, 2);
+                assert_eq!(right.position.line_number, 1);
+                assert_eq!(cells.len(), 1);
+                assert_eq!(cells[0].column, 1);
+                let left = cells[0].left.as_ref().unwrap();
+                let expected_value: <$domain as crate::engine::domain::Domain>::String =
+                    domain_format!($domain, "2");
+                assert_eq!(left.value, expected_value);
+                assert_eq!(
+                    left.position,
+                    Position {
+                        line_number: 2,
+                        column_number: 3
+                    }
+                );
+            } else {
+                panic!("wrong difference: {:?}", diff.differences()[1]);
+            }
+            assert!(matches!(diff.differences()[0], Difference::Removed(_)));
+
+            let diff = Differ::<$domain>::new()
+                .diff("a\n".as_bytes(), "a\n".as_bytes())
+                .unwrap();
+            assert!(diff.is_empty());
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
+
+#[test]
+fn write_ssv() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            let diff = Differ::<$domain>::new()
+                .with_header(true)
+                .key("id")
+                .diff(
+                    "id name\n1 John\n2 Jane\n".as_bytes(),
+                    "id name\n1 \"Jo hn\"\n3 Ann\n".as_bytes(),
+                )
+                .unwrap();
+            let mut output = Vec::new();
+
+            diff.write_ssv(&mut output).unwrap();
+
+            assert_eq!(
+                String::from_utf8(output).unwrap(),
+                format!(
+                    "change left right column old new{LF}\
+                     changed 2:3 2:3 name John {Q}Jo hn{Q}{LF}\
+                     removed 3:1 {Q}{Q} id 2 {Q}{Q}{LF}\
+                     removed 3:3 {Q}{Q} name Jane {Q}{Q}{LF}\
+                     added {Q}{Q} 3:1 id {Q}{Q} 3{LF}\
+                     added {Q}{Q} 3:3 name {Q}{Q} Ann{LF}"
+                )
+            );
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
+
+#[test]
+fn column_not_found() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            for differ in [
+                Differ::<$domain>::new()
+                    .with_header(true)
+                    .key(ColumnRef::from("x")),
+                Differ::<$domain>::new().key("x"),
+            ] {
+                let error = differ.diff(LEFT.as_bytes(), LEFT.as_bytes()).err().unwrap();
+                if let ReadWriteError::ColumnNotFound(ColumnNotFoundError(column)) = error {
+                    assert_eq!(column, "\"x\"");
+                } else {
+                    panic!("wrong error: {:?}", error);
+                }
+            }
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
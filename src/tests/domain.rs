@@ -0,0 +1,193 @@
+use std::borrow::Cow;
+use std::io::Read;
+use std::ops::Deref;
+
+use crate::engine::columns::ColumnRef;
+use crate::engine::domain::{Chars, Domain, DomainString};
+use crate::engine::projection::Projection;
+use crate::engine::reader::Reader;
+use crate::engine::slice_reader::SliceReader;
+use crate::engine::tokenizer::{Token, Tokenizer};
+use crate::engine::writer::Writer;
+use crate::engine::LineBreak;
+
+const INLINE_CAPACITY: usize = 15;
+
+/// A string that is stored inline when it is short.
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum SmallString {
+    Inline(u8, [u8; INLINE_CAPACITY]),
+    Heap(String),
+}
+
+impl Deref for SmallString {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        match self {
+            SmallString::Inline(length, bytes) => {
+                std::str::from_utf8(&bytes[..*length as usize]).unwrap()
+            }
+            SmallString::Heap(string) => string,
+        }
+    }
+}
+
+impl From<&str> for SmallString {
+    fn from(text: &str) -> Self {
+        if text.len() > INLINE_CAPACITY {
+            return SmallString::Heap(text.to_owned());
+        }
+        let mut bytes = [0; INLINE_CAPACITY];
+        bytes[..text.len()].copy_from_slice(text.as_bytes());
+        SmallString::Inline(text.len() as u8, bytes)
+    }
+}
+
+impl DomainString<char> for SmallString {
+    fn new() -> Self {
+        SmallString::from("")
+    }
+
+    fn push(&mut self, element: char) {
+        let mut string = self.to_string();
+        string.push(element);
+        *self = SmallString::from(string.as_str());
+    }
+
+    fn quotes(length: usize) -> Self {
+        SmallString::from("\"".repeat(length).as_str())
+    }
+
+    fn from_text(text: &str) -> Self {
+        SmallString::from(text)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct SmallDomain;
+
+impl Domain for SmallDomain {
+    type Element = char;
+    type ElementIterator<R: Read> = Chars<R>;
+    type String = SmallString;
+    type StringSlice = str;
+
+    const LF: char = '\n';
+    const CR: char = '\r';
+    const QUOTE: char = '"';
+    const SPACE: char = ' ';
+    const HASH: char = '#';
+
+    fn is_spacing_element(element: char) -> bool {
+        matches!(element, ' ' | '\t')
+    }
+
+    fn is_valid_spacing(spacing: &str) -> bool {
+        !spacing.is_empty() && spacing.chars().all(Self::is_spacing_element)
+    }
+
+    fn element_iterator<R: Read>(inner: R) -> Chars<R> {
+        Chars::new(inner)
+    }
+
+    fn slice_from_bytes(bytes: &[u8]) -> std::io::Result<&str> {
+        std::str::from_utf8(bytes)
+            .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error))
+    }
+
+    fn string_from_bytes(bytes: Vec<u8>) -> std::io::Result<SmallString> {
+        Ok(SmallString::from(Self::slice_from_bytes(&bytes)?))
+    }
+
+    fn concat(parts: &[&str]) -> SmallString {
+        SmallString::from(parts.concat().as_str())
+    }
+}
+
+const INPUT: &str = "name   note\n# comment\nJoão  \"a \"\"long\"\" value\"\r\n";
+
+#[test]
+fn tokenizer() {
+    let tokens = Tokenizer::<SmallDomain, _>::new(INPUT.as_bytes())
+        .map(|token| token.map(|token| token.value))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(
+        tokens,
+        [
+            Token::UnquotedValue(SmallString::from("name")),
+            Token::Spacing(SmallString::from("   ")),
+            Token::UnquotedValue(SmallString::from("note")),
+            Token::LineBreak(LineBreak::Lf),
+            Token::Comment(SmallString::from(" comment")),
+            Token::LineBreak(LineBreak::Lf),
+            Token::UnquotedValue(SmallString::from("João")),
+            Token::Spacing(SmallString::from("  ")),
+            Token::QuotedValue(SmallString::from("a \"long\" value")),
+            Token::LineBreak(LineBreak::CrLf),
+        ]
+    );
+    assert!(matches!(
+        tokens[8],
+        Token::QuotedValue(SmallString::Inline(..))
+    ));
+}
+
+#[test]
+fn read_and_write() {
+    let rows = Reader::<SmallDomain, _>::new(INPUT.as_bytes())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        rows,
+        [
+            vec![SmallString::from("name"), SmallString::from("note")],
+            vec![
+                SmallString::from("João"),
+                SmallString::from("a \"long\" value")
+            ],
+        ]
+    );
+
+    let mut output = Vec::new();
+    let mut writer = Writer::<SmallDomain, _>::new(&mut output);
+    for row in &rows {
+        writer.write_row(row.iter().map(Deref::deref)).unwrap();
+    }
+    writer
+        .write_row(["", "a value longer than the inline capacity"])
+        .unwrap();
+    writer.finish().unwrap();
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "name note\nJoão \"a \"\"long\"\" value\"\n\"\" \"a value longer than the inline capacity\"\n"
+    );
+}
+
+#[test]
+fn slice_reader() {
+    let rows = SliceReader::<SmallDomain>::new(INPUT.as_bytes())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(rows[1][0], Cow::Borrowed("João"));
+    assert_eq!(rows[1][1], Cow::<str>::Owned("a \"long\" value".to_owned()));
+}
+
+#[test]
+fn projection() {
+    let mut output = Vec::new();
+    Projection::<SmallDomain>::new()
+        .with_header(true)
+        .select_as(ColumnRef::name("note"), "renamed")
+        .transform(INPUT.as_bytes(), &mut output)
+        .unwrap();
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "renamed\n# comment\n\"a \"\"long\"\" value\"\n"
+    );
+}
@@ -0,0 +1,180 @@
+use std::io::{Read, Write};
+
+use crate::engine::domain::CharsDomain;
+use crate::engine::encoding::{DecodingReader, Encoding, EncodingWriter};
+use crate::engine::position::Position;
+use crate::engine::reader::Reader;
+use crate::engine::ReadError;
+
+const TEXT: &str = "a \"é €\"\n𝄞\n";
+
+fn decode(input: &[u8], encoding: Encoding) -> std::io::Result<(String, Encoding)> {
+    let mut reader = DecodingReader::new(input, encoding);
+    let mut output = String::new();
+    reader.read_to_string(&mut output)?;
+    Ok((output, reader.encoding()))
+}
+
+fn encode(text: &[u8], encoding: Encoding, bom: bool) -> std::io::Result<Vec<u8>> {
+    let mut output = Vec::new();
+    let mut writer = EncodingWriter::new(&mut output, encoding).with_bom(bom);
+    // Written byte by byte, to split the characters
+    for byte in text {
+        writer.write_all(&[*byte])?;
+    }
+    writer.finish()?;
+    Ok(output)
+}
+
+fn utf16le(text: &str) -> Vec<u8> {
+    text.encode_utf16().flat_map(u16::to_le_bytes).collect()
+}
+
+fn utf16be(text: &str) -> Vec<u8> {
+    text.encode_utf16().flat_map(u16::to_be_bytes).collect()
+}
+
+#[test]
+fn encoding_bom() {
+    assert_eq!(Encoding::Utf8.bom(), Some(&b"\xEF\xBB\xBF"[..]));
+    assert_eq!(Encoding::Latin1.bom(), None);
+    assert_eq!(Encoding::from_bom(b"\xEF\xBB\xBFa"), Some(Encoding::Utf8));
+    assert_eq!(Encoding::from_bom(b"\xFF\xFE"), Some(Encoding::Utf16Le));
+    assert_eq!(Encoding::from_bom(b"\xFE\xFFa"), Some(Encoding::Utf16Be));
+    assert_eq!(Encoding::from_bom(b"\xEF\xBB"), None);
+    assert_eq!(Encoding::from_bom(b""), None);
+}
+
+#[test]
+fn utf8() {
+    let input = [b"\xEF\xBB\xBF", TEXT.as_bytes()].concat();
+    assert_eq!(
+        decode(&input, Encoding::Latin1).unwrap(),
+        (TEXT.to_string(), Encoding::Utf8)
+    );
+    assert_eq!(
+        decode(TEXT.as_bytes(), Encoding::Utf8).unwrap(),
+        (TEXT.to_string(), Encoding::Utf8)
+    );
+    assert_eq!(
+        decode(b"", Encoding::Utf8).unwrap(),
+        (String::new(), Encoding::Utf8)
+    );
+    assert_eq!(
+        decode(b"a", Encoding::Utf8).unwrap(),
+        ("a".to_string(), Encoding::Utf8)
+    );
+
+    assert_eq!(
+        encode(TEXT.as_bytes(), Encoding::Utf8, true).unwrap(),
+        input
+    );
+    assert_eq!(
+        encode(TEXT.as_bytes(), Encoding::Utf8, false).unwrap(),
+        TEXT.as_bytes()
+    );
+    assert_eq!(encode(b"", Encoding::Utf8, true).unwrap(), b"\xEF\xBB\xBF");
+}
+
+#[test]
+fn utf16() {
+    let le = utf16le(TEXT);
+    let be = utf16be(TEXT);
+
+    assert_eq!(
+        decode(&le, Encoding::Utf16Le).unwrap(),
+        (TEXT.to_string(), Encoding::Utf16Le)
+    );
+    assert_eq!(
+        decode(&[b"\xFF\xFE", &le[..]].concat(), Encoding::Utf8).unwrap(),
+        (TEXT.to_string(), Encoding::Utf16Le)
+    );
+    assert_eq!(
+        decode(&be, Encoding::Utf16Be).unwrap(),
+        (TEXT.to_string(), Encoding::Utf16Be)
+    );
+    assert_eq!(
+        decode(&[b"\xFE\xFF", &be[..]].concat(), Encoding::Latin1).unwrap(),
+        (TEXT.to_string(), Encoding::Utf16Be)
+    );
+
+    assert_eq!(
+        encode(TEXT.as_bytes(), Encoding::Utf16Le, false).unwrap(),
+        le
+    );
+    assert_eq!(
+        encode(TEXT.as_bytes(), Encoding::Utf16Be, true).unwrap(),
+        [b"\xFE\xFF", &be[..]].concat()
+    );
+
+    for invalid in [&b"a\x00b"[..], b"\x00\xD8a\x00", b"\x00\xDC"] {
+        let error = decode(invalid, Encoding::Utf16Le).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
+
+#[test]
+fn utf16_split_in_chunks() {
+    // A surrogate pair split between two chunks of the inner reader
+    let mut input = utf16le(&"a".repeat(8 * 1024 / 2 - 2));
+    input.extend(utf16le("𝄞b"));
+    input.insert(0, 0xFE);
+    input.insert(0, 0xFF);
+
+    let (text, _) = decode(&input, Encoding::Utf8).unwrap();
+    assert!(text.ends_with("a𝄞b"));
+}
+
+#[test]
+fn latin1_and_windows_1252() {
+    assert_eq!(
+        decode(b"a\xE9\x80\x81", Encoding::Latin1).unwrap(),
+        ("aé\u{80}\u{81}".to_string(), Encoding::Latin1)
+    );
+    assert_eq!(
+        decode(b"a\xE9\x80\x81\x9F", Encoding::Windows1252).unwrap(),
+        ("aé€\u{81}Ÿ".to_string(), Encoding::Windows1252)
+    );
+
+    assert_eq!(
+        encode("aé\u{80}".as_bytes(), Encoding::Latin1, true).unwrap(),
+        b"a\xE9\x80"
+    );
+    assert_eq!(
+        encode("aé€\u{81}Ÿ".as_bytes(), Encoding::Windows1252, false).unwrap(),
+        b"a\xE9\x80\x81\x9F"
+    );
+
+    for (text, encoding) in [
+        ("€", Encoding::Latin1),
+        ("\u{80}", Encoding::Windows1252),
+        ("𝄞", Encoding::Windows1252),
+    ] {
+        let error = encode(text.as_bytes(), encoding, false).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
+
+#[test]
+fn invalid_utf8_written() {
+    let error = encode(b"a\xFFb", Encoding::Latin1, false).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+    let error = encode(b"a\xC3", Encoding::Latin1, false).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn positions_in_chars() {
+    let input = [b"\xFF\xFE", &utf16le("éé 𝄞 a\"b")[..]].concat();
+
+    let mut reader = Reader::<CharsDomain, _>::new(DecodingReader::new(&input[..], Encoding::Utf8));
+
+    assert!(matches!(
+        reader.next(),
+        Some(Err(ReadError::UnpairedQuote(Position {
+            line_number: 1,
+            column_number: 7
+        })))
+    ));
+}
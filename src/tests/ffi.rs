@@ -0,0 +1,170 @@
+use std::ffi::{c_int, CStr};
+use std::ptr;
+
+use crate::bformat;
+use crate::ffi::*;
+use crate::tests::support::{LF, Q};
+
+fn value(bytes: &[u8]) -> SsvValue {
+    SsvValue {
+        data: bytes.as_ptr(),
+        length: bytes.len(),
+    }
+}
+
+/// Reads all rows, returning the status that ended the reading.
+fn read_rows(reader: *mut SsvReader) -> (Vec<Vec<Vec<u8>>>, SsvStatus, SsvError) {
+    let mut rows = Vec::new();
+    let mut error = SsvError {
+        status: SsvStatus::Ok,
+        line_number: 0,
+        column_number: 0,
+        os_error: 0,
+    };
+    loop {
+        let mut values = ptr::null();
+        let mut count = 0;
+        let status = unsafe { ssv_reader_next_row(reader, &mut values, &mut count, &mut error) };
+        if status != SsvStatus::Ok {
+            return (rows, status, error);
+        }
+        let values = unsafe { std::slice::from_raw_parts(values, count) };
+        rows.push(
+            values
+                .iter()
+                .map(|value| {
+                    unsafe { std::slice::from_raw_parts(value.data, value.length) }.to_vec()
+                })
+                .collect(),
+        );
+    }
+}
+
+#[test]
+fn reader_from_buffer() {
+    let input = bformat!("a {Q}b c{Q} {Q}{Q}{LF}# comment{LF}d{LF}");
+
+    let reader = unsafe { ssv_reader_from_buffer(input.as_ptr(), input.len(), ptr::null_mut()) };
+    let (rows, status, _) = read_rows(reader);
+    unsafe { ssv_reader_free(reader) };
+
+    assert_eq!(status, SsvStatus::End);
+    assert_eq!(
+        rows,
+        [
+            vec![b"a".to_vec(), b"b c".to_vec(), b"".to_vec()],
+            vec![b"d".to_vec()]
+        ]
+    );
+}
+
+#[test]
+fn reader_error() {
+    let input = bformat!("a{LF}b {Q}c");
+
+    let reader = unsafe { ssv_reader_from_buffer(input.as_ptr(), input.len(), ptr::null_mut()) };
+    let (rows, status, error) = read_rows(reader);
+    let (_, status_after_error, _) = read_rows(reader);
+    unsafe { ssv_reader_free(reader) };
+
+    assert_eq!(rows, [vec![b"a".to_vec()]]);
+    assert_eq!(status, SsvStatus::UnclosedQuotedValue);
+    assert_eq!((error.line_number, error.column_number), (2, 5));
+    assert_eq!(status_after_error, SsvStatus::End);
+}
+
+#[test]
+fn writer_to_buffer() {
+    let writer = ssv_writer_new_buffer();
+    let row = [value(b"a"), value(b"b c"), value(b"")];
+
+    assert_eq!(
+        unsafe { ssv_writer_write_row(writer, row.as_ptr(), row.len(), ptr::null_mut()) },
+        SsvStatus::Ok
+    );
+    assert_eq!(
+        unsafe { ssv_writer_write_row(writer, ptr::null(), 0, ptr::null_mut()) },
+        SsvStatus::Ok
+    );
+    assert_eq!(
+        unsafe { ssv_writer_finish(writer, ptr::null_mut()) },
+        SsvStatus::Ok
+    );
+    assert_eq!(
+        unsafe { ssv_writer_write_row(writer, row.as_ptr(), row.len(), ptr::null_mut()) },
+        SsvStatus::Finished
+    );
+
+    let mut data = ptr::null();
+    let mut length = 0;
+    assert_eq!(
+        unsafe { ssv_writer_buffer(writer, &mut data, &mut length) },
+        SsvStatus::Ok
+    );
+    assert_eq!(
+        unsafe { std::slice::from_raw_parts(data, length) },
+        bformat!("a {Q}b c{Q} {Q}{Q}{LF}{LF}")
+    );
+    unsafe { ssv_writer_free(writer) };
+}
+
+#[test]
+fn invalid_arguments() {
+    let mut error = SsvError {
+        status: SsvStatus::Ok,
+        line_number: 0,
+        column_number: 0,
+        os_error: 0,
+    };
+
+    assert!(unsafe { ssv_reader_open_file(ptr::null(), &mut error) }.is_null());
+    assert_eq!(error.status, SsvStatus::InvalidArgument);
+    assert!(unsafe { ssv_reader_from_buffer(ptr::null(), 1, ptr::null_mut()) }.is_null());
+    assert_eq!(
+        unsafe { ssv_reader_next_row(ptr::null_mut(), &mut ptr::null(), &mut 0, ptr::null_mut()) },
+        SsvStatus::InvalidArgument
+    );
+
+    let writer = ssv_writer_new_buffer();
+    let row = [SsvValue {
+        data: ptr::null(),
+        length: 1,
+    }];
+    assert_eq!(
+        unsafe { ssv_writer_write_row(writer, row.as_ptr(), row.len(), &mut error) },
+        SsvStatus::InvalidArgument
+    );
+    unsafe { ssv_writer_free(writer) };
+}
+
+#[test]
+fn io_error() {
+    let path = std::env::temp_dir().join(format!("ssv-ffi-missing-{}/a.ssv", std::process::id()));
+    let path = std::ffi::CString::new(path.to_str().unwrap()).unwrap();
+    let mut error = SsvError {
+        status: SsvStatus::Ok,
+        line_number: 0,
+        column_number: 0,
+        os_error: 0,
+    };
+
+    assert!(unsafe { ssv_reader_open_file(path.as_ptr(), &mut error) }.is_null());
+    assert_eq!(error.status, SsvStatus::IoError);
+    assert_ne!(error.os_error, 0);
+}
+
+#[test]
+fn status_message() {
+    let message = |status| unsafe { CStr::from_ptr(ssv_status_message(status)) };
+
+    assert_eq!(
+        message(SsvStatus::UnpairedQuote as c_int),
+        c"unpaired quote"
+    );
+    assert_eq!(
+        message(SsvStatus::Finished as c_int),
+        c"writer already finished"
+    );
+    assert_eq!(message(2), c"unknown status");
+    assert_eq!(message(-9), c"unknown status");
+}
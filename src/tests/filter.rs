@@ -0,0 +1,235 @@
+use std::ops::Deref;
+
+use crate::engine::columns::ColumnNotFoundError;
+use crate::engine::domain::{BytesDomain, CharsDomain, Domain};
+use crate::engine::filter::{ExpressionError, Filter};
+use crate::engine::reader::Reader;
+use crate::engine::ReadWriteError;
+use crate::tests::support::{LF, Q};
+use crate::{bformat, domain_format};
+
+const INPUT: &str = "name age city\nJohn 35 Paris\nJane 25 \"\"\nBob 40,5\n\"Ann Lee\" 30 Rome\n";
+
+macro_rules! assert_filter {
+    ($expression:literal, [ $($name:literal),* $(,)? ] $(,)?) => {
+        _assert_filter_domain!(BytesDomain, $expression, [ $($name),* ]);
+        _assert_filter_domain!(CharsDomain, $expression, [ $($name),* ]);
+    };
+}
+
+macro_rules! _assert_filter_domain {
+    ($domain:ident, $expression:literal, [ $($name:literal),* ]) => {
+        let filter: Filter<$domain> = $expression.parse().unwrap();
+        let names: Vec<_> = filter
+            .apply(Reader::<$domain, _>::new(INPUT.as_bytes()))
+            .skip(1)
+            .map(|row| row.unwrap()[0].clone())
+            .collect();
+
+        let expected_names: Vec<<$domain as Domain>::String> = domain_format!($domain, [ $($name),* ]);
+        assert_eq!(names, expected_names, "{}", $expression);
+    };
+}
+
+macro_rules! assert_expression_error {
+    ($expression:literal, $expected_error:expr) => {
+        assert_eq!(
+            Filter::<BytesDomain>::parse($expression).err(),
+            Some($expected_error)
+        );
+        assert_eq!(
+            Filter::<CharsDomain>::parse($expression).err(),
+            Some($expected_error)
+        );
+    };
+}
+
+#[test]
+fn numeric_comparisons() {
+    assert_filter!("age > 30", ["John", "Bob"]);
+    assert_filter!("age >= 30", ["John", "Bob", "Ann Lee"]);
+    assert_filter!("age < 30", ["Jane"]);
+    assert_filter!("age <= 30", ["Jane", "Ann Lee"]);
+    assert_filter!("age = 30.0", ["Ann Lee"]);
+    assert_filter!("age == 40.5", ["Bob"]);
+    assert_filter!("age != 35", ["Jane", "Bob", "Ann Lee"]);
+    assert_filter!("age > 1e1", ["John", "Jane", "Bob", "Ann Lee"]);
+    assert_filter!("age > -1", ["John", "Jane", "Bob", "Ann Lee"]);
+}
+
+#[test]
+fn string_comparisons() {
+    assert_filter!("name = \"John\"", ["John"]);
+    assert_filter!("name < \"B\"", ["Ann Lee"]);
+    assert_filter!("city >= \"Paris\"", ["John", "Ann Lee"]);
+    assert_filter!("\"Paris\" = city", ["John"]);
+    assert_filter!("name != city", ["John", "Jane", "Ann Lee"]);
+}
+
+#[test]
+fn glob_matching() {
+    assert_filter!("name ~ \"J*\"", ["John", "Jane"]);
+    assert_filter!("name ~ \"J?n*\"", ["Jane"]);
+    assert_filter!("name ~ \"*n*e*\"", ["Jane", "Ann Lee"]);
+    assert_filter!("name ~ \"*\"", ["John", "Jane", "Bob", "Ann Lee"]);
+    assert_filter!("name ~ \"Jo\"", []);
+    assert_filter!("name !~ \"J*\"", ["Bob", "Ann Lee"]);
+    assert_filter!("city ~ \"*\"", ["John", "Jane", "Ann Lee"]);
+    assert_filter!("age ~ \"40\\,5\"", ["Bob"]);
+}
+
+#[test]
+fn glob_matching_elements() {
+    let input = "name\nñu\n";
+
+    let bytes_filter: Filter<BytesDomain> = "name ~ \"??u\"".parse().unwrap();
+    let rows: Vec<_> = bytes_filter
+        .apply(Reader::<BytesDomain, _>::new(input.as_bytes()))
+        .collect();
+    assert_eq!(rows.len(), 2);
+
+    let chars_filter: Filter<CharsDomain> = "name ~ \"?u\"".parse().unwrap();
+    let rows: Vec<_> = chars_filter
+        .apply(Reader::<CharsDomain, _>::new(input.as_bytes()))
+        .collect();
+    assert_eq!(rows.len(), 2);
+}
+
+#[test]
+fn checks() {
+    assert_filter!("city is null", ["Bob"]);
+    assert_filter!("city is not null", ["John", "Jane", "Ann Lee"]);
+    assert_filter!("city is empty", ["Jane", "Bob"]);
+    assert_filter!("city is not empty", ["John", "Ann Lee"]);
+}
+
+#[test]
+fn boolean_logic() {
+    assert_filter!("age > 30 and name ~ \"J*\"", ["John"]);
+    assert_filter!("age < 30 or city = \"Rome\"", ["Jane", "Ann Lee"]);
+    assert_filter!("not age > 30", ["Jane", "Ann Lee"]);
+    assert_filter!("name = \"Bob\" or name = \"Jane\" and age > 30", ["Bob"]);
+    assert_filter!("(name = \"Bob\" or name = \"Jane\") and age > 30", ["Bob"]);
+    assert_filter!(
+        "(name = \"Bob\" or name = \"Jane\") and not (age > 30)",
+        ["Jane"]
+    );
+    assert_filter!("not not city is null", ["Bob"]);
+}
+
+#[test]
+fn quoted_names_and_strings() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            let input = bformat!("{Q}first name{Q} and{LF}{Q}a{Q}{Q}b{Q} x{LF}c y{LF}");
+
+            let filter: Filter<$domain> = "`first name` = \"a\"\"b\" or `and` = \"y\""
+                .parse()
+                .unwrap();
+            let rows: Vec<_> = filter
+                .apply(Reader::<$domain, _>::new(input.deref()))
+                .map(|row| row.unwrap())
+                .collect();
+
+            assert_eq!(
+                rows,
+                vec![
+                    domain_format!($domain, ["first name", "and"]),
+                    domain_format!($domain, ["a{Q}b", "x"]),
+                    domain_format!($domain, ["c", "y"]),
+                ]
+            );
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
+
+#[test]
+fn matches() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            let filter: Filter<$domain> = "b = 2".parse().unwrap();
+            let rows: Vec<_> = Reader::<$domain, _>::new(b"a b\n1 2\n2 1\n".as_slice())
+                .map(|row| row.unwrap())
+                .collect();
+            let header = &rows[0];
+
+            assert!(filter.matches(header, &rows[1]).unwrap());
+            assert!(!filter.matches(header, &rows[2]).unwrap());
+            assert!(filter.matches(&header[..1], header).is_err());
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
+
+#[test]
+fn transform() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            let mut output = Vec::new();
+
+            let filter: Filter<$domain> = "age > 30".parse().unwrap();
+            filter.transform(INPUT.as_bytes(), &mut output).unwrap();
+
+            assert_eq!(
+                output,
+                bformat!("name age city{LF}John 35 Paris{LF}Bob 40,5{LF}")
+            );
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
+
+#[test]
+fn column_not_found() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            let filter: Filter<$domain> = "age > 1 and country = \"x\"".parse().unwrap();
+            let mut rows = filter.apply(Reader::<$domain, _>::new(INPUT.as_bytes()));
+
+            let error = rows.next().unwrap().unwrap_err();
+            if let ReadWriteError::ColumnNotFound(ColumnNotFoundError(column)) = error {
+                assert_eq!(column, "\"country\"");
+            } else {
+                panic!("wrong error: {:?}", error);
+            }
+            assert!(rows.next().is_none());
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
+
+#[test]
+fn expression_errors() {
+    assert_expression_error!("", ExpressionError::UnexpectedEnd(1));
+    assert_expression_error!("age >", ExpressionError::UnexpectedEnd(6));
+    assert_expression_error!("age > 30 and", ExpressionError::UnexpectedEnd(13));
+    assert_expression_error!("age 30", ExpressionError::UnexpectedToken("30".into(), 5));
+    assert_expression_error!(
+        "age > 30 or or",
+        ExpressionError::UnexpectedToken("or".into(), 13)
+    );
+    assert_expression_error!("(age > 30", ExpressionError::UnexpectedEnd(10));
+    assert_expression_error!("age > 30)", ExpressionError::UnexpectedToken(")".into(), 9));
+    assert_expression_error!(
+        "name ~ age",
+        ExpressionError::UnexpectedToken("age".into(), 8)
+    );
+    assert_expression_error!(
+        "city is full",
+        ExpressionError::UnexpectedToken("full".into(), 9)
+    );
+    assert_expression_error!("ñame & 1", ExpressionError::UnexpectedChar('&', 6));
+    assert_expression_error!("a ! b", ExpressionError::UnexpectedChar('!', 3));
+    assert_expression_error!("a = 1x", ExpressionError::UnexpectedChar('1', 5));
+    assert_expression_error!("a = \"b", ExpressionError::UnclosedQuote(5));
+    assert_expression_error!("`a = b", ExpressionError::UnclosedQuote(1));
+}
@@ -1,7 +1,8 @@
 use crate::engine::domain::{BytesDomain, CharsDomain};
 use crate::engine::fluent_writer::FluentWriter;
+use crate::engine::options::{CommentLineBreakPolicy, QuotingPolicy};
 use crate::engine::{LineBreak, WriteError};
-use crate::tests::support::{CRLF, LF, Q, TAB};
+use crate::tests::support::{CR, CRLF, LF, Q, TAB};
 use crate::{bformat, domain_format};
 
 macro_rules! assert_fluent_write {
@@ -47,7 +48,10 @@ macro_rules! _assert_fluent_write_unwrap {
     (set_default_line_break, $e:expr) => {
         $e
     };
-    (set_always_quoted, $e:expr) => {
+    (set_quoting_policy, $e:expr) => {
+        $e
+    };
+    (set_comment_line_break_policy, $e:expr) => {
         $e
     };
     ($_:ident, $e:expr) => {
@@ -146,6 +150,58 @@ fn write_comment() {
     assert_fluent_write!([write_comment("comment")], "#comment");
 }
 
+#[test]
+fn write_comment_with_line_break() {
+    assert_fluent_write_error!([], write_comment("abc{LF}def"), CommentWithLineBreak);
+    assert_fluent_write_error!([], write_comment("abc{CRLF}def"), CommentWithLineBreak);
+    assert_fluent_write_error!([], write_comment("abc{LF}"), CommentWithLineBreak);
+    assert_fluent_write!([write_comment("abc{CR}def")], "#abc{CR}def");
+}
+
+#[test]
+fn write_comment_with_trailing_cr() {
+    assert_fluent_write!([write_comment("abc{CR}")], "#abc{CR}");
+    assert_fluent_write_error!(
+        [write_comment("abc{CR}")],
+        write_line_break(),
+        CommentWithLineBreak,
+    );
+    assert_fluent_write_error!(
+        [write_comment("abc{CR}")],
+        write_value("def"),
+        CommentWithLineBreak,
+    );
+    assert_fluent_write!(
+        [
+            set_default_line_break(LineBreak::CrLf),
+            write_comment("abc{CR}"),
+            write_value("def"),
+        ],
+        "#abc{CR}{CRLF}def",
+    );
+}
+
+#[test]
+fn write_comment_split_at_line_breaks() {
+    assert_fluent_write!(
+        [
+            set_comment_line_break_policy(CommentLineBreakPolicy::Split),
+            write_value("abc"),
+            write_comment("first{LF}second{CRLF}third{CR}"),
+            write_value("def"),
+        ],
+        "abc{LF}#first{LF}#second{LF}#third{CR}{LF}#{LF}def",
+    );
+    assert_fluent_write!(
+        [
+            set_comment_line_break_policy(CommentLineBreakPolicy::Split),
+            set_default_line_break(LineBreak::CrLf),
+            write_comment("{LF}"),
+        ],
+        "#{CRLF}#",
+    );
+}
+
 #[test]
 fn value_followed_by_value_has_auto_spacing_in_between() {
     assert_fluent_write!([write_value("abc"), write_value("def")], "abc def");
@@ -364,21 +420,32 @@ fn set_default_line_break() {
 }
 
 #[test]
-fn set_always_quoted() {
+fn set_quoting_policy() {
     macro_rules! test_domain {
         ($domain:ident) => {
             let fluent_writer: FluentWriter<$domain, _> = FluentWriter::new(Vec::new());
 
-            assert_eq!(fluent_writer.always_quoted(), false);
-            assert_eq!(fluent_writer.options().always_quoted(), false);
+            assert_eq!(fluent_writer.quoting_policy(), QuotingPolicy::Minimal);
+            assert_eq!(
+                fluent_writer.options().quoting_policy(),
+                QuotingPolicy::Minimal
+            );
 
-            let mut fluent_writer = fluent_writer.set_always_quoted(true);
-            assert_eq!(fluent_writer.always_quoted(), true);
-            assert_eq!(fluent_writer.options().always_quoted(), true);
+            let mut fluent_writer = fluent_writer.set_quoting_policy(QuotingPolicy::Always);
+            assert_eq!(fluent_writer.quoting_policy(), QuotingPolicy::Always);
+            assert_eq!(
+                fluent_writer.options().quoting_policy(),
+                QuotingPolicy::Always
+            );
 
-            fluent_writer.options_mut().set_always_quoted(false);
-            assert_eq!(fluent_writer.always_quoted(), false);
-            assert_eq!(fluent_writer.options().always_quoted(), false);
+            fluent_writer
+                .options_mut()
+                .set_quoting_policy(QuotingPolicy::Never);
+            assert_eq!(fluent_writer.quoting_policy(), QuotingPolicy::Never);
+            assert_eq!(
+                fluent_writer.options().quoting_policy(),
+                QuotingPolicy::Never
+            );
         };
     }
 
@@ -415,40 +482,116 @@ fn default_line_break() {
 }
 
 #[test]
-fn always_quoted() {
-    /*
-       This code should work:
-
-           assert_fluent_write!(
-               [
-                   set_always_quoted(true),
-                   write_value("abc"),
-               ],
-               "{Q}abc{Q}",
-           );
-
-       For some reason, the compiler complains at the `true`, as if it had ignored the first rule in `_domain_arg`.
-
-       The code below was obtained from the above by using the "Inline macro" command in VS Code, and it works.
-    */
-
-    let mut destination = Vec::new();
-    let fluent_writer: FluentWriter<BytesDomain, _> = FluentWriter::new(&mut destination);
-    fluent_writer
-        .set_always_quoted(_domain_arg!(BytesDomain, true))
-        .write_value(_domain_arg!(BytesDomain, write_value, "abc"))
-        .unwrap()
-        .finish()
-        .unwrap();
-    assert_eq!(destination, domain_format!(BytesDomain, "{Q}abc{Q}"));
-
-    let mut destination = Vec::new();
-    let fluent_writer: FluentWriter<CharsDomain, _> = FluentWriter::new(&mut destination);
-    fluent_writer
-        .set_always_quoted(_domain_arg!(CharsDomain, true))
-        .write_value(_domain_arg!(CharsDomain, write_value, "abc"))
-        .unwrap()
-        .finish()
-        .unwrap();
-    assert_eq!(destination, domain_format!(BytesDomain, "{Q}abc{Q}"));
+fn quoting_policy_always() {
+    assert_fluent_write!(
+        [
+            set_quoting_policy(QuotingPolicy::Always),
+            write_value("abc"),
+            write_value("1,5"),
+            write_value("d e"),
+        ],
+        "{Q}abc{Q} {Q}1,5{Q} {Q}d e{Q}",
+    );
+}
+
+#[test]
+fn quoting_policy_non_numeric() {
+    assert_fluent_write!(
+        [
+            set_quoting_policy(QuotingPolicy::NonNumeric),
+            write_value("abc"),
+            write_value("1,5"),
+            write_value("-2.5e3"),
+            write_value("d e"),
+            write_value(""),
+        ],
+        "{Q}abc{Q} 1,5 -2.5e3 {Q}d e{Q} {Q}{Q}",
+    );
+}
+
+#[test]
+fn quoting_policy_never() {
+    assert_fluent_write!(
+        [
+            set_quoting_policy(QuotingPolicy::Never),
+            write_value("abc"),
+            write_quoted_value("d e"),
+        ],
+        "abc {Q}d e{Q}",
+    );
+
+    macro_rules! test_domain {
+        ($domain:ident) => {
+            for value in ["d e", "", "\"", "l\nf"] {
+                let fluent_writer: FluentWriter<$domain, _> = FluentWriter::new(Vec::new());
+                let error = fluent_writer
+                    .set_quoting_policy(QuotingPolicy::Never)
+                    .write_value(&domain_format!($domain, "abc"))
+                    .unwrap()
+                    .write_value(&domain_format!($domain, "{value}"))
+                    .unwrap_err();
+                assert!(matches!(error, WriteError::QuotesRequired), "{error:?}");
+            }
+
+            let fluent_writer: FluentWriter<$domain, _> = FluentWriter::new(Vec::new());
+            let error = fluent_writer
+                .set_quoting_policy(QuotingPolicy::Never)
+                .write_comment(&domain_format!($domain, "comment"))
+                .unwrap()
+                .write_value(&domain_format!($domain, "#not-a-comment"))
+                .unwrap_err();
+            assert!(matches!(error, WriteError::QuotesRequired), "{error:?}");
+        };
+    }
+
+    test_domain!(BytesDomain);
+    test_domain!(CharsDomain);
+}
+
+#[test]
+fn column_quoting_policies() {
+    macro_rules! test_domain {
+        ($domain:ident) => {
+            let mut destination = Vec::new();
+
+            let fluent_writer: FluentWriter<$domain, _> = FluentWriter::new(&mut destination);
+            fluent_writer
+                .set_column_quoting_policy(1, QuotingPolicy::Always)
+                .set_column_quoting_policy(2, QuotingPolicy::NonNumeric)
+                .write_value(&domain_format!($domain, "a"))
+                .unwrap()
+                .write_value(&domain_format!($domain, "b"))
+                .unwrap()
+                .write_spacing(&domain_format!($domain, "  "))
+                .unwrap()
+                .write_value(&domain_format!($domain, "c"))
+                .unwrap()
+                .write_value(&domain_format!($domain, "d"))
+                .unwrap()
+                .write_line_break()
+                .unwrap()
+                .write_value(&domain_format!($domain, "1"))
+                .unwrap()
+                .write_value(&domain_format!($domain, "2"))
+                .unwrap()
+                .write_value(&domain_format!($domain, "3"))
+                .unwrap()
+                .write_comment(&domain_format!($domain, "comment"))
+                .unwrap()
+                .write_value(&domain_format!($domain, "4"))
+                .unwrap()
+                .write_value(&domain_format!($domain, "5"))
+                .unwrap()
+                .finish()
+                .unwrap();
+
+            assert_eq!(
+                destination,
+                bformat!("a {Q}b{Q}  {Q}c{Q} d{LF}1 {Q}2{Q} 3{LF}#comment{LF}4 {Q}5{Q}")
+            );
+        };
+    }
+
+    test_domain!(BytesDomain);
+    test_domain!(CharsDomain);
 }
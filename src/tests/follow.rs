@@ -0,0 +1,172 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::engine::domain::{BytesDomain, CharsDomain};
+use crate::engine::follow::{FollowEvent, Follower};
+use crate::engine::position::Position;
+use crate::engine::ReadError;
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("ssv-follow-{name}-{}.ssv", std::process::id()))
+}
+
+fn append(path: &Path, data: &str) {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .unwrap();
+    file.write_all(data.as_bytes()).unwrap();
+}
+
+fn row(values: &[&str]) -> FollowEvent<CharsDomain> {
+    FollowEvent::Row(values.iter().map(|value| value.to_string()).collect())
+}
+
+#[test]
+fn pending_row_and_quoted_value() {
+    let path = temp_path("pending");
+    std::fs::write(&path, "a b\nc").unwrap();
+    let mut follower = Follower::<CharsDomain>::new(&path);
+
+    assert_eq!(follower.try_next().unwrap(), Some(row(&["a", "b"])));
+    assert_eq!(follower.try_next().unwrap(), None);
+
+    append(&path, " d\n# comment\n\"multi");
+    assert_eq!(follower.try_next().unwrap(), Some(row(&["c", "d"])));
+    assert_eq!(follower.try_next().unwrap(), None);
+
+    append(&path, "\nline\n");
+    assert_eq!(follower.try_next().unwrap(), None);
+
+    append(&path, "\" e\r\n");
+    assert_eq!(
+        follower.try_next().unwrap(),
+        Some(row(&["multi\nline\n", "e"]))
+    );
+    assert_eq!(follower.try_next().unwrap(), None);
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn missing_file() {
+    let path = temp_path("missing");
+    let mut follower = Follower::<BytesDomain>::new(&path);
+
+    assert_eq!(follower.try_next().unwrap(), None);
+
+    append(&path, "a\n");
+    assert_eq!(
+        follower.try_next().unwrap(),
+        Some(FollowEvent::Row(vec![b"a".to_vec()]))
+    );
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn skip_existing() {
+    let path = temp_path("skip");
+    std::fs::write(&path, "a\nb\nc").unwrap();
+    let mut follower = Follower::<CharsDomain>::new(&path).skip_existing(true);
+
+    assert_eq!(follower.try_next().unwrap(), None);
+
+    append(&path, "d\ne\n");
+    assert_eq!(follower.try_next().unwrap(), Some(row(&["cd"])));
+    assert_eq!(follower.try_next().unwrap(), Some(row(&["e"])));
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn truncation() {
+    let path = temp_path("truncation");
+    std::fs::write(&path, "a\nb\n\"c").unwrap();
+    let mut follower = Follower::<CharsDomain>::new(&path);
+
+    assert_eq!(follower.try_next().unwrap(), Some(row(&["a"])));
+    assert_eq!(follower.try_next().unwrap(), Some(row(&["b"])));
+    assert_eq!(follower.try_next().unwrap(), None);
+
+    std::fs::write(&path, "x\n").unwrap();
+    assert_eq!(follower.try_next().unwrap(), Some(FollowEvent::Truncated));
+    assert_eq!(follower.try_next().unwrap(), Some(row(&["x"])));
+    assert_eq!(follower.try_next().unwrap(), None);
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn error_position() {
+    let path = temp_path("error");
+    std::fs::write(&path, "a\n# comment\n").unwrap();
+    let mut follower = Follower::<CharsDomain>::new(&path);
+
+    assert_eq!(follower.try_next().unwrap(), Some(row(&["a"])));
+    assert_eq!(follower.try_next().unwrap(), None);
+
+    append(&path, "\"b\nc\" d\ne\"f\" g\n");
+    assert_eq!(follower.try_next().unwrap(), Some(row(&["b\nc", "d"])));
+    match follower.try_next() {
+        Err(ReadError::UnpairedQuote(position)) => assert_eq!(
+            position,
+            Position {
+                line_number: 5,
+                column_number: 2
+            }
+        ),
+        result => panic!("unexpected result: {result:?}"),
+    }
+    assert!(follower.next().is_none());
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn rotation() {
+    let path = temp_path("rotation");
+    let rotated_path = path.with_extension("1.ssv");
+    std::fs::write(&path, "a\nb").unwrap();
+    let mut follower = Follower::<CharsDomain>::new(&path);
+
+    assert_eq!(follower.try_next().unwrap(), Some(row(&["a"])));
+    assert_eq!(follower.try_next().unwrap(), None);
+
+    std::fs::rename(&path, &rotated_path).unwrap();
+    append(&rotated_path, "c\n");
+    std::fs::write(&path, "d\n").unwrap();
+
+    assert_eq!(follower.try_next().unwrap(), Some(row(&["bc"])));
+    assert_eq!(follower.try_next().unwrap(), Some(FollowEvent::Rotated));
+    assert_eq!(follower.try_next().unwrap(), Some(row(&["d"])));
+    assert_eq!(follower.try_next().unwrap(), None);
+
+    std::fs::remove_file(path).unwrap();
+    std::fs::remove_file(rotated_path).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn rotation_with_incomplete_value() {
+    let path = temp_path("rotation-error");
+    let rotated_path = path.with_extension("1.ssv");
+    std::fs::write(&path, "a \"b\n").unwrap();
+    let mut follower = Follower::<CharsDomain>::new(&path);
+
+    assert_eq!(follower.try_next().unwrap(), None);
+
+    std::fs::rename(&path, &rotated_path).unwrap();
+    std::fs::write(&path, "c\n").unwrap();
+
+    assert_eq!(follower.try_next().unwrap(), Some(row(&["a", "b\n"])));
+    assert_eq!(follower.try_next().unwrap(), Some(FollowEvent::Rotated));
+    assert_eq!(follower.try_next().unwrap(), Some(row(&["c"])));
+    assert_eq!(follower.try_next().unwrap(), None);
+
+    std::fs::remove_file(path).unwrap();
+    std::fs::remove_file(rotated_path).unwrap();
+}
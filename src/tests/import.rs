@@ -0,0 +1,158 @@
+use crate::bformat;
+use crate::engine::domain::{BytesDomain, CharsDomain};
+use crate::engine::import::{FixedWidthImporter, ImportError, MarkdownImporter};
+use crate::engine::reader::Reader;
+use crate::engine::render::{Renderer, TableFormat};
+use crate::tests::support::{LF, Q};
+
+macro_rules! assert_import {
+    ($importer_type:ident, $importer:expr, $input:expr, $expected_output:literal $(,)?) => {
+        _assert_import_domain!(
+            BytesDomain,
+            $importer_type,
+            $importer,
+            $input,
+            $expected_output
+        );
+        _assert_import_domain!(
+            CharsDomain,
+            $importer_type,
+            $importer,
+            $input,
+            $expected_output
+        );
+    };
+}
+
+macro_rules! _assert_import_domain {
+    ($domain:ident, $importer_type:ident, $importer:expr, $input:expr, $expected_output:literal) => {
+        let importer: $importer_type<$domain> = $importer;
+        let mut output = Vec::new();
+
+        importer.transform($input.as_bytes(), &mut output).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            String::from_utf8(bformat!($expected_output)).unwrap()
+        );
+    };
+}
+
+#[test]
+fn markdown() {
+    assert_import!(
+        MarkdownImporter,
+        MarkdownImporter::new(),
+        "# Title\n\
+         \n\
+         Name | Age\n\
+         :--- | :-:\n\
+         John | 53\n\
+         Mary\n\
+         \n\
+         | other | table |\n\
+         |-------|-------|\n\
+         | x     | y     |\n",
+        "Name Age{LF}John 53{LF}Mary {Q}{Q}{LF}",
+    );
+}
+
+#[test]
+fn markdown_escaping() {
+    assert_import!(
+        MarkdownImporter,
+        MarkdownImporter::new(),
+        "| a | b | c |\n\
+         |---|---|---|\n\
+         | x \\| y | \\*z\\* | 1<br>2<BR/>3 |\n\
+         | `\\|` | C:\\path | extra | ignored |\n",
+        "a b c{LF}\
+         {Q}x | y{Q} *z* {Q}1{LF}2{LF}3{Q}{LF}\
+         `|` C:\\path extra{LF}",
+    );
+}
+
+#[test]
+fn markdown_table_not_found() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            for input in ["", "a | b\n", "a | b\n- | x\n", "title\n---\n"] {
+                let error = MarkdownImporter::<$domain>::new()
+                    .read(input.as_bytes())
+                    .unwrap_err();
+                assert!(
+                    matches!(error, ImportError::TableNotFound),
+                    "wrong error: {:?}",
+                    error
+                );
+            }
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
+
+#[test]
+fn markdown_roundtrip() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            for header in [false, true] {
+                let input = "a b|c \"d\ne\"\n12 [x] \\\\\n";
+                let mut markdown = Vec::new();
+                Renderer::<$domain>::new(TableFormat::Markdown)
+                    .with_header(header)
+                    .render(Reader::<$domain, _>::new(input.as_bytes()), &mut markdown)
+                    .unwrap();
+
+                let mut output = Vec::new();
+                MarkdownImporter::<$domain>::new()
+                    .transform(markdown.as_slice(), &mut output)
+                    .unwrap();
+                assert_eq!(String::from_utf8(output).unwrap(), input);
+            }
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
+
+#[test]
+fn fixed_width_detected() {
+    assert_import!(
+        FixedWidthImporter,
+        FixedWidthImporter::new(),
+        "id name   city\n\
+         \n\
+         1  John   Paris\n\
+         22 Marie  \n\
+         3         Oslo\n",
+        "id name city{LF}1 John Paris{LF}22 Marie {Q}{Q}{LF}3 {Q}{Q} Oslo{LF}",
+    );
+    assert_import!(
+        FixedWidthImporter,
+        FixedWidthImporter::new(),
+        "  Name      Qty\n\
+         ==========  ===\n\
+         Jane Doe      7\n\
+         +--+\n",
+        "Name Qty{LF}{Q}Jane Doe{Q} 7{LF}",
+    );
+}
+
+#[test]
+fn fixed_width_explicit() {
+    assert_import!(
+        FixedWidthImporter,
+        FixedWidthImporter::new().with_boundaries([5, 0]),
+        "AB123XYZ  9\nCD45\nÉF678é\n",
+        "AB123 {Q}XYZ  9{Q}{LF}CD45 {Q}{Q}{LF}ÉF678 é{LF}",
+    );
+    assert_import!(
+        FixedWidthImporter,
+        FixedWidthImporter::new().with_boundaries([2, 5]),
+        "xxabcdef\n",
+        "abc def{LF}",
+    );
+}
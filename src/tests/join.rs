@@ -0,0 +1,293 @@
+use crate::engine::domain::{BytesDomain, CharsDomain};
+use crate::engine::join::{JoinError, JoinKind, JoinStrategy, Joiner, Side};
+use crate::engine::ReadWriteError;
+use crate::tests::support::{LF, Q};
+use crate::{bformat, domain_format, domain_format_ref};
+
+const CUSTOMERS: &str = "id name\n1 John\n2 Jane\n4 Ann\n";
+const ORDERS: &str = "order id\nA 1\nB 3\nC 1\nD 2\n";
+const SORTED_ORDERS: &str = "order id\nA 1\nC 1\nD 2\nB 3\n";
+
+macro_rules! assert_join {
+    ($joiner:expr, $left:expr, $right:expr, $expected_output:literal $(,)?) => {
+        _assert_join_domain!(BytesDomain, $joiner, $left, $right, $expected_output);
+        _assert_join_domain!(CharsDomain, $joiner, $left, $right, $expected_output);
+    };
+}
+
+macro_rules! _assert_join_domain {
+    ($domain:ident, $joiner:expr, $left:expr, $right:expr, $expected_output:literal) => {
+        let joiner: Joiner<$domain> = $joiner;
+        let mut output = Vec::new();
+
+        joiner
+            .join($left.as_bytes(), $right.as_bytes(), &mut output)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            String::from_utf8(bformat!($expected_output)).unwrap()
+        );
+    };
+}
+
+#[test]
+fn hash_join() {
+    assert_join!(
+        Joiner::new(JoinKind::Inner)
+            .with_header(true)
+            .on("id", "id"),
+        CUSTOMERS,
+        ORDERS,
+        "id name order{LF}1 John A{LF}1 John C{LF}2 Jane D{LF}",
+    );
+    assert_join!(
+        Joiner::new(JoinKind::Left).with_header(true).on("id", "id"),
+        CUSTOMERS,
+        ORDERS,
+        "id name order{LF}1 John A{LF}1 John C{LF}2 Jane D{LF}4 Ann {Q}{Q}{LF}",
+    );
+    assert_join!(
+        Joiner::new(JoinKind::FullOuter)
+            .with_header(true)
+            .on("id", "id"),
+        CUSTOMERS,
+        ORDERS,
+        "id name order{LF}1 John A{LF}1 John C{LF}2 Jane D{LF}4 Ann {Q}{Q}{LF}3 {Q}{Q} B{LF}",
+    );
+}
+
+#[test]
+fn merge_join() {
+    assert_join!(
+        Joiner::new(JoinKind::Inner)
+            .with_strategy(JoinStrategy::Merge)
+            .with_header(true)
+            .on("id", "id"),
+        CUSTOMERS,
+        SORTED_ORDERS,
+        "id name order{LF}1 John A{LF}1 John C{LF}2 Jane D{LF}",
+    );
+    assert_join!(
+        Joiner::new(JoinKind::Left)
+            .with_strategy(JoinStrategy::Merge)
+            .with_header(true)
+            .on("id", "id"),
+        CUSTOMERS,
+        SORTED_ORDERS,
+        "id name order{LF}1 John A{LF}1 John C{LF}2 Jane D{LF}4 Ann {Q}{Q}{LF}",
+    );
+    assert_join!(
+        Joiner::new(JoinKind::FullOuter)
+            .with_strategy(JoinStrategy::Merge)
+            .with_header(true)
+            .on("id", "id"),
+        CUSTOMERS,
+        SORTED_ORDERS,
+        "id name order{LF}1 John A{LF}1 John C{LF}2 Jane D{LF}3 {Q}{Q} B{LF}4 Ann {Q}{Q}{LF}",
+    );
+    assert_join!(
+        Joiner::new(JoinKind::Inner)
+            .with_strategy(JoinStrategy::Merge)
+            .on(0, 0),
+        "a 1\na 2\nb 3\n",
+        "a x\na y\nc z\n",
+        "a 1 x{LF}a 1 y{LF}a 2 x{LF}a 2 y{LF}",
+    );
+}
+
+#[test]
+fn multiple_keys_by_index() {
+    for strategy in [JoinStrategy::Hash, JoinStrategy::Merge] {
+        assert_join!(
+            Joiner::new(JoinKind::FullOuter)
+                .with_strategy(strategy)
+                .on(0, 1)
+                .on(1, 2),
+            "a 1 x\na 2 y\nb 1 z\n",
+            "P a 1\nQ b 2\n",
+            "a 1 x P{LF}a 2 y {Q}{Q}{LF}b 1 z {Q}{Q}{LF}b 2 {Q}{Q} Q{LF}",
+        );
+    }
+}
+
+#[test]
+fn name_collisions() {
+    assert_join!(
+        Joiner::new(JoinKind::Inner)
+            .with_header(true)
+            .on("id", "key"),
+        "id name\n1 John\n",
+        "key name id\n1 Doe x\n",
+        "id_left name_left name_right id_right{LF}1 John Doe x{LF}",
+    );
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            let mut output = Vec::new();
+            Joiner::<$domain>::new(JoinKind::Inner)
+                .with_header(true)
+                .with_suffixes(
+                    domain_format_ref!($domain, ".1"),
+                    domain_format_ref!($domain, ".2"),
+                )
+                .on("id", "id")
+                .join(
+                    "id name\n1 John\n".as_bytes(),
+                    "id name\n1 Doe\n".as_bytes(),
+                    &mut output,
+                )
+                .unwrap();
+            assert_eq!(output, bformat!("id name.1 name.2{LF}1 John Doe{LF}"));
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
+
+#[test]
+fn missing_values() {
+    assert_join!(
+        Joiner::new(JoinKind::Left).on(1, 0),
+        "a\nb x\n",
+        "\"\" 1\nx 2\n",
+        "a {Q}{Q} 1{LF}b x 2{LF}",
+    );
+}
+
+#[test]
+fn empty_inputs() {
+    assert_join!(
+        Joiner::new(JoinKind::FullOuter)
+            .with_header(true)
+            .on("id", "id"),
+        "",
+        ORDERS,
+        "id order{LF}1 A{LF}3 B{LF}1 C{LF}2 D{LF}",
+    );
+    assert_join!(
+        Joiner::new(JoinKind::FullOuter)
+            .with_header(true)
+            .on("id", "id"),
+        CUSTOMERS,
+        "",
+        "id name{LF}1 John{LF}2 Jane{LF}4 Ann{LF}",
+    );
+    assert_join!(Joiner::new(JoinKind::FullOuter).on(0, 0), "", "", "");
+}
+
+#[test]
+fn comments_are_skipped() {
+    assert_join!(
+        Joiner::new(JoinKind::Inner)
+            .with_header(true)
+            .on("id", "id"),
+        "# c\nid name\n# c\n1 John\n",
+        "id order\n# c\n1 A\n",
+        "id name order{LF}1 John A{LF}",
+    );
+}
+
+#[test]
+fn unsorted_input() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            for (left, right, expected_side, expected_line) in [
+                ("b\na\n", "a\n", Side::Left, 2),
+                ("a\n", "a\nc\n# c\nb\n", Side::Right, 4),
+            ] {
+                let error = Joiner::<$domain>::new(JoinKind::Inner)
+                    .with_strategy(JoinStrategy::Merge)
+                    .on(0, 0)
+                    .join(left.as_bytes(), right.as_bytes(), Vec::new())
+                    .unwrap_err();
+                if let JoinError::UnsortedInput(side, position) = error {
+                    assert_eq!(side, expected_side);
+                    assert_eq!(position.line_number, expected_line);
+                } else {
+                    panic!("wrong error: {:?}", error);
+                }
+            }
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
+
+#[test]
+fn too_many_values() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            for strategy in [JoinStrategy::Hash, JoinStrategy::Merge] {
+                for (left, right, expected_side, expected_line) in [
+                    ("1 a\n2 b c\n", "1 x\n2 y\n", Side::Left, 2),
+                    ("1 a\n2 b\n", "1 x\n# c\n2 y z\n", Side::Right, 3),
+                ] {
+                    let error = Joiner::<$domain>::new(JoinKind::Inner)
+                        .with_strategy(strategy)
+                        .on(0, 0)
+                        .join(left.as_bytes(), right.as_bytes(), Vec::new())
+                        .unwrap_err();
+                    if let JoinError::TooManyValues(side, position) = error {
+                        assert_eq!(side, expected_side);
+                        assert_eq!(position.line_number, expected_line);
+                    } else {
+                        panic!("wrong error: {:?}", error);
+                    }
+                }
+            }
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
+
+#[test]
+fn no_keys() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            let error = Joiner::<$domain>::new(JoinKind::Inner)
+                .join(CUSTOMERS.as_bytes(), ORDERS.as_bytes(), Vec::new())
+                .unwrap_err();
+            assert!(
+                matches!(error, JoinError::NoKeys),
+                "wrong error: {:?}",
+                error
+            );
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
+
+#[test]
+fn column_not_found() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            for joiner in [
+                Joiner::<$domain>::new(JoinKind::Inner)
+                    .with_header(true)
+                    .on("id", "x"),
+                Joiner::<$domain>::new(JoinKind::Inner).on("id", 0),
+            ] {
+                let error = joiner
+                    .join(CUSTOMERS.as_bytes(), ORDERS.as_bytes(), Vec::new())
+                    .unwrap_err();
+                assert!(
+                    matches!(
+                        error,
+                        JoinError::ReadWriteError(ReadWriteError::ColumnNotFound(_))
+                    ),
+                    "wrong error: {:?}",
+                    error
+                );
+            }
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
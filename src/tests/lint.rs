@@ -0,0 +1,235 @@
+use crate::bformat;
+use crate::engine::domain::{BytesDomain, CharsDomain};
+use crate::engine::lint::{Linter, Rule, RuleSet};
+use crate::tests::support::{CR, CRLF, LF, Q, TAB};
+
+macro_rules! assert_findings {
+    ($input:expr, [ $( ($rule:ident, $line_number:literal, $column_number:literal) ),* $(,)? ] $(,)?) => {
+        assert_findings!(Linter::new(), $input, [ $( ($rule, $line_number, $column_number) ),* ]);
+    };
+    ($linter:expr, $input:expr, [ $( ($rule:ident, $line_number:literal, $column_number:literal) ),* $(,)? ] $(,)?) => {
+        let input = bformat!($input);
+        let expected = vec![ $( (Rule::$rule, $line_number, $column_number) ),* ];
+
+        let linter: Linter<BytesDomain> = $linter;
+        _assert_findings_domain!(linter, input, expected);
+        let linter: Linter<CharsDomain> = $linter;
+        _assert_findings_domain!(linter, input, expected);
+    };
+}
+
+macro_rules! _assert_findings_domain {
+    ($linter:ident, $input:ident, $expected:ident) => {
+        let findings: Vec<_> = $linter
+            .lint($input.as_slice())
+            .unwrap()
+            .into_iter()
+            .map(|finding| {
+                (
+                    finding.rule,
+                    finding.position.line_number,
+                    finding.position.column_number,
+                )
+            })
+            .collect();
+        assert_eq!(findings, $expected);
+    };
+}
+
+macro_rules! assert_fix {
+    ($input:expr, $expected_output:expr, [ $( $remaining_rule:ident ),* $(,)? ] $(,)?) => {
+        let input = bformat!($input);
+        let expected_output = bformat!($expected_output);
+        let expected_remaining: Vec<Rule> = vec![ $( Rule::$remaining_rule ),* ];
+
+        _assert_fix_domain!(BytesDomain, input, expected_output, expected_remaining);
+        _assert_fix_domain!(CharsDomain, input, expected_output, expected_remaining);
+    };
+}
+
+macro_rules! _assert_fix_domain {
+    ($domain:ident, $input:ident, $expected_output:ident, $expected_remaining:ident) => {
+        let linter: Linter<$domain> = Linter::new();
+        let mut output = Vec::new();
+
+        let remaining = linter.fix($input.as_slice(), &mut output).unwrap();
+
+        assert_eq!(output, $expected_output);
+        let remaining: Vec<_> = remaining.into_iter().map(|finding| finding.rule).collect();
+        assert_eq!(remaining, $expected_remaining);
+
+        let findings = linter.lint(output.as_slice()).unwrap();
+        assert!(findings.iter().all(|finding| !finding.rule.is_fixable()));
+    };
+}
+
+#[test]
+fn no_findings() {
+    assert_findings!("", []);
+    assert_findings!("abc {Q}d e{Q}{LF}# comment {LF}ghi jkl{LF}", []);
+}
+
+#[test]
+fn mixed_line_breaks() {
+    assert_findings!(
+        "abc{LF}def{CRLF}ghi{LF}jkl{CRLF}",
+        [(MixedLineBreaks, 2, 4), (MixedLineBreaks, 4, 4)],
+    );
+    assert_findings!("abc{CRLF}def{LF}", [(MixedLineBreaks, 2, 4)]);
+}
+
+#[test]
+fn trailing_spacing() {
+    assert_findings!(
+        "abc {LF}def  {LF}  {LF}ghi ",
+        [
+            (TrailingSpacing, 1, 4),
+            (TrailingSpacing, 2, 4),
+            (TrailingSpacing, 3, 1),
+            (InconsistentValueCount, 3, 1),
+            (TrailingSpacing, 4, 4),
+        ],
+    );
+}
+
+#[test]
+fn mixed_spacing() {
+    assert_findings!(
+        "a b{LF}c{TAB}d{LF}e {TAB}f{LF}",
+        [(MixedSpacing, 2, 2), (MixedSpacing, 3, 2)],
+    );
+    assert_findings!("a{TAB}b{LF}c {TAB}d{LF}", [(MixedSpacing, 2, 2)]);
+}
+
+#[test]
+fn unnecessary_quotes() {
+    assert_findings!(
+        "{Q}abc{Q} {Q}d e{Q} {Q}{Q} {Q}{Q}{Q}{Q}{LF}{Q}#abc{Q} {Q}#def{Q} x y{LF}",
+        [(UnnecessaryQuotes, 1, 1), (UnnecessaryQuotes, 2, 8)],
+    );
+}
+
+#[test]
+fn inconsistent_value_count() {
+    assert_findings!(
+        "a b c{LF}# comment{LF}d e f{LF}g h{LF}{LF}i j k l",
+        [
+            (InconsistentValueCount, 4, 1),
+            (InconsistentValueCount, 5, 1),
+            (InconsistentValueCount, 6, 1),
+        ],
+    );
+}
+
+#[test]
+fn non_ascii_spacing() {
+    assert_findings!(
+        "abc d\u{A0}e{LF}f\u{2003}g h{LF}",
+        [(NonAsciiSpacing, 1, 5), (NonAsciiSpacing, 2, 1)]
+    );
+}
+
+#[test]
+fn disabled_rules() {
+    assert_findings!(
+        Linter::new(),
+        "{Q}abc{Q} {LF}def{CRLF}",
+        [
+            (UnnecessaryQuotes, 1, 1),
+            (TrailingSpacing, 1, 6),
+            (MixedLineBreaks, 2, 4),
+        ],
+    );
+
+    assert_findings!(
+        {
+            let mut linter = Linter::new();
+            linter.rules_mut().disable(Rule::TrailingSpacing);
+            linter
+        },
+        "{Q}abc{Q} {LF}def{CRLF}",
+        [(UnnecessaryQuotes, 1, 1), (MixedLineBreaks, 2, 4)],
+    );
+
+    assert_findings!(
+        {
+            let mut linter = Linter::new();
+            linter.set_rules(RuleSet::from_iter([Rule::MixedLineBreaks]));
+            linter
+        },
+        "{Q}abc{Q} {LF}def{CRLF}",
+        [(MixedLineBreaks, 2, 4)],
+    );
+}
+
+#[test]
+fn rule_ids() {
+    for rule in Rule::ALL {
+        assert_eq!(Rule::from_id(rule.id()), Some(rule));
+        assert_eq!(rule.to_string(), rule.id());
+    }
+    assert_eq!(Rule::from_id("unknown"), None);
+}
+
+#[test]
+fn fix() {
+    assert_fix!(
+        "{Q}abc{Q}  {TAB}{Q}d e{Q} {CRLF}# comment{LF}f{TAB}g  {LF}",
+        "abc     {Q}d e{Q}{CRLF}# comment{CRLF}f       g{CRLF}",
+        [],
+    );
+}
+
+#[test]
+fn fix_expands_tabs_from_output_column() {
+    assert_fix!(
+        "a b{LF}{Q}c{Q}{TAB}d{LF}{Q}{Q}{Q}{Q}{TAB}e{LF}",
+        "a b{LF}c       d{LF}{Q}{Q}{Q}{Q}    e{LF}",
+        [],
+    );
+}
+
+#[test]
+fn fix_keeps_consistent_tabs() {
+    assert_fix!(
+        "a{TAB}b{LF}c{TAB}{TAB}d{LF}",
+        "a{TAB}b{LF}c{TAB}{TAB}d{LF}",
+        []
+    );
+    assert_fix!(
+        "a{TAB}b{LF}c d{LF}efghijk  l{LF}",
+        "a{TAB}b{LF}c{TAB}d{LF}efghijk{TAB}l{LF}",
+        []
+    );
+}
+
+#[test]
+fn fix_keeps_unfixable_findings() {
+    assert_fix!(
+        "a b{LF}c d e {LF}f\u{A0}g h",
+        "a b{LF}c d e{LF}f\u{A0}g h",
+        [InconsistentValueCount, NonAsciiSpacing],
+    );
+}
+
+#[test]
+fn fix_keeps_comments_ending_with_cr() {
+    assert_fix!("a b{LF}# c{CR}", "a b{LF}# c{CR}", []);
+    assert_fix!(
+        "a b{CRLF}# c{CR}{CRLF}d e{CRLF}",
+        "a b{CRLF}# c{CR}{CRLF}d e{CRLF}",
+        [],
+    );
+
+    let input = bformat!("a b{LF}# c{CR}{CRLF}d e{CRLF}");
+    let mut output = Vec::new();
+    let remaining = Linter::<CharsDomain>::new()
+        .fix(input.as_slice(), &mut output)
+        .unwrap();
+    assert_eq!(output, bformat!("a b{LF}# c{CR}{CRLF}d e{LF}"));
+    let remaining: Vec<_> = remaining
+        .into_iter()
+        .map(|finding| (finding.rule, finding.position.line_number))
+        .collect();
+    assert_eq!(remaining, [(Rule::MixedLineBreaks, 2)]);
+}
@@ -0,0 +1,153 @@
+use crate::bformat;
+use crate::engine::columns::ColumnNotFoundError;
+use crate::engine::domain::{BytesDomain, CharsDomain};
+use crate::engine::mapping::{
+    parse_optional_value, parse_value, FieldColumn, FieldError, FromSsvRow, MappingError,
+    StructReader, StructWriter, ToSsvRow,
+};
+use crate::engine::options::QuotingPolicy;
+use crate::engine::{ReadError, ReadWriteError};
+use crate::tests::support::{LF, Q};
+
+#[derive(PartialEq, Debug)]
+struct Entry {
+    key: String,
+    count: Option<u32>,
+}
+
+impl FromSsvRow for Entry {
+    const COLUMNS: &'static [FieldColumn] = &[
+        FieldColumn {
+            field: "key",
+            name: Some("key"),
+            required: true,
+        },
+        FieldColumn {
+            field: "count",
+            name: Some("count"),
+            required: false,
+        },
+    ];
+
+    fn from_ssv_values(values: &[Option<&str>]) -> Result<Self, FieldError> {
+        Ok(Entry {
+            key: parse_value("key", values[0])?,
+            count: parse_optional_value("count", values[1])?,
+        })
+    }
+}
+
+impl ToSsvRow for Entry {
+    const HEADER: &'static [&'static str] = &["key", "count"];
+    const QUOTING_POLICIES: &'static [Option<QuotingPolicy>] = &[Some(QuotingPolicy::Always), None];
+
+    fn to_ssv_values(&self) -> Vec<String> {
+        vec![
+            self.key.clone(),
+            self.count
+                .map(|count| count.to_string())
+                .unwrap_or_default(),
+        ]
+    }
+}
+
+fn entry(key: &str, count: Option<u32>) -> Entry {
+    Entry {
+        key: key.to_owned(),
+        count,
+    }
+}
+
+#[test]
+fn struct_reader() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            let input = bformat!("count key{LF}1 a{LF}{Q}{Q} {Q}b c{Q}{LF}");
+
+            let entries: Vec<_> = StructReader::<$domain, _, Entry>::new(input.as_slice())
+                .with_header(true)
+                .map(Result::unwrap)
+                .collect();
+
+            assert_eq!(entries, [entry("a", Some(1)), entry("b c", None)]);
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
+
+#[test]
+fn struct_reader_optional_column_not_found() {
+    let input = bformat!("key{LF}a{LF}");
+
+    let entries: Vec<_> = StructReader::<CharsDomain, _, Entry>::new(input.as_slice())
+        .with_header(true)
+        .map(Result::unwrap)
+        .collect();
+
+    assert_eq!(entries, [entry("a", None)]);
+}
+
+#[test]
+fn struct_reader_errors() {
+    let input = [
+        bformat!("key count{LF}a x{LF}{Q}{Q}{LF}"),
+        vec![0xFF],
+        bformat!("{LF}b 2{LF}c {Q}"),
+    ]
+    .concat();
+
+    let results: Vec<_> = StructReader::<BytesDomain, _, Entry>::new(input.as_slice())
+        .with_header(true)
+        .collect();
+
+    assert!(matches!(
+        &results[..],
+        [
+            Err(MappingError::FieldError(
+                2,
+                FieldError::InvalidValue("count", _)
+            )),
+            Ok(_),
+            Err(MappingError::InvalidUtf8(4)),
+            Ok(_),
+            Err(MappingError::ReadWriteError(ReadWriteError::ReadError(
+                ReadError::UnclosedQuotedValue(_)
+            ))),
+        ]
+    ));
+    assert_eq!(results[1].as_ref().unwrap(), &entry("", None));
+
+    let mut reader = StructReader::<CharsDomain, _, Entry>::new(input.as_slice());
+    assert!(matches!(
+        reader.next(),
+        Some(Err(MappingError::ReadWriteError(ReadWriteError::ColumnNotFound(
+            ColumnNotFoundError(column)
+        )))) if column == "\"key\""
+    ));
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn struct_writer() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            let mut output = Vec::new();
+
+            let mut writer = StructWriter::<$domain, _, Entry>::new(&mut output).with_header(true);
+            writer
+                .write_all(&[entry("a", Some(1)), entry("b c", None)])
+                .unwrap();
+            writer.finish().unwrap();
+
+            assert_eq!(
+                output,
+                bformat!("key count{LF}{Q}a{Q} 1{LF}{Q}b c{Q} {Q}{Q}{LF}")
+            );
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
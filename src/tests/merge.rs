@@ -0,0 +1,243 @@
+use crate::bformat;
+use crate::engine::domain::{BytesDomain, CharsDomain};
+use crate::engine::merge::Merger;
+use crate::engine::ReadWriteError;
+use crate::tests::support::{CR, CRLF, LF, Q};
+
+const BASE: &str = "id name city\n1 John Paris\n2 Jane Rome\n3 Bob Oslo\n";
+
+macro_rules! assert_merge {
+    ($merger:expr, $base:expr, $ours:expr, $theirs:expr, $expected_output:literal, $expected_conflicts:expr $(,)?) => {
+        _assert_merge_domain!(
+            BytesDomain,
+            $merger,
+            $base,
+            $ours,
+            $theirs,
+            $expected_output,
+            $expected_conflicts
+        );
+        _assert_merge_domain!(
+            CharsDomain,
+            $merger,
+            $base,
+            $ours,
+            $theirs,
+            $expected_output,
+            $expected_conflicts
+        );
+    };
+}
+
+macro_rules! _assert_merge_domain {
+    ($domain:ident, $merger:expr, $base:expr, $ours:expr, $theirs:expr, $expected_output:literal, $expected_conflicts:expr) => {
+        let merger: Merger<$domain> = $merger;
+        let mut output = Vec::new();
+
+        let conflicts = merger
+            .merge(
+                $base.as_bytes(),
+                $ours.as_bytes(),
+                $theirs.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            String::from_utf8(bformat!($expected_output)).unwrap()
+        );
+        assert_eq!(conflicts, $expected_conflicts);
+    };
+}
+
+#[test]
+fn cells_changed_on_different_sides() {
+    assert_merge!(
+        Merger::new().with_header(true).key("id"),
+        BASE,
+        "id   name  city\n1 Johnny Paris\n2 Jane Rome\n3 Bob Oslo\n",
+        "id name city\n1 John Lyon\n2 Jane Rome\n3 Bob \"Oslo\"\n",
+        "id name   city{LF}\
+         1  Johnny Lyon{LF}\
+         2  Jane   Rome{LF}\
+         3  Bob    Oslo{LF}",
+        0,
+    );
+}
+
+#[test]
+fn same_cell_changed_on_both_sides() {
+    assert_merge!(
+        Merger::new().with_header(true).key("id"),
+        BASE,
+        "id name city\n1 John Lyon\n2 Jane Rome\n3 Bob Oslo\n",
+        "id name city\n1 Jon Nice\n2 Jane Milan\n3 Bob Oslo\n",
+        "id name city{LF}\
+         <<<<<<< ours{LF}\
+         1  Jon  Lyon{LF}\
+         ======={LF}\
+         1  Jon  Nice{LF}\
+         >>>>>>> theirs{LF}\
+         2  Jane Milan{LF}\
+         3  Bob  Oslo{LF}",
+        1,
+    );
+    assert_merge!(
+        Merger::new().with_header(true).key("id"),
+        BASE,
+        "id name city\n1 John Lyon\n",
+        "id name city\n1 John Lyon\n",
+        "id name city{LF}1  John Lyon{LF}",
+        0,
+    );
+}
+
+#[test]
+fn added_and_deleted_rows() {
+    assert_merge!(
+        Merger::new().with_header(true).key("id"),
+        BASE,
+        "id name city\n0 Ann Nice\n1 John Paris\n3 Bob Oslo\n",
+        "id name city\n1 John Paris\n4 Eve Rome\n2 Jane Rome\n5 Max Lima\n",
+        "id name city{LF}\
+         0  Ann  Nice{LF}\
+         1  John Paris{LF}\
+         4  Eve  Rome{LF}\
+         5  Max  Lima{LF}",
+        0,
+    );
+    assert_merge!(
+        Merger::new().with_header(true).key("id"),
+        BASE,
+        "id name city\n1 John Paris\n3 Bob Oslo\n",
+        "id name city\n1 John Paris\n2 Jane Milan\n3 Bob Oslo\n",
+        "id name city{LF}\
+         1  John Paris{LF}\
+         <<<<<<< ours{LF}\
+         ======={LF}\
+         2  Jane Milan{LF}\
+         >>>>>>> theirs{LF}\
+         3  Bob  Oslo{LF}",
+        1,
+    );
+    assert_merge!(
+        Merger::new().with_header(true).key("id"),
+        "",
+        "id name\n1 John\n",
+        "id name\n2 Jane\n",
+        "id name{LF}1  John{LF}2  Jane{LF}",
+        0,
+    );
+}
+
+#[test]
+fn columns() {
+    assert_merge!(
+        Merger::new().key(0),
+        "1 a b\n2 c d\n",
+        "1 a b x\n2 c d y\n",
+        "1 a\n2 C d\n",
+        "1 a {Q}{Q} x{LF}2 C d  y{LF}",
+        0,
+    );
+}
+
+#[test]
+fn without_keys() {
+    assert_merge!(
+        Merger::new(),
+        "a 1\nb 2\n",
+        "a 1\nc 3\n",
+        "b 2\na 1\nd 4\n",
+        "a 1{LF}c 3{LF}d 4{LF}",
+        0,
+    );
+}
+
+#[test]
+fn comments() {
+    assert_merge!(
+        Merger::new().key(0),
+        "a 1\nb 2\n",
+        "# first\na 1\n# second\nb 2\n# last\n",
+        "a 1\n",
+        "# first{LF}a 1{LF}# last{LF}",
+        0,
+    );
+
+    // Comments changed on a single side, and comments of added rows
+    assert_merge!(
+        Merger::new().key(0),
+        "# first\na 1\nb 2\n# last\n",
+        "# first\na 1\nb 2\n# last\n",
+        "# changed\na 1\n# added\nc 3\nb 2\n",
+        "# changed{LF}a 1{LF}# added{LF}c 3{LF}b 2{LF}",
+        0,
+    );
+
+    // Comments changed differently on both sides
+    assert_merge!(
+        Merger::new().key(0),
+        "# first\na 1\n# last\n",
+        "# ours\na 1\n# last\n",
+        "# theirs\na 1\n",
+        "<<<<<<< ours{LF}# ours{LF}======={LF}# theirs{LF}>>>>>>> theirs{LF}a 1{LF}",
+        1,
+    );
+}
+
+#[test]
+fn values_starting_with_hash() {
+    let input = "1 #x z\n22 y z\n\"#a\" 1 z\n";
+    assert_merge!(
+        Merger::new(),
+        input,
+        input,
+        input,
+        "1    #x z{LF}22   y  z{LF}{Q}#a{Q} 1  z{LF}",
+        0,
+    );
+}
+
+#[test]
+fn comments_ending_with_cr() {
+    let input = "a b\n# c\r";
+    assert_merge!(
+        Merger::new(),
+        input,
+        input,
+        input,
+        "a b{LF}# c{CR}{CRLF}",
+        0,
+    );
+}
+
+#[test]
+fn column_not_found() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            for (merger, input) in [
+                (Merger::<$domain>::new().with_header(true).key("x"), BASE),
+                (Merger::<$domain>::new().key("id"), BASE),
+            ] {
+                let error = merger
+                    .merge(
+                        input.as_bytes(),
+                        input.as_bytes(),
+                        input.as_bytes(),
+                        Vec::new(),
+                    )
+                    .unwrap_err();
+                assert!(
+                    matches!(error, ReadWriteError::ColumnNotFound(_)),
+                    "wrong error: {:?}",
+                    error
+                );
+            }
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
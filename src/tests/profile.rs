@@ -0,0 +1,325 @@
+use crate::engine::domain::{BytesDomain, CharsDomain, Domain, DomainString};
+use crate::engine::position::Position;
+use crate::engine::profile::{
+    ColumnSchema, ColumnType, DecimalSeparator, Profiler, Schema, SchemaError, ViolationKind,
+};
+use crate::engine::reader::Reader;
+use crate::tests::support::LF;
+use crate::{bformat, domain_format};
+
+const INPUT: &str = "\
+id flag amount price date name note\n\
+1 true 10 1.5 2024-01-31 John x\n\
+2 No -3 2 2024-02-29 \"Jane Doe\"\n\
+3 yes +7 -0.25 2023-12-01 John \"\"\n";
+
+#[test]
+fn column_types() {
+    for (values, expected_type) in [
+        ("true\nFALSE\nYes\nno\n", ColumnType::Boolean),
+        ("1\n-2\n+30\n", ColumnType::Integer),
+        (
+            "1\n-2.5\n0.125\n",
+            ColumnType::Decimal(DecimalSeparator::Point),
+        ),
+        (
+            "1\n-2,5\n0,125\n",
+            ColumnType::Decimal(DecimalSeparator::Comma),
+        ),
+        ("2024-02-29\n1999-12-31\n", ColumnType::Date),
+        ("1.5\n2,5\n", ColumnType::Text),
+        ("2023-02-29\n", ColumnType::Text),
+        ("2024-13-01\n", ColumnType::Text),
+        ("1.\n", ColumnType::Text),
+        ("1e3\n", ColumnType::Text),
+        ("\"\"\n", ColumnType::Text),
+        ("1\n\"\"\n2\n", ColumnType::Integer),
+    ] {
+        macro_rules! test_with_domain {
+            ($domain:ident) => {
+                let profile = Profiler::<$domain>::new()
+                    .profile(Reader::<$domain, _>::new(values.as_bytes()))
+                    .unwrap();
+                assert_eq!(profile.columns[0].column_type, expected_type, "{values:?}");
+            };
+        }
+
+        test_with_domain!(BytesDomain);
+        test_with_domain!(CharsDomain);
+    }
+}
+
+#[test]
+fn statistics() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            let profile = Profiler::<$domain>::new()
+                .with_header(true)
+                .profile(Reader::<$domain, _>::new(INPUT.as_bytes()))
+                .unwrap();
+            let value = |text: &str| <$domain as Domain>::String::from_text(text);
+            let values = |texts: [&str; 7]| -> Vec<Option<<$domain as Domain>::String>> {
+                texts.iter().map(|text| Some(value(text))).collect()
+            };
+
+            assert_eq!(profile.row_count, 3);
+            assert_eq!(
+                profile
+                    .columns
+                    .iter()
+                    .map(|column| column.name.clone())
+                    .collect::<Vec<_>>(),
+                values(["id", "flag", "amount", "price", "date", "name", "note"])
+            );
+            assert_eq!(
+                profile
+                    .columns
+                    .iter()
+                    .map(|column| column.column_type)
+                    .collect::<Vec<_>>(),
+                [
+                    ColumnType::Integer,
+                    ColumnType::Boolean,
+                    ColumnType::Integer,
+                    ColumnType::Decimal(DecimalSeparator::Point),
+                    ColumnType::Date,
+                    ColumnType::Text,
+                    ColumnType::Text,
+                ]
+            );
+            assert_eq!(
+                profile
+                    .columns
+                    .iter()
+                    .map(|column| column.min.clone())
+                    .collect::<Vec<_>>(),
+                values(["1", "No", "-3", "-0.25", "2023-12-01", "Jane Doe", "x"])
+            );
+            assert_eq!(
+                profile
+                    .columns
+                    .iter()
+                    .map(|column| column.max.clone())
+                    .collect::<Vec<_>>(),
+                values(["3", "yes", "10", "2", "2024-02-29", "John", "x"])
+            );
+            assert_eq!(
+                profile
+                    .columns
+                    .iter()
+                    .map(|column| column.longest.clone())
+                    .collect::<Vec<_>>(),
+                values(["1", "true", "10", "-0.25", "2024-01-31", "Jane Doe", "x"])
+            );
+            assert_eq!(
+                profile
+                    .columns
+                    .iter()
+                    .map(|column| column.distinct_estimate)
+                    .collect::<Vec<_>>(),
+                [3, 3, 3, 3, 3, 2, 2]
+            );
+
+            let note = &profile.columns[6];
+            assert_eq!(note.null_count, 1);
+            assert_eq!(note.empty_count, 1);
+            assert!((note.null_ratio() - 1.0 / 3.0).abs() < 1e-9);
+            assert!((note.empty_ratio() - 1.0 / 3.0).abs() < 1e-9);
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
+
+#[test]
+fn decimal_comma_extremes() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            let profile = Profiler::<$domain>::new()
+                .profile(Reader::<$domain, _>::new("10\n9,5\n-1,25\n".as_bytes()))
+                .unwrap();
+            let column = &profile.columns[0];
+            let expected_min: <$domain as Domain>::String = domain_format!($domain, "-1,25");
+            let expected_max: <$domain as Domain>::String = domain_format!($domain, "10");
+            assert_eq!(column.min, Some(expected_min));
+            assert_eq!(column.max, Some(expected_max));
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
+
+#[test]
+fn ragged_rows() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            let profile = Profiler::<$domain>::new()
+                .with_header(true)
+                .profile(Reader::<$domain, _>::new("a b c\n1\n1 2 3 4\n".as_bytes()))
+                .unwrap();
+
+            assert_eq!(profile.columns.len(), 4);
+            assert_eq!(
+                profile
+                    .columns
+                    .iter()
+                    .map(|column| column.null_count)
+                    .collect::<Vec<_>>(),
+                [0, 1, 1, 1]
+            );
+            assert_eq!(profile.columns[3].name, None);
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
+
+#[test]
+fn distinct_estimate() {
+    let input: String = (0..20_000).map(|i| format!("{}\n", i % 5_000)).collect();
+    let profile = Profiler::<BytesDomain>::new()
+        .profile(Reader::<BytesDomain, _>::new(input.as_bytes()))
+        .unwrap();
+
+    let estimate = profile.columns[0].distinct_estimate as f64;
+    assert!((estimate - 5_000.0).abs() < 5_000.0 * 0.05, "{estimate}");
+}
+
+#[test]
+fn schema() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            let schema = Profiler::<$domain>::new()
+                .with_header(true)
+                .profile(Reader::<$domain, _>::new(INPUT.as_bytes()))
+                .unwrap()
+                .schema();
+
+            let mut output = Vec::new();
+            schema.write(&mut output).unwrap();
+            assert_eq!(
+                String::from_utf8(output.clone()).unwrap(),
+                String::from_utf8(bformat!(
+                    "name type nullable{LF}\
+                     id integer false{LF}\
+                     flag boolean false{LF}\
+                     amount integer false{LF}\
+                     price decimal false{LF}\
+                     date date false{LF}\
+                     name text false{LF}\
+                     note text true{LF}"
+                ))
+                .unwrap()
+            );
+
+            assert_eq!(Schema::<$domain>::read(output.as_slice()).unwrap(), schema);
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
+
+#[test]
+fn validate() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            let value = |text: &str| <$domain as Domain>::String::from_text(text);
+            let schema = Schema::<$domain> {
+                columns: vec![
+                    ColumnSchema {
+                        name: value("id"),
+                        column_type: ColumnType::Integer,
+                        nullable: false,
+                    },
+                    ColumnSchema {
+                        name: value("price"),
+                        column_type: ColumnType::Decimal(DecimalSeparator::Comma),
+                        nullable: true,
+                    },
+                ],
+            };
+
+            let violations = schema
+                .validate("id cost\n# comment\n1 2,5\nx\n\"\" 1.5 extra\n".as_bytes())
+                .unwrap();
+
+            let found: Vec<_> = violations
+                .iter()
+                .map(|violation| {
+                    (
+                        violation.position.line_number,
+                        violation.column,
+                        violation.kind.clone(),
+                    )
+                })
+                .collect();
+            assert_eq!(
+                found,
+                [
+                    (
+                        1,
+                        1,
+                        ViolationKind::HeaderMismatch {
+                            expected: value("price"),
+                            found: Some(value("cost"))
+                        }
+                    ),
+                    (4, 0, ViolationKind::TypeMismatch(value("x"))),
+                    (5, 0, ViolationKind::MissingValue),
+                    (5, 1, ViolationKind::TypeMismatch(value("1.5"))),
+                    (5, 2, ViolationKind::UnexpectedValue(value("extra"))),
+                ]
+            );
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
+
+#[test]
+fn validate_without_names() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            let schema = Profiler::<$domain>::new()
+                .profile(Reader::<$domain, _>::new("1 a\n2 b\n".as_bytes()))
+                .unwrap()
+                .schema();
+
+            assert!(schema.validate("3 c\n".as_bytes()).unwrap().is_empty());
+            assert_eq!(schema.validate("c 3\n".as_bytes()).unwrap().len(), 1);
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
+
+#[test]
+fn invalid_schema() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            for (input, expected_line) in [
+                ("", 1),
+                ("name kind nullable\n", 1),
+                ("name type nullable\nid integer\n", 2),
+                ("name type nullable\nid number false\n", 2),
+                ("name type nullable\nid integer maybe\n", 2),
+            ] {
+                let error = Schema::<$domain>::read(input.as_bytes()).unwrap_err();
+                if let SchemaError::InvalidRow(Position { line_number, .. }) = error {
+                    assert_eq!(line_number, expected_line, "{input:?}");
+                } else {
+                    panic!("wrong error: {:?}", error);
+                }
+            }
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
@@ -0,0 +1,214 @@
+use std::ops::Deref;
+
+use crate::engine::columns::ColumnNotFoundError;
+use crate::engine::columns::ColumnRef;
+use crate::engine::domain::{BytesDomain, CharsDomain, Domain, DomainStringSlice};
+use crate::engine::projection::{Projection, SpecError};
+use crate::engine::ReadWriteError;
+use crate::tests::support::{LF, Q};
+use crate::{bformat, domain_format, domain_format_ref};
+
+macro_rules! assert_projection {
+    ($spec:literal, header: $header:literal, $input:literal, $expected_output:literal $(,)?) => {
+        _assert_projection_domain!(BytesDomain, $spec, $header, $input, $expected_output);
+        _assert_projection_domain!(CharsDomain, $spec, $header, $input, $expected_output);
+    };
+}
+
+macro_rules! _assert_projection_domain {
+    ($domain:ident, $spec:literal, $header:literal, $input:literal, $expected_output:literal) => {
+        let input = bformat!($input);
+        let mut output = Vec::new();
+
+        let projection: Projection<$domain> = $spec.parse().unwrap();
+        projection
+            .with_header($header)
+            .transform(input.deref(), &mut output)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            String::from_utf8(bformat!($expected_output)).unwrap()
+        );
+    };
+}
+
+macro_rules! assert_column_not_found {
+    ($spec:literal, header: $header:literal, $input:literal, $expected_column:literal $(,)?) => {
+        _assert_column_not_found_domain!(BytesDomain, $spec, $header, $input, $expected_column);
+        _assert_column_not_found_domain!(CharsDomain, $spec, $header, $input, $expected_column);
+    };
+}
+
+macro_rules! _assert_column_not_found_domain {
+    ($domain:ident, $spec:literal, $header:literal, $input:literal, $expected_column:literal) => {
+        let input = bformat!($input);
+
+        let projection: Projection<$domain> = $spec.parse().unwrap();
+        let error = projection
+            .with_header($header)
+            .transform(input.deref(), Vec::new())
+            .unwrap_err();
+
+        if let ReadWriteError::ColumnNotFound(ColumnNotFoundError(column)) = error {
+            assert_eq!(column, $expected_column);
+        } else {
+            panic!("wrong error: {:?}", error);
+        }
+    };
+}
+
+macro_rules! assert_spec_error {
+    ($spec:literal, $expected_error:expr) => {
+        assert_eq!(
+            Projection::<BytesDomain>::parse($spec).err(),
+            Some($expected_error)
+        );
+        assert_eq!(
+            Projection::<CharsDomain>::parse($spec).err(),
+            Some($expected_error)
+        );
+    };
+}
+
+#[test]
+fn select_by_index() {
+    assert_projection!("2, 0", header: false, "a b c{LF}d e f{LF}", "c a{LF}f d{LF}");
+    assert_projection!("1,1", header: false, "a b{LF}", "b b{LF}");
+}
+
+#[test]
+fn select_by_name() {
+    assert_projection!(
+        "c, a",
+        header: true,
+        "a b c{LF}1 2 3{LF}",
+        "c a{LF}3 1{LF}",
+    );
+    assert_projection!(
+        "\"x y\", \"1\"",
+        header: true,
+        "{Q}x y{Q} 1{LF}a b{LF}",
+        "{Q}x y{Q} 1{LF}a b{LF}",
+    );
+}
+
+#[test]
+fn rename() {
+    assert_projection!(
+        "x=b, y=0",
+        header: true,
+        "a b{LF}1 2{LF}",
+        "x y{LF}2 1{LF}",
+    );
+}
+
+#[test]
+fn all_and_exclude() {
+    assert_projection!("*, -1", header: false, "a b c{LF}d e{LF}", "a c{LF}d{LF}");
+    assert_projection!(
+        "c, *, -c",
+        header: true,
+        "a b c{LF}1 2 3{LF}",
+        "c a b{LF}3 1 2{LF}",
+    );
+}
+
+#[test]
+fn all_with_header_and_rows_of_other_lengths() {
+    assert_projection!(
+        "*, a",
+        header: true,
+        "a b c{LF}1{LF}",
+        "a b c a{LF}1 {Q}{Q} {Q}{Q} 1{LF}",
+    );
+    assert_projection!(
+        "*, -b, a",
+        header: true,
+        "a b c{LF}1{LF}",
+        "a c a{LF}1 {Q}{Q} 1{LF}",
+    );
+    assert_projection!("*, a", header: true, "a b{LF}1 2 3{LF}", "a b a{LF}1 2 1{LF}");
+}
+
+#[test]
+fn computed() {
+    assert_projection!(
+        "full=first+' '+last, tag='x'",
+        header: true,
+        "first last{LF}John Doe{LF}",
+        "full tag{LF}{Q}John Doe{Q} x{LF}",
+    );
+    assert_projection!("n=0+'''s'", header: false, "a{LF}", "a's{LF}");
+}
+
+#[test]
+fn comments_pass_through() {
+    assert_projection!(
+        "b",
+        header: true,
+        "# before{LF}a b{LF}# middle{LF}1 2{LF}",
+        "# before{LF}b{LF}# middle{LF}2{LF}",
+    );
+}
+
+#[test]
+fn missing_values() {
+    assert_projection!("0, 2", header: false, "a{LF}{LF}", "a {Q}{Q}{LF}{Q}{Q} {Q}{Q}{LF}");
+}
+
+#[test]
+fn column_not_found() {
+    assert_column_not_found!("a", header: false, "a{LF}", "\"a\"");
+    assert_column_not_found!("a, c", header: true, "a b{LF}", "\"c\"");
+    assert_column_not_found!("2", header: true, "a b{LF}", "2");
+    assert_column_not_found!("*, -x", header: true, "a b{LF}", "\"x\"");
+}
+
+#[test]
+fn builder() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            let input = bformat!("a b{LF}1 2{LF}");
+            let mut output = Vec::new();
+
+            Projection::<$domain>::new()
+                .with_header(true)
+                .select(ColumnRef::name(domain_format_ref!($domain, "b")))
+                .select_as(0, domain_format_ref!($domain, "x"))
+                .compute(
+                    domain_format_ref!($domain, "sum"),
+                    vec![0.into(), 1.into()],
+                    |values| {
+                        let sum: u32 = values
+                            .iter()
+                            .map(|value| String::from_utf8_lossy(value.as_bytes()).parse::<u32>())
+                            .map(|value| value.unwrap_or(0))
+                            .sum();
+                        <$domain as Domain>::String::from(sum.to_string())
+                    },
+                )
+                .transform(input.deref(), &mut output)
+                .unwrap();
+
+            assert_eq!(output, bformat!("b x sum{LF}2 1 3{LF}"));
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
+
+#[test]
+fn spec_errors() {
+    assert_spec_error!("", SpecError::UnexpectedEnd(1));
+    assert_spec_error!("a,", SpecError::UnexpectedEnd(3));
+    assert_spec_error!("a b", SpecError::UnexpectedChar('b', 3));
+    assert_spec_error!("a=", SpecError::UnexpectedEnd(3));
+    assert_spec_error!("a+b", SpecError::UnexpectedChar('+', 2));
+    assert_spec_error!("'a'", SpecError::UnexpectedChar('\'', 1));
+    assert_spec_error!("'a'=b", SpecError::UnexpectedChar('\'', 1));
+    assert_spec_error!("-'a'", SpecError::UnexpectedChar('\'', 2));
+    assert_spec_error!("ñ=\"a", SpecError::UnclosedQuote(3));
+    assert_spec_error!("a='b", SpecError::UnclosedQuote(3));
+}
@@ -1,7 +1,8 @@
 use std::ops::Deref;
 
 use crate::engine::domain::{BytesDomain, CharsDomain, Domain};
-use crate::engine::reader::Reader;
+use crate::engine::position::Position;
+use crate::engine::reader::{Reader, Record, RecordReader};
 use crate::engine::ReadError;
 use crate::tests::support::{CRLF, LF, Q};
 use crate::{bformat, domain_format};
@@ -83,3 +84,54 @@ fn error() {
 
     assert!(remaining.next().is_none());
 }
+
+#[test]
+fn records() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            let input = bformat!("# first{LF}  abc {Q}def{Q}{CRLF}{LF}#second");
+
+            let records: Vec<_> = RecordReader::<$domain, _>::new(input.deref())
+                .map(|record| record.unwrap())
+                .map(|record| (record.value, record.position))
+                .collect();
+
+            assert_eq!(
+                records,
+                vec![
+                    (
+                        Record::Comment(domain_format!($domain, " first")),
+                        Position {
+                            line_number: 1,
+                            column_number: 1
+                        }
+                    ),
+                    (
+                        Record::Row(domain_format!($domain, ["abc", "def"])),
+                        Position {
+                            line_number: 2,
+                            column_number: 1
+                        }
+                    ),
+                    (
+                        Record::Row(Vec::new()),
+                        Position {
+                            line_number: 3,
+                            column_number: 1
+                        }
+                    ),
+                    (
+                        Record::Comment(domain_format!($domain, "second")),
+                        Position {
+                            line_number: 4,
+                            column_number: 1
+                        }
+                    ),
+                ]
+            );
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
@@ -0,0 +1,156 @@
+use crate::engine::domain::{BytesDomain, CharsDomain};
+use crate::engine::reader::Reader;
+use crate::engine::render::{BorderStyle, Renderer, TableFormat};
+use crate::engine::ReadWriteError;
+use crate::tests::support::Q;
+
+const INPUT: &str = "\"#\" Name Age Note\n1 \"John Doe\" 53 \"a.k.a. \"\"Joe\"\"\"\n77 Mary 23\n";
+
+macro_rules! assert_render {
+    ($renderer:expr, $input:expr, $expected_output:expr $(,)?) => {
+        _assert_render_domain!(BytesDomain, $renderer, $input, $expected_output);
+        _assert_render_domain!(CharsDomain, $renderer, $input, $expected_output);
+    };
+}
+
+macro_rules! _assert_render_domain {
+    ($domain:ident, $renderer:expr, $input:expr, $expected_output:expr) => {
+        let renderer: Renderer<$domain> = $renderer;
+        let mut output = Vec::new();
+
+        renderer
+            .render(Reader::<$domain, _>::new($input.as_bytes()), &mut output)
+            .unwrap();
+
+        assert_eq!(String::from_utf8(output).unwrap(), $expected_output);
+    };
+}
+
+#[test]
+fn markdown() {
+    assert_render!(
+        Renderer::new(TableFormat::Markdown).with_header(true),
+        INPUT,
+        format!(
+            "\
+| #  | Name     | Age | Note         |
+|---:|----------|----:|--------------|
+|  1 | John Doe |  53 | a.k.a. {Q}Joe{Q} |
+| 77 | Mary     |  23 |              |
+"
+        ),
+    );
+    assert_render!(
+        Renderer::new(TableFormat::Markdown),
+        "a 1\nbc\n",
+        "\
+|    |   |
+|----|--:|
+| a  | 1 |
+| bc |   |
+",
+    );
+}
+
+#[test]
+fn markdown_escaping() {
+    assert_render!(
+        Renderer::new(TableFormat::Markdown).with_header(true),
+        "x\n\"a|b\"\n\"*c*\nd\"\n",
+        "\
+| x          |
+|------------|
+| a\\|b       |
+| \\*c\\*<br>d |
+",
+    );
+}
+
+#[test]
+fn html() {
+    assert_render!(
+        Renderer::new(TableFormat::Html).with_header(true),
+        INPUT,
+        "\
+<table>
+<thead>
+<tr><th style=\"text-align: right\">#</th><th>Name</th><th style=\"text-align: right\">Age</th><th>Note</th></tr>
+</thead>
+<tbody>
+<tr><td style=\"text-align: right\">1</td><td>John Doe</td><td style=\"text-align: right\">53</td><td>a.k.a. &quot;Joe&quot;</td></tr>
+<tr><td style=\"text-align: right\">77</td><td>Mary</td><td style=\"text-align: right\">23</td><td></td></tr>
+</tbody>
+</table>
+",
+    );
+    assert_render!(
+        Renderer::new(TableFormat::Html),
+        "\"<a href='x'>&</a>\" \"b\nc\"\n",
+        "\
+<table>
+<tbody>
+<tr><td>&lt;a href=&#39;x&#39;&gt;&amp;&lt;/a&gt;</td><td>b<br>c</td></tr>
+</tbody>
+</table>
+",
+    );
+}
+
+#[test]
+fn text() {
+    assert_render!(
+        Renderer::new(TableFormat::Text(BorderStyle::Unicode)).with_header(true),
+        INPUT,
+        format!(
+            "\
+┌────┬──────────┬─────┬──────────────┐
+│ #  │ Name     │ Age │ Note         │
+├────┼──────────┼─────┼──────────────┤
+│  1 │ John Doe │  53 │ a.k.a. {Q}Joe{Q} │
+│ 77 │ Mary     │  23 │              │
+└────┴──────────┴─────┴──────────────┘
+"
+        ),
+    );
+    assert_render!(
+        Renderer::new(TableFormat::Text(BorderStyle::Ascii)),
+        "\"first\r\nsecond\" -1,5\nx \"\"\n",
+        "\
++--------+------+
+| first  | -1,5 |
+| second |      |
+| x      |      |
++--------+------+
+",
+    );
+}
+
+#[test]
+fn empty_input() {
+    for format in [
+        TableFormat::Markdown,
+        TableFormat::Text(BorderStyle::Unicode),
+    ] {
+        assert_render!(Renderer::new(format).with_header(true), "", "");
+    }
+    assert_render!(Renderer::new(TableFormat::Html), "", "<table>\n</table>\n");
+}
+
+#[test]
+fn read_error() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            let error = Renderer::<$domain>::new(TableFormat::Html)
+                .render(Reader::<$domain, _>::new("a\"b\n".as_bytes()), Vec::new())
+                .unwrap_err();
+            assert!(
+                matches!(error, ReadWriteError::ReadError(_)),
+                "wrong error: {:?}",
+                error
+            );
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
@@ -0,0 +1,107 @@
+use std::io::Cursor;
+
+use crate::engine::domain::{BytesDomain, CharsDomain};
+use crate::engine::position::Position;
+use crate::engine::reader::Reader;
+use crate::engine::reverse_reader::ReverseReader;
+use crate::engine::ReadError;
+
+const INPUTS: &[&str] = &[
+    "",
+    "\n",
+    "\n\n",
+    "a",
+    "a\n",
+    "a b\r\nc\n\nd",
+    "# comment\na\n# \"quoted\" comment\nb\n# last",
+    "\"multi\nline\" x\n\"\n\"\n\"crlf\r\nvalue\"\r\n",
+    "\"a\n# not a comment\" b\n",
+    "\"a\n# \" b\n",
+    "# odd \" comment\na b\n",
+    "a\n# odd \" comment\n\"b\n# c\" d\n# e \"\n",
+    "\"x\n# y\"\n#\"\n\"z\n#\n#\"\n",
+    "a\"\"b \"\"\"\" \"c\"\"\nd\"\n",
+    "órgão \"ação\nçã\"",
+];
+
+fn reversed<T>(mut vec: Vec<T>) -> Vec<T> {
+    vec.reverse();
+    vec
+}
+
+#[test]
+fn same_as_reader_reversed() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            for input in INPUTS {
+                let expected: Vec<_> = Reader::<$domain, _>::new(input.as_bytes())
+                    .collect::<Result<_, _>>()
+                    .unwrap();
+                let actual: Vec<_> = ReverseReader::<$domain, _>::new(Cursor::new(input))
+                    .collect::<Result<_, _>>()
+                    .unwrap();
+                assert_eq!(actual, reversed(expected), "{input:?}");
+            }
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
+
+#[test]
+fn long_input() {
+    let mut input = String::new();
+    for i in 0..5_000 {
+        input.push_str(&format!("{i} \"multi\nline {i}\"\n# comment {i}\n"));
+        if i % 1_000 == 0 {
+            input.push_str(&format!("\"{}\"\n", "long ".repeat(5_000)));
+        }
+    }
+
+    let expected: Vec<_> = Reader::<BytesDomain, _>::new(input.as_bytes())
+        .collect::<Result<_, _>>()
+        .unwrap();
+    let actual: Vec<_> = ReverseReader::<BytesDomain, _>::new(Cursor::new(&input))
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(actual, reversed(expected));
+}
+
+#[test]
+fn last_rows_only() {
+    let input = "\"unclosed\nvalue\n".to_string() + &"a b\n".repeat(10_000) + "c d\n";
+
+    let rows: Vec<_> = ReverseReader::<CharsDomain, _>::new(Cursor::new(input))
+        .take(2)
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(rows, [["c", "d"], ["a", "b"]]);
+}
+
+#[test]
+fn error() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            let mut reader = ReverseReader::<$domain, _>::new(Cursor::new("a\nb \"c\nd\"e\nf\n"));
+
+            assert!(reader.next().unwrap().is_ok());
+            let error = reader.next().unwrap().unwrap_err();
+            if let ReadError::UnpairedQuote(position) = error {
+                assert_eq!(
+                    position,
+                    Position {
+                        line_number: 2,
+                        column_number: 2
+                    }
+                );
+            } else {
+                panic!("wrong error: {:?}", error);
+            }
+            assert!(reader.next().is_none());
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
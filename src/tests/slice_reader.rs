@@ -0,0 +1,148 @@
+use std::borrow::Cow;
+use std::ops::Deref;
+
+use crate::engine::domain::{BytesDomain, CharsDomain, Domain, DomainStringSlice};
+use crate::engine::position::WithPosition;
+use crate::engine::reader::{Record, RecordReader};
+use crate::engine::slice_reader::{SliceReader, SliceRecord, SliceRecordReader};
+use crate::engine::ReadError;
+
+const INPUTS: &[&str] = &[
+    "",
+    "\n",
+    "\n\n",
+    "a",
+    "a\n",
+    "a b\nc\td\r\ne",
+    "  a  b  \n\t\n",
+    "# comment\na\n#\n# last",
+    "#a\r\n#b\rc\n",
+    "a #b\n #c\n",
+    "\"a b\" \"\" \"c\"\"d\" \"\"\"\"",
+    "\"multi\nline\" x\n\"crlf\r\nvalue\"\n",
+    "a\"\"b \"\"c \"\"\"\"d",
+    "\"\"\"x\" \"\"\" y\"",
+    "a\rb \"c\rd\"\r\n",
+    "órgão \"ação\"\nçã",
+    "a\"b",
+    "a\"",
+    "ab\"\nc",
+    "\"a\"b",
+    "\"a\"\rb",
+    "x\n\"unclosed",
+    "\"unclosed\n",
+    "\"",
+    "\"\"\"",
+    "a b\n\"c\nd\"e",
+];
+
+/// Converts the records of a `SliceRecordReader` to the records of a
+/// `RecordReader`, stopping at the first error.
+fn records<D: Domain, I, T>(records: I, convert: impl Fn(T) -> Record<D>) -> Vec<String>
+where
+    I: Iterator<Item = Result<WithPosition<T>, ReadError>>,
+{
+    records
+        .map(|result| match result {
+            Ok(record) => format!("{:?} at {:?}", convert(record.value), record.position),
+            Err(ReadError::IoError(error)) => format!("IoError({:?})", error.kind()),
+            Err(error) => format!("{error:?}"),
+        })
+        .collect()
+}
+
+#[test]
+fn same_as_record_reader() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            for input in INPUTS {
+                let expected = records(
+                    RecordReader::<$domain, _>::new(input.as_bytes()),
+                    |record| record,
+                );
+                let actual = records::<$domain, _, _>(
+                    SliceRecordReader::<$domain>::new(input.as_bytes()),
+                    |record| match record {
+                        SliceRecord::Row(row) => {
+                            Record::Row(row.into_iter().map(Cow::into_owned).collect())
+                        }
+                        SliceRecord::Comment(comment) => Record::Comment(comment.into_owned()),
+                    },
+                );
+                assert_eq!(actual, expected, "{input:?}");
+            }
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
+
+#[test]
+fn borrowed_values() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            let input = b"a \"b c\" \"d\"\"e\" \"\" f\"\"\n";
+            let row = SliceReader::<$domain>::new(input).next().unwrap().unwrap();
+
+            let borrowed: Vec<_> = row
+                .iter()
+                .map(|value| matches!(value, Cow::Borrowed(_)))
+                .collect();
+            assert_eq!(borrowed, [true, true, false, false, false]);
+            assert_eq!(
+                row.iter()
+                    .map(|value| value.deref().as_bytes())
+                    .collect::<Vec<_>>(),
+                [&b"a"[..], b"b c", b"d\"e", b"", b"f\""]
+            );
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
+
+#[test]
+fn invalid_utf8() {
+    let input = b"a\n\"b\xFF\"\n";
+
+    let rows: Vec<_> = SliceReader::<BytesDomain>::new(input)
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(rows, [vec![&b"a"[..]], vec![&b"b\xFF"[..]]]);
+
+    let mut reader = SliceReader::<CharsDomain>::new(input);
+    assert_eq!(reader.next().unwrap().unwrap(), ["a"]);
+    let error = reader.next().unwrap().unwrap_err();
+    assert!(
+        matches!(&error, ReadError::IoError(error) if error.kind() == std::io::ErrorKind::InvalidData),
+        "wrong error: {:?}",
+        error
+    );
+    assert!(reader.next().is_none());
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn mapped_file() {
+    use crate::engine::slice_reader::MappedFile;
+
+    let path = std::env::temp_dir().join(format!("ssv-mapped-file-{}.ssv", std::process::id()));
+    std::fs::write(&path, "# comment\na \"b c\"\n").unwrap();
+    let empty_path = path.with_extension("empty.ssv");
+    std::fs::write(&empty_path, "").unwrap();
+
+    let file = unsafe { MappedFile::<CharsDomain>::open(&path) }.unwrap();
+    let rows: Vec<_> = file.rows().collect::<Result<_, _>>().unwrap();
+    assert_eq!(rows, [vec!["a", "b c"]]);
+    assert_eq!(file.records().count(), 2);
+
+    let empty = unsafe { MappedFile::<BytesDomain>::open(&empty_path) }.unwrap();
+    assert!(empty.as_bytes().is_empty());
+    assert_eq!(empty.rows().count(), 0);
+
+    drop(file);
+    std::fs::remove_file(path).unwrap();
+    std::fs::remove_file(empty_path).unwrap();
+}
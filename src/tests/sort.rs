@@ -0,0 +1,214 @@
+use std::ops::Deref;
+
+use crate::bformat;
+use crate::engine::columns::ColumnNotFoundError;
+use crate::engine::columns::ColumnRef;
+use crate::engine::domain::{BytesDomain, CharsDomain};
+use crate::engine::sort::{Collation, SortKey, Sorter};
+use crate::engine::ReadWriteError;
+use crate::tests::support::{LF, Q};
+
+macro_rules! assert_sorting {
+    ($sorter:expr, $input:literal, $expected_output:literal $(,)?) => {
+        _assert_sorting_domain!(BytesDomain, $sorter, $input, $expected_output);
+        _assert_sorting_domain!(CharsDomain, $sorter, $input, $expected_output);
+    };
+}
+
+macro_rules! _assert_sorting_domain {
+    ($domain:ident, $sorter:expr, $input:literal, $expected_output:literal) => {
+        let input = bformat!($input);
+        let expected_output = bformat!($expected_output);
+
+        // In memory, and with spilled runs of several sizes, merged at once
+        // or in several passes
+        for run_size in [Sorter::<$domain>::DEFAULT_RUN_SIZE, 1, 2, 3] {
+            for merge_fan_in in [Sorter::<$domain>::DEFAULT_MERGE_FAN_IN, 2] {
+                let sorter: Sorter<$domain> = $sorter;
+                let mut output = Vec::new();
+
+                sorter
+                    .run_size(run_size)
+                    .merge_fan_in(merge_fan_in)
+                    .sort(input.deref(), &mut output)
+                    .unwrap();
+
+                assert_eq!(
+                    String::from_utf8(output).unwrap(),
+                    String::from_utf8(expected_output.clone()).unwrap(),
+                    "run size: {run_size}, merge fan-in: {merge_fan_in}"
+                );
+            }
+        }
+    };
+}
+
+#[test]
+fn without_keys() {
+    assert_sorting!(Sorter::new(), "", "");
+    assert_sorting!(
+        Sorter::new(),
+        "b 2{LF}a 3{LF}b 1{LF}a{LF}",
+        "a{LF}a 3{LF}b 1{LF}b 2{LF}",
+    );
+}
+
+#[test]
+fn by_index() {
+    assert_sorting!(
+        Sorter::new().by(SortKey::ascending(1)),
+        "a 3{LF}b 1{LF}c 2{LF}d{LF}",
+        "d{LF}b 1{LF}c 2{LF}a 3{LF}",
+    );
+    assert_sorting!(
+        Sorter::new().by(SortKey::descending(1)),
+        "a 3{LF}b 1{LF}c 2{LF}d{LF}",
+        "a 3{LF}c 2{LF}b 1{LF}d{LF}",
+    );
+}
+
+#[test]
+fn by_name() {
+    assert_sorting!(
+        Sorter::new().with_header(true).by(SortKey::ascending("y")),
+        "x y{LF}a 3{LF}b 1{LF}c 2{LF}",
+        "x y{LF}b 1{LF}c 2{LF}a 3{LF}",
+    );
+}
+
+#[test]
+fn multiple_keys() {
+    assert_sorting!(
+        Sorter::new()
+            .by(SortKey::ascending(0))
+            .by(SortKey::descending(1)),
+        "b 1{LF}a 1{LF}b 2{LF}a 2{LF}",
+        "a 2{LF}a 1{LF}b 2{LF}b 1{LF}",
+    );
+}
+
+#[test]
+fn stable() {
+    assert_sorting!(
+        Sorter::new().by(SortKey::ascending(0)),
+        "b 1{LF}a 1{LF}b 2{LF}a 2{LF}b 3{LF}a 3{LF}a 4{LF}",
+        "a 1{LF}a 2{LF}a 3{LF}a 4{LF}b 1{LF}b 2{LF}b 3{LF}",
+    );
+    assert_sorting!(
+        Sorter::new().by(SortKey::descending(0)),
+        "b 1{LF}a 1{LF}b 2{LF}a 2{LF}b 3{LF}a 3{LF}a 4{LF}",
+        "b 1{LF}b 2{LF}b 3{LF}a 1{LF}a 2{LF}a 3{LF}a 4{LF}",
+    );
+}
+
+#[test]
+fn collations() {
+    assert_sorting!(
+        Sorter::new().by(SortKey::ascending(0)),
+        "10{LF}9{LF}-1{LF}x{LF}1,5{LF}",
+        "-1{LF}1,5{LF}10{LF}9{LF}x{LF}",
+    );
+    assert_sorting!(
+        Sorter::new().by(SortKey::ascending(0).with_collation(Collation::Numeric)),
+        "10{LF}9{LF}-1{LF}x{LF}1,5{LF}{Q}{Q}{LF}",
+        "-1{LF}1,5{LF}9{LF}10{LF}{Q}{Q}{LF}x{LF}",
+    );
+    assert_sorting!(
+        Sorter::new().by(SortKey::ascending(0).with_collation(Collation::Natural)),
+        "file10{LF}file9{LF}file09a{LF}file{LF}file9b{LF}a100b2{LF}a100b10{LF}",
+        "a100b2{LF}a100b10{LF}file{LF}file9{LF}file9b{LF}file09a{LF}file10{LF}",
+    );
+}
+
+#[test]
+fn comments() {
+    assert_sorting!(
+        Sorter::new().with_header(true).by(SortKey::ascending("k")),
+        "# leading{LF}k{LF}# c{LF}c{LF}a{LF}# b{LF}b{LF}# trailing{LF}",
+        "# leading{LF}k{LF}a{LF}# b{LF}b{LF}# c{LF}c{LF}# trailing{LF}",
+    );
+    assert_sorting!(
+        Sorter::new().by(SortKey::ascending(0)),
+        "# leading{LF}c{LF}a{LF}# b{LF}b{LF}",
+        "# leading{LF}a{LF}# b{LF}b{LF}c{LF}",
+    );
+    assert_sorting!(
+        Sorter::new(),
+        "# only{LF}# comments{LF}",
+        "# only{LF}# comments{LF}"
+    );
+}
+
+#[test]
+fn special_values() {
+    assert_sorting!(
+        Sorter::new().by(SortKey::ascending(0)),
+        "{Q}c d{Q}{LF}{LF}{Q}a{LF}b{Q} {Q}{Q}{Q}{Q}{LF}",
+        "{LF}{Q}a{LF}b{Q} {Q}{Q}{Q}{Q}{LF}{Q}c d{Q}{LF}",
+    );
+}
+
+#[test]
+fn temp_files_are_removed() {
+    let temp_dir = std::env::temp_dir().join(format!("ssv-sort-test-{}", std::process::id()));
+    std::fs::create_dir(&temp_dir).unwrap();
+
+    let mut output = Vec::new();
+    Sorter::<BytesDomain>::new()
+        .run_size(1)
+        .merge_fan_in(2)
+        .temp_dir(&temp_dir)
+        .sort(b"e\nc\nb\nd\na\n".as_slice(), &mut output)
+        .unwrap();
+    assert_eq!(output, b"a\nb\nc\nd\ne\n");
+
+    assert_eq!(std::fs::read_dir(&temp_dir).unwrap().count(), 0);
+    std::fs::remove_dir(&temp_dir).unwrap();
+}
+
+#[test]
+fn multiple_merge_passes() {
+    let input = (0..100)
+        .map(|index| format!("{} {index}\n", (index * 37) % 10))
+        .collect::<String>();
+    let mut expected_output = input.lines().collect::<Vec<_>>();
+    expected_output.sort_by_key(|line| line.split(' ').next().unwrap().to_owned());
+    let expected_output = expected_output
+        .into_iter()
+        .map(|line| format!("{line}\n"))
+        .collect::<String>();
+
+    let mut output = Vec::new();
+    Sorter::<BytesDomain>::new()
+        .by(SortKey::ascending(0))
+        .run_size(3)
+        .merge_fan_in(3)
+        .sort(input.as_bytes(), &mut output)
+        .unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), expected_output);
+}
+
+#[test]
+fn column_not_found() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            for (sorter, input) in [
+                (Sorter::<$domain>::new().with_header(true), "a b\n"),
+                (Sorter::<$domain>::new(), "x\n"),
+            ] {
+                let error = sorter
+                    .by(SortKey::ascending(ColumnRef::from("x")))
+                    .sort(input.as_bytes(), Vec::new())
+                    .unwrap_err();
+                if let ReadWriteError::ColumnNotFound(ColumnNotFoundError(column)) = error {
+                    assert_eq!(column, "\"x\"");
+                } else {
+                    panic!("wrong error: {:?}", error);
+                }
+            }
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
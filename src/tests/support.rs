@@ -1,3 +1,5 @@
+#![allow(missing_docs)]
+
 pub const Q: char = '"';
 pub const TAB: char = '\t';
 pub const LF: char = '\n';
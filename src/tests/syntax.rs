@@ -0,0 +1,149 @@
+use crate::engine::domain::{BytesDomain, CharsDomain};
+use crate::engine::fluent_writer::FluentWriter;
+use crate::engine::tokenizer::Tokenizer as EngineTokenizer;
+use crate::engine::ReadError;
+use crate::syntax::tokenizer::Tokenizer;
+use crate::syntax::writer::{
+    CommentLineBreakPolicy, FmtSink, QuotingPolicy, SinkWriteError, SinkWriter,
+};
+use crate::syntax::LineBreak;
+
+const INPUTS: &[&str] = &[
+    "",
+    "\n",
+    "a b\nc\td\r\ne",
+    "  a  b  \n\t\n",
+    "# comment\na\n#\n# last",
+    "#a\r\n#b\rc\n",
+    "\"a b\" \"\" \"c\"\"d\" \"\"\"\"",
+    "\"multi\nline\" x\n\"crlf\r\nvalue\"\n",
+    "a\"\"b \"\"c \"\"\"\"d",
+    "a\rb \"c\rd\"\r\n",
+    "órgão \"ação\"\nçã",
+    "a\"b",
+    "\"a\"b",
+    "x\n\"unclosed",
+    "\"\"\"",
+];
+
+/// Formats the results of a tokenizer, converting the errors to `ReadError`.
+fn results<T: std::fmt::Debug, E: Into<ReadError>>(
+    results: impl Iterator<Item = Result<T, E>>,
+) -> Vec<String> {
+    results
+        .map(|result| format!("{:?}", result.map_err(Into::into)))
+        .collect()
+}
+
+#[test]
+fn tokenizer_from_bytes() {
+    for input in INPUTS {
+        assert_eq!(
+            results(Tokenizer::from_bytes(input.as_bytes())),
+            results(EngineTokenizer::<BytesDomain, _>::new(input.as_bytes())),
+            "{input:?}"
+        );
+    }
+}
+
+#[test]
+fn tokenizer_from_text() {
+    for input in INPUTS {
+        assert_eq!(
+            results(Tokenizer::from_text(input)),
+            results(EngineTokenizer::<CharsDomain, _>::new(input.as_bytes())),
+            "{input:?}"
+        );
+    }
+}
+
+#[test]
+fn tokenizer_is_fused_after_error() {
+    let mut tokenizer = Tokenizer::from_text("a\"b c");
+
+    assert!(tokenizer.next().unwrap().is_err());
+    assert!(tokenizer.next().is_none());
+}
+
+#[test]
+fn sink_writer() {
+    let mut output = String::new();
+    let mut writer = SinkWriter::<CharsDomain, _>::new(FmtSink(&mut output));
+    writer.set_default_line_break(LineBreak::CrLf);
+    writer.set_default_spacing("\t".to_owned()).unwrap();
+    writer.set_comment_line_break_policy(CommentLineBreakPolicy::Split);
+    writer.write_value("#a").unwrap();
+    writer.write_value("b\"c").unwrap();
+    writer.write_quoted_value("d").unwrap();
+    writer.write_comment("one\ntwo").unwrap();
+    writer.write_spacing("  ").unwrap();
+    writer.write_value("").unwrap();
+    writer.write_this_line_break(LineBreak::Lf).unwrap();
+    writer.set_quoting_policy(QuotingPolicy::NonNumeric);
+    writer.write_value("1,5").unwrap();
+    writer.write_value("x").unwrap();
+
+    let mut expected = Vec::new();
+    FluentWriter::<CharsDomain, _>::new(&mut expected)
+        .set_default_line_break(LineBreak::CrLf)
+        .set_default_spacing("\t".to_owned())
+        .unwrap()
+        .set_comment_line_break_policy(CommentLineBreakPolicy::Split)
+        .write_value("#a")
+        .unwrap()
+        .write_value("b\"c")
+        .unwrap()
+        .write_quoted_value("d")
+        .unwrap()
+        .write_comment("one\ntwo")
+        .unwrap()
+        .write_spacing("  ")
+        .unwrap()
+        .write_value("")
+        .unwrap()
+        .write_this_line_break(LineBreak::Lf)
+        .unwrap()
+        .set_quoting_policy(QuotingPolicy::NonNumeric)
+        .write_value("1,5")
+        .unwrap()
+        .write_value("x")
+        .unwrap()
+        .finish()
+        .unwrap();
+
+    assert_eq!(output.as_bytes(), expected);
+    assert_eq!(
+        output,
+        "\"#a\"\tb\"\"c\t\"d\"\r\n#one\r\n#two\r\n  \"\"\n1,5\t\"x\""
+    );
+}
+
+#[test]
+fn sink_writer_errors() {
+    let mut writer = SinkWriter::<BytesDomain, _>::new(Vec::new());
+    assert_eq!(
+        writer.write_spacing(b"a"),
+        Err(SinkWriteError::InvalidSpacing)
+    );
+    assert_eq!(
+        writer.set_default_spacing(Vec::new()),
+        Err(SinkWriteError::InvalidSpacing)
+    );
+    assert_eq!(
+        writer.write_comment(b"a\nb"),
+        Err(SinkWriteError::CommentWithLineBreak)
+    );
+    writer.set_quoting_policy(QuotingPolicy::Never);
+    assert_eq!(
+        writer.write_value(b"a b"),
+        Err(SinkWriteError::QuotesRequired)
+    );
+    assert_eq!(writer.into_inner(), b"");
+
+    let mut output = String::new();
+    let mut writer = SinkWriter::<BytesDomain, _>::new(FmtSink(&mut output));
+    assert_eq!(
+        writer.write_value(b"\xFF"),
+        Err(SinkWriteError::SinkError(std::fmt::Error))
+    );
+}
@@ -0,0 +1,131 @@
+use std::ops::Deref;
+
+use crate::engine::domain::{BytesDomain, CharsDomain};
+use crate::engine::token_writer::TokenWriter;
+use crate::engine::tokenizer::{Token, Tokenizer};
+use crate::engine::{LineBreak, WriteError};
+use crate::tests::support::{CR, CRLF, LF, Q, TAB};
+use crate::{bformat, domain_format};
+
+macro_rules! assert_replay {
+    ($input:literal) => {
+        _assert_replay_domain!(BytesDomain, $input);
+        _assert_replay_domain!(CharsDomain, $input);
+    };
+}
+
+macro_rules! _assert_replay_domain {
+    ($domain:ident, $input:literal) => {
+        let input = bformat!($input);
+        let mut output = Vec::new();
+
+        let mut token_writer: TokenWriter<$domain, _> = TokenWriter::new(&mut output);
+        for token in Tokenizer::<$domain, _>::new(input.deref()) {
+            token_writer.write_token(&token.unwrap().value).unwrap();
+        }
+        token_writer.finish().unwrap();
+
+        assert_eq!(output, input);
+    };
+}
+
+macro_rules! assert_token_write_error {
+    ([ $($token:ident ( $($arg:tt)* )),* $(,)? ], $expected_error:ident $(,)?) => {
+        _assert_token_write_error_domain!(BytesDomain, [ $($token ( $($arg)* )),* ], $expected_error);
+        _assert_token_write_error_domain!(CharsDomain, [ $($token ( $($arg)* )),* ], $expected_error);
+    };
+}
+
+macro_rules! _assert_token_write_error_domain {
+    ($domain:ident, [ $($token:ident ( $($arg:tt)* )),* ], $expected_error:ident) => {
+        let tokens: Vec<Token<$domain>> = vec![ $( _token!($domain, $token ( $($arg)* )) ),* ];
+        let (last_token, tokens) = tokens.split_last().unwrap();
+        let mut output = Vec::new();
+
+        let mut token_writer: TokenWriter<$domain, _> = TokenWriter::new(&mut output);
+        token_writer.write_tokens(tokens).unwrap();
+        let error = token_writer.write_token(last_token).unwrap_err();
+        assert!(matches!(error, WriteError::$expected_error), "{error:?}");
+
+        token_writer.finish().unwrap();
+    };
+}
+
+macro_rules! _token {
+    ($_domain:ident, LineBreak($line_break:expr)) => {
+        Token::LineBreak($line_break)
+    };
+    ($domain:ident, $token:ident($value:literal)) => {
+        Token::$token(domain_format!($domain, $value))
+    };
+}
+
+#[test]
+fn replay() {
+    assert_replay!("");
+    assert_replay!("abc def{LF}ghi{TAB} {Q}j k{Q}  {CRLF}");
+    assert_replay!("  {Q}{Q}{LF}{LF}# comment{CRLF}{Q}#abc{Q} #def");
+    assert_replay!("abc{Q}{Q}def {Q}{Q}{Q}{Q} {Q}multi{LF}line{Q}{LF}");
+    assert_replay!("{Q}{Q}abc {Q}abc{Q} a{CR}b #{CR}{LF}");
+    assert_replay!("#{LF}#{CR}");
+}
+
+#[test]
+fn value_after_value() {
+    assert_token_write_error!(
+        [UnquotedValue("abc"), UnquotedValue("def")],
+        UnexpectedToken
+    );
+    assert_token_write_error!([QuotedValue("abc"), QuotedValue("def")], UnexpectedToken);
+}
+
+#[test]
+fn value_after_comment() {
+    assert_token_write_error!([Comment("abc"), UnquotedValue("def")], UnexpectedToken);
+}
+
+#[test]
+fn spacing_after_spacing() {
+    assert_token_write_error!([Spacing(" "), Spacing("{TAB}")], UnexpectedToken);
+}
+
+#[test]
+fn spacing_after_comment() {
+    assert_token_write_error!([Comment("abc"), Spacing(" ")], UnexpectedToken);
+}
+
+#[test]
+fn comment_not_at_line_begin() {
+    assert_token_write_error!([Spacing(" "), Comment("abc")], UnexpectedToken);
+    assert_token_write_error!([UnquotedValue("abc"), Comment("def")], UnexpectedToken);
+}
+
+#[test]
+fn lf_after_cr() {
+    assert_token_write_error!(
+        [UnquotedValue("abc{CR}"), LineBreak(LineBreak::Lf)],
+        UnexpectedToken
+    );
+    assert_token_write_error!(
+        [Comment("abc{CR}"), LineBreak(LineBreak::Lf)],
+        UnexpectedToken
+    );
+}
+
+#[test]
+fn unquoted_value_requiring_quotes() {
+    assert_token_write_error!([UnquotedValue("")], QuotesRequired);
+    assert_token_write_error!([UnquotedValue("a b")], QuotesRequired);
+    assert_token_write_error!([UnquotedValue("{Q}")], QuotesRequired);
+    assert_token_write_error!([UnquotedValue("#abc")], QuotesRequired);
+}
+
+#[test]
+fn invalid_spacing() {
+    assert_token_write_error!([Spacing("abc")], InvalidSpacing);
+}
+
+#[test]
+fn comment_with_line_break() {
+    assert_token_write_error!([Comment("abc{LF}def")], CommentWithLineBreak);
+}
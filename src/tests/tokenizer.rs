@@ -413,7 +413,7 @@ fn hash_after_spacing_is_not_comment() {
 fn io_error() {
     let input: &[u8] = b"a\nb\xFF"; // Invalid UTF-8
 
-    let tokenizer: Tokenizer<CharsDomain, _> = Tokenizer::new(input.deref());
+    let tokenizer: Tokenizer<CharsDomain, _> = Tokenizer::new(input);
 
     let mut tokenizer = tokenizer.skip_while(|result| result.is_ok());
 
@@ -0,0 +1,248 @@
+use crate::engine::columns::ColumnNotFoundError;
+use crate::engine::domain::{BytesDomain, CharsDomain};
+use crate::engine::update::{UpdateError, Updater};
+use crate::engine::ReadWriteError;
+use crate::tests::support::{CRLF, LF, Q};
+use crate::{bformat, domain_format, domain_format_ref};
+
+macro_rules! assert_update {
+    ($updater:expr, $input:literal, $key:literal, $column:expr, $new_value:literal, $expected:literal $(,)?) => {
+        _assert_update_domain!(
+            BytesDomain,
+            $updater,
+            $input,
+            $key,
+            $column,
+            $new_value,
+            $expected
+        );
+        _assert_update_domain!(
+            CharsDomain,
+            $updater,
+            $input,
+            $key,
+            $column,
+            $new_value,
+            $expected
+        );
+    };
+}
+
+macro_rules! _assert_update_domain {
+    ($domain:ident, $updater:expr, $input:literal, $key:literal, $column:expr, $new_value:literal, $expected:literal) => {
+        let updater: Updater<$domain> = $updater;
+        let mut output = Vec::new();
+
+        updater
+            .update(
+                bformat!($input).as_slice(),
+                &mut output,
+                domain_format_ref!($domain, $key),
+                $column,
+                domain_format_ref!($domain, $new_value),
+            )
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            String::from_utf8(bformat!($expected)).unwrap()
+        );
+    };
+}
+
+#[test]
+fn layout_is_preserved() {
+    assert_update!(
+        Updater::new(),
+        "#comment{CRLF}a\t1 {Q}x{Q}{LF}{LF}  b 2   {Q}y{Q}  {CRLF}c 3",
+        "b",
+        1,
+        "20",
+        "#comment{CRLF}a\t1 {Q}x{Q}{LF}{LF}  b 20   {Q}y{Q}  {CRLF}c 3",
+    );
+    assert_update!(Updater::new(), "a 1{LF}b 2", "b", 1, "3", "a 1{LF}b 3",);
+}
+
+#[test]
+fn quoting() {
+    assert_update!(
+        Updater::new(),
+        "a {Q}1{Q} x{LF}b 2 y{LF}",
+        "a",
+        1,
+        "3",
+        "a {Q}3{Q} x{LF}b 2 y{LF}",
+    );
+    assert_update!(
+        Updater::new(),
+        "a 1{LF}b 2{LF}",
+        "b",
+        1,
+        "two {Q}words{Q}",
+        "a 1{LF}b {Q}two {Q}{Q}words{Q}{Q}{Q}{LF}",
+    );
+    assert_update!(
+        Updater::new().key(1),
+        "a 1{LF}b 2{LF}",
+        "2",
+        0,
+        "#b",
+        "a    1{LF}{Q}#b{Q} 2{LF}",
+    );
+    assert_update!(
+        Updater::new(),
+        "a 1{LF}",
+        "a",
+        1,
+        "x{CRLF}y",
+        "a {Q}x{CRLF}y{Q}{LF}",
+    );
+}
+
+#[test]
+fn alignment() {
+    assert_update!(
+        Updater::new(),
+        "a    1  x{LF}bbb  2  y{LF}",
+        "a",
+        0,
+        "cc",
+        "cc   1  x{LF}bbb  2  y{LF}",
+    );
+    assert_update!(
+        Updater::new(),
+        "a    1  x{LF}bbb  2  y{LF}# comment{LF}dd{LF}",
+        "a",
+        0,
+        "ccccc",
+        "ccccc  1  x{LF}bbb    2  y{LF}# comment{LF}dd{LF}",
+    );
+    assert_update!(
+        Updater::new(),
+        "a 1    x{LF}b 22   y{LF}c 3 z{LF}",
+        "b",
+        1,
+        "22222",
+        "a 1       x{LF}b 22222   y{LF}c 3 z{LF}",
+    );
+    // Not aligned
+    assert_update!(
+        Updater::new(),
+        "a    1{LF}bb 2{LF}",
+        "a",
+        0,
+        "ccccccc",
+        "ccccccc    1{LF}bb 2{LF}",
+    );
+    // Aligned with tabs
+    assert_update!(
+        Updater::new(),
+        "a\t1{LF}b\t2{LF}",
+        "a",
+        0,
+        "ccccccc",
+        "ccccccc\t1{LF}b\t2{LF}",
+    );
+    // Multi-line values
+    assert_update!(
+        Updater::new().key(1),
+        "{Q}a{LF}b{Q} 1  x{LF}cc   2  y{LF}",
+        "2",
+        0,
+        "d{LF}e",
+        "{Q}a{LF}b{Q} 1  x{LF}{Q}d{LF}e{Q}   2  y{LF}",
+    );
+}
+
+#[test]
+fn multiple_rows_and_missing_columns() {
+    assert_update!(
+        Updater::new(),
+        "a 1{LF}b 2{LF}a{LF}a 3 {LF}",
+        "a",
+        2,
+        "x",
+        "a 1 x{LF}b 2{LF}a {Q}{Q} x{LF}a 3 x{LF}",
+    );
+}
+
+#[test]
+fn header() {
+    assert_update!(
+        Updater::new().with_header(true).key("id"),
+        "# people{LF}name id{LF}John 1{LF}Jane 2{LF}",
+        "2",
+        "name",
+        "Mary",
+        "# people{LF}name id{LF}John 1{LF}Mary 2{LF}",
+    );
+    assert_update!(
+        Updater::new().with_header(true),
+        "name value{LF}name 1{LF}",
+        "name",
+        "value",
+        "2",
+        "name value{LF}name 2{LF}",
+    );
+}
+
+#[test]
+fn errors() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            let update = |updater: Updater<$domain>, input: &str, column: &str| {
+                updater.update(
+                    input.as_bytes(),
+                    Vec::new(),
+                    domain_format_ref!($domain, "a"),
+                    column,
+                    domain_format_ref!($domain, "x"),
+                )
+            };
+
+            assert!(matches!(
+                update(Updater::new(), "a 1", "value"),
+                Err(UpdateError::ReadWriteError(ReadWriteError::ColumnNotFound(ColumnNotFoundError(column)))) if column == "\"value\""
+            ));
+            assert!(matches!(
+                update(Updater::new().with_header(true).key("id"), "name value\na 1", "value"),
+                Err(UpdateError::ReadWriteError(ReadWriteError::ColumnNotFound(ColumnNotFoundError(column)))) if column == "\"id\""
+            ));
+            assert!(matches!(
+                update(Updater::new().with_header(true), "name value\nb 1\n", "value"),
+                Err(UpdateError::RowNotFound)
+            ));
+            assert!(matches!(
+                update(Updater::new().with_header(true), "name value\na \"1", "value"),
+                Err(UpdateError::ReadWriteError(ReadWriteError::ReadError(_)))
+            ));
+        };
+    }
+
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
+
+#[test]
+fn update_file() {
+    let path = std::env::temp_dir().join(format!("ssv-update-{}.ssv", std::process::id()));
+    std::fs::write(&path, "# settings\nport  8080\nhost  localhost\n").unwrap();
+    let updater = Updater::<CharsDomain>::new();
+
+    updater.update_file(&path, "port", 1, "80").unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "# settings\nport  80\nhost  localhost\n"
+    );
+
+    assert!(matches!(
+        updater.update_file(&path, "user", 1, "root"),
+        Err(UpdateError::RowNotFound)
+    ));
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "# settings\nport  80\nhost  localhost\n"
+    );
+
+    std::fs::remove_file(path).unwrap();
+}
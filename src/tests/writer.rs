@@ -1,7 +1,8 @@
 use crate::engine::domain::{BytesDomain, CharsDomain, Domain};
+use crate::engine::options::{CommentLineBreakPolicy, QuotingPolicy};
 use crate::engine::writer::Writer;
-use crate::engine::LineBreak;
-use crate::tests::support::{CRLF, LF, Q, TAB};
+use crate::engine::{LineBreak, WriteError};
+use crate::tests::support::{CR, CRLF, LF, Q, TAB};
 use crate::{bformat, domain_format, domain_format_ref};
 
 fn assert_writer<D, F>(f: F, expected: Vec<u8>)
@@ -211,12 +212,14 @@ fn default_line_break() {
 }
 
 #[test]
-fn always_quoted() {
+fn quoting_policy() {
     macro_rules! test_domain {
         ($domain:ident) => {
             assert_writer::<$domain, _>(
                 |writer| {
-                    writer.options_mut().set_always_quoted(true);
+                    writer
+                        .options_mut()
+                        .set_quoting_policy(QuotingPolicy::Always);
                     writer
                         .write_rows(domain_format_ref!(
                             $domain,
@@ -234,3 +237,148 @@ fn always_quoted() {
     test_domain!(BytesDomain);
     test_domain!(CharsDomain);
 }
+
+#[test]
+fn column_quoting_policy() {
+    macro_rules! test_domain {
+        ($domain:ident) => {
+            assert_writer::<$domain, _>(
+                |writer| {
+                    writer.set_column_quoting_policy(1, QuotingPolicy::NonNumeric);
+                    writer
+                        .write_rows(domain_format_ref!(
+                            $domain,
+                            [["abc", "def", "ghi"], ["123", "4,56", "789"]],
+                        ))
+                        .unwrap();
+                },
+                bformat!("abc {Q}def{Q} ghi{LF}123 4,56 789{LF}"),
+            );
+        };
+    }
+
+    test_domain!(BytesDomain);
+    test_domain!(CharsDomain);
+}
+
+#[test]
+fn quoting_policy_never_error() {
+    macro_rules! test_domain {
+        ($domain:ident) => {
+            let mut writer: Writer<$domain, _> = Writer::new(Vec::new());
+            writer
+                .options_mut()
+                .set_quoting_policy(QuotingPolicy::Never);
+
+            let error = writer
+                .write_row(domain_format_ref!($domain, ["abc", "def ghi"]))
+                .unwrap_err();
+            assert!(matches!(error, WriteError::QuotesRequired), "{error:?}");
+        };
+    }
+
+    test_domain!(BytesDomain);
+    test_domain!(CharsDomain);
+}
+
+#[test]
+fn write_comment_line_with_line_break() {
+    macro_rules! test_domain {
+        ($domain:ident) => {
+            let mut writer: Writer<$domain, _> = Writer::new(Vec::new());
+            let error = writer
+                .write_comment_line(domain_format_ref!($domain, "abc{LF}def"))
+                .unwrap_err();
+            assert!(
+                matches!(error, WriteError::CommentWithLineBreak),
+                "{error:?}"
+            );
+
+            assert_writer::<$domain, _>(
+                |writer| {
+                    writer
+                        .options_mut()
+                        .set_comment_line_break_policy(CommentLineBreakPolicy::Split);
+                    writer
+                        .write_comment_line(domain_format_ref!($domain, "abc{LF}def"))
+                        .unwrap();
+                    writer
+                        .write_row(domain_format_ref!($domain, ["ghi"]))
+                        .unwrap();
+                },
+                bformat!("#abc{LF}#def{LF}ghi{LF}"),
+            );
+        };
+    }
+
+    test_domain!(BytesDomain);
+    test_domain!(CharsDomain);
+}
+
+#[test]
+fn clear_column_quoting_policy() {
+    macro_rules! test_domain {
+        ($domain:ident) => {
+            assert_writer::<$domain, _>(
+                |writer| {
+                    writer.set_column_quoting_policy(0, QuotingPolicy::Always);
+                    writer.set_column_quoting_policy(1, QuotingPolicy::Always);
+                    assert_eq!(writer.column_quoting_policy(1), QuotingPolicy::Always);
+                    writer.clear_column_quoting_policy(1);
+                    assert_eq!(writer.column_quoting_policy(1), QuotingPolicy::Minimal);
+                    writer
+                        .write_row(domain_format_ref!($domain, ["abc", "def"]))
+                        .unwrap();
+                },
+                bformat!("{Q}abc{Q} def{LF}"),
+            );
+        };
+    }
+
+    test_domain!(BytesDomain);
+    test_domain!(CharsDomain);
+}
+
+#[test]
+fn row_writer_drop_error() {
+    macro_rules! test_domain {
+        ($domain:ident) => {
+            let mut buffer = [0; 3];
+            let mut writer: Writer<$domain, _> = Writer::new(&mut buffer[..]);
+
+            let mut row_writer = writer.new_row();
+            row_writer
+                .write_value(domain_format_ref!($domain, "abc"))
+                .unwrap();
+            drop(row_writer);
+
+            let error = writer.finish().unwrap_err();
+            assert!(matches!(error, WriteError::IoError(_)), "{error:?}");
+        };
+    }
+
+    test_domain!(BytesDomain);
+    test_domain!(CharsDomain);
+}
+
+#[test]
+fn align_keeps_comments_ending_with_cr() {
+    macro_rules! test_with_domain {
+        ($domain:ident) => {
+            let mut output = Vec::new();
+
+            crate::engine::align::<$domain, _, _>(
+                bformat!("a bbb{LF}# c{CR}{CRLF}dd e{LF}# f{CR}").as_slice(),
+                &mut output,
+            )
+            .unwrap();
+
+            assert_eq!(
+                output,
+                bformat!("a  bbb{LF}# c{CR}{CRLF}dd e{LF}# f{CR}{CRLF}")
+            );
+        };
+    }
+    test_with_domain!(BytesDomain);
+    test_with_domain!(CharsDomain);
+}
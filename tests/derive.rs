@@ -0,0 +1,191 @@
+#![cfg(feature = "derive")]
+
+use ssv::chars::{
+    ColumnNotFoundError, FieldError, FromSsvRow, MappingError, ReadWriteError, StructReader,
+    StructWriter, ToSsvRow,
+};
+
+#[derive(FromSsvRow, ToSsvRow, PartialEq, Debug)]
+struct Point(i32, i32, #[ssv(quote = "always")] String);
+
+#[derive(FromSsvRow, ToSsvRow, PartialEq, Debug)]
+struct Item {
+    #[ssv(column = "Name", quote = "never")]
+    name: String,
+    #[ssv(column = "Price")]
+    price: f64,
+    #[ssv(column = "Quantity", default)]
+    quantity: u32,
+    #[ssv(column = "Unit", default = "default_unit")]
+    unit: String,
+    #[ssv(column = "Note")]
+    note: Option<String>,
+    #[ssv(column = "Code", optional)]
+    code: MaybeCode,
+}
+
+type MaybeCode = Option<u16>;
+
+fn default_unit() -> String {
+    "piece".to_owned()
+}
+
+fn read<T: FromSsvRow>(input: &str, header: bool) -> Vec<Result<T, MappingError>> {
+    StructReader::<_, T>::new(input.as_bytes())
+        .with_header(header)
+        .collect()
+}
+
+fn write<T: ToSsvRow>(values: &[T], header: bool) -> String {
+    let mut output = Vec::new();
+    let mut writer = StructWriter::<_, T>::new(&mut output).with_header(header);
+    writer.write_all(values).unwrap();
+    writer.finish().unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn by_position() {
+    let points = read::<Point>("1 2 a\n-3 4 \"b c\"\n", false);
+
+    assert_eq!(
+        points.into_iter().map(Result::unwrap).collect::<Vec<_>>(),
+        [Point(1, 2, "a".to_owned()), Point(-3, 4, "b c".to_owned())]
+    );
+}
+
+#[test]
+fn by_position_with_header() {
+    let points = [Point(1, 2, "a".to_owned())];
+
+    assert_eq!(write(&points, true), "0 1 2\n1 2 \"a\"\n");
+    assert_eq!(
+        read::<Point>("x y z\n1 2 a\n", true)
+            .into_iter()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>(),
+        points
+    );
+}
+
+#[test]
+fn by_name() {
+    let items = read::<Item>(
+        "Unit Price Name Quantity Note\n\
+         kg 2.5 Apples 3 \"\"\n\
+         \"\" 10 Pears \"\" fresh\n",
+        true,
+    );
+
+    assert_eq!(
+        items.into_iter().map(Result::unwrap).collect::<Vec<_>>(),
+        [
+            Item {
+                name: "Apples".to_owned(),
+                price: 2.5,
+                quantity: 3,
+                unit: "kg".to_owned(),
+                note: None,
+                code: None,
+            },
+            Item {
+                name: "Pears".to_owned(),
+                price: 10.0,
+                quantity: 0,
+                unit: "piece".to_owned(),
+                note: Some("fresh".to_owned()),
+                code: None,
+            },
+        ]
+    );
+}
+
+#[test]
+fn missing_values() {
+    let items = read::<Item>("Name Price Code\nApples 2.5 7\nPears\n", true);
+
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0].as_ref().unwrap().code, Some(7));
+    assert!(matches!(
+        items[1],
+        Err(MappingError::FieldError(
+            3,
+            FieldError::MissingValue("price")
+        ))
+    ));
+}
+
+#[test]
+fn invalid_value() {
+    let items = read::<Item>("Name Price\nApples cheap\n", true);
+
+    assert!(matches!(
+        &items[..],
+        [Err(MappingError::FieldError(
+            2,
+            FieldError::InvalidValue("price", _)
+        ))]
+    ));
+}
+
+#[test]
+fn column_not_found() {
+    let items = read::<Item>("Name Cost\nApples 2.5\n", true);
+
+    assert!(matches!(
+        &items[..],
+        [Err(MappingError::ReadWriteError(ReadWriteError::ColumnNotFound(
+            ColumnNotFoundError(column)
+        )))] if column == "\"Price\""
+    ));
+    assert!(matches!(
+        &read::<Item>("Apples 2.5\n", false)[..],
+        [Err(MappingError::ReadWriteError(
+            ReadWriteError::ColumnNotFound(_)
+        ))]
+    ));
+}
+
+#[test]
+fn write_by_name() {
+    let items = [
+        Item {
+            name: "Apples".to_owned(),
+            price: 2.5,
+            quantity: 3,
+            unit: "kg".to_owned(),
+            note: Some("a b".to_owned()),
+            code: Some(7),
+        },
+        Item {
+            name: "Pears".to_owned(),
+            price: 10.0,
+            quantity: 0,
+            unit: "piece".to_owned(),
+            note: None,
+            code: None,
+        },
+    ];
+
+    assert_eq!(
+        write(&items, true),
+        "Name Price Quantity Unit Note Code\n\
+         Apples 2.5 3 kg \"a b\" 7\n\
+         Pears 10 0 piece \"\" \"\"\n"
+    );
+
+    let mut output = Vec::new();
+    let mut writer = StructWriter::<_, Item>::new(&mut output);
+    let mut item = items.into_iter().next().unwrap();
+    item.name = "Red apples".to_owned();
+    assert!(matches!(
+        writer.write(&item),
+        Err(ssv::chars::WriteError::QuotesRequired)
+    ));
+}
+
+#[test]
+fn header_without_rows() {
+    assert_eq!(write::<Point>(&[], true), "0 1 2\n");
+    assert_eq!(write::<Point>(&[], false), "");
+}
@@ -0,0 +1,138 @@
+#![cfg(feature = "std")]
+
+use std::path::PathBuf;
+use std::process::Command;
+
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("ssv-git-test-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(&path).unwrap();
+        TempDir(path)
+    }
+
+    fn file(&self, name: &str, content: &str) -> PathBuf {
+        let path = self.0.join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+fn ssv_git(args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_ssv-git"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn merge() {
+    let dir = TempDir::new("merge");
+    let base = dir.file("base", "id name city\n1 John Paris\n2 Jane Rome\n");
+    let ours = dir.file("ours", "id name  city\n1 Johnny Paris\n2 Jane   Rome\n");
+    let theirs = dir.file("theirs", "id name city\n1 John Lyon\n2 Jane Milan\n");
+
+    let output = ssv_git(&[
+        "merge",
+        "--header",
+        "--key",
+        "id",
+        base.to_str().unwrap(),
+        ours.to_str().unwrap(),
+        theirs.to_str().unwrap(),
+    ]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        std::fs::read_to_string(&ours).unwrap(),
+        "id name   city\n1  Johnny Lyon\n2  Jane   Milan\n"
+    );
+    // The temporary file was renamed over "ours"
+    assert_eq!(std::fs::read_dir(&dir.0).unwrap().count(), 3);
+}
+
+#[test]
+fn merge_with_conflicts() {
+    let dir = TempDir::new("conflicts");
+    let base = dir.file("base", "1 a\n");
+    let ours = dir.file("ours", "1 b\n");
+    let theirs = dir.file("theirs", "1 c\n");
+
+    let output = ssv_git(&[
+        "merge",
+        "--key",
+        "0",
+        base.to_str().unwrap(),
+        ours.to_str().unwrap(),
+        theirs.to_str().unwrap(),
+    ]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        std::fs::read_to_string(&ours).unwrap(),
+        "<<<<<<< ours\n1 b\n=======\n1 c\n>>>>>>> theirs\n"
+    );
+}
+
+#[test]
+fn diff() {
+    let dir = TempDir::new("diff");
+    let old = dir.file("old", "id name\n1 John\n");
+    let new = dir.file("new", "id   name\n1    Jon\n");
+
+    let expected_output = "changed row at 2:1 -> 2:1\n  name: John (2:3) -> Jon (2:6)\n";
+    let output = ssv_git(&[
+        "diff",
+        "--header",
+        "--key",
+        "id",
+        old.to_str().unwrap(),
+        new.to_str().unwrap(),
+    ]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), expected_output);
+
+    // Arguments of git external diff drivers
+    let output = ssv_git(&[
+        "diff",
+        "--header",
+        "data.ssv",
+        old.to_str().unwrap(),
+        "0000000",
+        "100644",
+        new.to_str().unwrap(),
+        "0000000",
+        "100644",
+    ]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), expected_output);
+}
+
+#[test]
+fn textconv() {
+    let dir = TempDir::new("textconv");
+    let file = dir.file("file", "id name\n100 \"John Doe\"\n");
+
+    let output = ssv_git(&["textconv", file.to_str().unwrap()]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "id  name\n100 \"John Doe\"\n"
+    );
+}
+
+#[test]
+fn usage() {
+    let output = ssv_git(&["merge", "a"]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stderr).unwrap().contains("usage:"));
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "std")]
+
 use std::ops::Deref;
 
 use ssv::engine::domain::{BytesDomain, CharsDomain, Domain};
@@ -55,7 +57,7 @@ mod combinations {
         pub fn new(number_of_elements: usize, combinations_size: usize) -> Self {
             RawCombinationsIterator {
                 last_element: number_of_elements - 1,
-                next_combination: Some(std::iter::repeat(0).take(combinations_size).collect()),
+                next_combination: Some(std::iter::repeat_n(0, combinations_size).collect()),
             }
         }
     }
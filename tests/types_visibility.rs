@@ -1,9 +1,24 @@
+#![cfg(feature = "std")]
+
 mod bytes {
     use std::ops::Deref;
 
     use ssv::bytes::{
-        FluentWriter, Options, Position, ReadError, ReadResult, Reader, RowWriter, Token,
-        Tokenizer, WithPosition, WriteError, WriteResult, Writer,
+        align, read_file, AggregateError, AggregateResult, Aggregated, Aggregation, Aggregator,
+        BorderStyle, CellDifference, Collation, ColumnNotFoundError, ColumnProfile, ColumnRef,
+        ColumnSchema, ColumnType, CommentLineBreakPolicy, CompressingWriter, Compression,
+        DecimalSeparator, DecodingReader, DecompressingReader, Diff, DiffRow, Differ, Difference,
+        Direction, Encoding, EncodingWriter, ExpressionError, FieldColumn, FieldError, FileReader,
+        Filter, Filtered, Finding, FixedWidthImporter, FluentWriter, FollowEvent, Follower,
+        FromSsvRow, GroupingStrategy, ImportError, ImportResult, JoinError, JoinKind, JoinResult,
+        JoinStrategy, Joiner, Linter, MappingError, MappingResult, MarkdownImporter, Merger,
+        NumberFormat, Options, Position, Profile, Profiler, Projected, Projection, QuotingPolicy,
+        ReadError, ReadResult, ReadWriteError, ReadWriteResult, Reader, Record, RecordReader,
+        Renderer, ReverseReader, RowWriter, Rule, RuleSet, Schema, SchemaError, SchemaResult, Side,
+        SliceReader, SliceRecord, SliceRecordReader, SliceValue, SortKey, Sorter, SpecError,
+        StructReader, StructWriter, TableFormat, ToSsvRow, Token, TokenWriter, Tokenizer,
+        UpdateError, UpdateResult, Updater, Violation, ViolationKind, WithPosition, WriteError,
+        WriteMode, WriteResult, Writer,
     };
 
     #[test]
@@ -11,27 +26,24 @@ mod bytes {
         let input = Vec::new();
         let mut tokenizer: Tokenizer<_> = Tokenizer::new(input.deref());
 
-        match tokenizer.next() {
-            Some(result) => {
-                let result: ReadResult<_> = result;
-                match result {
-                    Ok(token_with_position) => {
-                        let WithPosition {
-                            value: token,
-                            position,
-                        } = token_with_position;
-                        let _: Token = token;
-                        let Position {
-                            line_number: _,
-                            column_number: _,
-                        } = position;
-                    }
-                    Err(error) => {
-                        let _: ReadError = error;
-                    }
+        if let Some(result) = tokenizer.next() {
+            let result: ReadResult<_> = result;
+            match result {
+                Ok(token_with_position) => {
+                    let WithPosition {
+                        value: token,
+                        position,
+                    } = token_with_position;
+                    let _: Token = token;
+                    let Position {
+                        line_number: _,
+                        column_number: _,
+                    } = position;
+                }
+                Err(error) => {
+                    let _: ReadError = error;
                 }
             }
-            None => (),
         }
     }
 
@@ -40,19 +52,16 @@ mod bytes {
         let input = Vec::new();
         let mut reader: Reader<_> = Reader::new(input.deref());
 
-        match reader.next() {
-            Some(result) => {
-                let result: ReadResult<_> = result;
-                match result {
-                    Ok(row) => {
-                        let _: Vec<Vec<u8>> = row;
-                    }
-                    Err(error) => {
-                        let _: ReadError = error;
-                    }
+        if let Some(result) = reader.next() {
+            let result: ReadResult<_> = result;
+            match result {
+                Ok(row) => {
+                    let _: Vec<Vec<u8>> = row;
+                }
+                Err(error) => {
+                    let _: ReadError = error;
                 }
             }
-            None => (),
         }
     }
 
@@ -64,7 +73,9 @@ mod bytes {
 
     #[test]
     fn options() {
-        let _: Options = Options::new();
+        let mut options: Options = Options::new();
+        options.set_quoting_policy(QuotingPolicy::Minimal);
+        options.set_comment_line_break_policy(CommentLineBreakPolicy::Reject);
     }
 
     #[test]
@@ -72,6 +83,11 @@ mod bytes {
         let _: FluentWriter<_> = FluentWriter::new(Vec::new());
     }
 
+    #[test]
+    fn token_writer() {
+        let _: TokenWriter<_> = TokenWriter::new(Vec::new());
+    }
+
     #[test]
     fn writer() {
         let mut writer: Writer<_> = Writer::new(Vec::new());
@@ -88,14 +104,326 @@ mod bytes {
             }
         }
     }
+
+    #[test]
+    fn linter() {
+        let input = Vec::new();
+        let mut linter: Linter = Linter::new();
+        let _: &mut RuleSet = linter.rules_mut();
+
+        let result: ReadResult<_> = linter.lint(input.deref());
+        if let Ok(findings) = result {
+            for finding in findings {
+                let Finding { rule, position } = finding;
+                let _: Rule = rule;
+                let _: Position = position;
+            }
+        }
+
+        let result: ReadWriteResult<_> = linter.fix(input.deref(), Vec::new());
+        if let Err(error) = result {
+            let _: ReadWriteError = error;
+        }
+    }
+
+    #[test]
+    fn projection() {
+        let input = Vec::new();
+        let result: Result<Projection, SpecError> = "0, *".parse();
+        if let Ok(projection) = result {
+            let projection = projection.with_header(false).select(ColumnRef::Index(1));
+
+            let records: RecordReader<_> = RecordReader::new(input.deref());
+            let projected: Projected<_> = projection.apply(records);
+            for record in projected {
+                match record {
+                    Ok(Record::Row(_)) | Ok(Record::Comment(_)) => {}
+                    Err(error) => {
+                        let error: ReadWriteError = error;
+                        if let ReadWriteError::ColumnNotFound(error) = error {
+                            let _: ColumnNotFoundError = error;
+                        }
+                    }
+                }
+            }
+
+            let _: ReadWriteResult<()> = projection.transform(input.deref(), Vec::new());
+        }
+    }
+
+    #[test]
+    fn filter() {
+        let input = Vec::new();
+        let result: Result<Filter, ExpressionError> = "a is not empty".parse();
+        if let Ok(filter) = result {
+            let filtered: Filtered<_> = filter.apply(Reader::new(input.deref()));
+            for row in filtered {
+                if let Err(error) = row {
+                    let _: ReadWriteError = error;
+                }
+            }
+
+            let _: ReadWriteResult<()> = filter.transform(input.deref(), Vec::new());
+        }
+    }
+
+    #[test]
+    fn sorter() {
+        let input = Vec::new();
+        let key: SortKey = SortKey::descending(0).with_collation(Collation::Natural);
+        let _: Direction = key.direction();
+        let sorter: Sorter = Sorter::new().by(key);
+
+        let result: ReadWriteResult<()> = sorter.sort(input.deref(), Vec::new());
+        if let Err(error) = result {
+            let _: ReadWriteError = error;
+        }
+    }
+
+    #[test]
+    fn joiner() {
+        let input = Vec::new();
+        let joiner: Joiner = Joiner::new(JoinKind::FullOuter)
+            .with_strategy(JoinStrategy::Merge)
+            .on(0, 0);
+
+        let result: JoinResult<()> = joiner.join(input.deref(), input.deref(), Vec::new());
+        if let Err(JoinError::UnsortedInput(side, _)) = result {
+            let _: Side = side;
+        }
+    }
+
+    #[test]
+    fn struct_mapping() {
+        struct Empty;
+        impl FromSsvRow for Empty {
+            const COLUMNS: &'static [FieldColumn] = &[];
+
+            fn from_ssv_values(_: &[Option<&str>]) -> Result<Self, FieldError> {
+                Ok(Empty)
+            }
+        }
+        impl ToSsvRow for Empty {
+            const HEADER: &'static [&'static str] = &[];
+            const QUOTING_POLICIES: &'static [Option<QuotingPolicy>] = &[];
+
+            fn to_ssv_values(&self) -> Vec<String> {
+                Vec::new()
+            }
+        }
+
+        let input = Vec::new();
+        let reader: StructReader<_, Empty> = StructReader::new(input.deref()).with_header(true);
+        for result in reader {
+            let _: MappingResult<Empty> = result;
+            if let Err(MappingError::FieldError(row_number, FieldError::MissingValue(field))) =
+                result
+            {
+                let _: (usize, &str) = (row_number, field);
+            }
+        }
+
+        let mut writer: StructWriter<_, Empty> = StructWriter::new(Vec::new());
+        writer.write(&Empty).unwrap();
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn aggregator() {
+        let input = Vec::new();
+        let aggregator: Aggregator = Aggregator::new()
+            .with_strategy(GroupingStrategy::Sorted)
+            .with_number_format(NumberFormat::DECIMAL_COMMA)
+            .group_by(0)
+            .with(Aggregation::count());
+
+        let aggregated: Aggregated<_> = aggregator.apply(Reader::new(input.deref()));
+        for row in aggregated {
+            let _: AggregateResult<_> = row;
+        }
+        let result: AggregateResult<()> = aggregator.transform(input.deref(), Vec::new());
+        if let Err(AggregateError::TooManyGroups(max_groups)) = result {
+            let _: usize = max_groups;
+        }
+    }
+
+    #[test]
+    fn differ() {
+        let input = Vec::new();
+        let differ: Differ = Differ::new().with_header(true).key(0);
+
+        let result: ReadWriteResult<Diff> = differ.diff(input.deref(), input.deref());
+        match result {
+            Ok(diff) => {
+                for difference in diff.differences() {
+                    let _: &Difference = difference;
+                    if let Difference::Changed { left, cells, .. } = difference {
+                        let _: &DiffRow = left;
+                        let _: &Vec<CellDifference> = cells;
+                    }
+                }
+            }
+            Err(ReadWriteError::ColumnNotFound(ColumnNotFoundError(_))) => {}
+            Err(_) => {}
+        }
+    }
+
+    #[test]
+    fn merger() {
+        let input = Vec::new();
+        let merger: Merger = Merger::new().with_header(true).key(0);
+
+        let result: ReadWriteResult<usize> =
+            merger.merge(input.deref(), input.deref(), input.deref(), Vec::new());
+        if let Err(ReadWriteError::ColumnNotFound(ColumnNotFoundError(column))) = result {
+            let _: String = column;
+        }
+
+        let _: ReadWriteResult<()> = align(input.deref(), Vec::new());
+    }
+
+    #[test]
+    fn profiler() {
+        let input = Vec::new();
+        let profiler: Profiler = Profiler::new().with_header(true);
+
+        let profile: Profile = profiler.profile(Reader::new(input.deref())).unwrap();
+        for column in &profile.columns {
+            let _: &ColumnProfile = column;
+            if let ColumnType::Decimal(separator) = column.column_type {
+                let _: DecimalSeparator = separator;
+            }
+        }
+
+        let schema: Schema = profile.schema();
+        let _: &Vec<ColumnSchema> = &schema.columns;
+        let result: SchemaResult<Vec<Violation>> = schema.validate(input.deref());
+        if let Ok(violations) = result {
+            for violation in violations {
+                let _: ViolationKind = violation.kind;
+            }
+        }
+        if let Err(SchemaError::InvalidRow(position)) = Schema::read(input.deref()) {
+            let _: Position = position;
+        }
+    }
+    #[test]
+    fn renderer() {
+        let input = Vec::new();
+        let renderer: Renderer = Renderer::new(TableFormat::Text(BorderStyle::Ascii));
+
+        let result: ReadWriteResult<()> = renderer.render(Reader::new(input.deref()), Vec::new());
+        if let Err(ReadWriteError::ReadError(read_error)) = result {
+            let _: ReadError = read_error;
+        }
+    }
+    #[test]
+    fn importers() {
+        let input = Vec::new();
+        let importer: MarkdownImporter = MarkdownImporter::new();
+        if let Err(ImportError::TableNotFound) = importer.read(input.deref()) {}
+
+        let importer: FixedWidthImporter = FixedWidthImporter::new().with_boundaries([0, 8]);
+        let result: ImportResult<()> = importer.transform(input.deref(), Vec::new());
+        result.unwrap();
+    }
+    #[test]
+    fn slice_reader() {
+        let input = Vec::new();
+        let reader: SliceReader = SliceReader::new(&input);
+        for row in reader {
+            let _: Vec<SliceValue> = row.unwrap();
+        }
+
+        let reader: SliceRecordReader = SliceRecordReader::new(&input);
+        for record in reader {
+            if let SliceRecord::Comment(comment) = record.unwrap().value {
+                let _: SliceValue = comment;
+            }
+        }
+    }
+    #[test]
+    fn reverse_reader() {
+        let input = std::io::Cursor::new(Vec::new());
+        let reader: ReverseReader<_> = ReverseReader::new(input);
+        for row in reader {
+            let _: ReadResult<_> = row;
+        }
+    }
+
+    #[test]
+    fn follower() {
+        let path = std::env::temp_dir().join("ssv-visibility-follower-missing.ssv");
+        let mut follower: Follower = Follower::new(path)
+            .poll_interval(std::time::Duration::from_millis(10))
+            .skip_existing(true);
+        let event: ReadResult<Option<FollowEvent>> = follower.try_next();
+        assert!(matches!(event, Ok(None) | Ok(Some(FollowEvent::Row(_)))));
+    }
+
+    #[test]
+    fn write_mode() {
+        let mode: WriteMode = WriteMode::default();
+        assert_eq!(mode, WriteMode::Truncate);
+        assert_ne!(WriteMode::Atomic, WriteMode::Append);
+    }
+
+    #[test]
+    fn updater() {
+        let updater: Updater = Updater::new().with_header(true).key("id");
+        let result: UpdateResult<()> =
+            updater.update(&b"id x\n1 a\n"[..], Vec::new(), b"1", "x", b"b");
+        result.unwrap();
+        let error: UpdateError = Updater::default()
+            .update(&b""[..], Vec::new(), b"1", 1, b"b")
+            .unwrap_err();
+        assert!(matches!(error, UpdateError::RowNotFound));
+    }
+
+    #[test]
+    fn compression() {
+        let compression: Compression = Compression::from_extension("file.ssv");
+        let mut writer: CompressingWriter<Vec<u8>> =
+            CompressingWriter::new(Vec::new(), compression).unwrap();
+        std::io::Write::write_all(&mut writer, b"a").unwrap();
+        writer.finish().unwrap();
+        let reader: DecompressingReader<&[u8]> = DecompressingReader::new(&b"a"[..]).unwrap();
+        let _: Reader<DecompressingReader<&[u8]>> = Reader::new(reader);
+        if let Ok(reader) = read_file("missing.ssv") {
+            let _: Reader<FileReader> = reader;
+        }
+    }
+
+    #[test]
+    fn encoding() {
+        let encoding: Encoding = Encoding::from_bom(b"\xFF\xFE").unwrap();
+        let reader: DecodingReader<&[u8]> = DecodingReader::new(&b""[..], encoding);
+        let _: Reader<DecodingReader<&[u8]>> = Reader::new(reader);
+        let writer: EncodingWriter<Vec<u8>> =
+            EncodingWriter::new(Vec::new(), Encoding::Latin1).with_bom(false);
+        writer.finish().unwrap();
+    }
 }
 
 mod chars {
     use std::ops::Deref;
 
     use ssv::chars::{
-        FluentWriter, Options, Position, ReadError, ReadResult, Reader, RowWriter, Token,
-        Tokenizer, WithPosition, WriteError, WriteResult, Writer,
+        align, read_file, AggregateError, AggregateResult, Aggregated, Aggregation, Aggregator,
+        BorderStyle, CellDifference, Collation, ColumnNotFoundError, ColumnProfile, ColumnRef,
+        ColumnSchema, ColumnType, CommentLineBreakPolicy, CompressingWriter, Compression,
+        DecimalSeparator, DecodingReader, DecompressingReader, Diff, DiffRow, Differ, Difference,
+        Direction, Encoding, EncodingWriter, ExpressionError, FieldColumn, FieldError, FileReader,
+        Filter, Filtered, Finding, FixedWidthImporter, FluentWriter, FollowEvent, Follower,
+        FromSsvRow, GroupingStrategy, ImportError, ImportResult, JoinError, JoinKind, JoinResult,
+        JoinStrategy, Joiner, Linter, MappingError, MappingResult, MarkdownImporter, Merger,
+        NumberFormat, Options, Position, Profile, Profiler, Projected, Projection, QuotingPolicy,
+        ReadError, ReadResult, ReadWriteError, ReadWriteResult, Reader, Record, RecordReader,
+        Renderer, ReverseReader, RowWriter, Rule, RuleSet, Schema, SchemaError, SchemaResult, Side,
+        SliceReader, SliceRecord, SliceRecordReader, SliceValue, SortKey, Sorter, SpecError,
+        StructReader, StructWriter, TableFormat, ToSsvRow, Token, TokenWriter, Tokenizer,
+        UpdateError, UpdateResult, Updater, Violation, ViolationKind, WithPosition, WriteError,
+        WriteMode, WriteResult, Writer,
     };
 
     #[test]
@@ -103,27 +431,24 @@ mod chars {
         let input = Vec::new();
         let mut tokenizer: Tokenizer<_> = Tokenizer::new(input.deref());
 
-        match tokenizer.next() {
-            Some(result) => {
-                let result: ReadResult<_> = result;
-                match result {
-                    Ok(token_with_position) => {
-                        let WithPosition {
-                            value: token,
-                            position,
-                        } = token_with_position;
-                        let _: Token = token;
-                        let Position {
-                            line_number: _,
-                            column_number: _,
-                        } = position;
-                    }
-                    Err(error) => {
-                        let _: ReadError = error;
-                    }
+        if let Some(result) = tokenizer.next() {
+            let result: ReadResult<_> = result;
+            match result {
+                Ok(token_with_position) => {
+                    let WithPosition {
+                        value: token,
+                        position,
+                    } = token_with_position;
+                    let _: Token = token;
+                    let Position {
+                        line_number: _,
+                        column_number: _,
+                    } = position;
+                }
+                Err(error) => {
+                    let _: ReadError = error;
                 }
             }
-            None => (),
         }
     }
 
@@ -132,19 +457,16 @@ mod chars {
         let input = Vec::new();
         let mut reader: Reader<_> = Reader::new(input.deref());
 
-        match reader.next() {
-            Some(result) => {
-                let result: ReadResult<_> = result;
-                match result {
-                    Ok(row) => {
-                        let _: Vec<String> = row;
-                    }
-                    Err(error) => {
-                        let _: ReadError = error;
-                    }
+        if let Some(result) = reader.next() {
+            let result: ReadResult<_> = result;
+            match result {
+                Ok(row) => {
+                    let _: Vec<String> = row;
+                }
+                Err(error) => {
+                    let _: ReadError = error;
                 }
             }
-            None => (),
         }
     }
 
@@ -156,7 +478,9 @@ mod chars {
 
     #[test]
     fn options() {
-        let _: Options = Options::new();
+        let mut options: Options = Options::new();
+        options.set_quoting_policy(QuotingPolicy::Minimal);
+        options.set_comment_line_break_policy(CommentLineBreakPolicy::Reject);
     }
 
     #[test]
@@ -164,6 +488,11 @@ mod chars {
         let _: FluentWriter<_> = FluentWriter::new(Vec::new());
     }
 
+    #[test]
+    fn token_writer() {
+        let _: TokenWriter<_> = TokenWriter::new(Vec::new());
+    }
+
     #[test]
     fn writer() {
         let mut writer: Writer<_> = Writer::new(Vec::new());
@@ -180,4 +509,303 @@ mod chars {
             }
         }
     }
+
+    #[test]
+    fn linter() {
+        let input = Vec::new();
+        let mut linter: Linter = Linter::new();
+        let _: &mut RuleSet = linter.rules_mut();
+
+        let result: ReadResult<_> = linter.lint(input.deref());
+        if let Ok(findings) = result {
+            for finding in findings {
+                let Finding { rule, position } = finding;
+                let _: Rule = rule;
+                let _: Position = position;
+            }
+        }
+
+        let result: ReadWriteResult<_> = linter.fix(input.deref(), Vec::new());
+        if let Err(error) = result {
+            let _: ReadWriteError = error;
+        }
+    }
+
+    #[test]
+    fn projection() {
+        let input = Vec::new();
+        let result: Result<Projection, SpecError> = "0, *".parse();
+        if let Ok(projection) = result {
+            let projection = projection.with_header(false).select(ColumnRef::Index(1));
+
+            let records: RecordReader<_> = RecordReader::new(input.deref());
+            let projected: Projected<_> = projection.apply(records);
+            for record in projected {
+                match record {
+                    Ok(Record::Row(_)) | Ok(Record::Comment(_)) => {}
+                    Err(error) => {
+                        let error: ReadWriteError = error;
+                        if let ReadWriteError::ColumnNotFound(error) = error {
+                            let _: ColumnNotFoundError = error;
+                        }
+                    }
+                }
+            }
+
+            let _: ReadWriteResult<()> = projection.transform(input.deref(), Vec::new());
+        }
+    }
+
+    #[test]
+    fn filter() {
+        let input = Vec::new();
+        let result: Result<Filter, ExpressionError> = "a is not empty".parse();
+        if let Ok(filter) = result {
+            let filtered: Filtered<_> = filter.apply(Reader::new(input.deref()));
+            for row in filtered {
+                if let Err(error) = row {
+                    let _: ReadWriteError = error;
+                }
+            }
+
+            let _: ReadWriteResult<()> = filter.transform(input.deref(), Vec::new());
+        }
+    }
+
+    #[test]
+    fn sorter() {
+        let input = Vec::new();
+        let key: SortKey = SortKey::descending(0).with_collation(Collation::Natural);
+        let _: Direction = key.direction();
+        let sorter: Sorter = Sorter::new().by(key);
+
+        let result: ReadWriteResult<()> = sorter.sort(input.deref(), Vec::new());
+        if let Err(error) = result {
+            let _: ReadWriteError = error;
+        }
+    }
+
+    #[test]
+    fn joiner() {
+        let input = Vec::new();
+        let joiner: Joiner = Joiner::new(JoinKind::FullOuter)
+            .with_strategy(JoinStrategy::Merge)
+            .on(0, 0);
+
+        let result: JoinResult<()> = joiner.join(input.deref(), input.deref(), Vec::new());
+        if let Err(JoinError::UnsortedInput(side, _)) = result {
+            let _: Side = side;
+        }
+    }
+
+    #[test]
+    fn struct_mapping() {
+        struct Empty;
+        impl FromSsvRow for Empty {
+            const COLUMNS: &'static [FieldColumn] = &[];
+
+            fn from_ssv_values(_: &[Option<&str>]) -> Result<Self, FieldError> {
+                Ok(Empty)
+            }
+        }
+        impl ToSsvRow for Empty {
+            const HEADER: &'static [&'static str] = &[];
+            const QUOTING_POLICIES: &'static [Option<QuotingPolicy>] = &[];
+
+            fn to_ssv_values(&self) -> Vec<String> {
+                Vec::new()
+            }
+        }
+
+        let input = Vec::new();
+        let reader: StructReader<_, Empty> = StructReader::new(input.deref()).with_header(true);
+        for result in reader {
+            let _: MappingResult<Empty> = result;
+            if let Err(MappingError::FieldError(row_number, FieldError::MissingValue(field))) =
+                result
+            {
+                let _: (usize, &str) = (row_number, field);
+            }
+        }
+
+        let mut writer: StructWriter<_, Empty> = StructWriter::new(Vec::new());
+        writer.write(&Empty).unwrap();
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn aggregator() {
+        let input = Vec::new();
+        let aggregator: Aggregator = Aggregator::new()
+            .with_strategy(GroupingStrategy::Sorted)
+            .with_number_format(NumberFormat::DECIMAL_COMMA)
+            .group_by(0)
+            .with(Aggregation::count());
+
+        let aggregated: Aggregated<_> = aggregator.apply(Reader::new(input.deref()));
+        for row in aggregated {
+            let _: AggregateResult<_> = row;
+        }
+        let result: AggregateResult<()> = aggregator.transform(input.deref(), Vec::new());
+        if let Err(AggregateError::TooManyGroups(max_groups)) = result {
+            let _: usize = max_groups;
+        }
+    }
+
+    #[test]
+    fn differ() {
+        let input = Vec::new();
+        let differ: Differ = Differ::new().with_header(true).key(0);
+
+        let result: ReadWriteResult<Diff> = differ.diff(input.deref(), input.deref());
+        match result {
+            Ok(diff) => {
+                for difference in diff.differences() {
+                    let _: &Difference = difference;
+                    if let Difference::Changed { left, cells, .. } = difference {
+                        let _: &DiffRow = left;
+                        let _: &Vec<CellDifference> = cells;
+                    }
+                }
+            }
+            Err(ReadWriteError::ColumnNotFound(ColumnNotFoundError(_))) => {}
+            Err(_) => {}
+        }
+    }
+
+    #[test]
+    fn merger() {
+        let input = Vec::new();
+        let merger: Merger = Merger::new().with_header(true).key(0);
+
+        let result: ReadWriteResult<usize> =
+            merger.merge(input.deref(), input.deref(), input.deref(), Vec::new());
+        if let Err(ReadWriteError::ColumnNotFound(ColumnNotFoundError(column))) = result {
+            let _: String = column;
+        }
+
+        let _: ReadWriteResult<()> = align(input.deref(), Vec::new());
+    }
+
+    #[test]
+    fn profiler() {
+        let input = Vec::new();
+        let profiler: Profiler = Profiler::new().with_header(true);
+
+        let profile: Profile = profiler.profile(Reader::new(input.deref())).unwrap();
+        for column in &profile.columns {
+            let _: &ColumnProfile = column;
+            if let ColumnType::Decimal(separator) = column.column_type {
+                let _: DecimalSeparator = separator;
+            }
+        }
+
+        let schema: Schema = profile.schema();
+        let _: &Vec<ColumnSchema> = &schema.columns;
+        let result: SchemaResult<Vec<Violation>> = schema.validate(input.deref());
+        if let Ok(violations) = result {
+            for violation in violations {
+                let _: ViolationKind = violation.kind;
+            }
+        }
+        if let Err(SchemaError::InvalidRow(position)) = Schema::read(input.deref()) {
+            let _: Position = position;
+        }
+    }
+    #[test]
+    fn renderer() {
+        let input = Vec::new();
+        let renderer: Renderer = Renderer::new(TableFormat::Text(BorderStyle::Ascii));
+
+        let result: ReadWriteResult<()> = renderer.render(Reader::new(input.deref()), Vec::new());
+        if let Err(ReadWriteError::ReadError(read_error)) = result {
+            let _: ReadError = read_error;
+        }
+    }
+    #[test]
+    fn importers() {
+        let input = Vec::new();
+        let importer: MarkdownImporter = MarkdownImporter::new();
+        if let Err(ImportError::TableNotFound) = importer.read(input.deref()) {}
+
+        let importer: FixedWidthImporter = FixedWidthImporter::new().with_boundaries([0, 8]);
+        let result: ImportResult<()> = importer.transform(input.deref(), Vec::new());
+        result.unwrap();
+    }
+    #[test]
+    fn slice_reader() {
+        let input = Vec::new();
+        let reader: SliceReader = SliceReader::new(&input);
+        for row in reader {
+            let _: Vec<SliceValue> = row.unwrap();
+        }
+
+        let reader: SliceRecordReader = SliceRecordReader::new(&input);
+        for record in reader {
+            if let SliceRecord::Comment(comment) = record.unwrap().value {
+                let _: SliceValue = comment;
+            }
+        }
+    }
+    #[test]
+    fn reverse_reader() {
+        let input = std::io::Cursor::new(Vec::new());
+        let reader: ReverseReader<_> = ReverseReader::new(input);
+        for row in reader {
+            let _: ReadResult<_> = row;
+        }
+    }
+
+    #[test]
+    fn follower() {
+        let path = std::env::temp_dir().join("ssv-visibility-follower-missing.ssv");
+        let mut follower: Follower = Follower::new(path)
+            .poll_interval(std::time::Duration::from_millis(10))
+            .skip_existing(true);
+        let event: ReadResult<Option<FollowEvent>> = follower.try_next();
+        assert!(matches!(event, Ok(None) | Ok(Some(FollowEvent::Row(_)))));
+    }
+
+    #[test]
+    fn write_mode() {
+        let mode: WriteMode = WriteMode::default();
+        assert_eq!(mode, WriteMode::Truncate);
+        assert_ne!(WriteMode::Atomic, WriteMode::Append);
+    }
+
+    #[test]
+    fn updater() {
+        let updater: Updater = Updater::new().with_header(true).key("id");
+        let result: UpdateResult<()> =
+            updater.update(&b"id x\n1 a\n"[..], Vec::new(), "1", "x", "b");
+        result.unwrap();
+        let error: UpdateError = Updater::default()
+            .update(&b""[..], Vec::new(), "1", 1, "b")
+            .unwrap_err();
+        assert!(matches!(error, UpdateError::RowNotFound));
+    }
+
+    #[test]
+    fn compression() {
+        let compression: Compression = Compression::from_extension("file.ssv");
+        let mut writer: CompressingWriter<Vec<u8>> =
+            CompressingWriter::new(Vec::new(), compression).unwrap();
+        std::io::Write::write_all(&mut writer, b"a").unwrap();
+        writer.finish().unwrap();
+        let reader: DecompressingReader<&[u8]> = DecompressingReader::new(&b"a"[..]).unwrap();
+        let _: Reader<DecompressingReader<&[u8]>> = Reader::new(reader);
+        if let Ok(reader) = read_file("missing.ssv") {
+            let _: Reader<FileReader> = reader;
+        }
+    }
+
+    #[test]
+    fn encoding() {
+        let encoding: Encoding = Encoding::from_bom(b"\xFF\xFE").unwrap();
+        let reader: DecodingReader<&[u8]> = DecodingReader::new(&b""[..], encoding);
+        let _: Reader<DecodingReader<&[u8]>> = Reader::new(reader);
+        let writer: EncodingWriter<Vec<u8>> =
+            EncodingWriter::new(Vec::new(), Encoding::Latin1).with_bom(false);
+        writer.finish().unwrap();
+    }
 }